@@ -0,0 +1,147 @@
+use crate::git::config_value;
+
+/// 剪刀線：在編輯器中此行（含註解字元）以下的內容會被捨棄
+pub const SCISSORS: &str = "------------------------ >8 ------------------------";
+
+/// core.commentChar 為 auto 時依序嘗試的註解字元（與 git 相同）
+const AUTO_COMMENT_CHARS: &str = "#;@!$%^&|:";
+
+/// 整理 commit 訊息的方式（git 的 commit.cleanup）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// 移除註解行，並整理空白
+    Strip,
+    /// 只整理空白（移除行尾空白、連續空行與開頭結尾的空行）
+    Whitespace,
+    /// 完全不修改
+    Verbatim,
+    /// 同 whitespace，但剪刀線以下的內容會被捨棄
+    Scissors,
+}
+
+impl Mode {
+    /// 依 commit.cleanup 設定決定；未設定或為 default 時，在編輯器中撰寫為 strip，否則為 whitespace
+    pub fn from_config(edited: bool) -> Mode {
+        match config_value("commit.cleanup").as_deref() {
+            Some("strip") => Mode::Strip,
+            Some("whitespace") => Mode::Whitespace,
+            Some("verbatim") => Mode::Verbatim,
+            Some("scissors") => Mode::Scissors,
+            _ if edited => Mode::Strip,
+            _ => Mode::Whitespace,
+        }
+    }
+}
+
+/// 註解字元（core.commentString 或 core.commentChar，預設為 #）；
+/// 設為 auto 時選用 content 中沒有出現在行首的字元
+pub fn comment_prefix(content: &str) -> String {
+    let configured = config_value("core.commentString").or_else(|| config_value("core.commentChar"));
+    prefix_for(configured.as_deref(), content)
+}
+
+/// 依設定值決定註解字元
+fn prefix_for(configured: Option<&str>, content: &str) -> String {
+    match configured {
+        Some("auto") => AUTO_COMMENT_CHARS
+            .chars()
+            .find(|c| !content.lines().any(|line| line.starts_with(*c)))
+            .unwrap_or('#')
+            .to_string(),
+        Some(prefix) if !prefix.is_empty() => prefix.to_string(),
+        _ => "#".to_string(),
+    }
+}
+
+/// 剪刀線（含註解字元）
+pub fn scissors_line(comment: &str) -> String {
+    format!("{} {}", comment, SCISSORS)
+}
+
+/// 移除行尾空白、合併連續的空行並移除開頭與結尾的空行（同 git stripspace）
+fn strip_whitespace<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    let mut output: Vec<&str> = Vec::new();
+    for line in lines.map(str::trim_end) {
+        if line.is_empty() && output.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        output.push(line);
+    }
+    while output.last().is_some_and(|line| line.is_empty()) {
+        output.pop();
+    }
+    output.join("\n")
+}
+
+/// 依 git 的規則整理 commit 訊息：strip 與 scissors 捨棄剪刀線以下的內容，strip 另外移除註解行
+pub fn apply(message: &str, mode: Mode, comment: &str) -> String {
+    let scissors = scissors_line(comment);
+    let above_scissors = || message.lines().take_while(|line| *line != scissors);
+    match mode {
+        Mode::Verbatim => message.to_string(),
+        Mode::Whitespace => strip_whitespace(message.lines()),
+        Mode::Scissors => strip_whitespace(above_scissors()),
+        Mode::Strip => strip_whitespace(above_scissors().filter(|line| !line.starts_with(comment))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESSAGE: &str = "\n\nfeat: 新增設定  \n\n\n# 請輸入 commit 訊息\n內文\n\n# ------------------------ >8 ------------------------\ndiff --git a b\n";
+
+    #[test]
+    fn strip_removes_comments_and_scissors() {
+        assert_eq!(apply(MESSAGE, Mode::Strip, "#"), "feat: 新增設定\n\n內文");
+    }
+
+    #[test]
+    fn whitespace_keeps_comments() {
+        assert_eq!(
+            apply(MESSAGE, Mode::Whitespace, "#"),
+            "feat: 新增設定\n\n# 請輸入 commit 訊息\n內文\n\n# ------------------------ >8 ------------------------\ndiff --git a b"
+        );
+    }
+
+    #[test]
+    fn scissors_keeps_comments_above_the_line() {
+        assert_eq!(apply(MESSAGE, Mode::Scissors, "#"), "feat: 新增設定\n\n# 請輸入 commit 訊息\n內文");
+    }
+
+    #[test]
+    fn verbatim_keeps_everything() {
+        assert_eq!(apply(MESSAGE, Mode::Verbatim, "#"), MESSAGE);
+    }
+
+    #[test]
+    fn strip_uses_configured_comment_prefix() {
+        let message = "fix: 修正 #12\n; 註解\n// 註解\n\n; ------------------------ >8 ------------------------\n捨棄";
+        assert_eq!(apply(message, Mode::Strip, ";"), "fix: 修正 #12\n// 註解");
+        assert_eq!(apply(message, Mode::Strip, "//"), "fix: 修正 #12\n; 註解\n\n; ------------------------ >8 ------------------------\n捨棄");
+    }
+
+    #[test]
+    fn comment_and_scissors_must_start_the_line() {
+        // 與 git 相同，前面有空白的行不視為註解或剪刀線
+        let message = "docs: 說明\n\n  # 縮排的井字號\n  # ------------------------ >8 ------------------------\n保留";
+        assert_eq!(apply(message, Mode::Strip, "#"), "docs: 說明\n\n  # 縮排的井字號\n  # ------------------------ >8 ------------------------\n保留");
+        // 註解字元不同的剪刀線不會截斷訊息
+        assert_eq!(apply("a\n# ------------------------ >8 ------------------------\nb", Mode::Scissors, ";"), "a\n# ------------------------ >8 ------------------------\nb");
+    }
+
+    #[test]
+    fn prefix_for_handles_auto_and_defaults() {
+        assert_eq!(prefix_for(None, ""), "#");
+        assert_eq!(prefix_for(Some(""), ""), "#");
+        assert_eq!(prefix_for(Some(";"), "# a"), ";");
+        assert_eq!(prefix_for(Some("//"), ""), "//");
+        assert_eq!(prefix_for(Some("auto"), "#1 修正\n; 說明"), "@");
+        assert_eq!(prefix_for(Some("auto"), "feat: #1"), "#");
+    }
+
+    #[test]
+    fn scissors_line_uses_comment_prefix() {
+        assert_eq!(scissors_line(";"), "; ------------------------ >8 ------------------------");
+    }
+}
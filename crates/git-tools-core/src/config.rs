@@ -0,0 +1,8 @@
+use std::env;
+use std::path::PathBuf;
+
+/// 工具的設定目錄（~/.config/<tool>）
+pub fn config_dir(tool: &str) -> PathBuf {
+    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".config").join(tool)
+}
@@ -0,0 +1,150 @@
+use anyhow::{Context, Result};
+use git2::{Config, IndexEntryExtendedFlag, Repository, StatusOptions, StatusShow, Tree};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// 在目前目錄執行 git，回傳 stdout；失敗時以 stderr 作為錯誤訊息
+pub fn git(args: &[&str]) -> Result<String> {
+    run(None, args)
+}
+
+/// 在 dir 中執行 git，回傳 stdout；失敗時以 stderr 作為錯誤訊息
+pub fn git_in(dir: &Path, args: &[&str]) -> Result<String> {
+    run(Some(dir), args)
+}
+
+fn run(dir: Option<&Path>, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    if let Some(dir) = dir {
+        command.arg("-C").arg(dir);
+    }
+    // 輸出由呼叫端擷取，不讓 git 詢問帳號密碼而停住
+    let output = command
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .output()
+        .with_context(|| format!("無法執行 git {}", args.first().unwrap_or(&"")))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} 執行失敗：{}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 取得當前分支名稱
+pub fn get_current_branch(repo: &Repository) -> Result<String> {
+    let head = repo.head()?;
+    let branch_name = head
+        .shorthand()
+        .unwrap_or("main")
+        .to_string();
+    Ok(branch_name)
+}
+
+/// 取得 staged 的檔案列表（不含以 git add -N 標記為 intent-to-add、內容尚未加入的檔案）；
+/// 只比對 HEAD 與 index、不掃描工作目錄，大型 repository 中也能很快完成
+pub fn get_staged_files(repo: &Repository) -> Result<Vec<String>> {
+    let mut opts = StatusOptions::new();
+    opts.show(StatusShow::Index).include_untracked(false);
+    let index = repo.index()?;
+    let intent_to_add = |path: &str| {
+        index
+            .get_path(Path::new(path), 0)
+            .is_some_and(|entry| entry.flags_extended & IndexEntryExtendedFlag::INTENT_TO_ADD.bits() != 0)
+    };
+
+    let statuses = repo.statuses(Some(&mut opts))?;
+    let mut staged_files = Vec::new();
+
+    for entry in statuses.iter() {
+        let status = entry.status();
+        if status.is_index_new()
+            || status.is_index_modified()
+            || status.is_index_deleted()
+            || status.is_index_renamed()
+            || status.is_index_typechange()
+        {
+            if let Some(path) = entry.path().filter(|path| !intent_to_add(path)) {
+                staged_files.push(path.to_string());
+            }
+        }
+    }
+
+    Ok(staged_files)
+}
+
+/// 將已追蹤檔案的修改與刪除加入 staged（同 git commit -a，不包含未追蹤的檔案），回傳更新的檔案；
+/// 指定 pathspecs 時只檢查符合的檔案，未指定時為整個 repository
+pub fn stage_tracked_changes(repo: &Repository, pathspecs: &[String]) -> Result<Vec<String>> {
+    let mut index = repo.index()?;
+    let mut updated = Vec::new();
+    let all = ["*".to_string()];
+    index.update_all(
+        if pathspecs.is_empty() { &all[..] } else { pathspecs },
+        Some(&mut |path: &Path, _: &[u8]| {
+            updated.push(path.display().to_string());
+            0
+        }),
+    )?;
+    index.write()?;
+    Ok(updated)
+}
+
+/// 取得 HEAD 的 tree（尚無 commit 時為 None）
+pub fn head_tree(repo: &Repository) -> Option<Tree<'_>> {
+    repo.head().ok()?.peel_to_tree().ok()
+}
+
+/// 取得 tree 中檔案的內容
+pub fn blob_content(repo: &Repository, tree: Option<&Tree>, path: &str) -> Option<Vec<u8>> {
+    let entry = tree?.get_path(Path::new(path)).ok()?;
+    let blob = repo.find_blob(entry.id()).ok()?;
+    Some(blob.content().to_vec())
+}
+
+/// 取得檔案在 HEAD 與 index（staged）中的原始內容，不存在時為 None
+pub fn file_blobs(repo: &Repository, path: &str) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    let old = blob_content(repo, head_tree(repo).as_ref(), path);
+    let new = repo
+        .index()
+        .ok()
+        .and_then(|index| index.get_path(Path::new(path), 0))
+        .and_then(|entry| repo.find_blob(entry.id).ok())
+        .map(|blob| blob.content().to_vec());
+    (old, new)
+}
+
+/// 取得檔案在 HEAD 與 index（staged）中的文字內容，不存在時為空字串
+pub fn file_versions(repo: &Repository, path: &str) -> (String, String) {
+    let to_string = |bytes: Option<Vec<u8>>| {
+        bytes.map(|b| String::from_utf8_lossy(&b).to_string()).unwrap_or_default()
+    };
+    let (old, new) = file_blobs(repo, path);
+    (to_string(old), to_string(new))
+}
+
+/// 讀取 git 設定值（包含目前所在 repository 的設定），未設定時為 None
+pub fn config_value(key: &str) -> Option<String> {
+    let config = match Repository::discover(".") {
+        Ok(repo) => repo.config().ok()?,
+        Err(_) => Config::open_default().ok()?,
+    };
+    config.get_string(key).ok()
+}
+
+/// 驗證分支名稱
+pub fn is_valid_branch_name(name: &str) -> bool {
+    // Git 分支名稱規則：不能包含空格、~、^、:、?、*、[、]、\
+    // 以及不能以 / 或 . 開頭
+    let invalid_chars = [' ', '~', '^', ':', '?', '*', '[', ']', '\\'];
+
+    if name.starts_with('/') || name.starts_with('.') {
+        return false;
+    }
+
+    !name.chars().any(|c| invalid_chars.contains(&c))
+}
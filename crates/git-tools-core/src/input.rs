@@ -0,0 +1,190 @@
+use crate::{cleanup, layout, plain};
+use anyhow::Result;
+use colored::*;
+use console::{measure_text_width, Key, Term};
+use dialoguer::Editor;
+use std::io::{self, BufRead};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 標題建議的長度上限
+pub const SUBJECT_LIMIT: usize = 50;
+/// 標題與內文每行的長度上限（超過時 git log 等工具會折行或截斷）
+pub const LINE_LIMIT: usize = 72;
+
+const CTRL_D: char = '\u{4}';
+const CTRL_X: char = '\u{18}';
+
+/// 在編輯器中撰寫時附加的說明
+const EDITOR_HELP: &str = "請輸入 commit 訊息：第一行為標題（建議 50 欄內），空一行後為內文與 footer（每行 72 欄內）。";
+
+/// 依長度上限顯示「N/上限」：未超過建議值為綠色，超過建議值但未超過 72 為黃色，超過 72 為紅色
+fn counter(length: usize, limit: usize) -> String {
+    let text = format!("{}/{}", length, limit);
+    if length <= limit {
+        text.green().to_string()
+    } else if length <= LINE_LIMIT {
+        text.yellow().to_string()
+    } else {
+        text.red().to_string()
+    }
+}
+
+/// 編輯中訊息的狀態列：目前所在的行與其長度
+fn status_line(lines: &[String]) -> String {
+    let current = lines.len() - 1;
+    let length = measure_text_width(&lines[current]);
+    let position = if current == 0 {
+        format!("標題 {}", counter(length, SUBJECT_LIMIT))
+    } else {
+        format!("第 {} 行 {}", current + 1, counter(length, LINE_LIMIT))
+    };
+    let subject_length = measure_text_width(&lines[0]);
+    let subject = if current > 0 && subject_length > SUBJECT_LIMIT {
+        format!("  標題 {}", counter(subject_length, SUBJECT_LIMIT))
+    } else {
+        String::new()
+    };
+    format!("{}{}  {}", position, subject, "Enter 換行、Ctrl-D 完成、Ctrl-X 改用編輯器、Esc 取消".dimmed())
+}
+
+/// 整理輸入的訊息：移除行尾空白與結尾空行，標題與內文之間補上空行
+fn finish<S: AsRef<str>>(lines: &[S]) -> String {
+    let mut lines: Vec<&str> = lines.iter().map(|line| line.as_ref().trim_end()).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    if lines.len() > 1 && !lines[1].is_empty() {
+        lines.insert(1, "");
+    }
+    lines.join("\n").trim().to_string()
+}
+
+/// 畫面上佔用的列數（超過終端機寬度的行會折行）
+fn rows(line: &str, columns: usize) -> usize {
+    measure_text_width(line).max(1).div_ceil(columns)
+}
+
+/// 編輯器中的說明：依 commit.cleanup 告知哪些內容會被忽略（whitespace、verbatim 不會移除註解，因此不加說明）
+fn editor_help(mode: cleanup::Mode, comment: &str) -> String {
+    match mode {
+        cleanup::Mode::Strip => format!(
+            "\n{c} {}\n{c} 以「{c}」開頭的行會被忽略，內容為空時取消。\n",
+            EDITOR_HELP,
+            c = comment
+        ),
+        cleanup::Mode::Scissors => format!(
+            "\n{}\n{c} 請勿修改或刪除上面這一行，以下的內容會被忽略。\n{c} {}\n",
+            cleanup::scissors_line(comment),
+            EDITOR_HELP,
+            c = comment
+        ),
+        cleanup::Mode::Whitespace | cleanup::Mode::Verbatim => String::new(),
+    }
+}
+
+/// 在編輯器（$VISUAL、$EDITOR，未設定時為 vi 或記事本）中撰寫 commit 訊息，以 initial 為初始內容；
+/// 依 git 的 commit.cleanup 與 core.commentChar 整理內容（預設移除註解行），未存檔或內容為空時回傳 None
+pub fn editor(initial: &str) -> Result<Option<String>> {
+    let mode = cleanup::Mode::from_config(true);
+    let comment = cleanup::comment_prefix(initial);
+    let Some(content) = Editor::new().edit(&format!("{}\n{}", initial, editor_help(mode, &comment)))? else {
+        return Ok(None);
+    };
+    let message = match mode {
+        cleanup::Mode::Verbatim => content.trim_end_matches('\n').to_string(),
+        mode => finish(&cleanup::apply(&content, mode, &comment).lines().collect::<Vec<_>>()),
+    };
+    Ok((!message.trim().is_empty()).then_some(message))
+}
+
+/// 從 stdin 逐行讀取 commit 訊息，輸入空行或讀到結尾時結束（第一行為標題，其後為內文）
+fn read_lines(prompt: &str) -> Result<Option<String>> {
+    eprintln!("{}（第一行為標題，輸入空行結束）：", prompt);
+    let mut lines = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            break;
+        }
+        lines.push(line);
+    }
+    let message = finish(&lines);
+    Ok((!message.is_empty()).then_some(message))
+}
+
+/// 多行的 commit 訊息輸入：第一行為標題，Enter 換行輸入內文，Ctrl-D 完成、Esc 取消（回傳 None），
+/// Ctrl-X 將目前的內容帶到編輯器中繼續撰寫；
+/// 輸入時即時顯示目前這一行的長度（標題建議 50 欄內、每行 72 欄內，中日韓字元佔兩欄）
+///
+/// 不在終端機中時改為逐行讀取 stdin，輸入空行結束。
+pub fn message(prompt: &str) -> Result<Option<String>> {
+    let term = Term::stderr();
+    if !term.is_term() {
+        return read_lines(prompt);
+    }
+
+    let theme = plain::theme();
+    let mut lines = vec![String::new()];
+    let mut error: Option<&str> = None;
+    term.hide_cursor()?;
+    let result = loop {
+        let mut header = String::new();
+        theme.format_input_prompt(&mut header, prompt, None)?;
+        let mut screen = vec![header];
+        screen.extend(lines.iter().map(|line| format!("  {}", line)));
+        // 以「_」標示游標所在位置（輸入只會加在最後一行的結尾）
+        if let Some(last) = screen.last_mut() {
+            last.push_str(&"_".dimmed().to_string());
+        }
+        screen.push(match error.take() {
+            Some(error) => error.red().to_string(),
+            None => status_line(&lines),
+        });
+        let columns = layout::width();
+        let height: usize = screen.iter().map(|line| rows(line, columns)).sum();
+        term.write_line(&screen.join("\n"))?;
+
+        let key = term.read_key()?;
+        term.clear_last_lines(height)?;
+        let current = lines.last_mut().expect("至少有一行");
+        match key {
+            Key::Enter => lines.push(String::new()),
+            Key::Backspace => {
+                if let Some((start, _)) = current.grapheme_indices(true).next_back() {
+                    current.truncate(start);
+                } else if lines.len() > 1 {
+                    lines.pop();
+                }
+            }
+            Key::Char(CTRL_D) => {
+                let message = finish(&lines);
+                if message.is_empty() {
+                    error = Some("Commit 訊息不能為空");
+                } else {
+                    break Some(message);
+                }
+            }
+            Key::Char(CTRL_X) => {
+                term.show_cursor()?;
+                let edited = editor(&finish(&lines));
+                term.hide_cursor()?;
+                match edited? {
+                    Some(message) => break Some(message),
+                    None => error = Some("編輯器中沒有內容，繼續在此輸入"),
+                }
+            }
+            Key::Escape | Key::CtrlC => break None,
+            Key::Tab => current.push(' '),
+            Key::Char(c) if !c.is_control() => current.push(c),
+            _ => {}
+        }
+    };
+    term.show_cursor()?;
+
+    if let Some(message) = &result {
+        let mut line = String::new();
+        theme.format_input_prompt_selection(&mut line, prompt, message.lines().next().unwrap_or_default())?;
+        term.write_line(&line)?;
+    }
+    Ok(result)
+}
@@ -0,0 +1,240 @@
+use crate::plain;
+use anyhow::{Context, Result};
+use console::{measure_text_width, truncate_str, Term};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// 無法取得終端機寬度時（例如輸出導向檔案）使用的寬度
+const DEFAULT_WIDTH: usize = 80;
+/// 預覽框分隔線的最大寬度
+const MAX_RULE_WIDTH: usize = 72;
+/// 選單項目前的游標欄位（例如「❯ 」）
+pub const MENU_INDENT: usize = 2;
+
+/// 終端機的顯示寬度（欄數）
+pub fn width() -> usize {
+    Term::stdout()
+        .size_checked()
+        .map(|(_, columns)| columns as usize)
+        .filter(|columns| *columns >= 20)
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// 扣除前後綴後剩下的可用寬度（至少保留 10 欄）
+pub fn available(used: usize) -> usize {
+    width().saturating_sub(used).max(10)
+}
+
+/// 超過寬度時截斷結尾並加上「…」（依顯示寬度計算，中日韓字元佔兩欄；不會切斷組合字元與 emoji 序列）
+pub fn truncate(text: &str, max: usize) -> String {
+    // 含 ANSI 色碼的文字交由 console 處理
+    if text.contains('\x1b') {
+        return truncate_str(text, max, "…").to_string();
+    }
+    if measure_text_width(text) <= max {
+        return text.to_string();
+    }
+    let budget = max.saturating_sub(1);
+    let mut truncated = String::new();
+    let mut used = 0;
+    for grapheme in text.graphemes(true) {
+        let w = grapheme_width(grapheme);
+        if used + w > budget {
+            break;
+        }
+        truncated.push_str(grapheme);
+        used += w;
+    }
+    truncated.push('…');
+    truncated
+}
+
+/// 超過寬度時截斷中間並加上「…」，保留開頭與較多的結尾（適合路徑，讓檔名保持完整）
+pub fn truncate_middle(text: &str, max: usize) -> String {
+    if measure_text_width(text) <= max {
+        return text.to_string();
+    }
+    let budget = max.saturating_sub(1);
+    let head_budget = budget / 3;
+    let tail_budget = budget - head_budget;
+
+    let mut head = String::new();
+    let mut head_width = 0;
+    for grapheme in text.graphemes(true) {
+        let w = grapheme_width(grapheme);
+        if head_width + w > head_budget {
+            break;
+        }
+        head.push_str(grapheme);
+        head_width += w;
+    }
+    let mut tail: Vec<&str> = Vec::new();
+    let mut tail_width = 0;
+    for grapheme in text.graphemes(true).rev() {
+        let w = grapheme_width(grapheme);
+        if tail_width + w > tail_budget {
+            break;
+        }
+        tail.push(grapheme);
+        tail_width += w;
+    }
+    let tail: String = tail.into_iter().rev().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// 單一字形（grapheme）的顯示寬度：組合字元不佔寬度，emoji 序列以第一個字元計算
+fn grapheme_width(grapheme: &str) -> usize {
+    // emoji 加上 U+FE0F 時以兩欄顯示
+    if grapheme.contains('\u{fe0f}') {
+        return 2;
+    }
+    grapheme
+        .chars()
+        .next()
+        .map_or(0, |c| measure_text_width(c.encode_utf8(&mut [0; 4])))
+}
+
+/// 將一行文字依寬度折行：優先在空白處斷開，沒有空白（例如中文）時在字形之間斷開
+pub fn wrap(line: &str, max: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+    for grapheme in line.graphemes(true) {
+        let w = grapheme_width(grapheme);
+        if current_width + w > max && !current.is_empty() {
+            // 超出寬度的正好是空白時直接在此斷開，不需退回前一個空白
+            if grapheme == " " {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+                continue;
+            }
+            match current.rfind(' ').filter(|&i| i > 0) {
+                Some(i) => {
+                    let rest = current[i + 1..].to_string();
+                    current.truncate(i);
+                    lines.push(std::mem::take(&mut current));
+                    current_width = measure_text_width(&rest);
+                    current = rest;
+                }
+                None => {
+                    lines.push(std::mem::take(&mut current));
+                    current_width = 0;
+                }
+            }
+        }
+        current.push_str(grapheme);
+        current_width += w;
+    }
+    lines.push(current);
+    lines
+}
+
+/// 預覽框的分隔線（不超過終端機寬度）
+pub fn rule() -> String {
+    "─".repeat(width().min(MAX_RULE_WIDTH))
+}
+
+/// 以 git 設定的 pager（core.pager、GIT_PAGER、PAGER，預設為 less）顯示長文字；pager 為 cat 時直接輸出
+pub fn page(text: &str) -> Result<()> {
+    let pager = Command::new("git")
+        .args(["var", "GIT_PAGER"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        .filter(|pager| !pager.is_empty())
+        .unwrap_or_else(|| "less".to_string());
+    if pager == "cat" {
+        if plain::enabled() {
+            print!("{}", plain::text(text));
+        } else {
+            print!("{}", text);
+        }
+        return Ok(());
+    }
+
+    // 與 git 相同，透過 shell 執行 pager 設定（可包含參數）
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", &pager]);
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.args(["-c", &pager]);
+        command
+    };
+    let mut child = command
+        .env("LESS", std::env::var("LESS").unwrap_or_else(|_| "FRX".to_string()))
+        .stdin(Stdio::piped())
+        .spawn()
+        .context(format!("無法執行 pager：{}", pager))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // 使用者提早離開 pager 時寫入會失敗，可忽略
+        let _ = stdin.write_all(text.as_bytes());
+    }
+    child.wait().context("pager 執行失敗")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 家庭 emoji：三個人像以 ZWJ 連接成一個字形
+    const FAMILY: &str = "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}";
+
+    #[test]
+    fn truncate_keeps_text_within_limit() {
+        assert_eq!(truncate("hello", 5), "hello");
+        assert_eq!(truncate("hello world", 8), "hello w…");
+        assert_eq!(truncate("abc", 1), "…");
+        assert_eq!(truncate("", 5), "");
+    }
+
+    #[test]
+    fn truncate_counts_cjk_as_two_columns() {
+        assert_eq!(truncate("中文字串", 8), "中文字串");
+        assert_eq!(truncate("中文字串", 7), "中文字…");
+        assert_eq!(truncate("中文字串", 6), "中文…");
+    }
+
+    #[test]
+    fn truncate_keeps_graphemes_whole() {
+        assert_eq!(truncate("cafe\u{301} au lait", 5), "cafe\u{301}…");
+        assert_eq!(truncate(&format!("{}ab", FAMILY), 3), format!("{}…", FAMILY));
+        assert_eq!(truncate("\u{200b}\u{200b}", 1), "\u{200b}\u{200b}");
+    }
+
+    #[test]
+    fn truncate_middle_keeps_file_name() {
+        let path = "src/components/very/long/path/file.rs";
+        assert_eq!(truncate_middle(path, path.len()), path);
+        assert_eq!(truncate_middle(path, 20), "src/co…/path/file.rs");
+        assert_eq!(truncate_middle("資料夾/子資料夾/檔案.rs", 14), "資料…/檔案.rs");
+    }
+
+    #[test]
+    fn truncate_middle_keeps_graphemes_whole() {
+        // 開頭的 emoji 序列放不下時整個捨棄，結尾保留完整的序列與組合字元
+        let text = format!("{}abcdef{}e\u{301}", FAMILY, FAMILY);
+        assert_eq!(truncate_middle(&text, 6), format!("…f{}e\u{301}", FAMILY));
+        assert_eq!(truncate_middle(&text, 8), format!("{}…ef{}e\u{301}", FAMILY, FAMILY));
+    }
+
+    #[test]
+    fn wrap_prefers_spaces() {
+        assert_eq!(wrap("hello world foo", 11), ["hello world", "foo"]);
+        assert_eq!(wrap("aaa bbb", 3), ["aaa", "bbb"]);
+        assert_eq!(wrap("abcde", 5), ["abcde"]);
+        assert_eq!(wrap("", 5), [""]);
+    }
+
+    #[test]
+    fn wrap_breaks_cjk_between_graphemes() {
+        assert_eq!(wrap("中文中文中文", 5), ["中文", "中文", "中文"]);
+        assert_eq!(wrap("中文中文", 4), ["中文", "中文"]);
+        assert_eq!(wrap("e\u{301}e\u{301}e\u{301}", 2), ["e\u{301}e\u{301}", "e\u{301}"]);
+        assert_eq!(wrap(&format!("{}{}", FAMILY, FAMILY), 3), [FAMILY, FAMILY]);
+    }
+}
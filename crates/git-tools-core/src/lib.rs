@@ -0,0 +1,11 @@
+//! 各工具共用的元件：git 輔助函數、設定檔與檔案路徑、字串截取與終端機介面（選單、訊息輸入、版面、純文字模式）
+
+pub mod cleanup;
+pub mod config;
+pub mod git;
+pub mod input;
+pub mod layout;
+pub mod menu;
+pub mod path;
+pub mod plain;
+pub mod text;
@@ -0,0 +1,66 @@
+use crate::plain;
+use anyhow::Result;
+use console::{Key, Term};
+use dialoguer::Select;
+
+/// 數字鍵對應的選項：以「N. 」開頭的第一個選項
+fn numbered_item(items: &[String], digit: char) -> Option<usize> {
+    let prefix = format!("{}. ", digit);
+    items.iter().position(|item| item.starts_with(&prefix))
+}
+
+/// 單選選單：方向鍵（或 j/k）移動、Enter 確認、Esc 或 q 取消，
+/// 按下數字鍵直接選擇以「N. 」開頭的選項（例如按 1 選擇第一個建議）
+///
+/// 不在終端機中時改用 dialoguer 的選單。
+pub fn select(prompt: &str, items: &[String], default: usize) -> Result<Option<usize>> {
+    let term = Term::stderr();
+    if !term.is_term() || items.is_empty() {
+        return Ok(Select::with_theme(plain::theme())
+            .with_prompt(prompt)
+            .items(items)
+            .default(default)
+            .interact_opt()?);
+    }
+
+    let theme = plain::theme();
+    let mut cursor = default.min(items.len() - 1);
+    term.hide_cursor()?;
+    let selection = loop {
+        let mut lines = Vec::with_capacity(items.len() + 1);
+        let mut line = String::new();
+        theme.format_select_prompt(&mut line, prompt)?;
+        lines.push(line);
+        for (i, item) in items.iter().enumerate() {
+            let mut line = String::new();
+            theme.format_select_prompt_item(&mut line, item, i == cursor)?;
+            lines.push(line);
+        }
+        term.write_line(&lines.join("\n"))?;
+
+        let key = term.read_key()?;
+        term.clear_last_lines(lines.len())?;
+        match key {
+            Key::ArrowUp | Key::BackTab | Key::Char('k') => cursor = (cursor + items.len() - 1) % items.len(),
+            Key::ArrowDown | Key::Tab | Key::Char('j') => cursor = (cursor + 1) % items.len(),
+            Key::Home => cursor = 0,
+            Key::End => cursor = items.len() - 1,
+            Key::Enter | Key::Char(' ') => break Some(cursor),
+            Key::Escape | Key::Char('q') => break None,
+            Key::Char(c @ '1'..='9') => {
+                if let Some(index) = numbered_item(items, c) {
+                    break Some(index);
+                }
+            }
+            _ => {}
+        }
+    };
+    term.show_cursor()?;
+
+    if let Some(index) = selection {
+        let mut line = String::new();
+        theme.format_select_prompt_selection(&mut line, prompt, &items[index])?;
+        term.write_line(&line)?;
+    }
+    Ok(selection)
+}
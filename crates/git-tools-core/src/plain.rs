@@ -0,0 +1,121 @@
+use dialoguer::theme::{ColorfulTheme, SimpleTheme, Theme};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+
+/// 是否為純文字模式（--plain）
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+static COLORFUL: LazyLock<ColorfulTheme> = LazyLock::new(ColorfulTheme::default);
+
+/// 啟用純文字模式：關閉顏色，之後的輸出以 ASCII 標記取代 emoji 與框線字元
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+    colored::control::set_override(false);
+    console::set_colors_enabled(false);
+    console::set_colors_enabled_stderr(false);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// 有意義的符號對應的 ASCII 標記；其餘 emoji 只是裝飾，直接移除
+fn marker(c: char) -> Option<&'static str> {
+    Some(match c {
+        '⚠' => "[!]",
+        '✓' | '✔' => "[ok]",
+        '✗' | '⛔' => "[x]",
+        '→' => "->",
+        '←' => "<-",
+        '▶' | '❯' => ">",
+        '★' => "*",
+        '☆' => ".",
+        '─' | '━' => "-",
+        '│' | '┃' => "|",
+        '└' | '├' | '┌' | '┐' | '┘' | '┤' | '┬' | '┴' | '┼' => "+",
+        '…' => "...",
+        _ => return None,
+    })
+}
+
+/// 是否為裝飾用的 emoji（含變體選擇符）
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32,
+        0x1F000..=0x1FAFF   // 各類 emoji 與符號
+        | 0x2600..=0x27BF   // 雜項符號與 Dingbats
+        | 0x2B00..=0x2BFF   // 箭頭與符號補充
+        | 0x2300..=0x23FF   // 技術符號（例如 ⏱）
+        | 0xFE0F            // emoji 變體選擇符
+        | 0x200D            // 零寬連接符
+    )
+}
+
+/// 將 emoji 與框線字元換成 ASCII 標記（移除裝飾用 emoji 與其後的空白）
+pub fn text(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        if let Some(marker) = marker(c) {
+            output.push_str(marker);
+            // 跳過 ⚠️ 等符號後的變體選擇符
+            while chars.peek() == Some(&'\u{FE0F}') {
+                chars.next();
+            }
+        } else if is_emoji(c) {
+            while chars.peek().is_some_and(|&next| is_emoji(next) || next == ' ') {
+                chars.next();
+            }
+        } else {
+            output.push(c);
+        }
+    }
+    output
+}
+
+/// 純文字模式的選單主題：使用 ASCII 游標，並轉換提示與選項中的符號
+struct PlainTheme;
+
+impl Theme for PlainTheme {
+    fn format_prompt(&self, f: &mut dyn fmt::Write, prompt: &str) -> fmt::Result {
+        SimpleTheme.format_prompt(f, &text(prompt))
+    }
+
+    fn format_error(&self, f: &mut dyn fmt::Write, err: &str) -> fmt::Result {
+        SimpleTheme.format_error(f, &text(err))
+    }
+
+    fn format_confirm_prompt(&self, f: &mut dyn fmt::Write, prompt: &str, default: Option<bool>) -> fmt::Result {
+        SimpleTheme.format_confirm_prompt(f, &text(prompt), default)
+    }
+
+    fn format_confirm_prompt_selection(
+        &self,
+        f: &mut dyn fmt::Write,
+        prompt: &str,
+        selection: Option<bool>,
+    ) -> fmt::Result {
+        SimpleTheme.format_confirm_prompt_selection(f, &text(prompt), selection)
+    }
+
+    fn format_input_prompt(&self, f: &mut dyn fmt::Write, prompt: &str, default: Option<&str>) -> fmt::Result {
+        SimpleTheme.format_input_prompt(f, &text(prompt), default)
+    }
+
+    fn format_input_prompt_selection(&self, f: &mut dyn fmt::Write, prompt: &str, sel: &str) -> fmt::Result {
+        SimpleTheme.format_input_prompt_selection(f, &text(prompt), &text(sel))
+    }
+
+    fn format_select_prompt_item(&self, f: &mut dyn fmt::Write, item: &str, active: bool) -> fmt::Result {
+        SimpleTheme.format_select_prompt_item(f, &text(item), active)
+    }
+}
+
+/// 互動選單使用的主題（純文字模式時不使用 emoji 與顏色）
+pub fn theme() -> &'static dyn Theme {
+    if enabled() {
+        &PlainTheme
+    } else {
+        &*COLORFUL
+    }
+}
@@ -0,0 +1,52 @@
+//! 依字元邊界截取字串（以位元組位置切片在多位元組 UTF-8 字元中間會 panic）
+
+/// 前 max_chars 個字元
+pub fn head(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+/// 最後 max_chars 個字元
+pub fn tail(text: &str, max_chars: usize) -> &str {
+    if max_chars == 0 {
+        return "";
+    }
+    match text.char_indices().rev().nth(max_chars - 1) {
+        Some((start, _)) => &text[start..],
+        None => text,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_cuts_at_char_boundaries() {
+        assert_eq!(head("中文abc", 2), "中文");
+        assert_eq!(head("中文abc", 5), "中文abc");
+        assert_eq!(head("中文abc", 6), "中文abc");
+        assert_eq!(head("中文abc", 0), "");
+        assert_eq!(head("", 3), "");
+    }
+
+    #[test]
+    fn tail_cuts_at_char_boundaries() {
+        assert_eq!(tail("中文abc", 4), "文abc");
+        assert_eq!(tail("中文abc", 5), "中文abc");
+        assert_eq!(tail("中文abc", 6), "中文abc");
+        assert_eq!(tail("中文abc", 0), "");
+        assert_eq!(tail("", 3), "");
+    }
+
+    #[test]
+    fn head_and_tail_count_chars_not_graphemes() {
+        // 組合字元與 ZWJ 各算一個字元，不會在 UTF-8 位元組中間切斷
+        assert_eq!(head("e\u{301}x", 1), "e");
+        assert_eq!(tail("xe\u{301}", 1), "\u{301}");
+        assert_eq!(head("\u{1f468}\u{200d}\u{1f469}", 2), "\u{1f468}\u{200d}");
+        assert_eq!(tail("\u{200b}\u{200b}a", 2), "\u{200b}a");
+    }
+}
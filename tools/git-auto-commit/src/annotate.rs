@@ -0,0 +1,93 @@
+use crate::cli::AnnotateArgs;
+use crate::config::load_llm_config;
+use crate::notes;
+use crate::progress::Timeline;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Confirm;
+use git_tools_core::git::git;
+use git_tools_core::plain;
+
+/// 送給 LLM 的 diff 最多字元數
+const MAX_DIFF_CHARS: usize = 8000;
+
+/// annotate 子指令：由 LLM 依 commit 的 diff 說明這個 commit 實際改了什麼，可存為 git note
+pub fn run(args: &AnnotateArgs) -> Result<()> {
+    println!("\n{}\n", "🔎 Commit 說明".cyan().bold());
+
+    let sha = git(&["rev-parse", "--verify", &format!("{}^{{commit}}", args.commit)])
+        .with_context(|| format!("找不到 commit：{}", args.commit))?
+        .trim()
+        .to_string();
+    let meta = git(&["log", "-1", "--format=%h%x1f%an%x1f%ci%x1f%B", &sha])?;
+    let mut fields = meta.splitn(4, '\x1f');
+    let short = fields.next().unwrap_or_default().to_string();
+    let author = fields.next().unwrap_or_default().to_string();
+    let date: String = fields.next().unwrap_or_default().chars().take(16).collect();
+    let message = fields.next().unwrap_or_default().trim().to_string();
+    let subject = message.lines().next().unwrap_or_default().to_string();
+
+    println!("{}", format!("{}  {}  {}", short, author, date).dimmed());
+    println!("{}", subject.bold());
+    println!();
+
+    if let Some(existing) = notes::show(&sha) {
+        println!("{}", format!("📎 已有說明（{}）：", notes::NOTES_REF).blue());
+        println!("{}", existing);
+        println!();
+        let regenerate = Confirm::with_theme(plain::theme())
+            .with_prompt("重新產生說明？")
+            .default(false)
+            .interact()?;
+        if !regenerate {
+            return Ok(());
+        }
+    }
+
+    let diff = git(&["show", "--format=", "--no-color", "--no-prefix", &sha])?;
+    let stats = git(&["show", "--format=", "--stat", "--no-color", &sha])?;
+    let diff_preview: String = diff.chars().take(MAX_DIFF_CHARS).collect();
+    let config = load_llm_config();
+    let prompt = config
+        .annotate_prompt
+        .replace("{sha}", &short)
+        .replace("{subject}", &subject)
+        .replace("{message}", &message)
+        .replace("{stats}", stats.trim())
+        .replace("{diff}", &diff_preview)
+        .replace("{language}", &config.language);
+
+    let mut timeline = Timeline::new();
+    timeline.start("生成說明");
+    let explanation = match crate::call_llm_cli(&prompt, &config) {
+        Ok(response) if !response.trim().is_empty() => {
+            timeline.finish();
+            response.trim().to_string()
+        }
+        Ok(_) => {
+            timeline.fail();
+            anyhow::bail!("LLM 沒有回應內容");
+        }
+        Err(e) => {
+            timeline.fail();
+            return Err(e.context("無法生成說明"));
+        }
+    };
+
+    println!();
+    println!("{}", explanation);
+    println!();
+
+    let save = args.note
+        || Confirm::with_theme(plain::theme())
+            .with_prompt(format!("存為 git note（{}）？", notes::NOTES_REF))
+            .default(false)
+            .interact()?;
+    if save {
+        notes::write(&sha, &explanation)?;
+        println!("{}", format!("✓ 已存為 {} 的 note", short).green());
+        println!("{}", format!("  查看：git log --notes={} {}", notes::NOTES_REF, short).dimmed());
+    }
+    println!();
+    Ok(())
+}
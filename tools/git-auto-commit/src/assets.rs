@@ -0,0 +1,237 @@
+use git2::Repository;
+use git_tools_core::git::file_blobs;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp", "ico", "svg"];
+const FONT_EXTENSIONS: &[&str] = &["ttf", "otf", "woff", "woff2", "eot"];
+
+static SVG_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?s)<svg\b[^>]*>").unwrap());
+static SVG_WIDTH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\swidth=["']([\d.]+)(?:px)?["']"#).unwrap());
+static SVG_HEIGHT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"\sheight=["']([\d.]+)(?:px)?["']"#).unwrap());
+static SVG_VIEWBOX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"viewBox=["'][\d.-]+[\s,]+[\d.-]+[\s,]+([\d.]+)[\s,]+([\d.]+)["']"#).unwrap());
+
+/// 單一圖片、字型等靜態資源的變化
+struct AssetChange {
+    path: String,
+    old_size: Option<usize>,
+    new_size: Option<usize>,
+    old_dimensions: Option<(u32, u32)>,
+    new_dimensions: Option<(u32, u32)>,
+}
+
+/// staged 變更中的靜態資源
+pub struct AssetSummary {
+    /// 所有 staged 檔案是否都是靜態資源
+    pub assets_only: bool,
+    changes: Vec<AssetChange>,
+}
+
+/// 是否為圖片或字型檔
+pub fn is_asset(path: &str) -> bool {
+    let extension = Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    extension.is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.as_str()) || FONT_EXTENSIONS.contains(&e.as_str()))
+}
+
+fn u16_be(data: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_be_bytes(data.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn u16_le(data: &[u8], at: usize) -> Option<u32> {
+    Some(u16::from_le_bytes(data.get(at..at + 2)?.try_into().ok()?) as u32)
+}
+
+fn u24_le(data: &[u8], at: usize) -> Option<u32> {
+    let bytes = data.get(at..at + 3)?;
+    Some(bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16)
+}
+
+fn u32_be(data: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn i32_le(data: &[u8], at: usize) -> Option<i32> {
+    Some(i32::from_le_bytes(data.get(at..at + 4)?.try_into().ok()?))
+}
+
+/// 逐一掃描 JPEG 的區段，從 SOF 區段取得尺寸
+fn jpeg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let mut i = 2;
+    while i + 9 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            i += 1;
+            continue;
+        }
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            return Some((u16_be(data, i + 7)?, u16_be(data, i + 5)?));
+        }
+        i += 2 + u16_be(data, i + 2)? as usize;
+    }
+    None
+}
+
+fn webp_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    match data.get(12..16)? {
+        b"VP8 " => Some((u16_le(data, 26)? & 0x3FFF, u16_le(data, 28)? & 0x3FFF)),
+        b"VP8L" => {
+            let b = data.get(21..25)?;
+            let width = 1 + (b[0] as u32 | (b[1] as u32 & 0x3F) << 8);
+            let height = 1 + ((b[1] as u32) >> 6 | (b[2] as u32) << 2 | (b[3] as u32 & 0x0F) << 10);
+            Some((width, height))
+        }
+        b"VP8X" => Some((1 + u24_le(data, 24)?, 1 + u24_le(data, 27)?)),
+        _ => None,
+    }
+}
+
+fn svg_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    let text = String::from_utf8_lossy(data);
+    let tag = SVG_TAG.find(&text)?.as_str();
+    let number = |value: &str| value.parse::<f64>().ok().map(|v| v.round() as u32);
+    if let (Some(width), Some(height)) = (SVG_WIDTH.captures(tag), SVG_HEIGHT.captures(tag)) {
+        return Some((number(&width[1])?, number(&height[1])?));
+    }
+    let caps = SVG_VIEWBOX.captures(tag)?;
+    Some((number(&caps[1])?, number(&caps[2])?))
+}
+
+/// 從檔頭讀取圖片尺寸（寬, 高），不支援的格式回傳 None
+fn dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some((u32_be(data, 16)?, u32_be(data, 20)?))
+    } else if data.starts_with(b"GIF8") {
+        Some((u16_le(data, 6)?, u16_le(data, 8)?))
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        jpeg_dimensions(data)
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        webp_dimensions(data)
+    } else if data.starts_with(b"BM") {
+        Some((i32_le(data, 18)?.unsigned_abs(), i32_le(data, 22)?.unsigned_abs()))
+    } else if data.starts_with(&[0, 0, 1, 0]) {
+        // ICO 的尺寸以 0 表示 256
+        let size = |b: u8| if b == 0 { 256 } else { b as u32 };
+        Some((size(*data.get(6)?), size(*data.get(7)?)))
+    } else {
+        svg_dimensions(data)
+    }
+}
+
+/// 以易讀的單位顯示檔案大小
+fn format_size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{} B", bytes)
+    } else if bytes < 1024 * 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1024.0 / 1024.0)
+    }
+}
+
+fn format_dimensions((width, height): (u32, u32)) -> String {
+    format!("{}×{}", width, height)
+}
+
+impl AssetChange {
+    /// 大小與尺寸的變化，例如「12.3 KB → 8.1 KB（-34%），512×512 → 256×256」
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match (self.old_size, self.new_size) {
+            (Some(old), Some(new)) => {
+                let percent = if old == 0 {
+                    String::new()
+                } else {
+                    format!("（{:+.0}%）", (new as f64 - old as f64) / old as f64 * 100.0)
+                };
+                parts.push(format!("{} → {}{}", format_size(old), format_size(new), percent));
+            }
+            (None, Some(new)) => parts.push(format!("新增，{}", format_size(new))),
+            (Some(old), None) => parts.push(format!("刪除，{}", format_size(old))),
+            (None, None) => {}
+        }
+        match (self.old_dimensions, self.new_dimensions) {
+            (Some(old), Some(new)) if old != new => {
+                parts.push(format!("{} → {}", format_dimensions(old), format_dimensions(new)))
+            }
+            (_, Some(dimensions)) | (Some(dimensions), None) => parts.push(format_dimensions(dimensions)),
+            (None, None) => {}
+        }
+        parts.join("，")
+    }
+}
+
+/// 找出 staged 的圖片與字型，比較 HEAD 與 staged 版本的大小與尺寸；沒有靜態資源時回傳 None
+pub fn analyze(repo: &Repository, files: &[String]) -> Option<AssetSummary> {
+    let changes: Vec<AssetChange> = files
+        .iter()
+        .filter(|f| is_asset(f))
+        .map(|path| {
+            let (old, new) = file_blobs(repo, path);
+            AssetChange {
+                path: path.clone(),
+                old_size: old.as_ref().map(Vec::len),
+                new_size: new.as_ref().map(Vec::len),
+                old_dimensions: old.as_deref().and_then(dimensions),
+                new_dimensions: new.as_deref().and_then(dimensions),
+            }
+        })
+        .collect();
+    if changes.is_empty() {
+        return None;
+    }
+
+    Some(AssetSummary {
+        assets_only: changes.len() == files.len(),
+        changes,
+    })
+}
+
+impl AssetSummary {
+    /// 單一檔案的變化說明（顯示在 staged 檔案列表）
+    pub fn describe(&self, path: &str) -> Option<String> {
+        let change = self.changes.iter().find(|c| c.path == path)?;
+        let description = change.describe();
+        (!description.is_empty()).then_some(description)
+    }
+
+    /// 提供給提示詞的摘要
+    pub fn prompt_note(&self) -> String {
+        let mut lines = vec!["這次變更包含圖片或字型等靜態資源（diff 中看不到內容），大小與尺寸的變化如下：".to_string()];
+        for change in &self.changes {
+            lines.push(format!("- {}：{}", change.path, change.describe()));
+        }
+        if self.assets_only {
+            lines.push("變更只涉及靜態資源，commit 類型請使用 chore(assets)；若是調整視覺樣式可使用 style".to_string());
+        }
+        lines.join("\n")
+    }
+
+    /// 只變更靜態資源時的備用訊息，例如「chore(assets): update logo.png」
+    pub fn fallback_message(&self) -> String {
+        let names: Vec<&str> = self
+            .changes
+            .iter()
+            .map(|c| Path::new(&c.path).file_name().and_then(|n| n.to_str()).unwrap_or(&c.path))
+            .collect();
+        let verb = if self.changes.iter().all(|c| c.old_size.is_none()) {
+            "add"
+        } else if self.changes.iter().all(|c| c.new_size.is_none()) {
+            "remove"
+        } else {
+            "update"
+        };
+        match names.as_slice() {
+            [name] => format!("chore(assets): {} {}", verb, name),
+            [first, second] => format!("chore(assets): {} {} and {}", verb, first, second),
+            _ => format!("chore(assets): {} {} assets", verb, names.len()),
+        }
+    }
+}
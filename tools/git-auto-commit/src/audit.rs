@@ -0,0 +1,144 @@
+use crate::config::{get_config_dir, AuditConfig, LlmConfig};
+use anyhow::{Context, Result};
+use chrono::Local;
+use git2::Repository;
+use serde::Serialize;
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 稽核紀錄中的 repository 識別資訊
+#[derive(Serialize)]
+struct RepoIdentity {
+    path: Option<String>,
+    remote: Option<String>,
+    branch: Option<String>,
+    head: Option<String>,
+}
+
+/// 單筆稽核紀錄（JSON Lines 格式，一行一筆）
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp: String,
+    repo: RepoIdentity,
+    command: &'a str,
+    model: &'a str,
+    prompt: &'a str,
+    response: Option<&'a str>,
+    error: Option<String>,
+}
+
+/// 取得稽核紀錄檔路徑
+fn get_audit_path(audit: &AuditConfig) -> PathBuf {
+    match &audit.path {
+        Some(path) => PathBuf::from(path),
+        None => get_config_dir().join("audit.log"),
+    }
+}
+
+/// 取得當前 repository 的識別資訊（路徑、origin、分支與 HEAD）
+fn get_repo_identity() -> RepoIdentity {
+    let repo = env::current_dir()
+        .ok()
+        .and_then(|dir| Repository::discover(dir).ok());
+
+    let Some(repo) = repo else {
+        return RepoIdentity {
+            path: None,
+            remote: None,
+            branch: None,
+            head: None,
+        };
+    };
+
+    let path = repo
+        .workdir()
+        .unwrap_or_else(|| repo.path())
+        .display()
+        .to_string();
+    let remote = repo
+        .find_remote("origin")
+        .ok()
+        .and_then(|r| r.url().map(|u| u.to_string()));
+    let head = repo.head().ok();
+    let branch = head
+        .as_ref()
+        .and_then(|h| h.shorthand().map(|s| s.to_string()));
+    let head = head
+        .and_then(|h| h.target())
+        .map(|oid| oid.to_string());
+
+    RepoIdentity {
+        path: Some(path),
+        remote,
+        branch,
+        head,
+    }
+}
+
+/// 輪替紀錄檔：audit.log → audit.log.1 → ... → audit.log.N（超過 N 的刪除）
+fn rotate_if_needed(path: &Path, audit: &AuditConfig) -> Result<()> {
+    let size = match fs::metadata(path) {
+        Ok(meta) => meta.len(),
+        Err(_) => return Ok(()),
+    };
+
+    if size < audit.max_size_kb * 1024 {
+        return Ok(());
+    }
+
+    let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+
+    if audit.max_files == 0 {
+        fs::remove_file(path).context("無法移除稽核紀錄檔")?;
+        return Ok(());
+    }
+
+    let oldest = rotated(audit.max_files);
+    if oldest.exists() {
+        fs::remove_file(&oldest).context("無法移除最舊的稽核紀錄檔")?;
+    }
+    for n in (1..audit.max_files).rev() {
+        let from = rotated(n);
+        if from.exists() {
+            fs::rename(&from, rotated(n + 1)).context("無法輪替稽核紀錄檔")?;
+        }
+    }
+    fs::rename(path, rotated(1)).context("無法輪替稽核紀錄檔")?;
+
+    Ok(())
+}
+
+/// 將一組提示詞與回應附加到稽核紀錄檔
+pub fn record(config: &LlmConfig, prompt: &str, result: &Result<String>) -> Result<()> {
+    let path = get_audit_path(&config.audit);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("無法建立稽核紀錄目錄")?;
+    }
+
+    rotate_if_needed(&path, &config.audit)?;
+
+    let entry = AuditEntry {
+        timestamp: Local::now().to_rfc3339(),
+        repo: get_repo_identity(),
+        command: &config.command,
+        model: &config.model,
+        prompt,
+        response: result.as_ref().ok().map(|r| r.as_str()),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+
+    let mut line = serde_json::to_string(&entry).context("無法序列化稽核紀錄")?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!("無法開啟稽核紀錄檔：{}", path.display()))?;
+    file.write_all(line.as_bytes())
+        .context("無法寫入稽核紀錄")?;
+
+    Ok(())
+}
@@ -0,0 +1,179 @@
+use crate::config::BitbucketConfig;
+use crate::forge::RemoteRepo;
+use crate::github::{Issue, PullRequest, PullRequestRef};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::env;
+
+#[derive(Debug, Deserialize)]
+struct Page {
+    values: Vec<BitbucketPullRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketPullRequest {
+    id: u64,
+    links: Links,
+    destination: Endpoint,
+}
+
+#[derive(Debug, Deserialize)]
+struct Links {
+    html: Link,
+}
+
+#[derive(Debug, Deserialize)]
+struct Link {
+    href: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Endpoint {
+    branch: Branch,
+}
+
+#[derive(Debug, Deserialize)]
+struct Branch {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BitbucketIssue {
+    id: u64,
+    title: String,
+    state: String,
+    links: Links,
+}
+
+impl From<BitbucketPullRequest> for PullRequest {
+    fn from(pr: BitbucketPullRequest) -> Self {
+        PullRequest {
+            number: pr.id,
+            html_url: pr.links.html.href,
+            base: PullRequestRef {
+                name: pr.destination.branch.name,
+            },
+        }
+    }
+}
+
+impl From<BitbucketIssue> for Issue {
+    fn from(issue: BitbucketIssue) -> Self {
+        Issue {
+            number: issue.id,
+            title: issue.title,
+            html_url: issue.links.html.href,
+            state: issue.state,
+        }
+    }
+}
+
+/// Bitbucket Cloud REST API 用戶端（token 取自 BITBUCKET_TOKEN 環境變數，可使用 repository 或 workspace access token）
+pub struct Client {
+    api_url: String,
+    token: String,
+    /// workspace 與 repository slug
+    repo: RemoteRepo,
+}
+
+impl Client {
+    pub fn new(config: &BitbucketConfig, repo: RemoteRepo) -> Result<Self> {
+        let token = env::var("BITBUCKET_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .context("未設定 BITBUCKET_TOKEN 環境變數")?;
+        Ok(Self {
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            token,
+            repo,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/repositories/{}/{}{}",
+            self.api_url, self.repo.owner, self.repo.name, path
+        )
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        ureq::request(method, url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/json")
+            .set("User-Agent", "git-auto-commit")
+    }
+
+    /// 查詢指定分支目前開啟中的 pull request
+    pub fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        let query = format!("source.branch.name=\"{}\" AND state=\"OPEN\"", branch);
+        let page: Page = self
+            .request("GET", &self.url("/pullrequests"))
+            .query("q", &query)
+            .call()
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 Bitbucket 回應")?;
+        Ok(page.values.into_iter().next().map(PullRequest::from))
+    }
+
+    /// 建立 pull request
+    pub fn create_pull_request(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<PullRequest> {
+        let pr: BitbucketPullRequest = self
+            .request("POST", &self.url("/pullrequests"))
+            .send_json(json!({
+                "title": title,
+                "description": body,
+                "source": { "branch": { "name": head } },
+                "destination": { "branch": { "name": base } },
+            }))
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 Bitbucket 回應")?;
+        Ok(pr.into())
+    }
+
+    /// 修改 pull request 的目標分支
+    pub fn update_base(&self, number: u64, base: &str) -> Result<PullRequest> {
+        let pr: BitbucketPullRequest = self
+            .request("PUT", &self.url(&format!("/pullrequests/{}", number)))
+            .send_json(json!({ "destination": { "branch": { "name": base } } }))
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 Bitbucket 回應")?;
+        Ok(pr.into())
+    }
+
+    /// 查詢 issue（不存在或 repository 未啟用 issue tracker 時回傳 None）
+    pub fn find_issue(&self, number: u64) -> Result<Option<Issue>> {
+        match self.request("GET", &self.url(&format!("/issues/{}", number))).call() {
+            Ok(response) => {
+                let issue: BitbucketIssue = response.into_json().context("無法解析 Bitbucket 回應")?;
+                Ok(Some(issue.into()))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(api_error(e)),
+        }
+    }
+}
+
+/// 將 ureq 錯誤轉為包含 Bitbucket 錯誤訊息的錯誤
+fn api_error(error: ureq::Error) -> anyhow::Error {
+    match error {
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v.pointer("/error/message").and_then(|m| m.as_str()).map(String::from))
+                .unwrap_or(body);
+            anyhow::anyhow!("Bitbucket API 錯誤（{}）：{}", code, message)
+        }
+        ureq::Error::Transport(e) => anyhow::anyhow!("無法連線到 Bitbucket：{}", e),
+    }
+}
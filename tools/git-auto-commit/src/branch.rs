@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use chrono::Local;
+use git2::{BranchType, ConfigLevel, Repository};
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// 已存在的同名分支
+#[derive(Debug, Clone)]
+pub struct BranchCollision {
+    /// 本地是否已有同名分支
+    pub local: bool,
+    /// 已有同名分支的 remote（例如 origin）
+    pub remotes: Vec<String>,
+}
+
+/// 取得所有 remote 名稱
+fn remote_names(repo: &Repository) -> Vec<String> {
+    repo.remotes()
+        .map(|names| names.iter().flatten().map(|n| n.to_string()).collect())
+        .unwrap_or_default()
+}
+
+/// 檢查分支名稱是否已存在於本地或任何 remote（依據本地的 remote-tracking 參照）
+pub fn find_collision(repo: &Repository, name: &str) -> Option<BranchCollision> {
+    let local = repo.find_branch(name, BranchType::Local).is_ok();
+    let remotes: Vec<String> = remote_names(repo)
+        .into_iter()
+        .filter(|remote| {
+            repo.find_reference(&format!("refs/remotes/{}/{}", remote, name))
+                .is_ok()
+        })
+        .collect();
+
+    if local || !remotes.is_empty() {
+        Some(BranchCollision { local, remotes })
+    } else {
+        None
+    }
+}
+
+/// 將用途說明寫入 branch.<name>.description（git branch --edit-description 使用的設定）
+pub fn set_description(repo: &Repository, name: &str, description: &str) -> Result<()> {
+    let mut config = repo
+        .config()
+        .and_then(|config| config.open_level(ConfigLevel::Local))
+        .context("無法開啟 repository 設定")?;
+    config
+        .set_str(&format!("branch.{}.description", name), description)
+        .context("無法寫入分支說明")?;
+    Ok(())
+}
+
+/// 記錄 plan 子指令擬定的 commit 標題草稿（branch.<name>.autoCommitDraft，刪除分支時一併移除）
+pub fn set_draft_commit(repo: &Repository, name: &str, title: &str) -> Result<()> {
+    let mut config = repo
+        .config()
+        .and_then(|config| config.open_level(ConfigLevel::Local))
+        .context("無法開啟 repository 設定")?;
+    config
+        .set_str(&format!("branch.{}.autoCommitDraft", name), title)
+        .context("無法寫入 commit 標題草稿")?;
+    Ok(())
+}
+
+/// 分支的 commit 標題草稿（未以 plan 子指令建立時為 None）
+pub fn draft_commit(repo: &Repository, name: &str) -> Option<String> {
+    let config = repo.config().ok()?;
+    config
+        .get_string(&format!("branch.{}.autoCommitDraft", name))
+        .ok()
+        .filter(|title| !title.trim().is_empty())
+}
+
+/// 移除分支的 commit 標題草稿（已用於 commit 後）
+pub fn clear_draft_commit(repo: &Repository, name: &str) {
+    if let Ok(mut config) = repo.config().and_then(|config| config.open_level(ConfigLevel::Local)) {
+        let _ = config.remove(&format!("branch.{}.autoCommitDraft", name));
+    }
+}
+
+/// 取得本地與 remote 上所有分支的名稱（remote 分支去除 remote 前綴，重複者只保留一個）
+pub fn existing_branch_names(repo: &Repository) -> Vec<String> {
+    let remotes = remote_names(repo);
+    let mut names: Vec<String> = Vec::new();
+
+    let Ok(branches) = repo.branches(None) else {
+        return names;
+    };
+    for (branch, branch_type) in branches.flatten() {
+        let Ok(Some(name)) = branch.name() else {
+            continue;
+        };
+        let name = match branch_type {
+            BranchType::Local => name.to_string(),
+            BranchType::Remote => {
+                let stripped = remotes
+                    .iter()
+                    .find_map(|remote| name.strip_prefix(&format!("{}/", remote)));
+                match stripped {
+                    Some("HEAD") | None => continue,
+                    Some(stripped) => stripped.to_string(),
+                }
+            }
+        };
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    names
+}
+
+/// 結尾的日期或數字後綴（例如 -20240101、-2024-01-01、-2）
+static DATE_SUFFIX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"([-_](\d{4}[-_]?\d{2}[-_]?\d{2}|\d{1,3}))+$").expect("有效的正規表達式")
+});
+
+/// 去除名稱結尾的日期或數字後綴，用於比對相似分支
+fn strip_date_suffix(name: &str) -> &str {
+    match DATE_SUFFIX.find(name) {
+        Some(m) => &name[..m.start()],
+        None => name,
+    }
+}
+
+/// 檢查分支名稱是否與現有分支相同，或只有日期、數字後綴不同
+pub fn duplicates_existing(name: &str, existing: &[String]) -> bool {
+    let normalized = strip_date_suffix(name);
+    existing
+        .iter()
+        .any(|e| e == name || strip_date_suffix(e) == normalized)
+}
+
+/// 找出第一個未被使用的數字後綴名稱（例如 feature/login-2）
+pub fn next_available_name(repo: &Repository, name: &str) -> String {
+    (2..)
+        .map(|n| format!("{}-{}", name, n))
+        .find(|candidate| find_collision(repo, candidate).is_none())
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// 分支名稱模板可用的變數
+pub struct TemplateVars {
+    /// 使用者名稱（由 git 設定的 user.name 或 email 轉換）
+    pub user: Option<String>,
+    /// 偵測到的 ticket 編號（例如 PROJ-123）
+    pub ticket: Option<String>,
+    /// 當前日期（YYYYMMDD）
+    pub date: String,
+}
+
+impl TemplateVars {
+    /// 收集模板變數：ticket 依序取自指定值、當前分支名稱
+    pub fn detect(
+        repo: &Repository,
+        current_branch: &str,
+        ticket: Option<&str>,
+        ticket_pattern: &str,
+    ) -> Self {
+        let ticket = ticket.map(|t| t.to_string()).or_else(|| {
+            Regex::new(ticket_pattern)
+                .ok()
+                .and_then(|re| re.find(current_branch).map(|m| m.as_str().to_string()))
+        });
+
+        Self {
+            user: detect_user(repo),
+            ticket,
+            date: Local::now().format("%Y%m%d").to_string(),
+        }
+    }
+}
+
+/// 轉為分支名稱可用的格式：小寫英數字，其他字元以連字號取代
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_matches('-').to_string()
+}
+
+/// 由 git 設定取得使用者名稱；user.name 無法轉為英數字時（例如中文姓名）改用 email 的帳號部分
+fn detect_user(repo: &Repository) -> Option<String> {
+    let config = repo.config().ok()?;
+    config
+        .get_string("user.name")
+        .ok()
+        .map(|name| slugify(&name))
+        .filter(|slug| !slug.is_empty())
+        .or_else(|| {
+            config
+                .get_string("user.email")
+                .ok()
+                .and_then(|email| email.split('@').next().map(slugify))
+                .filter(|slug| !slug.is_empty())
+        })
+}
+
+/// 依模板產生分支名稱，`suggestion` 為 LLM 建議的「type/description」
+///
+/// 支援變數：`{type}`、`{slug}`、`{ticket}`、`{user}`、`{date}`。
+/// 沒有值的變數會被移除，並清理多餘的分隔符號。
+pub fn apply_template(template: &str, suggestion: &str, vars: &TemplateVars) -> String {
+    let (branch_type, slug) = match suggestion.split_once('/') {
+        Some((branch_type, slug)) => (branch_type, slug),
+        None => ("", suggestion),
+    };
+
+    let mut name = template
+        .replace("{type}", branch_type)
+        .replace("{slug}", slug)
+        .replace("{ticket}", vars.ticket.as_deref().unwrap_or(""))
+        .replace("{user}", vars.user.as_deref().unwrap_or(""))
+        .replace("{date}", &vars.date);
+
+    // 清理因變數為空而產生的連續或開頭、結尾的分隔符號
+    loop {
+        let cleaned = name
+            .replace("//", "/")
+            .replace("--", "-")
+            .replace("/-", "/")
+            .replace("-/", "/")
+            .replace("_/", "/")
+            .replace("/_", "/");
+        if cleaned == name {
+            break;
+        }
+        name = cleaned;
+    }
+
+    name.trim_matches(|c| c == '/' || c == '-' || c == '_').to_string()
+}
@@ -0,0 +1,240 @@
+use crate::config::{ChangelogCommit, ChangelogConfig, ChangelogFormat};
+use crate::{plain, CommitSummary};
+use anyhow::{Context, Result};
+use colored::*;
+use console::Term;
+use dialoguer::Input;
+use git2::Repository;
+use git_tools_core::git::git_in;
+use std::fs;
+use std::path::Path;
+
+/// 新建 CHANGELOG 時的標題
+const TITLE: &str = "# Changelog";
+/// 版本區段的標題前綴（Keep a Changelog 格式，例如 ## [1.2.0] - 2024-01-01）
+const RELEASE_PREFIX: &str = "## ";
+/// 分類小節的標題前綴（例如 ### Added）
+const SECTION_PREFIX: &str = "### ";
+
+/// 標題拆成 commit 類型與項目內容（有 scope 時保留為「scope: 描述」）
+fn entry(subject: &str) -> Option<(String, String)> {
+    let (head, description) = subject.split_once(':')?;
+    let head = head.trim_end_matches('!');
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, scope)) => (kind, Some(scope.trim_end_matches(')'))),
+        None => (head, None),
+    };
+    let description = description.trim();
+    if kind.is_empty() || description.is_empty() {
+        return None;
+    }
+    let text = match scope.filter(|scope| !scope.is_empty()) {
+        Some(scope) => format!("{}: {}", scope, description),
+        None => description.to_string(),
+    };
+    Some((kind.to_string(), text))
+}
+
+/// Keep a Changelog 的分類
+fn section(kind: &str) -> &'static str {
+    match kind {
+        "feat" => "Added",
+        "fix" => "Fixed",
+        _ => "Changed",
+    }
+}
+
+/// towncrier 的片段類型
+fn fragment_type(kind: &str) -> &'static str {
+    match kind {
+        "feat" => "feature",
+        "fix" => "bugfix",
+        _ => "misc",
+    }
+}
+
+/// 在 Unreleased 區段的分類小節最後加入項目；沒有 Unreleased 區段或小節時建立
+fn insert(content: &str, heading: &str, item: &str) -> String {
+    let mut lines: Vec<String> = if content.trim().is_empty() {
+        vec![TITLE.to_string()]
+    } else {
+        content.lines().map(String::from).collect()
+    };
+    let is_release = |line: &str| line.starts_with(RELEASE_PREFIX);
+
+    let start = match lines
+        .iter()
+        .position(|line| is_release(line) && line.to_lowercase().contains("unreleased"))
+    {
+        Some(index) => index,
+        None => {
+            // 放在檔案開頭的標題與說明之後、第一個版本之前
+            let mut at = lines.iter().position(|line| is_release(line)).unwrap_or(lines.len());
+            while at > 0 && lines[at - 1].trim().is_empty() {
+                at -= 1;
+            }
+            lines.splice(at..at, [String::new(), "## [Unreleased]".to_string()]);
+            at + 1
+        }
+    };
+    let end = lines[start + 1..]
+        .iter()
+        .position(|line| is_release(line))
+        .map_or(lines.len(), |offset| start + 1 + offset);
+
+    let heading = format!("{}{}", SECTION_PREFIX, heading);
+    match lines[start + 1..end].iter().position(|line| line.trim() == heading) {
+        Some(offset) => {
+            // 小節中最後一個非空白行之後
+            let at_heading = start + 1 + offset;
+            let last = (at_heading + 1..end)
+                .take_while(|&i| !lines[i].starts_with(SECTION_PREFIX))
+                .filter(|&i| !lines[i].trim().is_empty())
+                .last();
+            match last {
+                Some(last) => lines.insert(last + 1, item.to_string()),
+                None => {
+                    lines.splice(at_heading + 1..at_heading + 1, [String::new(), item.to_string()]);
+                }
+            }
+        }
+        None => {
+            let mut at = end;
+            while at > start + 1 && lines[at - 1].trim().is_empty() {
+                at -= 1;
+            }
+            lines.splice(at..at, [String::new(), heading, String::new(), item.to_string()]);
+            if lines.get(at + 4).is_some_and(|line| !line.trim().is_empty()) {
+                lines.insert(at + 4, String::new());
+            }
+        }
+    }
+    format!("{}\n", lines.join("\n").trim_end())
+}
+
+/// 寫入項目，回傳變更的檔案（相對於 repository 根目錄）
+fn write(workdir: &Path, config: &ChangelogConfig, kind: &str, item: &str, sha: &str) -> Result<String> {
+    match config.format {
+        ChangelogFormat::File => {
+            let path = workdir.join(&config.file);
+            let content = fs::read_to_string(&path).unwrap_or_default();
+            let updated = insert(&content, section(kind), &format!("- {}", item));
+            fs::write(&path, updated).with_context(|| format!("無法寫入 {}", path.display()))?;
+            Ok(config.file.clone())
+        }
+        ChangelogFormat::Fragment => {
+            // 沒有 issue 編號時 towncrier 以 + 開頭表示不連結 issue 的片段
+            let name = format!("+{}.{}.md", &sha[..sha.len().min(7)], fragment_type(kind));
+            let dir = workdir.join(&config.fragment_dir);
+            fs::create_dir_all(&dir).with_context(|| format!("無法建立 {}", dir.display()))?;
+            fs::write(dir.join(&name), format!("{}\n", item)).with_context(|| format!("無法寫入 {}", name))?;
+            Ok(format!("{}/{}", config.fragment_dir.trim_end_matches('/'), name))
+        }
+    }
+}
+
+/// commit 類型在設定的 types 中時，在 CHANGELOG 加入對應的項目（在終端機中可先修改或清空略過），
+/// 依設定併入剛建立的 commit 或另外建立 commit；併入時更新 summary 的 SHA
+pub fn offer(repo: &Repository, config: &ChangelogConfig, summary: &mut CommitSummary) -> Result<()> {
+    let Some((kind, text)) = entry(&summary.subject) else {
+        return Ok(());
+    };
+    if !config.types.contains(&kind) {
+        return Ok(());
+    }
+    let workdir = repo.workdir().context("bare repository 無法更新 CHANGELOG")?;
+    // CHANGELOG 已有未 commit 的修改時不動它，避免把使用者的修改一併 commit
+    if config.format == ChangelogFormat::File && !git_in(workdir, &["status", "--porcelain", "--", &config.file])?.is_empty() {
+        println!("{}", format!("⚠️  {} 有未 commit 的修改，略過 CHANGELOG 更新", config.file).yellow());
+        return Ok(());
+    }
+
+    println!();
+    let text = if Term::stderr().is_term() {
+        Input::<String>::with_theme(plain::theme())
+            .with_prompt("📰 CHANGELOG 項目（清空則略過）")
+            .with_initial_text(&text)
+            .allow_empty(true)
+            .interact_text()?
+            .trim()
+            .to_string()
+    } else {
+        text
+    };
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    let path = write(workdir, config, &kind, &text, &summary.sha)?;
+    git_in(workdir, &["add", "--", &path])?;
+    match config.commit {
+        ChangelogCommit::Amend => {
+            // 只併入 CHANGELOG，其他仍在 staging area 的變更維持不變；hooks 已在原本的 commit 時執行過
+            git_in(workdir, &["commit", "--amend", "--no-edit", "--no-verify", "--quiet", "--", &path])?;
+            summary.sha = git_in(workdir, &["rev-parse", "HEAD"])?.trim().to_string();
+            summary.files.push(path.clone());
+            println!("{}", format!("📰 已將 {} 的項目併入 commit {}", path, &summary.sha[..7]).green());
+        }
+        ChangelogCommit::Separate => {
+            let message = format!("docs(changelog): {}", text);
+            git_in(workdir, &["commit", "--quiet", "-m", &message, "--", &path])?;
+            let sha = git_in(workdir, &["rev-parse", "--short", "HEAD"])?.trim().to_string();
+            println!("{}", format!("📰 已在 {} 加入項目並建立 commit {}", path, sha).green());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_keeps_scope_in_text() {
+        assert_eq!(entry("feat(auth): add login"), Some(("feat".to_string(), "auth: add login".to_string())));
+        assert_eq!(entry("fix!: drop legacy flag"), Some(("fix".to_string(), "drop legacy flag".to_string())));
+        assert_eq!(entry("refactor(): tidy up"), Some(("refactor".to_string(), "tidy up".to_string())));
+        assert_eq!(entry("update readme"), None);
+        assert_eq!(entry("feat: "), None);
+    }
+
+    #[test]
+    fn section_and_fragment_type_map_kinds() {
+        assert_eq!(section("feat"), "Added");
+        assert_eq!(section("perf"), "Changed");
+        assert_eq!(fragment_type("fix"), "bugfix");
+        assert_eq!(fragment_type("docs"), "misc");
+    }
+
+    #[test]
+    fn insert_creates_changelog() {
+        assert_eq!(insert("", "Added", "- login"), "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- login\n");
+    }
+
+    #[test]
+    fn insert_adds_unreleased_before_first_release() {
+        let content = "# Changelog\n\nAll notable changes.\n\n## [0.1.0] - 2024-01-01\n\n### Added\n\n- init\n";
+        assert_eq!(
+            insert(content, "Fixed", "- crash"),
+            "# Changelog\n\nAll notable changes.\n\n## [Unreleased]\n\n### Fixed\n\n- crash\n\n## [0.1.0] - 2024-01-01\n\n### Added\n\n- init\n"
+        );
+    }
+
+    #[test]
+    fn insert_appends_to_existing_section() {
+        let content = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- a\n\n### Fixed\n\n- b\n\n## [0.1.0]\n";
+        assert_eq!(
+            insert(content, "Added", "- c"),
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- a\n- c\n\n### Fixed\n\n- b\n\n## [0.1.0]\n"
+        );
+    }
+
+    #[test]
+    fn insert_adds_section_at_end_of_unreleased() {
+        let content = "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- a\n\n## [0.1.0]\n";
+        assert_eq!(
+            insert(content, "Fixed", "- b"),
+            "# Changelog\n\n## [Unreleased]\n\n### Added\n\n- a\n\n### Fixed\n\n- b\n\n## [0.1.0]\n"
+        );
+    }
+}
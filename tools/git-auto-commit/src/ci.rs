@@ -0,0 +1,245 @@
+use colored::*;
+use git2::Repository;
+use git_tools_core::git::file_versions;
+use regex::Regex;
+use serde_yaml::Value;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// GitLab CI 中不是 job 的頂層關鍵字
+const GITLAB_KEYWORDS: &[&str] = &[
+    "stages",
+    "variables",
+    "default",
+    "include",
+    "workflow",
+    "image",
+    "services",
+    "before_script",
+    "after_script",
+    "cache",
+];
+
+static JENKINS_STAGE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"stage\s*\(\s*['"]([^'"]+)['"]"#).unwrap());
+
+/// job 名稱與其步驟
+type Jobs = Vec<(String, Vec<String>)>;
+
+/// 單一 CI 設定檔中 job 與步驟的增減
+struct FileSummary {
+    path: String,
+    added_jobs: Vec<String>,
+    removed_jobs: Vec<String>,
+    /// （job, 步驟）
+    added_steps: Vec<(String, String)>,
+    removed_steps: Vec<(String, String)>,
+}
+
+impl FileSummary {
+    fn is_empty(&self) -> bool {
+        self.added_jobs.is_empty()
+            && self.removed_jobs.is_empty()
+            && self.added_steps.is_empty()
+            && self.removed_steps.is_empty()
+    }
+}
+
+/// staged 變更中 CI 設定（GitHub Actions、GitLab CI、Jenkins）的摘要
+pub struct CiSummary {
+    /// 所有 staged 檔案是否都是 CI 設定
+    pub ci_only: bool,
+    files: Vec<FileSummary>,
+}
+
+/// 是否為 CI 設定檔
+pub fn is_ci_file(path: &str) -> bool {
+    let file_name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("");
+    (path.starts_with(".github/workflows/") && (file_name.ends_with(".yml") || file_name.ends_with(".yaml")))
+        || file_name == ".gitlab-ci.yml"
+        || file_name == "Jenkinsfile"
+}
+
+/// 步驟的顯示名稱：name，否則 uses，否則 run 的第一行
+fn step_label(step: &Value) -> Option<String> {
+    ["name", "uses", "run"]
+        .iter()
+        .find_map(|key| step.get(key).and_then(Value::as_str))
+        .and_then(|label| label.lines().next())
+        .map(|label| label.trim().to_string())
+}
+
+fn github_jobs(content: &str) -> Jobs {
+    let Ok(workflow) = serde_yaml::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    let Some(jobs) = workflow.get("jobs").and_then(Value::as_mapping) else {
+        return Vec::new();
+    };
+    jobs.iter()
+        .filter_map(|(name, job)| {
+            let steps = job
+                .get("steps")
+                .and_then(Value::as_sequence)
+                .map(|steps| steps.iter().filter_map(step_label).collect())
+                .unwrap_or_default();
+            Some((name.as_str()?.to_string(), steps))
+        })
+        .collect()
+}
+
+fn gitlab_jobs(content: &str) -> Jobs {
+    let Ok(Value::Mapping(config)) = serde_yaml::from_str::<Value>(content) else {
+        return Vec::new();
+    };
+    config
+        .iter()
+        .filter_map(|(name, job)| {
+            let name = name.as_str()?;
+            // 以 . 開頭的是模板，不是實際執行的 job
+            if GITLAB_KEYWORDS.contains(&name) || name.starts_with('.') || !job.is_mapping() {
+                return None;
+            }
+            let steps = match job.get("script") {
+                Some(Value::Sequence(lines)) => lines.iter().filter_map(|l| l.as_str().map(String::from)).collect(),
+                Some(Value::String(line)) => vec![line.clone()],
+                _ => Vec::new(),
+            };
+            Some((name.to_string(), steps))
+        })
+        .collect()
+}
+
+fn jenkins_stages(content: &str) -> Jobs {
+    JENKINS_STAGE
+        .captures_iter(content)
+        .map(|caps| (caps[1].to_string(), Vec::new()))
+        .collect()
+}
+
+fn jobs(path: &str, content: &str) -> Jobs {
+    match Path::new(path).file_name().and_then(|n| n.to_str()) {
+        Some(".gitlab-ci.yml") => gitlab_jobs(content),
+        Some("Jenkinsfile") => jenkins_stages(content),
+        _ => github_jobs(content),
+    }
+}
+
+fn summarize(path: &str, before: &Jobs, after: &Jobs) -> FileSummary {
+    let mut summary = FileSummary {
+        path: path.to_string(),
+        added_jobs: Vec::new(),
+        removed_jobs: Vec::new(),
+        added_steps: Vec::new(),
+        removed_steps: Vec::new(),
+    };
+
+    for (name, steps) in after {
+        match before.iter().find(|(n, _)| n == name) {
+            None => summary.added_jobs.push(name.clone()),
+            Some((_, old_steps)) => {
+                for step in steps.iter().filter(|s| !old_steps.contains(s)) {
+                    summary.added_steps.push((name.clone(), step.clone()));
+                }
+                for step in old_steps.iter().filter(|s| !steps.contains(s)) {
+                    summary.removed_steps.push((name.clone(), step.clone()));
+                }
+            }
+        }
+    }
+    for (name, _) in before {
+        if !after.iter().any(|(n, _)| n == name) {
+            summary.removed_jobs.push(name.clone());
+        }
+    }
+    summary
+}
+
+/// 比對 staged 的 CI 設定，找出新增與移除的 job 和步驟；沒有 CI 設定檔時回傳 None
+pub fn analyze(repo: &Repository, files: &[String]) -> Option<CiSummary> {
+    let ci_files: Vec<&String> = files.iter().filter(|f| is_ci_file(f)).collect();
+    if ci_files.is_empty() {
+        return None;
+    }
+
+    let summaries = ci_files
+        .iter()
+        .map(|path| {
+            let (old, new) = file_versions(repo, path);
+            summarize(path, &jobs(path, &old), &jobs(path, &new))
+        })
+        .collect();
+
+    Some(CiSummary {
+        ci_only: ci_files.len() == files.len(),
+        files: summaries,
+    })
+}
+
+impl CiSummary {
+    /// 顯示 job 與步驟的增減
+    pub fn print(&self) {
+        println!("{}", "⚙️  偵測到 CI 設定變更：".blue());
+        for file in &self.files {
+            println!("  {}", file.path.bold());
+            if file.is_empty() {
+                println!("    {}", "（job 與步驟沒有增減）".dimmed());
+            }
+            for job in &file.added_jobs {
+                println!("    {} job {}", "+".green(), job);
+            }
+            for job in &file.removed_jobs {
+                println!("    {} job {}", "-".red(), job);
+            }
+            for (job, step) in &file.added_steps {
+                println!("    {} {} › {}", "+".green(), job.dimmed(), step);
+            }
+            for (job, step) in &file.removed_steps {
+                println!("    {} {} › {}", "-".red(), job.dimmed(), step);
+            }
+        }
+    }
+
+    /// 提供給提示詞的摘要
+    pub fn prompt_note(&self) -> String {
+        let mut lines = vec!["這次變更包含 CI 設定，job 與步驟的增減如下：".to_string()];
+        for file in &self.files {
+            lines.push(format!("- {}", file.path));
+            if file.is_empty() {
+                lines.push("  - （job 與步驟沒有增減，只修改設定內容）".to_string());
+            }
+            if !file.added_jobs.is_empty() {
+                lines.push(format!("  - 新增 job：{}", file.added_jobs.join(", ")));
+            }
+            if !file.removed_jobs.is_empty() {
+                lines.push(format!("  - 移除 job：{}", file.removed_jobs.join(", ")));
+            }
+            for (job, step) in &file.added_steps {
+                lines.push(format!("  - 新增步驟（{}）：{}", job, step));
+            }
+            for (job, step) in &file.removed_steps {
+                lines.push(format!("  - 移除步驟（{}）：{}", job, step));
+            }
+        }
+        if self.ci_only {
+            lines.push("變更只涉及 CI 設定，commit 類型請使用 ci，並在描述中說明 job 或步驟的變化".to_string());
+        }
+        lines.join("\n")
+    }
+}
+
+/// 將 Conventional Commits 標題的類型換成 ci（保留 scope 與 !）
+pub fn with_ci_type(message: &str) -> String {
+    let Some((prefix, rest)) = message.split_once(':') else {
+        return message.to_string();
+    };
+    if prefix.contains('\n') {
+        return message.to_string();
+    }
+    let type_end = prefix.find(['(', '!']).unwrap_or(prefix.len());
+    let commit_type = &prefix[..type_end];
+    if commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return message.to_string();
+    }
+    format!("ci{}:{}", &prefix[type_end..], rest)
+}
@@ -0,0 +1,281 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Git 自動 Commit 工具：透過 LLM 根據 staged 變更產生 commit 訊息與分支名稱建議
+#[derive(Debug, Parser)]
+#[command(name = "git-auto-commit", version)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Commands>,
+
+    /// 顯示詳細資訊（例如各步驟耗時摘要）
+    #[arg(short, long)]
+    pub verbose: bool,
+
+    /// 純文字模式：不使用 emoji、框線字元與顏色，改用 [ok]、[!] 等 ASCII 標記（適合螢幕閱讀器與字型支援有限的終端機）
+    #[arg(long, global = true)]
+    pub plain: bool,
+
+    /// 快速模式：留在目前分支（workflow 允許時）並使用第一個建議，預覽後只需確認一次；
+    /// 其餘選單（作者身分、相依套件分組、評分、後續動作等）皆採預設選項
+    #[arg(short, long)]
+    pub quick: bool,
+
+    /// 離線模式：不呼叫 LLM 與任何網路服務，改依變更的檔案與 diff 內容以規則產生建議
+    #[arg(long, conflicts_with_all = ["rewrite", "describe", "compare_prompts"])]
+    pub offline: bool,
+
+    /// 嚴格模式：staged 變更新增了 TODO、FIXME、HACK 等待辦註記時拒絕 commit
+    #[arg(long)]
+    pub strict: bool,
+
+    /// 可重現模式：temperature 固定為 0 並使用固定的 seed（未設定 seed 時為 0），
+    /// 方便測試提示詞的修改或在 CI 中產生訊息；需設定 temperature_flag 與 seed_flag
+    #[arg(long)]
+    pub reproducible: bool,
+
+    /// 以兩個提示詞模板（TOML，需包含 combined_prompt）分別生成建議並排比較，並記錄較好的一組
+    #[arg(long, num_args = 2, value_names = ["A", "B"])]
+    pub compare_prompts: Option<Vec<PathBuf>>,
+
+    /// 設定 commit 的作者與 committer 時間（例如 "yesterday 18:00"、"2024-01-01 10:00"）
+    #[arg(long)]
+    pub date: Option<String>,
+
+    /// 不依 diff 生成建議，改由 LLM 將自己的描述（任何語言）改寫為符合格式的 commit 訊息；
+    /// 未提供描述時會詢問
+    #[arg(long, value_name = "描述", num_args = 0..=1, default_missing_value = "", conflicts_with = "compare_prompts")]
+    pub rewrite: Option<String>,
+
+    /// 用自己的話補充這次變更的目的（例如設定值調整的業務原因），與 diff 一起送給 LLM；
+    /// 未提供內容時會詢問
+    #[arg(long, value_name = "說明", num_args = 0..=1, default_missing_value = "", conflicts_with = "rewrite")]
+    pub describe: Option<String>,
+
+    /// 同 git commit -a：先將所有已追蹤檔案的修改與刪除加入 staged（不包含未追蹤的檔案）
+    #[arg(short, long)]
+    pub all: bool,
+
+    /// 只 commit 符合 pathspec 的 staged 變更（可指定多個），建議也只依這些變更產生；
+    /// 其餘 staged 的檔案在 commit 期間暫時移出，完成後恢復
+    #[arg(long, value_name = "PATHSPEC", num_args = 1..)]
+    pub only: Vec<String>,
+
+    /// 只掃描工作目錄中符合 pathspec 的路徑（-a 與未追蹤檔案的偵測），加快大型 repository 的啟動；
+    /// 不影響 commit 的內容，要限制 commit 的檔案請用 --only
+    #[arg(long, value_name = "PATHSPEC", num_args = 1..)]
+    pub paths: Vec<String>,
+
+    /// 不生成訊息，直接為指定的 commit 建立 fixup! commit（同 git commit --fixup），之後以 git rebase --autosquash 併入
+    #[arg(long, value_name = "COMMIT", conflicts_with_all = ["squash", "rewrite", "describe", "compare_prompts", "export_patch"])]
+    pub fixup: Option<String>,
+
+    /// 不生成訊息，直接為指定的 commit 建立 squash! commit（同 git commit --squash），可附上併入時的說明
+    #[arg(long, value_name = "COMMIT", conflicts_with_all = ["rewrite", "describe", "compare_prompts", "export_patch"])]
+    pub squash: Option<String>,
+
+    /// 不建立 commit，改將 staged 變更匯出為 git format-patch 格式的檔案（以選定的訊息為標題與內文），
+    /// 供以郵件或上傳 patch 的流程使用；未指定目錄時寫入目前目錄
+    #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = ".")]
+    pub export_patch: Option<PathBuf>,
+
+    /// commit 訊息與 PR 說明使用的語言（例如 English），會記在此 repository，之後未指定時沿用
+    #[arg(long)]
+    pub language: Option<String>,
+
+    /// 指定 ticket 編號，用於分支名稱模板中的 {ticket}
+    #[arg(long)]
+    pub ticket: Option<String>,
+
+    /// 輸出逐行的機器可讀事件（STAGED、SUGGESTION、RESULT 等），供 GUI 等外部程式使用；
+    /// 未指定路徑時寫入 stderr，也可指定檔案或 FIFO
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "-")]
+    pub porcelain: Option<String>,
+}
+
+/// 子指令（未指定時執行 commit 流程）
+#[derive(Debug, Subcommand)]
+pub enum Commands {
+    /// 顯示當前分支所在的堆疊，並為每一層建立 base 正確的 GitHub PR
+    Stack(StackArgs),
+    /// 執行設定精靈（選擇 LLM CLI、測試連線、語言與工作流程），並寫入設定檔
+    Setup,
+    /// 檢查 git、repository、設定檔、LLM 連線與 hooks，並列出修正方式
+    Doctor,
+    /// 檢查 GitHub releases，下載此平台的最新版本並在驗證 SHA-256 後取代目前的執行檔
+    SelfUpdate(SelfUpdateArgs),
+    /// 整理指定時間以來自己的 commit，由 LLM 產生站立會議（standup）用的工作摘要
+    Summary(SummaryArgs),
+    /// 顯示歷史紀錄的統計
+    Stats(StatsArgs),
+    /// 只建立分支：依工作描述或 staged 變更建議分支名稱，建立並切換（不 commit）
+    Branch(BranchArgs),
+    /// 開始寫程式前依工作描述建議分支名稱與 commit 標題草稿，立即建立分支；草稿在之後 commit 時作為建議
+    Plan(PlanArgs),
+    /// 依上一個 tag 以來的 commit 推斷版本號，並由 LLM 撰寫重點，建立 annotated tag
+    Tag(TagArgs),
+    /// 以描述進行中工作的訊息 stash 目前的變更；list 可搜尋並還原 stash
+    Stash(StashArgs),
+    /// 分析分支上的 commit，建議 fixup、調整順序與改寫籠統的訊息，確認後執行 git rebase -i
+    RebasePlan(RebasePlanArgs),
+    /// 由 LLM 依 diff 說明指定的 commit 改了什麼（適合 bisect 與追查歷史），可存為 git note
+    Annotate(AnnotateArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct StackArgs {
+    /// 主幹分支（預設沿用上次指定的分支，否則依 origin/HEAD 判斷，再否則使用 main 或 master）
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// 推送分支與建立 PR 使用的 remote
+    #[arg(long, default_value = "origin")]
+    pub remote: String,
+
+    /// 只顯示堆疊，不建立 PR
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SelfUpdateArgs {
+    /// 只檢查是否有新版本，不下載
+    #[arg(long)]
+    pub check: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct SummaryArgs {
+    /// 要整理的 repository 路徑（可指定多個，預設為當前目錄）
+    pub repos: Vec<PathBuf>,
+
+    /// 起始時間（例如 "9am"、"yesterday 18:00"、"2 days ago"，預設為今天 00:00；--weekly 時為本週一 00:00）
+    #[arg(long)]
+    pub since: Option<String>,
+
+    /// 結束時間（格式同 --since，預設為現在）
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// 產生依專案分組的週報
+    #[arg(long)]
+    pub weekly: bool,
+
+    /// 一併納入尚未 commit 的變更（git diff HEAD），描述進行中的工作
+    #[arg(long)]
+    pub uncommitted: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct PlanArgs {
+    /// 要進行的工作描述（任何語言皆可）；未提供時會詢問
+    pub description: Option<String>,
+
+    /// 指定 ticket 編號，用於分支名稱模板中的 {ticket}
+    #[arg(long)]
+    pub ticket: Option<String>,
+
+    /// 不呼叫 LLM，由描述以規則產生分支名稱與 commit 標題
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct BranchArgs {
+    /// 要進行的工作描述（任何語言皆可）；未提供時依 staged 變更建議，沒有 staged 變更時會詢問
+    pub description: Option<String>,
+
+    /// 指定 ticket 編號，用於分支名稱模板中的 {ticket}
+    #[arg(long)]
+    pub ticket: Option<String>,
+
+    /// 不呼叫 LLM，改由描述或檔案內容以規則產生分支名稱
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct TagArgs {
+    /// 指定版本升級的層級（預設依 commit 類型推斷：不相容的變更為 major、feat 為 minor、其餘為 patch）
+    #[arg(long, value_enum)]
+    pub bump: Option<BumpLevel>,
+
+    /// 不呼叫 LLM，改依 commit 類型分組列出標題作為 tag 訊息
+    #[arg(long)]
+    pub offline: bool,
+
+    /// 建立後推送 tag 到 origin
+    #[arg(long)]
+    pub push: bool,
+
+    /// 不更新 Cargo.toml、package.json 等版本檔案（預設依 version_files 設定更新並建立 release commit）
+    #[arg(long)]
+    pub no_version_files: bool,
+}
+
+/// 版本升級的層級
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum BumpLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+#[derive(Debug, Args)]
+pub struct StashArgs {
+    #[command(subcommand)]
+    pub action: Option<StashAction>,
+
+    /// 一併 stash 未追蹤的檔案
+    #[arg(short = 'u', long)]
+    pub include_untracked: bool,
+
+    /// 不呼叫 LLM，以變更的檔案名稱作為訊息
+    #[arg(long)]
+    pub offline: bool,
+}
+
+/// stash 的動作（未指定時 stash 目前的變更）
+#[derive(Debug, Subcommand)]
+pub enum StashAction {
+    /// 依關鍵字搜尋 stash 的說明與檔案路徑，選擇後可套用、檢視或刪除
+    List {
+        /// 搜尋關鍵字（未提供時會詢問）
+        query: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct RebasePlanArgs {
+    /// 比較的 base 分支（預設沿用上次指定的分支，否則依 origin/HEAD 判斷，再否則使用 main 或 master）
+    #[arg(long)]
+    pub base: Option<String>,
+
+    /// 不呼叫 LLM，只整理 fixup 與 WIP commit，籠統的訊息僅標示
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct AnnotateArgs {
+    /// 要說明的 commit（預設為 HEAD）
+    #[arg(default_value = "HEAD")]
+    pub commit: String,
+
+    /// 不詢問，直接將說明存為 git note（refs/notes/auto-commit）
+    #[arg(long)]
+    pub note: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    #[command(subcommand)]
+    pub view: StatsView,
+}
+
+/// 統計的項目
+#[derive(Debug, Subcommand)]
+pub enum StatsView {
+    /// 依提示詞模板與模型彙整建議的評分與直接採用率
+    Prompts,
+}
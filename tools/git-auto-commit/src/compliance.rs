@@ -0,0 +1,44 @@
+use crate::config::ComplianceConfig;
+use colored::*;
+use regex::Regex;
+
+/// 禁止出現在 commit 訊息與分支名稱中的字詞（例如內部代號、客戶名稱）
+#[derive(Default)]
+pub struct Filter {
+    rules: Vec<Regex>,
+}
+
+impl Filter {
+    /// 依設定建立規則：banned_words 不分大小寫比對子字串，banned_patterns 為正規表示式
+    pub fn new(config: &ComplianceConfig) -> Self {
+        let words = config
+            .banned_words
+            .iter()
+            .filter(|word| !word.is_empty())
+            .map(|word| format!("(?i){}", regex::escape(word)));
+        let patterns = config.banned_patterns.iter().cloned();
+
+        let mut rules = Vec::new();
+        for pattern in words.chain(patterns) {
+            match Regex::new(&pattern) {
+                Ok(regex) => rules.push(regex),
+                Err(e) => println!("{}", format!("⚠️  banned_patterns 格式錯誤，已略過：{}（{}）", pattern, e).yellow()),
+            }
+        }
+        Self { rules }
+    }
+
+    /// 找出文字中禁止使用的內容（重複者只列一次）
+    pub fn violations(&self, text: &str) -> Vec<String> {
+        let mut found: Vec<String> = Vec::new();
+        for rule in &self.rules {
+            for m in rule.find_iter(text) {
+                let matched = m.as_str().to_string();
+                if !matched.is_empty() && !found.contains(&matched) {
+                    found.push(matched);
+                }
+            }
+        }
+        found
+    }
+}
@@ -0,0 +1,246 @@
+use crate::config::{LlmConfig, SplitStrictness};
+use crate::whitespace;
+use anyhow::Result;
+use colored::*;
+
+/// 送給 LLM 判斷的 diff 最多字元數
+const MAX_DIFF_CHARS: usize = 6000;
+
+/// 變更的類別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Code,
+    Docs,
+    Config,
+    /// 內容只有縮排、空白或空行的調整
+    Formatting,
+}
+
+impl Category {
+    fn label(self) -> &'static str {
+        match self {
+            Category::Code => "程式碼",
+            Category::Docs => "文件",
+            Category::Config => "設定",
+            Category::Formatting => "格式調整",
+        }
+    }
+}
+
+/// 依路徑判斷檔案的類別（不含格式調整）
+fn category_of(path: &str) -> Category {
+    let lower = path.to_lowercase();
+    let name = lower.rsplit('/').next().unwrap_or(&lower);
+    let extension = name.rsplit_once('.').map(|(_, ext)| ext).unwrap_or_default();
+    if name.starts_with("requirements")
+        || name.starts_with('.')
+        || lower.starts_with(".github/")
+        || matches!(name, "dockerfile" | "makefile")
+        || matches!(
+            extension,
+            "toml" | "yaml" | "yml" | "json" | "ini" | "cfg" | "conf" | "lock" | "xml" | "properties" | "env"
+        )
+    {
+        Category::Config
+    } else if lower.starts_with("docs/")
+        || lower.starts_with("doc/")
+        || name.starts_with("readme")
+        || name.starts_with("changelog")
+        || name.starts_with("license")
+        || matches!(extension, "md" | "mdx" | "rst" | "adoc" | "txt")
+    {
+        Category::Docs
+    } else {
+        Category::Code
+    }
+}
+
+/// 檔案所在的頂層目錄（根目錄的檔案為空字串）
+fn area_of(path: &str) -> &str {
+    path.split_once('/').map(|(dir, _)| dir).unwrap_or_default()
+}
+
+/// 一組相關的變更（拆分時成為一個 commit）
+pub struct Concern {
+    pub categories: Vec<Category>,
+    /// 頂層目錄（根目錄的檔案為空字串）
+    areas: Vec<String>,
+    pub files: Vec<String>,
+}
+
+impl Concern {
+    /// 例如「程式碼、設定（src/, tests/）」
+    pub fn label(&self) -> String {
+        let categories: Vec<&str> = self.categories.iter().map(|c| c.label()).collect();
+        let areas: Vec<String> = self
+            .areas
+            .iter()
+            .filter(|area| !area.is_empty())
+            .map(|area| format!("{}/", area))
+            .collect();
+        if areas.is_empty() {
+            categories.join("、")
+        } else {
+            format!("{}（{}）", categories.join("、"), areas.join(", "))
+        }
+    }
+
+    /// 只有格式調整
+    pub fn formatting_only(&self) -> bool {
+        self.categories == [Category::Formatting]
+    }
+
+    fn absorb(&mut self, other: Concern) {
+        self.categories.extend(other.categories);
+        self.categories.sort();
+        self.categories.dedup();
+        self.areas.extend(other.areas);
+        self.areas.sort();
+        self.areas.dedup();
+        self.files.extend(other.files);
+    }
+}
+
+/// 合併為一組
+fn merge(concerns: Vec<Concern>) -> Option<Concern> {
+    concerns.into_iter().reduce(|mut merged, concern| {
+        merged.absorb(concern);
+        merged
+    })
+}
+
+/// 同類別的組合併為一組
+fn merge_by_category(concerns: Vec<Concern>) -> Vec<Concern> {
+    let mut merged: Vec<Concern> = Vec::new();
+    for concern in concerns {
+        match merged.iter_mut().find(|m| m.categories == concern.categories) {
+            Some(existing) => existing.absorb(concern),
+            None => merged.push(concern),
+        }
+    }
+    merged
+}
+
+/// 中等嚴格程度：程式碼變更合併為一組，同一個目錄或根目錄的文件與設定視為其中一部分，格式調整另成一組
+fn merge_related(concerns: Vec<Concern>) -> Vec<Concern> {
+    let (formatting, rest): (Vec<Concern>, Vec<Concern>) = concerns.into_iter().partition(Concern::formatting_only);
+    let (code, others): (Vec<Concern>, Vec<Concern>) =
+        rest.into_iter().partition(|c| c.categories == [Category::Code]);
+
+    let mut merged = Vec::new();
+    let mut separate = Vec::new();
+    match merge(code) {
+        Some(mut code) => {
+            for concern in others {
+                let area = &concern.areas[0];
+                if area.is_empty() || code.areas.contains(area) {
+                    code.absorb(concern);
+                } else {
+                    separate.push(concern);
+                }
+            }
+            merged.push(code);
+        }
+        None => separate = others,
+    }
+    merged.extend(merge_by_category(separate));
+    merged.extend(merge(formatting));
+    merged
+}
+
+/// 依類別與頂層目錄將 staged 檔案分組，再依嚴格程度合併相關的組；多於一組時表示混合了多個關注點
+pub fn plan(files: &[String], strictness: SplitStrictness) -> Result<Vec<Concern>> {
+    let formatting = whitespace::formatting_only(files)?;
+    let mut concerns: Vec<Concern> = Vec::new();
+    for path in files {
+        let category = if formatting.contains(path) { Category::Formatting } else { category_of(path) };
+        let area = area_of(path);
+        match concerns.iter_mut().find(|c| c.categories == [category] && c.areas == [area]) {
+            Some(concern) => concern.files.push(path.clone()),
+            None => concerns.push(Concern {
+                categories: vec![category],
+                areas: vec![area.to_string()],
+                files: vec![path.clone()],
+            }),
+        }
+    }
+
+    Ok(match strictness {
+        SplitStrictness::Low => {
+            let (formatting, rest): (Vec<Concern>, Vec<Concern>) =
+                concerns.into_iter().partition(Concern::formatting_only);
+            merge(rest).into_iter().chain(merge(formatting)).collect()
+        }
+        SplitStrictness::Medium => merge_related(concerns),
+        SplitStrictness::High => {
+            concerns.sort_by(|a, b| (&a.categories, &a.areas).cmp(&(&b.categories, &b.areas)));
+            concerns
+        }
+    })
+}
+
+/// 顯示各組變更
+pub fn print(concerns: &[Concern], reason: Option<&str>) {
+    println!("{}", format!("✂️  staged 變更似乎混合了 {} 個關注點：", concerns.len()).yellow());
+    for concern in concerns {
+        println!("  {}", concern.label());
+        for file in &concern.files {
+            println!("    {}", file.dimmed());
+        }
+    }
+    if let Some(reason) = reason {
+        println!("{}", format!("  LLM：{}", reason).dimmed());
+    }
+    println!();
+}
+
+/// 只有格式調整的組使用的 commit 訊息（不需 LLM）
+pub fn formatting_message(concern: &Concern) -> String {
+    match concern.files.as_slice() {
+        [file] => format!("style: 調整 {} 的格式", file),
+        files => format!("style: 調整 {} 個檔案的格式", files.len()),
+    }
+}
+
+/// LLM 對各組變更是否相關的判斷
+pub enum Judgment {
+    /// 屬於同一個目的，附上理由
+    Related(String),
+    /// 彼此無關，附上理由
+    Separate(String),
+}
+
+/// 請 LLM 判斷各組變更是否屬於同一個目的，無法解析回應時回傳 None
+pub fn judge(concerns: &[Concern], diff: &str, config: &LlmConfig) -> Result<Option<Judgment>> {
+    let groups: Vec<String> = concerns
+        .iter()
+        .map(|concern| format!("- {}：{}", concern.label(), concern.files.join(", ")))
+        .collect();
+    let diff_preview: String = diff.chars().take(MAX_DIFF_CHARS).collect();
+    let prompt = config
+        .split
+        .prompt
+        .replace("{count}", &concerns.len().to_string())
+        .replace("{groups}", &groups.join("\n"))
+        .replace("{diff}", &diff_preview)
+        .replace("{language}", &config.language);
+
+    let response = crate::call_llm_cli(&prompt, config)?;
+    Ok(response.lines().find_map(|line| {
+        let line = line.trim().trim_start_matches(['*', '`']);
+        let reason = |verdict: &str| {
+            line[verdict.len()..]
+                .trim_start_matches(['*', '`'])
+                .trim_start_matches([':', '：', ' '])
+                .trim()
+                .to_string()
+        };
+        if line.starts_with("SEPARATE") {
+            Some(Judgment::Separate(reason("SEPARATE")))
+        } else if line.starts_with("RELATED") {
+            Some(Judgment::Related(reason("RELATED")))
+        } else {
+            None
+        }
+    }))
+}
@@ -0,0 +1,1974 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+
+/// LLM CLI 設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LlmConfig {
+    /// LLM CLI 指令（例如：gemini）
+    #[serde(default = "default_command")]
+    pub command: String,
+    /// 提示參數標記（例如：-p）
+    #[serde(default = "default_prompt_flag")]
+    pub prompt_flag: String,
+    /// 提示詞的傳遞方式：arg（接在 prompt_flag 之後）、stdin 或 file（寫入暫存檔，路徑可在 extra_args 中以 {prompt_file} 指定）
+    #[serde(default)]
+    pub prompt_via: PromptVia,
+    /// 模型參數標記（例如：--model）
+    #[serde(default = "default_model_flag")]
+    pub model_flag: String,
+    /// 模型名稱（例如：gemini-2.5-flash）
+    #[serde(default = "default_model")]
+    pub model: String,
+    /// 額外參數（例如：--yolo）
+    #[serde(default = "default_extra_args")]
+    pub extra_args: Vec<String>,
+    /// temperature 參數標記（例如：--temperature），空字串表示 LLM CLI 不支援，不傳遞 temperature
+    #[serde(default)]
+    pub temperature_flag: String,
+    /// temperature（未設定時使用 LLM CLI 的預設值）
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    /// seed 參數標記（例如：--seed），空字串表示 LLM CLI 不支援，不傳遞 seed
+    #[serde(default)]
+    pub seed_flag: String,
+    /// 固定的 seed（未設定時不傳遞）
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// commit 訊息與 PR 說明使用的語言（提示詞中的 {language}）
+    #[serde(default = "default_language")]
+    pub language: String,
+    /// 合併的提示詞模板
+    #[serde(default = "default_combined_prompt")]
+    pub combined_prompt: String,
+    /// 每次生成建議前都詢問使用者對變更的說明（同 --describe）
+    #[serde(default)]
+    pub ask_description: bool,
+    /// 提示詞確定後立即在背景呼叫 LLM，檢查關注點等步驟進行時同時生成建議
+    #[serde(default = "default_true")]
+    pub pregenerate: bool,
+    /// 選擇 commit 訊息後是否需要在預覽下方確認：always、custom-only（只有自訂訊息需要確認）或 never
+    #[serde(default)]
+    pub confirm: ConfirmMode,
+    /// 是否在 .git/auto-commit-state.toml 記住此 repository 上次的選擇（commit 類型、是否推送、語言、base 分支），作為下次的預設值
+    #[serde(default = "default_true")]
+    pub remember: bool,
+    /// commit 後為下一個任務建議分支名稱的提示詞模板
+    #[serde(default = "default_next_branch_prompt")]
+    pub next_branch_prompt: String,
+    /// branch 子指令依工作描述建議分支名稱的提示詞模板
+    #[serde(default = "default_branch_prompt")]
+    pub branch_prompt: String,
+    /// plan 子指令依工作描述建議分支名稱與 commit 標題草稿的提示詞模板
+    #[serde(default = "default_plan_prompt")]
+    pub plan_prompt: String,
+    /// stack 子指令生成 PR 標題與說明的提示詞模板
+    #[serde(default = "default_pr_prompt")]
+    pub pr_prompt: String,
+    /// tag 子指令撰寫 annotated tag 訊息的提示詞模板
+    #[serde(default = "default_tag_prompt")]
+    pub tag_prompt: String,
+    /// stash 子指令描述進行中工作的提示詞模板
+    #[serde(default = "default_stash_prompt")]
+    pub stash_prompt: String,
+    /// annotate 子指令說明 commit 的提示詞模板
+    #[serde(default = "default_annotate_prompt")]
+    pub annotate_prompt: String,
+    /// --rewrite 模式將使用者的描述改寫為 commit 訊息的提示詞模板
+    #[serde(default = "default_rewrite_prompt")]
+    pub rewrite_prompt: String,
+    /// summary 子指令產生工作摘要的提示詞模板
+    #[serde(default = "default_summary_prompt")]
+    pub summary_prompt: String,
+    /// summary --weekly 產生週報的提示詞模板
+    #[serde(default = "default_weekly_report_prompt")]
+    pub weekly_report_prompt: String,
+    /// 稽核紀錄設定
+    #[serde(default)]
+    pub audit: AuditConfig,
+    /// 各模型的價格表（美元 / 每百萬 tokens），設定的模型覆寫或加入內建的價格表
+    #[serde(default = "default_pricing", deserialize_with = "merge_pricing")]
+    pub pricing: HashMap<String, ModelPricing>,
+    /// 每日預算設定
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    /// 依 diff 大小自動選擇模型的設定
+    #[serde(default)]
+    pub model_selection: ModelSelectionConfig,
+    /// 同時以兩個模型生成建議的設定
+    #[serde(default)]
+    pub dual_model: DualModelConfig,
+    /// 建議內容檢查設定
+    #[serde(default)]
+    pub coverage: CoverageConfig,
+    /// 建議的排序與去除重複設定
+    #[serde(default)]
+    pub ranking: RankingConfig,
+    /// 啟動時同步 remote 的設定
+    #[serde(default)]
+    pub fetch: FetchConfig,
+    /// 可切換的作者身分（例如 [identities.work]）
+    #[serde(default)]
+    pub identities: BTreeMap<String, Identity>,
+    /// 分支名稱模板（例如 {ticket}/{type}-{slug}），未設定時使用 LLM 建議的 type/description
+    #[serde(default)]
+    pub branch_template: Option<String>,
+    /// 工作流程預設（git-flow、github-flow 或 trunk），影響分支前綴、base 分支與是否允許直接 commit 到主幹
+    #[serde(default)]
+    pub workflow: Option<Workflow>,
+    /// 偵測 ticket 編號的正規表達式
+    #[serde(default = "default_ticket_pattern")]
+    pub ticket_pattern: String,
+    /// lefthook / lint-staged 預先檢查設定
+    #[serde(default)]
+    pub preflight: PreflightConfig,
+    /// GitHub API 設定
+    #[serde(default)]
+    pub github: GitHubConfig,
+    /// Bitbucket Cloud API 設定（stack 子指令使用）
+    #[serde(default)]
+    pub bitbucket: BitbucketConfig,
+    /// GitLab API 設定（stack 子指令使用）
+    #[serde(default)]
+    pub gitlab: GitLabConfig,
+    /// 代管服務（GitHub、GitLab、Bitbucket、Gerrit）的偵測設定
+    #[serde(default)]
+    pub forge: ForgeConfig,
+    /// 新版本檢查設定
+    #[serde(default)]
+    pub update: UpdateConfig,
+    /// 外掛設定（PATH 上名為 git-auto-commit-<name> 的執行檔）
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+    /// Lua 腳本設定
+    #[serde(default)]
+    pub script: ScriptConfig,
+    /// session 紀錄設定（記錄每次執行建立的 commit）
+    #[serde(default)]
+    pub session: SessionConfig,
+    /// 分支用途說明設定（寫入 branch.<name>.description）
+    #[serde(default)]
+    pub branch_description: BranchDescriptionConfig,
+    /// commit 訊息的拼字與用語檢查
+    #[serde(default)]
+    pub terminology: TerminologyConfig,
+    /// 禁止出現在 commit 訊息與分支名稱中的字詞
+    #[serde(default)]
+    pub compliance: ComplianceConfig,
+    /// 相依套件更新的偵測設定
+    #[serde(default)]
+    pub deps: DepsConfig,
+    /// 資料庫 migration 的偵測設定
+    #[serde(default)]
+    pub migration: MigrationConfig,
+    /// 基礎設施設定變更的偵測設定
+    #[serde(default)]
+    pub infra: InfraConfig,
+    /// CI 設定變更的偵測設定
+    #[serde(default)]
+    pub ci: CiConfig,
+    /// Jupyter notebook 的 diff 設定
+    #[serde(default)]
+    pub notebook: NotebookConfig,
+    /// 靜態資源變更的設定
+    #[serde(default)]
+    pub assets: AssetsConfig,
+    /// 未追蹤與新增檔案的設定
+    #[serde(default)]
+    pub new_files: NewFilesConfig,
+    /// 有風險的變更（移除錯誤處理、停用測試等）的檢查設定
+    #[serde(default)]
+    pub risk: RiskConfig,
+    /// 新增的 TODO、FIXME 等待辦註記的追蹤設定
+    #[serde(default)]
+    pub todo: TodoConfig,
+    /// 新增的除錯程式碼（console.log、dbg! 等）的偵測設定
+    #[serde(default)]
+    pub debug_leftovers: DebugLeftoversConfig,
+    /// 新檔案的授權標頭檢查設定
+    #[serde(default)]
+    pub license_header: LicenseHeaderConfig,
+    /// 混合多個關注點的 staged 變更的拆分提示設定
+    #[serde(default)]
+    pub split: SplitConfig,
+    /// Gerrit 審查流程的設定
+    #[serde(default)]
+    pub gerrit: GerritConfig,
+    /// 自動加在每個 commit 訊息最後的 trailer（例如 [[trailers]] key = "Reviewed-by"）
+    #[serde(default)]
+    pub trailers: Vec<TrailerConfig>,
+    /// 合併 commit 的訊息設定
+    #[serde(default)]
+    pub merge: MergeConfig,
+    /// 建議的評分與 commit 歷史紀錄設定
+    #[serde(default)]
+    pub history: HistoryConfig,
+    /// 以 embeddings 搜尋相似的過去 commit 作為範例
+    #[serde(default)]
+    pub embeddings: EmbeddingsConfig,
+    /// 將 LLM 的完整分析存為 git note
+    #[serde(default)]
+    pub notes: NotesConfig,
+    /// LLM 審查筆記（邊界情況、測試缺口）
+    #[serde(default)]
+    pub review: ReviewConfig,
+    /// commit 後同步更新 CHANGELOG
+    #[serde(default)]
+    pub changelog: ChangelogConfig,
+    /// tag 子指令同步更新的版本檔案
+    #[serde(default)]
+    pub version_files: VersionFilesConfig,
+    /// tag 子指令建立 tag 前的檢查
+    #[serde(default)]
+    pub release_checks: ReleaseChecksConfig,
+}
+
+/// 相依套件更新的偵測設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DepsConfig {
+    /// 只變更相依套件檔案時，依版本變化直接產生 chore(deps) 訊息，不呼叫 LLM
+    #[serde(default = "default_true")]
+    pub detect: bool,
+}
+
+impl Default for DepsConfig {
+    fn default() -> Self {
+        Self { detect: true }
+    }
+}
+
+/// 資料庫 migration 的偵測設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MigrationConfig {
+    /// 偵測 staged 的 migration 檔案（diesel、sqlx、flyway），摘要 schema 操作、commit 前確認並加上 Migration: 註記
+    #[serde(default = "default_true")]
+    pub detect: bool,
+}
+
+impl Default for MigrationConfig {
+    fn default() -> Self {
+        Self { detect: true }
+    }
+}
+
+/// 基礎設施設定（Terraform、Kubernetes、Helm）變更的偵測設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct InfraConfig {
+    /// 偵測 *.tf、helm/、k8s/ 的變更，將資源層級的摘要加入提示詞
+    #[serde(default = "default_true")]
+    pub detect: bool,
+    /// 只變更基礎設施設定時，commit 標題使用的 scope（空字串表示不加）
+    #[serde(default = "default_infra_scope")]
+    pub scope: String,
+}
+
+fn default_infra_scope() -> String {
+    "infra".to_string()
+}
+
+impl Default for InfraConfig {
+    fn default() -> Self {
+        Self {
+            detect: true,
+            scope: default_infra_scope(),
+        }
+    }
+}
+
+/// CI 設定（GitHub Actions、GitLab CI、Jenkins）變更的偵測設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CiConfig {
+    /// 偵測 CI 設定的變更，將 job 與步驟的增減加入提示詞；只變更 CI 設定時使用 ci 類型
+    #[serde(default = "default_true")]
+    pub detect: bool,
+}
+
+impl Default for CiConfig {
+    fn default() -> Self {
+        Self { detect: true }
+    }
+}
+
+/// Jupyter notebook 的 diff 設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NotebookConfig {
+    /// 提示詞中的 .ipynb diff 只比對 cell 原始碼，去除輸出、執行次數與 metadata
+    #[serde(default = "default_true")]
+    pub strip_outputs: bool,
+}
+
+impl Default for NotebookConfig {
+    fn default() -> Self {
+        Self { strip_outputs: true }
+    }
+}
+
+/// 有風險的變更的檢查設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RiskConfig {
+    /// 依規則檢查 staged diff（移除錯誤處理、變更驗證或加解密程式碼、停用測試），在 commit 預覽中顯示風險摘要
+    #[serde(default = "default_true")]
+    pub detect: bool,
+}
+
+impl Default for RiskConfig {
+    fn default() -> Self {
+        Self { detect: true }
+    }
+}
+
+/// 待辦註記的追蹤設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TodoConfig {
+    /// 視為待辦註記的標記（區分大小寫，以完整單字比對），空陣列表示不追蹤
+    #[serde(default = "default_todo_markers")]
+    pub markers: Vec<String>,
+    /// commit 時在內文最後加上 TODO: 區塊，列出新增的待辦註記與位置
+    #[serde(default)]
+    pub append_section: bool,
+    /// 有新增的待辦註記時拒絕 commit（等同 --strict）
+    #[serde(default)]
+    pub strict: bool,
+}
+
+fn default_todo_markers() -> Vec<String> {
+    vec!["TODO".to_string(), "FIXME".to_string(), "HACK".to_string()]
+}
+
+impl Default for TodoConfig {
+    fn default() -> Self {
+        Self {
+            markers: default_todo_markers(),
+            append_section: false,
+            strict: false,
+        }
+    }
+}
+
+/// 除錯程式碼的偵測設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DebugLeftoversConfig {
+    /// 偵測 staged 變更新增的除錯程式碼，commit 前提醒並可檢視所在的 hunk
+    #[serde(default = "default_true")]
+    pub detect: bool,
+    /// 依語言（rust、javascript、go、python、ruby、php、java）或副檔名覆寫內建的正規表示式，空陣列表示不檢查
+    #[serde(default)]
+    pub patterns: BTreeMap<String, Vec<String>>,
+}
+
+impl Default for DebugLeftoversConfig {
+    fn default() -> Self {
+        Self {
+            detect: true,
+            patterns: BTreeMap::new(),
+        }
+    }
+}
+
+/// 新檔案的授權標頭檢查設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LicenseHeaderConfig {
+    /// 授權標頭的內容（不含註解符號，可用 {year}），未設定且沒有專案模板檔時不檢查
+    #[serde(default)]
+    pub template: Option<String>,
+    /// 專案模板檔的路徑（相對於 repository 根目錄），存在時優先於 template
+    #[serde(default = "default_license_project_template")]
+    pub project_template: String,
+    /// 檢查的副檔名
+    #[serde(default = "default_license_extensions")]
+    pub extensions: Vec<String>,
+}
+
+fn default_license_project_template() -> String {
+    ".license-header.txt".to_string()
+}
+
+fn default_license_extensions() -> Vec<String> {
+    [
+        "rs", "js", "jsx", "ts", "tsx", "mjs", "cjs", "go", "java", "kt", "swift", "c", "h", "cc", "cpp", "hpp", "cs",
+        "scala", "dart", "php", "py", "rb", "sh", "sql", "lua",
+    ]
+    .iter()
+    .map(|ext| ext.to_string())
+    .collect()
+}
+
+impl Default for LicenseHeaderConfig {
+    fn default() -> Self {
+        Self {
+            template: None,
+            project_template: default_license_project_template(),
+            extensions: default_license_extensions(),
+        }
+    }
+}
+
+/// 混合多個關注點的 staged 變更的處理方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitMode {
+    /// 不檢查
+    Off,
+    /// 只列出各組變更並提醒
+    Warn,
+    /// 詢問是否依關注點拆成多個 commit
+    #[default]
+    Offer,
+}
+
+/// 判斷是否混合多個關注點的嚴格程度
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitStrictness {
+    /// 只有格式調整混入其他變更時
+    Low,
+    /// 另外包含與程式碼不在同一個目錄的文件或設定變更時
+    #[default]
+    Medium,
+    /// 不同類別或不同頂層目錄的變更都視為不同的關注點
+    High,
+}
+
+/// 混合多個關注點（例如程式碼變更夾帶其他檔案的格式調整）的拆分提示設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SplitConfig {
+    /// 處理方式：off、warn 或 offer
+    #[serde(default)]
+    pub mode: SplitMode,
+    /// 判斷的嚴格程度：low、medium 或 high
+    #[serde(default)]
+    pub strictness: SplitStrictness,
+    /// 依檔案判斷為混合時，再請 LLM 確認各組變更是否彼此無關
+    #[serde(default = "default_true")]
+    pub llm_check: bool,
+    /// 請 LLM 判斷的提示詞模板
+    #[serde(default = "default_split_prompt")]
+    pub prompt: String,
+}
+
+fn default_split_prompt() -> String {
+    r#"以下 staged 變更依檔案類型與目錄分為 {count} 組：
+{groups}
+
+Diff 內容：
+{diff}
+
+請判斷這些變更是否屬於同一個目的（例如功能與其對應的測試、文件、設定屬於同一個目的；順手修改的格式或無關的文件則不是）。
+只回覆一行：RELATED（應放在同一個 commit）或 SEPARATE（應拆成多個 commit），後面接冒號與一句使用{language}的理由，例如：
+SEPARATE: 格式調整與功能變更無關"#
+        .to_string()
+}
+
+impl Default for SplitConfig {
+    fn default() -> Self {
+        Self {
+            mode: SplitMode::default(),
+            strictness: SplitStrictness::default(),
+            llm_check: true,
+            prompt: default_split_prompt(),
+        }
+    }
+}
+
+/// 圖片、字型等靜態資源變更的設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AssetsConfig {
+    /// 將靜態資源的大小與尺寸變化加入提示詞；只變更靜態資源時建議 chore(assets) 訊息
+    #[serde(default = "default_true")]
+    pub detect: bool,
+}
+
+impl Default for AssetsConfig {
+    fn default() -> Self {
+        Self { detect: true }
+    }
+}
+
+/// 未追蹤與新增檔案的設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NewFilesConfig {
+    /// 有未追蹤的檔案時列出，並詢問是否加入 staged（git add）或標記為 intent-to-add（git add -N）
+    #[serde(default = "default_true")]
+    pub ask_untracked: bool,
+    /// 將 staged 的新檔案內容另外加入提示詞（新檔案通常說明了這次 commit 的目的，且不受 diff 截斷影響）
+    #[serde(default = "default_true")]
+    pub include_content: bool,
+    /// 每個新檔案加入提示詞的最多字元數
+    #[serde(default = "default_new_file_max_chars")]
+    pub max_chars: usize,
+    /// 最多加入幾個新檔案的內容
+    #[serde(default = "default_new_file_max_files")]
+    pub max_files: usize,
+}
+
+fn default_new_file_max_chars() -> usize {
+    1500
+}
+
+fn default_new_file_max_files() -> usize {
+    5
+}
+
+impl Default for NewFilesConfig {
+    fn default() -> Self {
+        Self {
+            ask_untracked: true,
+            include_content: true,
+            max_chars: default_new_file_max_chars(),
+            max_files: default_new_file_max_files(),
+        }
+    }
+}
+
+/// 合併 commit 的訊息設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct MergeConfig {
+    /// 合併、rebase、cherry-pick 進行中時，依雙方的 commit 與衝突的解決方式產生訊息（不呼叫 LLM），取代 git 預設的訊息
+    #[serde(default = "default_true")]
+    pub detect: bool,
+    /// 逐一詢問每個衝突檔案的解決方式（可留空），寫入 commit 訊息
+    #[serde(default)]
+    pub ask_resolution: bool,
+}
+
+impl Default for MergeConfig {
+    fn default() -> Self {
+        Self {
+            detect: true,
+            ask_resolution: false,
+        }
+    }
+}
+
+/// 建議的評分與 commit 歷史紀錄設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HistoryConfig {
+    /// 將由 LLM 建議產生的 commit 訊息連同提示詞模板與模型寫入歷史紀錄
+    #[serde(default)]
+    pub enabled: bool,
+    /// commit 後詢問對建議的評分（1–5），可用 stats prompts 比較各模板與模型的表現
+    #[serde(default = "default_true")]
+    pub ask_rating: bool,
+    /// 紀錄檔路徑（預設：~/.config/git-auto-commit/history.jsonl）
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 提示詞附上此 repository 最近 N 則高評分的 commit 訊息作為範例（0 表示不附上）
+    #[serde(default)]
+    pub few_shot: usize,
+    /// 作為範例的最低評分（未評分的紀錄只在直接採用建議時列入）
+    #[serde(default = "default_few_shot_min_rating")]
+    pub few_shot_min_rating: u8,
+}
+
+fn default_few_shot_min_rating() -> u8 {
+    4
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            ask_rating: true,
+            path: None,
+            few_shot: 0,
+            few_shot_min_rating: default_few_shot_min_rating(),
+        }
+    }
+}
+
+/// 以 embeddings 搜尋相似的過去 commit 的設定（使用 OpenAI 相容的 /embeddings API）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct EmbeddingsConfig {
+    /// 為過去的 commit 建立向量索引，生成建議時將最相似的 commit 訊息作為範例加入提示詞
+    #[serde(default)]
+    pub enabled: bool,
+    /// API 位址（預設為本機的 Ollama；OpenAI 為 https://api.openai.com/v1）
+    #[serde(default = "default_embeddings_api_url")]
+    pub api_url: String,
+    /// embeddings 模型名稱
+    #[serde(default = "default_embeddings_model")]
+    pub model: String,
+    /// 存放 API key 的環境變數名稱（例如 OPENAI_API_KEY），未設定表示不需驗證
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// 加入提示詞的相似 commit 數量
+    #[serde(default = "default_embeddings_top_k")]
+    pub top_k: usize,
+    /// 建立索引時往回檢查的 commit 數量（從 HEAD 起算）
+    #[serde(default = "default_embeddings_index_limit")]
+    pub index_limit: usize,
+}
+
+fn default_embeddings_api_url() -> String {
+    "http://localhost:11434/v1".to_string()
+}
+
+fn default_embeddings_model() -> String {
+    "nomic-embed-text".to_string()
+}
+
+fn default_embeddings_top_k() -> usize {
+    3
+}
+
+fn default_embeddings_index_limit() -> usize {
+    500
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            api_url: default_embeddings_api_url(),
+            model: default_embeddings_model(),
+            api_key_env: None,
+            top_k: default_embeddings_top_k(),
+            index_limit: default_embeddings_index_limit(),
+        }
+    }
+}
+
+/// 完整分析的 git note 設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct NotesConfig {
+    /// 生成建議時一併請 LLM 撰寫詳細的變更說明與風險，commit 後存為 refs/notes/auto-commit 的 note
+    #[serde(default)]
+    pub enabled: bool,
+    /// 附加在生成建議提示詞最後的要求，可用變數：{language}
+    #[serde(default = "default_notes_prompt")]
+    pub prompt: String,
+}
+
+fn default_notes_prompt() -> String {
+    r#"另外，請在 [COMMITS] 區塊之後加上 [ANALYSIS] 區塊，使用{language}撰寫這次變更的完整分析（commit 訊息維持簡潔，詳細內容放在這裡）：
+1. 變更說明：逐項說明改了什麼、為什麼這樣改
+2. 風險與注意事項：可能影響的功能、相容性、需要額外測試或審查的地方；沒有時寫「無」
+
+[ANALYSIS]
+變更說明：
+- ...
+風險與注意事項：
+- ..."#
+        .to_string()
+}
+
+impl Default for NotesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prompt: default_notes_prompt(),
+        }
+    }
+}
+
+/// commit 後保存審查筆記的位置
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReviewSave {
+    /// 只在預覽中顯示
+    #[default]
+    None,
+    /// 附加到 file 指定的 Markdown 檔案
+    File,
+    /// 存為 refs/notes/auto-commit-review 的 git note
+    Note,
+}
+
+/// LLM 審查筆記的設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReviewConfig {
+    /// 生成建議時一併請 LLM 以審查者的角度列出邊界情況與測試缺口，顯示在預覽中
+    #[serde(default)]
+    pub enabled: bool,
+    /// commit 後保存審查筆記的位置：none、file 或 note
+    #[serde(default)]
+    pub save: ReviewSave,
+    /// save = "file" 時附加的檔案（相對於 repository 根目錄）
+    #[serde(default = "default_review_file")]
+    pub file: String,
+    /// 附加在生成建議提示詞最後的要求，可用變數：{language}
+    #[serde(default = "default_review_prompt")]
+    pub prompt: String,
+}
+
+fn default_review_file() -> String {
+    "REVIEW_NOTES.md".to_string()
+}
+
+fn default_review_prompt() -> String {
+    r#"另外，請在最後加上 [REVIEW] 區塊，以程式碼審查者的角度使用{language}列出這次變更值得再檢查的地方（每項一行，最多 5 項，沒有時寫「無」）：
+- 邊界情況：空值、錯誤處理、並行、相容性等可能遺漏的情況
+- 測試缺口：缺少或需要補充的測試
+
+[REVIEW]
+- ..."#
+        .to_string()
+}
+
+impl Default for ReviewConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            save: ReviewSave::None,
+            file: default_review_file(),
+            prompt: default_review_prompt(),
+        }
+    }
+}
+
+/// CHANGELOG 的格式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangelogFormat {
+    /// 在 file 的 Unreleased 區段加入項目（Keep a Changelog 格式）
+    #[default]
+    File,
+    /// 在 fragment_dir 建立片段檔案（towncrier 等工具於發布時合併）
+    Fragment,
+}
+
+/// CHANGELOG 變更的 commit 方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangelogCommit {
+    /// 併入剛建立的 commit（git commit --amend）
+    #[default]
+    Amend,
+    /// 另外建立一個 docs(changelog) commit
+    Separate,
+}
+
+/// commit 後同步更新 CHANGELOG 的設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ChangelogConfig {
+    /// commit 的類型在 types 中時，加入對應的 CHANGELOG 項目
+    #[serde(default)]
+    pub enabled: bool,
+    /// 要記錄的 commit 類型
+    #[serde(default = "default_changelog_types")]
+    pub types: Vec<String>,
+    /// 更新 file 或建立 fragment_dir 中的片段檔案
+    #[serde(default)]
+    pub format: ChangelogFormat,
+    /// format = "file" 時更新的檔案（相對於 repository 根目錄）
+    #[serde(default = "default_changelog_file")]
+    pub file: String,
+    /// format = "fragment" 時建立片段檔案的目錄（相對於 repository 根目錄）
+    #[serde(default = "default_changelog_fragment_dir")]
+    pub fragment_dir: String,
+    /// 併入剛建立的 commit（amend）或另外建立 commit（separate）
+    #[serde(default)]
+    pub commit: ChangelogCommit,
+}
+
+fn default_changelog_types() -> Vec<String> {
+    vec!["feat".to_string(), "fix".to_string()]
+}
+
+fn default_changelog_file() -> String {
+    "CHANGELOG.md".to_string()
+}
+
+fn default_changelog_fragment_dir() -> String {
+    "changelog.d".to_string()
+}
+
+impl Default for ChangelogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            types: default_changelog_types(),
+            format: ChangelogFormat::File,
+            file: default_changelog_file(),
+            fragment_dir: default_changelog_fragment_dir(),
+            commit: ChangelogCommit::Amend,
+        }
+    }
+}
+
+/// 記載版本號的檔案
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VersionFile {
+    /// 檔案路徑（相對於 repository 根目錄），不存在時略過
+    pub path: String,
+    /// 比對版本號的 regex，取代第一個符合處的第一個 capture group（沒有 group 時取代整個符合的文字）；
+    /// 同一個檔案可設定多個項目，依序套用
+    pub pattern: String,
+}
+
+/// tag 子指令同步更新版本檔案的設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct VersionFilesConfig {
+    /// 建立 tag 前將新版本號寫入版本檔案，並以 release commit 提交
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// release commit 的標題，可用變數：{version}、{tag}；內文為 tag 訊息的重點。
+    /// 不另外呼叫 LLM，標題只由此模板產生
+    #[serde(default = "default_release_message")]
+    pub message: String,
+    /// 要更新的檔案與比對版本號的 regex
+    #[serde(default = "default_version_files")]
+    pub files: Vec<VersionFile>,
+}
+
+fn default_release_message() -> String {
+    "chore(release): {version}".to_string()
+}
+
+/// TOML 中 table 內的 version 欄位：從 table 標頭開始，只跨過不以 [ 開頭的行（不會進入下一個 table）
+fn toml_table_version(table: &str) -> String {
+    format!(r#"(?m)^\[{}\][^\n]*\n(?:(?:[^\[\n][^\n]*)?\n)*?[ \t]*version\s*=\s*"([^"]+)""#, table)
+}
+
+fn default_version_files() -> Vec<VersionFile> {
+    // workspace 的根目錄 Cargo.toml 可能同時有 [package] 與 [workspace.package]，各自更新；
+    // [dependencies.<name>] 等 table 中的 version 不會被比對到
+    [
+        ("Cargo.toml", toml_table_version("package")),
+        ("Cargo.toml", toml_table_version(r"workspace\.package")),
+        ("package.json", r#""version"\s*:\s*"([^"]+)""#.to_string()),
+        ("pyproject.toml", toml_table_version("project")),
+        ("pyproject.toml", toml_table_version(r"tool\.poetry")),
+    ]
+    .into_iter()
+    .map(|(path, pattern)| VersionFile {
+        path: path.to_string(),
+        pattern,
+    })
+    .collect()
+}
+
+impl Default for VersionFilesConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            message: default_release_message(),
+            files: default_version_files(),
+        }
+    }
+}
+
+/// tag 子指令建立 tag 前的檢查，任一項未通過時中止
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ReleaseChecksConfig {
+    /// 驗證 HEAD 歷史中已簽署的 tag（git verify-tag）
+    #[serde(default = "default_true")]
+    pub verify_signatures: bool,
+    /// 工作目錄與 staging area 沒有未 commit 的變更（不含未追蹤的檔案）
+    #[serde(default = "default_true")]
+    pub require_clean: bool,
+    /// 目前分支沒有落後 upstream（先執行 git fetch）
+    #[serde(default = "default_true")]
+    pub require_up_to_date: bool,
+}
+
+impl Default for ReleaseChecksConfig {
+    fn default() -> Self {
+        Self {
+            verify_signatures: true,
+            require_clean: true,
+            require_up_to_date: true,
+        }
+    }
+}
+
+/// Gerrit 審查流程的設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GerritConfig {
+    /// 啟用 Gerrit 模式：加上 Change-Id、依 Gerrit 預設規則檢查訊息，commit 後詢問是否推送到 refs/for/<branch>
+    #[serde(default)]
+    pub enabled: bool,
+    /// 標題的建議長度上限（Gerrit 預設為 65）
+    #[serde(default = "default_gerrit_subject_max_length")]
+    pub subject_max_length: usize,
+    /// 內文每行的建議長度上限（Gerrit 預設為 70）
+    #[serde(default = "default_gerrit_body_max_line_length")]
+    pub body_max_line_length: usize,
+    /// 推送的 remote
+    #[serde(default = "default_gerrit_remote")]
+    pub remote: String,
+    /// 審查的目標分支（未設定時使用目前分支的 upstream，否則為目前分支）
+    #[serde(default)]
+    pub branch: Option<String>,
+}
+
+fn default_gerrit_subject_max_length() -> usize {
+    65
+}
+
+fn default_gerrit_body_max_line_length() -> usize {
+    70
+}
+
+fn default_gerrit_remote() -> String {
+    "origin".to_string()
+}
+
+impl Default for GerritConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            subject_max_length: default_gerrit_subject_max_length(),
+            body_max_line_length: default_gerrit_body_max_line_length(),
+            remote: default_gerrit_remote(),
+            branch: None,
+        }
+    }
+}
+
+/// 自動加入的 trailer：值依序取自 value、env、command，都沒有值時以 prompt 詢問，仍沒有值時不加入
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TrailerConfig {
+    /// trailer 名稱（例如 Reviewed-by、Ticket、Change-type）
+    pub key: String,
+    /// 固定值，可用變數 {ticket}、{user}、{branch}（變數沒有值時視為未設定）
+    #[serde(default)]
+    pub value: Option<String>,
+    /// 從環境變數取得值
+    #[serde(default)]
+    pub env: Option<String>,
+    /// 以 shell 指令的輸出（第一行）為值，例如 "git config user.email"
+    #[serde(default)]
+    pub command: Option<String>,
+    /// 以上都沒有值時詢問的提示文字（輸入空白時不加入）
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// 訊息已有同名 trailer 時的處理方式（git interpret-trailers 的 --if-exists：
+    /// addIfDifferentNeighbor、addIfDifferent、add、replace、doNothing），未設定時依 git 的 trailer.* 設定
+    #[serde(default)]
+    pub if_exists: Option<String>,
+}
+
+/// 禁用字詞設定（例如內部代號、不雅用語、客戶名稱）
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ComplianceConfig {
+    /// 禁止使用的字詞（不分大小寫）
+    #[serde(default)]
+    pub banned_words: Vec<String>,
+    /// 禁止符合的正規表示式
+    #[serde(default)]
+    pub banned_patterns: Vec<String>,
+}
+
+/// 拼字與用語檢查設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TerminologyConfig {
+    /// 是否在 commit 預覽時檢查錯字與用語
+    #[serde(default)]
+    pub enabled: bool,
+    /// 是否使用內建的常見英文錯字與繁體中文慣用詞
+    #[serde(default = "default_true")]
+    pub builtin: bool,
+    /// 自訂用語表：不建議的寫法 = 建議的寫法
+    #[serde(default)]
+    pub glossary: BTreeMap<String, String>,
+    /// 專案用語表的路徑（相對於 repository 根目錄，格式同 glossary）
+    #[serde(default = "default_project_glossary")]
+    pub project_glossary: String,
+}
+
+fn default_project_glossary() -> String {
+    ".commit-glossary.toml".to_string()
+}
+
+impl Default for TerminologyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            builtin: true,
+            glossary: BTreeMap::new(),
+            project_glossary: default_project_glossary(),
+        }
+    }
+}
+
+/// 分支用途說明設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BranchDescriptionConfig {
+    /// 建立新分支時是否將一句話的用途說明寫入 branch.<name>.description
+    #[serde(default)]
+    pub enabled: bool,
+    /// 生成用途說明的提示詞，可用變數：{branch}、{stats}、{file_summary}、{diff}、{language}
+    #[serde(default = "default_branch_description_prompt")]
+    pub prompt: String,
+}
+
+fn default_branch_description_prompt() -> String {
+    r#"你是一個 Git 專家。使用者剛建立分支 {branch}，請根據以下變更，用一句話說明這個分支的用途。
+
+變更統計：
+{stats}
+
+檔案列表與類型：
+{file_summary}
+
+詳細變更（Git diff）：
+```
+{diff}
+```
+
+要求：
+1. 使用{language}，不超過 60 字
+2. 只回覆這一句話，不要加引號、標點以外的符號或其他說明"#
+        .to_string()
+}
+
+impl Default for BranchDescriptionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            prompt: default_branch_description_prompt(),
+        }
+    }
+}
+
+/// session 紀錄設定
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct SessionConfig {
+    /// 是否將每次執行建立的 commit 寫入 session 紀錄檔
+    #[serde(default)]
+    pub enabled: bool,
+    /// 紀錄檔路徑（預設：~/.config/git-auto-commit/sessions.jsonl）
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// Lua 腳本設定：腳本可定義 transform_prompt 與 transform_suggestions 函數
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ScriptConfig {
+    /// 腳本路徑（支援 ~），未設定表示不使用
+    #[serde(default)]
+    pub path: Option<String>,
+}
+
+/// 外掛設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PluginsConfig {
+    /// 允許載入的外掛名稱（git-auto-commit-<name> 的 <name>）；未列出的執行檔不會被執行
+    #[serde(default)]
+    pub allowed: Vec<String>,
+    /// 每次呼叫外掛的時間限制（秒）
+    #[serde(default = "default_plugin_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_plugin_timeout_secs() -> u64 {
+    10
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            allowed: Vec::new(),
+            timeout_secs: default_plugin_timeout_secs(),
+        }
+    }
+}
+
+/// 新版本檢查設定
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct UpdateConfig {
+    /// 是否每週檢查一次新版本並顯示提示（會連線到 GitHub，預設關閉）
+    #[serde(default)]
+    pub check: bool,
+    /// 發布 release 的 GitHub repository（owner/name）
+    #[serde(default = "default_update_repository")]
+    pub repository: String,
+}
+
+fn default_update_repository() -> String {
+    "vantist/tools".to_string()
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check: false,
+            repository: default_update_repository(),
+        }
+    }
+}
+
+/// GitHub API 設定（token 取自 GITHUB_TOKEN 或 GH_TOKEN 環境變數）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GitHubConfig {
+    /// API 位址（GitHub Enterprise 請改為 https://<host>/api/v3）
+    #[serde(default = "default_github_api_url")]
+    pub api_url: String,
+}
+
+fn default_github_api_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            api_url: default_github_api_url(),
+        }
+    }
+}
+
+/// Bitbucket Cloud API 設定（token 取自 BITBUCKET_TOKEN 環境變數）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BitbucketConfig {
+    /// API 位址
+    #[serde(default = "default_bitbucket_api_url")]
+    pub api_url: String,
+}
+
+fn default_bitbucket_api_url() -> String {
+    "https://api.bitbucket.org/2.0".to_string()
+}
+
+impl Default for BitbucketConfig {
+    fn default() -> Self {
+        Self {
+            api_url: default_bitbucket_api_url(),
+        }
+    }
+}
+
+/// GitLab API 設定（token 取自 GITLAB_TOKEN 環境變數）
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct GitLabConfig {
+    /// API 位址（未設定時使用 https://<remote 主機>/api/v4）
+    #[serde(default)]
+    pub api_url: Option<String>,
+}
+
+/// 代管服務的種類
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeKind {
+    GitHub,
+    GitLab,
+    Bitbucket,
+    Gerrit,
+}
+
+/// 代管服務的偵測設定
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ForgeConfig {
+    /// 自架主機對應的服務種類（例如 "git.example.com" = "gitlab"），未列出的主機依名稱判斷
+    #[serde(default)]
+    pub hosts: BTreeMap<String, ForgeKind>,
+    /// ticket 為 issue 編號（例如 #123）時，向代管服務查詢 issue 標題並加入提示詞
+    #[serde(default)]
+    pub issue_lookup: bool,
+    /// 推送後以瀏覽器開啟建立 PR 的頁面（位於主幹時為 commit 頁面）
+    #[serde(default)]
+    pub open_browser: bool,
+}
+
+/// 工作流程預設
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Workflow {
+    /// main + develop，feature/bugfix 從 develop 分出，release/hotfix 處理發布
+    GitFlow,
+    /// main 加上短期的功能分支，透過 PR 合併
+    GithubFlow,
+    /// 直接在主幹上開發，分支（若有）只存在很短的時間
+    Trunk,
+}
+
+/// 將提示詞傳給 LLM CLI 的方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptVia {
+    /// 作為命令列參數，接在 prompt_flag 之後
+    #[default]
+    Arg,
+    /// 寫入 stdin（不傳 prompt_flag）
+    Stdin,
+    /// 寫入暫存檔，以 prompt_flag 加上檔案路徑傳遞；extra_args 中有 {prompt_file} 時改為代入該處
+    File,
+}
+
+/// 選擇 commit 訊息後的確認方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfirmMode {
+    /// 一律在預覽下方確認
+    #[default]
+    Always,
+    /// 只有自訂的訊息需要確認，選擇建議時預覽後直接 commit
+    CustomOnly,
+    /// 預覽後直接 commit
+    Never,
+}
+
+/// 是否執行 lefthook / lint-staged 的預先檢查
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PreflightMode {
+    /// 偵測到設定時詢問
+    #[default]
+    Ask,
+    /// 偵測到設定時一律執行
+    Always,
+    /// 不執行
+    Never,
+}
+
+/// lefthook / lint-staged 預先檢查設定
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct PreflightConfig {
+    /// 執行方式：ask、always 或 never
+    #[serde(default)]
+    pub mode: PreflightMode,
+}
+
+fn default_ticket_pattern() -> String {
+    r"[A-Z][A-Z0-9]+-\d+".to_string()
+}
+
+/// 作者身分（commit 時覆寫 user.name 與 user.email）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+}
+
+/// 模型價格（美元 / 每百萬 tokens）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ModelPricing {
+    /// 輸入（提示詞）價格
+    pub input_per_million: f64,
+    /// 輸出（回應）價格
+    pub output_per_million: f64,
+}
+
+fn default_pricing() -> HashMap<String, ModelPricing> {
+    let mut pricing = HashMap::new();
+    pricing.insert(
+        "gemini-2.5-flash".to_string(),
+        ModelPricing {
+            input_per_million: 0.30,
+            output_per_million: 2.50,
+        },
+    );
+    pricing.insert(
+        "gemini-2.5-pro".to_string(),
+        ModelPricing {
+            input_per_million: 1.25,
+            output_per_million: 10.00,
+        },
+    );
+    pricing
+}
+
+/// 將設定檔的價格與內建的價格表合併（只加入一個模型時不會失去其他模型的價格）
+fn merge_pricing<'de, D>(deserializer: D) -> Result<HashMap<String, ModelPricing>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let configured = HashMap::<String, ModelPricing>::deserialize(deserializer)?;
+    let mut pricing = default_pricing();
+    pricing.extend(configured);
+    Ok(pricing)
+}
+
+/// 超過每日預算時的處理方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetAction {
+    /// 顯示警告但仍繼續呼叫 LLM
+    #[default]
+    Warn,
+    /// 拒絕呼叫 LLM，改用備用建議
+    Refuse,
+}
+
+/// 每日預算設定
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct BudgetConfig {
+    /// 每日花費上限（美元），未設定表示不限制
+    #[serde(default)]
+    pub daily_limit: Option<f64>,
+    /// 每日呼叫次數上限，未設定表示不限制
+    #[serde(default)]
+    pub daily_call_limit: Option<u32>,
+    /// 超過上限時的處理方式：warn 或 refuse
+    #[serde(default)]
+    pub on_exceed: BudgetAction,
+}
+
+/// 依 diff 大小自動選擇模型的設定（小型變更使用較快、便宜的模型，大型變更使用較強的模型）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ModelSelectionConfig {
+    /// 小型變更使用的模型，未設定時使用 model
+    #[serde(default)]
+    pub small_model: Option<String>,
+    /// 大型變更使用的模型，未設定時使用 model
+    #[serde(default)]
+    pub large_model: Option<String>,
+    /// 變更行數（新增與刪除合計）不超過此值時視為小型變更
+    #[serde(default = "default_small_max_lines")]
+    pub small_max_lines: usize,
+    /// 變更行數達到此值時視為大型變更
+    #[serde(default = "default_large_min_lines")]
+    pub large_min_lines: usize,
+    /// 變更檔案數達到此值時也視為大型變更
+    #[serde(default = "default_large_min_files")]
+    pub large_min_files: usize,
+}
+
+fn default_small_max_lines() -> usize {
+    30
+}
+
+fn default_large_min_lines() -> usize {
+    400
+}
+
+fn default_large_min_files() -> usize {
+    15
+}
+
+impl Default for ModelSelectionConfig {
+    fn default() -> Self {
+        Self {
+            small_model: None,
+            large_model: None,
+            small_max_lines: default_small_max_lines(),
+            large_min_lines: default_large_min_lines(),
+            large_min_files: default_large_min_files(),
+        }
+    }
+}
+
+/// 同時以兩個模型生成建議的設定（平行呼叫，選單中交錯列出並標示模型）
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct DualModelConfig {
+    /// 第二個模型（使用相同的 LLM CLI），未設定時只使用 model
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// 稽核紀錄設定（記錄送往 LLM 的提示詞與回應）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AuditConfig {
+    /// 是否啟用稽核紀錄
+    #[serde(default)]
+    pub enabled: bool,
+    /// 紀錄檔路徑（預設：~/.config/git-auto-commit/audit.log）
+    #[serde(default)]
+    pub path: Option<String>,
+    /// 單一紀錄檔大小上限（KB），超過時輪替
+    #[serde(default = "default_audit_max_size_kb")]
+    pub max_size_kb: u64,
+    /// 保留的輪替檔案數量
+    #[serde(default = "default_audit_max_files")]
+    pub max_files: usize,
+}
+
+fn default_audit_max_size_kb() -> u64 {
+    10 * 1024
+}
+
+fn default_audit_max_files() -> usize {
+    5
+}
+
+impl Default for AuditConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: None,
+            max_size_kb: default_audit_max_size_kb(),
+            max_files: default_audit_max_files(),
+        }
+    }
+}
+
+fn default_command() -> String {
+    "gemini".to_string()
+}
+
+fn default_prompt_flag() -> String {
+    "-p".to_string()
+}
+
+fn default_model_flag() -> String {
+    "--model".to_string()
+}
+
+fn default_model() -> String {
+    "gemini-2.5-flash".to_string()
+}
+
+fn default_extra_args() -> Vec<String> {
+    vec![]
+}
+
+fn default_language() -> String {
+    "繁體中文".to_string()
+}
+
+fn default_combined_prompt() -> String {
+    r#"你是一個 Git 專家。請根據以下資訊，生成分支名稱和 commit 訊息建議。
+
+變更統計：
+{stats}
+
+檔案列表與類型：
+{file_summary}
+
+詳細變更（Git diff with context）：
+```
+{diff}
+```
+
+已存在的分支（請勿建議相同或僅日期不同的名稱）：
+{existing_branches}
+
+Determine the best branch naming prefixes.
+
+Here are the prefixes you can choose from:
+
+{branch_prefixes}
+
+Determine the best label for the commit.
+
+Here are the labels you can choose from:
+
+- build: Changes that affect the build system or external dependencies (example scopes: gulp, broccoli, npm)
+- chore: Updating libraries, copyrights, or other repo settings, includes updating dependencies.
+- ci: Changes to our CI configuration files and scripts (example scopes: Travis, Circle, GitHub Actions)
+- docs: Non-code changes, such as fixing typos or adding new documentation (example scopes: Markdown files)
+- feat: A commit of the type feat introduces a new feature to the codebase
+- fix: A commit of the type fix patches a bug in your codebase
+- perf: A code change that improves performance
+- refactor: A code change that neither fixes a bug nor adds a feature
+- style: Changes that do not affect the meaning of the code (white-space, formatting, missing semi-colons, etc.)
+- test: Adding missing tests or correcting existing tests
+
+請按照以下格式回覆：
+
+[BRANCHES]
+feature/example-feature
+fix/example-bug
+chore/example-task
+
+[COMMITS]
+feat: 新增使用者登入功能
+
+實作完整的使用者登入流程，包含密碼驗證與 session 管理。
+
+
+fix: 修正資料庫連線錯誤
+
+修正了在高並發情況下資料庫連線池耗盡的問題。
+
+
+chore: 更新專案依賴套件
+
+更新所有依賴套件至最新穩定版本，提升安全性。
+
+要求：
+1. 仔細分析 diff 的完整上下文，理解變更的真實意圖
+2. [BRANCHES] 區塊包含 3 個分支名稱建議，格式為「type/description」
+   - type 可選：請依據 naming prefixes 選擇最合適的類型
+   - description 使用英文小寫，單字之間用連字號 - 連接，不超過 30 字元
+3. [COMMITS] 區塊包含 3 個 commit 訊息建議
+   - **重要**：每個 commit 訊息必須以「type:」開頭（type 為英文）
+   - 第一行格式：「type: 簡短描述」，type 使用英文，描述使用{language}
+   - type 可選：請依據上述 labels 選擇最合適的類型
+   - 描述要精確反映實際變更內容，不超過 50 字
+   - 並補充說明，在第二行之後使用{language}詳細說明（限 5 行內）
+   - **重要**：每個 commit 訊息之間必須用空行分隔
+4. 不要使用 markdown 格式，不要編號
+5. 善用函數名稱、變數名稱等上下文資訊來理解變更目的
+6. 確保每個 commit 訊息都是完整且獨立的，不要將說明文字誤認為獨立的 commit"#
+        .to_string()
+}
+
+fn default_next_branch_prompt() -> String {
+    r#"你是一個 Git 專家。使用者剛在分支 {current_branch} 上完成一個 commit，接下來要從這個 commit 分出新分支進行下一個任務。
+請根據下一個任務的描述，建議 3 個分支名稱。
+
+下一個任務的描述：
+{description}
+
+已存在的分支（請勿建議相同或僅日期不同的名稱）：
+{existing_branches}
+
+可用的分支前綴：
+{branch_prefixes}
+
+要求：
+1. 格式為「type/description」，type 請依據上述前綴選擇
+2. description 使用英文小寫，單字之間用連字號 - 連接，不超過 30 字元
+3. 每行一個分支名稱，不要使用 markdown 格式，不要編號，不要其他說明"#
+        .to_string()
+}
+
+fn default_branch_prompt() -> String {
+    r#"你是一個 Git 專家。使用者目前在分支 {current_branch}，準備開始一項新的工作，需要先建立分支。
+請根據工作的描述，建議 3 個分支名稱。
+
+工作的描述：
+{description}
+
+已存在的分支（請勿建議相同或僅日期不同的名稱）：
+{existing_branches}
+
+可用的分支前綴：
+{branch_prefixes}
+
+要求：
+1. 格式為「type/description」，type 請依據上述前綴選擇
+2. description 使用英文小寫，單字之間用連字號 - 連接，不超過 30 字元
+3. 每行一個分支名稱，不要使用 markdown 格式，不要編號，不要其他說明"#
+        .to_string()
+}
+
+fn default_plan_prompt() -> String {
+    r#"你是一個 Git 專家。使用者目前在分支 {current_branch}，準備開始一項新的工作，還沒有撰寫任何程式碼。
+請根據工作的描述，建議 3 個分支名稱，並預先擬定完成這項工作時的 commit 標題。
+
+工作的描述：
+{description}
+
+已存在的分支（請勿建議相同或僅日期不同的名稱）：
+{existing_branches}
+
+可用的分支前綴：
+{branch_prefixes}
+
+要求：
+1. 分支名稱格式為「type/description」，type 請依據上述前綴選擇；description 使用英文小寫，單字之間用連字號 - 連接，不超過 30 字元
+2. commit 標題格式為「type: 簡短描述」，type 使用英文，描述使用{language}，不超過 50 字元
+3. 不要使用 markdown 格式，不要編號，不要其他說明
+
+請按照以下格式回覆：
+[BRANCHES]
+分支名稱（每行一個）
+
+[COMMIT]
+commit 標題"#
+        .to_string()
+}
+
+fn default_summary_prompt() -> String {
+    r#"你是一位工程師的助理。請根據以下 {since} 以來的 Git commit，撰寫站立會議（standup）用的工作摘要。
+
+Commits（時間、分支、標題與內容）：
+{commits}
+
+尚未 commit 的變更：
+{uncommitted}
+
+請按照以下格式，使用{language}回覆：
+已完成：
+- 將相關的 commit 依主題合併為一項，每項一行，描述做了什麼與目的
+進行中：
+- 根據尚未 commit 的變更描述正在進行的工作（沒有則省略此段）
+
+不要使用 markdown 標題或粗體，不要列出 commit 的 SHA"#
+        .to_string()
+}
+
+fn default_weekly_report_prompt() -> String {
+    r#"你是一位工程師的助理。請根據以下 {since} 至 {until} 的 Git commit，撰寫一份依專案分組的週報。
+
+Commits（依專案分組，包含時間、分支、標題與內容）：
+{commits}
+
+尚未 commit 的變更：
+{uncommitted}
+
+請按照以下格式，使用{language}回覆：
+每個專案一段，以「專案名稱」為一行開頭，接著：
+- 將相關的 commit 依主題合併為一項，每項一行，描述完成的功能、修正與目的
+- 有尚未 commit 的變更時，最後加一項「進行中：」描述正在進行的工作
+最後以「下週計畫：」一行開頭，根據進行中的工作列出 1–3 項後續工作（無法判斷時省略此段）
+
+沒有 commit 的專案請省略；不要使用 markdown 標題或粗體，不要列出 commit 的 SHA"#
+        .to_string()
+}
+
+fn default_rewrite_prompt() -> String {
+    r#"你是一個 Git 專家。使用者已經用自己的話（可能是任何語言、口語或不完整的句子）描述了這次的變更，
+請保留使用者的原意，將其改寫為符合 Conventional Commits 規範的 commit 訊息，並建議分支名稱。
+
+使用者的描述：
+{message}
+
+變更統計：
+{stats}
+
+變更的檔案：
+{files}
+
+已存在的分支（請勿建議相同或僅日期不同的名稱）：
+{existing_branches}
+
+可用的分支前綴：
+{branch_prefixes}
+
+請按照以下格式回覆：
+
+[BRANCHES]
+feature/example-feature
+
+[COMMITS]
+feat: 新增使用者登入功能
+
+實作完整的使用者登入流程，包含密碼驗證與 session 管理。
+
+要求：
+1. [BRANCHES] 區塊包含 3 個分支名稱建議，格式為「type/description」，description 使用英文小寫與連字號，不超過 30 字元
+2. [COMMITS] 區塊只包含 1 個 commit 訊息
+   - 第一行格式：「type: 簡短描述」，type 使用英文（feat、fix、chore、docs、style、refactor、test、build、ci、perf），描述使用{language}，不超過 50 字
+   - 若使用者的描述包含更多細節，在第二行之後使用{language}補充說明（限 5 行內）
+   - 不要加入使用者沒有提到的內容
+3. 不要使用 markdown 格式，不要編號"#
+        .to_string()
+}
+
+fn default_pr_prompt() -> String {
+    r#"你是一個 Git 專家。請根據以下資訊，為分支 {branch} 撰寫 pull request 的標題與說明。
+這個 PR 是堆疊（stacked）PR 中的一層，base 分支為 {base}，只需描述這一層的變更。
+
+Commits：
+{commits}
+
+變更統計：
+{stats}
+
+詳細變更（Git diff）：
+```
+{diff}
+```
+
+請按照以下格式回覆：
+第一行為 PR 標題（格式：「type: 簡短描述」，type 使用英文，描述使用{language}，不超過 50 字）
+第二行留空
+第三行之後為 PR 說明，使用{language}條列主要變更（markdown 格式）"#
+        .to_string()
+}
+
+fn default_tag_prompt() -> String {
+    r#"你是一個 Git 專家。請根據以下自 {previous} 以來的 commit，為版本 {tag} 撰寫 annotated tag 的訊息。
+
+Commits（由舊到新）：
+{commits}
+
+請按照以下格式回覆：
+第一行為標題（格式：「{tag}：簡短描述這個版本的重點」，描述使用{language}，不超過 50 字）
+第二行留空
+第三行之後使用{language}條列這個版本的重點（新功能、修正、不相容的變更），合併相關的 commit，
+每行以「- 」開頭，不超過 8 項，不要使用其他 markdown 格式"#
+        .to_string()
+}
+
+fn default_stash_prompt() -> String {
+    r#"你是一個 Git 專家。使用者在分支 {branch} 上有尚未完成的變更，準備先 stash 起來。
+請根據以下變更，寫一句描述進行中工作的 stash 訊息，讓使用者一週後仍能看出這是什麼工作。
+
+變更的檔案：
+{file_summary}
+
+變更統計：
+{stats}
+
+詳細變更（Git diff）：
+```
+{diff}
+```
+
+要求：
+1. 使用{language}，不超過 60 字
+2. 說明正在做什麼以及進度（例如「登入頁改用新的驗證 API，錯誤處理尚未完成」）
+3. 只回覆一行訊息，不要使用 markdown 格式或其他說明"#
+        .to_string()
+}
+
+fn default_annotate_prompt() -> String {
+    r#"你是一位資深工程師，正在協助同事追查問題（例如 git bisect）或了解程式碼的歷史。
+請根據以下 commit 的訊息與實際的 diff，說明這個 commit 改了什麼。
+
+Commit {sha}：
+{message}
+
+變更統計：
+{stats}
+
+詳細變更（Git diff）：
+```
+{diff}
+```
+
+請使用{language}回覆，包含：
+1. 一句話摘要這個 commit 的實際效果
+2. 條列主要的行為變化（以「- 」開頭），指出涉及的模組或函數
+3. 若 commit 訊息與實際變更不符，或可能引入問題（例如改變預設值、移除檢查），另外列出
+不要使用 markdown 標題，不要重複貼上 diff"#
+        .to_string()
+}
+
+/// 籠統建議的處理方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageMode {
+    /// 不檢查
+    Off,
+    /// 在選單中標示籠統或與變更無關的建議
+    #[default]
+    Warn,
+    /// 重新請 LLM 生成，取代籠統的建議（最多一次）
+    Regenerate,
+}
+
+/// 建議內容檢查設定（檢查 commit 標題是否提及變更的檔案或符號）
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct CoverageConfig {
+    /// 處理方式：off、warn 或 regenerate
+    #[serde(default)]
+    pub mode: CoverageMode,
+}
+
+/// 建議的排序設定：依權重為每個 commit 建議評分，由高至低排列並移除標題相近的建議
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RankingConfig {
+    /// 是否排序並去除重複（關閉時維持 LLM 回應的順序）
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 標題相似度（0–1）達到此值時視為重複，只保留分數較高者；設為 1 只移除相同的標題
+    #[serde(default = "default_dedup_similarity")]
+    pub dedup_similarity: f64,
+    /// 各評分項目的權重
+    #[serde(default)]
+    pub weights: RankingWeights,
+}
+
+/// 評分項目的權重（各項分數為 0–1，設為 0 表示不列入）
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct RankingWeights {
+    /// 具體程度：標題是否提及變更的檔案或符號，而非籠統的描述
+    #[serde(default = "default_specificity_weight")]
+    pub specificity: f64,
+    /// 格式規則：Conventional Commits 的 type、標題長度上限、結尾沒有句號、標題與內文之間空行
+    #[serde(default = "default_conformance_weight")]
+    pub conformance: f64,
+    /// 標題長度：50 欄內最佳，越長分數越低
+    #[serde(default = "default_length_weight")]
+    pub length: f64,
+}
+
+fn default_dedup_similarity() -> f64 {
+    0.8
+}
+
+fn default_specificity_weight() -> f64 {
+    3.0
+}
+
+fn default_conformance_weight() -> f64 {
+    2.0
+}
+
+fn default_length_weight() -> f64 {
+    1.0
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            dedup_similarity: default_dedup_similarity(),
+            weights: RankingWeights::default(),
+        }
+    }
+}
+
+impl Default for RankingWeights {
+    fn default() -> Self {
+        Self {
+            specificity: default_specificity_weight(),
+            conformance: default_conformance_weight(),
+            length: default_length_weight(),
+        }
+    }
+}
+
+/// 啟動時執行 git fetch 的設定，讓分支衝突檢查與領先/落後資訊使用最新資料
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FetchConfig {
+    /// 是否在啟動時執行 git fetch
+    #[serde(default)]
+    pub on_start: bool,
+    /// 是否加上 --prune 清除已刪除的 remote 分支
+    #[serde(default = "default_true")]
+    pub prune: bool,
+    /// 時間限制（秒），逾時則改用本地資料
+    #[serde(default = "default_fetch_timeout_secs")]
+    pub timeout_secs: u64,
+    /// 目前分支落後 upstream 時，在生成建議前提醒並詢問是否先執行 git pull --rebase
+    #[serde(default = "default_true")]
+    pub offer_rebase: bool,
+    /// 淺層 clone 中使用依賴歷史的功能（tag、summary、rebase-plan、stack）時，是否加深歷史
+    #[serde(default)]
+    pub deepen: DeepenMode,
+    /// 加深時往前多取的 commit 數，0 表示取得完整歷史（--unshallow）
+    #[serde(default = "default_deepen_by")]
+    pub deepen_by: u32,
+}
+
+/// 淺層 clone 加深歷史的方式
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DeepenMode {
+    /// 詢問是否加深（不在終端機中時視為 never）
+    #[default]
+    Ask,
+    /// 自動加深
+    Always,
+    /// 不加深，只提醒歷史不完整
+    Never,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_fetch_timeout_secs() -> u64 {
+    10
+}
+
+fn default_deepen_by() -> u32 {
+    200
+}
+
+impl Default for FetchConfig {
+    fn default() -> Self {
+        Self {
+            on_start: false,
+            prune: true,
+            timeout_secs: default_fetch_timeout_secs(),
+            offer_rebase: true,
+            deepen: DeepenMode::default(),
+            deepen_by: default_deepen_by(),
+        }
+    }
+}
+
+impl Default for LlmConfig {
+    fn default() -> Self {
+        Self {
+            command: default_command(),
+            prompt_flag: default_prompt_flag(),
+            prompt_via: PromptVia::default(),
+            model_flag: default_model_flag(),
+            model: default_model(),
+            extra_args: default_extra_args(),
+            temperature_flag: String::new(),
+            temperature: None,
+            seed_flag: String::new(),
+            seed: None,
+            language: default_language(),
+            combined_prompt: default_combined_prompt(),
+            ask_description: false,
+            pregenerate: true,
+            confirm: ConfirmMode::default(),
+            remember: true,
+            next_branch_prompt: default_next_branch_prompt(),
+            branch_prompt: default_branch_prompt(),
+            plan_prompt: default_plan_prompt(),
+            pr_prompt: default_pr_prompt(),
+            tag_prompt: default_tag_prompt(),
+            stash_prompt: default_stash_prompt(),
+            annotate_prompt: default_annotate_prompt(),
+            rewrite_prompt: default_rewrite_prompt(),
+            summary_prompt: default_summary_prompt(),
+            weekly_report_prompt: default_weekly_report_prompt(),
+            audit: AuditConfig::default(),
+            pricing: default_pricing(),
+            budget: BudgetConfig::default(),
+            model_selection: ModelSelectionConfig::default(),
+            dual_model: DualModelConfig::default(),
+            coverage: CoverageConfig::default(),
+            ranking: RankingConfig::default(),
+            fetch: FetchConfig::default(),
+            identities: BTreeMap::new(),
+            branch_template: None,
+            workflow: None,
+            ticket_pattern: default_ticket_pattern(),
+            preflight: PreflightConfig::default(),
+            github: GitHubConfig::default(),
+            bitbucket: BitbucketConfig::default(),
+            gitlab: GitLabConfig::default(),
+            forge: ForgeConfig::default(),
+            update: UpdateConfig::default(),
+            plugins: PluginsConfig::default(),
+            script: ScriptConfig::default(),
+            session: SessionConfig::default(),
+            branch_description: BranchDescriptionConfig::default(),
+            terminology: TerminologyConfig::default(),
+            compliance: ComplianceConfig::default(),
+            deps: DepsConfig::default(),
+            migration: MigrationConfig::default(),
+            infra: InfraConfig::default(),
+            ci: CiConfig::default(),
+            notebook: NotebookConfig::default(),
+            assets: AssetsConfig::default(),
+            new_files: NewFilesConfig::default(),
+            risk: RiskConfig::default(),
+            todo: TodoConfig::default(),
+            debug_leftovers: DebugLeftoversConfig::default(),
+            license_header: LicenseHeaderConfig::default(),
+            split: SplitConfig::default(),
+            gerrit: GerritConfig::default(),
+            trailers: Vec::new(),
+            merge: MergeConfig::default(),
+            history: HistoryConfig::default(),
+            embeddings: EmbeddingsConfig::default(),
+            notes: NotesConfig::default(),
+            review: ReviewConfig::default(),
+            changelog: ChangelogConfig::default(),
+            version_files: VersionFilesConfig::default(),
+            release_checks: ReleaseChecksConfig::default(),
+        }
+    }
+}
+
+/// 取得設定目錄路徑
+pub fn get_config_dir() -> PathBuf {
+    git_tools_core::config::config_dir("git-auto-commit")
+}
+
+/// 取得設定檔路徑
+pub fn get_config_path() -> PathBuf {
+    get_config_dir().join("config.toml")
+}
+
+/// 載入 LLM 設定
+pub fn load_llm_config() -> LlmConfig {
+    let config_path = get_config_path();
+    
+    if config_path.exists() {
+        match fs::read_to_string(&config_path) {
+            Ok(content) => {
+                match toml::from_str::<LlmConfig>(&content) {
+                    Ok(config) => {
+                        println!("{}", format!("📝 已載入設定檔：{}", config_path.display()).dimmed());
+                        return config;
+                    }
+                    Err(e) => {
+                        println!("{}", format!("⚠️  設定檔格式錯誤：{}，使用預設設定", e).yellow());
+                    }
+                }
+            }
+            Err(e) => {
+                println!("{}", format!("⚠️  無法讀取設定檔：{}，使用預設設定", e).yellow());
+            }
+        }
+    }
+    
+    LlmConfig::default()
+}
@@ -0,0 +1,411 @@
+use crate::encoding;
+use crate::plain;
+use crate::GitSuggestions;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{Confirm, Input, Select};
+use git2::{Oid, Repository, RepositoryState};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 可能產生衝突的進行中操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+}
+
+impl Operation {
+    /// 依 repository 的狀態判斷進行中的操作
+    pub fn detect(repo: &Repository) -> Option<Self> {
+        match repo.state() {
+            RepositoryState::Merge => Some(Self::Merge),
+            RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => {
+                Some(Self::Rebase)
+            }
+            RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some(Self::CherryPick),
+            RepositoryState::Revert | RepositoryState::RevertSequence => Some(Self::Revert),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Rebase => "rebase",
+            Self::CherryPick => "cherry-pick",
+            Self::Revert => "revert",
+        }
+    }
+
+    /// 記錄被套用 commit 的檔案（.git/ 下）
+    fn head_file(self) -> &'static str {
+        match self {
+            Self::Merge => "MERGE_HEAD",
+            Self::Rebase => "REBASE_HEAD",
+            Self::CherryPick => "CHERRY_PICK_HEAD",
+            Self::Revert => "REVERT_HEAD",
+        }
+    }
+}
+
+/// 該行是否為衝突標記
+///
+/// 單獨的 ======= 也是 Markdown、reStructuredText 標題的底線，只以 <<<<<<< 與 >>>>>>> 判斷。
+fn is_marker(line: &str) -> bool {
+    line.starts_with("<<<<<<< ") || line.starts_with(">>>>>>> ") || line == "<<<<<<<" || line == ">>>>>>>"
+}
+
+/// 檔案是否仍有衝突標記
+fn has_conflict_markers(content: &str) -> bool {
+    content.lines().any(is_marker)
+}
+
+/// 第一個衝突標記的行號（從 1 起算）
+fn first_marker(content: &str) -> Option<usize> {
+    content.lines().position(is_marker).map(|line| line + 1)
+}
+
+/// staged 內容中仍有衝突標記的檔案與第一個標記的行號
+pub fn staged_markers(repo: &Repository, files: &[String]) -> Vec<(String, usize)> {
+    let Ok(index) = repo.index() else {
+        return Vec::new();
+    };
+    files
+        .iter()
+        .filter_map(|path| {
+            let entry = index.get_path(Path::new(path), 0)?;
+            let blob = repo.find_blob(entry.id).ok()?;
+            if blob.is_binary() {
+                return None;
+            }
+            let line = first_marker(&String::from_utf8_lossy(blob.content()))?;
+            Some((path.clone(), line))
+        })
+        .collect()
+}
+
+/// 檢查 index 中的衝突檔案：仍有衝突標記時列出並回傳 false；都已解決時詢問是否加入 staged
+pub fn ensure_resolved(repo: &Repository, operation: Operation) -> Result<bool> {
+    let index = repo.index().context("無法讀取 index")?;
+    let mut paths: Vec<String> = Vec::new();
+    for conflict in index.conflicts().context("無法讀取衝突檔案")? {
+        let conflict = conflict.context("無法讀取衝突檔案")?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        if let Some(entry) = entry {
+            let path = String::from_utf8_lossy(&entry.path).to_string();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+    if paths.is_empty() {
+        return Ok(true);
+    }
+
+    let workdir = repo.workdir().context("bare repository 無法解決衝突")?;
+    println!("{}", format!("⚔️  {} 進行中，衝突檔案：", operation.name()).yellow());
+    let mut unresolved = 0;
+    for path in &paths {
+        // 刪除的檔案視為已解決
+        let content = fs::read(workdir.join(path)).unwrap_or_default();
+        if has_conflict_markers(&String::from_utf8_lossy(&content)) {
+            unresolved += 1;
+            println!("{}", format!("  ✗ {}（仍有衝突標記）", path).red());
+        } else {
+            println!("{}", format!("  ✓ {}", path).green());
+        }
+    }
+    println!();
+
+    if unresolved > 0 {
+        println!("{}", format!("⚠️  仍有 {} 個檔案有衝突標記，請先解決後再執行", unresolved).yellow());
+        return Ok(false);
+    }
+    if !Confirm::with_theme(plain::theme())
+        .with_prompt(format!("將已解決的 {} 個檔案加入 staged？", paths.len()))
+        .default(true)
+        .interact()?
+    {
+        return Ok(false);
+    }
+
+    let status = Command::new("git")
+        .args(["add", "-A", "--"])
+        .args(&paths)
+        .status()
+        .context("無法執行 git add")?;
+    if !status.success() {
+        anyhow::bail!("git add 執行失敗");
+    }
+    Ok(true)
+}
+
+/// git 在 .git/MERGE_MSG 中記錄的衝突檔案（「# Conflicts:」之後以 tab 開頭的行）
+pub fn recorded_conflicts(repo: &Repository) -> Vec<String> {
+    let message = fs::read_to_string(repo.path().join("MERGE_MSG")).unwrap_or_default();
+    message
+        .lines()
+        .skip_while(|line| line.trim_start_matches('#').trim() != "Conflicts:")
+        .skip(1)
+        .map(|line| line.trim_start_matches('#'))
+        .take_while(|line| line.starts_with('\t') || line.trim().is_empty())
+        .filter_map(|line| {
+            let path = line.trim();
+            (!path.is_empty()).then(|| path.to_string())
+        })
+        .collect()
+}
+
+/// 衝突的解決方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Choice {
+    /// 與 HEAD 相同
+    Ours,
+    /// 與被套用的 commit 相同
+    Theirs,
+    /// 手動合併雙方
+    Combined,
+    /// 刪除檔案
+    Deleted,
+}
+
+/// 單一衝突檔案的解決方式
+pub struct Resolution {
+    pub path: String,
+    choice: Choice,
+    operation: Operation,
+    /// 使用者補充的說明
+    note: Option<String>,
+}
+
+impl Resolution {
+    fn describe(&self) -> &'static str {
+        match (self.choice, self.operation) {
+            (Choice::Ours, Operation::Merge | Operation::Revert) => "保留目前分支的版本",
+            (Choice::Ours, _) => "保留目前分支的版本，捨棄此 commit 的變更",
+            (Choice::Theirs, Operation::Merge) => "採用合併進來的版本",
+            (Choice::Theirs, Operation::Revert) => "採用還原後的版本",
+            (Choice::Theirs, _) => "採用此 commit 的版本",
+            (Choice::Combined, _) => "手動合併雙方的變更",
+            (Choice::Deleted, _) => "刪除檔案",
+        }
+    }
+
+    fn line(&self) -> String {
+        match &self.note {
+            Some(note) => format!("- {}：{}；{}", self.path, self.describe(), note),
+            None => format!("- {}：{}", self.path, self.describe()),
+        }
+    }
+}
+
+/// 取得 tree 中檔案的 blob
+fn tree_blob(repo: &Repository, commit: Oid, path: &str) -> Option<Oid> {
+    let tree = repo.find_commit(commit).ok()?.tree().ok()?;
+    tree.get_path(std::path::Path::new(path)).ok().map(|entry| entry.id())
+}
+
+/// 被套用 commit 的版本：revert 時為被還原 commit 的上一版
+fn theirs_commit(repo: &Repository, operation: Operation) -> Option<Oid> {
+    let content = fs::read_to_string(repo.path().join(operation.head_file())).ok()?;
+    let oid = Oid::from_str(content.lines().next()?.trim()).ok()?;
+    if operation == Operation::Revert {
+        repo.find_commit(oid).ok()?.parent_id(0).ok()
+    } else {
+        Some(oid)
+    }
+}
+
+/// 比對 staged 版本與雙方的版本，判斷每個衝突檔案的解決方式；ask 為 true 時逐一詢問補充說明
+pub fn resolutions(repo: &Repository, operation: Operation, ask: bool) -> Result<Vec<Resolution>> {
+    let paths = recorded_conflicts(repo);
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+    let index = repo.index().context("無法讀取 index")?;
+    let head = repo.head().ok().and_then(|h| h.target());
+    let theirs = theirs_commit(repo, operation);
+
+    let mut resolutions = Vec::new();
+    for path in paths {
+        let staged = index.get_path(std::path::Path::new(&path), 0).map(|entry| entry.id);
+        let ours = head.and_then(|oid| tree_blob(repo, oid, &path));
+        let their = theirs.and_then(|oid| tree_blob(repo, oid, &path));
+        let choice = match staged {
+            None => Choice::Deleted,
+            Some(id) if Some(id) == ours => Choice::Ours,
+            Some(id) if Some(id) == their => Choice::Theirs,
+            Some(_) => Choice::Combined,
+        };
+        resolutions.push(Resolution {
+            path,
+            choice,
+            operation,
+            note: None,
+        });
+    }
+
+    if ask {
+        println!("{}", "請簡述每個衝突的解決方式（會寫入 commit 訊息，可留空）".blue());
+        for resolution in &mut resolutions {
+            let note: String = Input::with_theme(plain::theme())
+                .with_prompt(format!("{}（{}）", resolution.path, resolution.describe()))
+                .allow_empty(true)
+                .interact_text()?;
+            let note = note.trim();
+            resolution.note = (!note.is_empty()).then(|| note.to_string());
+        }
+        println!();
+    }
+    Ok(resolutions)
+}
+
+/// 顯示每個衝突檔案的解決方式
+pub fn print(resolutions: &[Resolution]) {
+    if resolutions.is_empty() {
+        return;
+    }
+    println!("{}", "已解決的衝突：".blue());
+    for resolution in resolutions {
+        println!("{}", format!("  {}", resolution.line()).dimmed());
+    }
+    println!();
+}
+
+/// commit 訊息中說明衝突解決方式的段落
+pub fn section(resolutions: &[Resolution]) -> String {
+    let lines: Vec<String> = resolutions.iter().map(Resolution::line).collect();
+    format!("解決衝突：\n{}", lines.join("\n"))
+}
+
+/// rebase、cherry-pick、revert 時的訊息建議：沿用原本的訊息，並加上衝突的解決方式
+pub fn replay_suggestions(repo: &Repository, operation: Operation, resolutions: &[Resolution]) -> GitSuggestions {
+    let default_message = fs::read_to_string(repo.path().join("MERGE_MSG")).unwrap_or_default();
+    let mut message: String = default_message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    if message.is_empty() {
+        message = theirs_commit(repo, operation)
+            .and_then(|oid| repo.find_commit(oid).ok())
+            .map(|commit| encoding::message(&commit).trim().to_string())
+            .filter(|message| !message.is_empty())
+            .unwrap_or_else(|| format!("chore: 完成 {}", operation.name()));
+    }
+
+    let mut commit_messages = Vec::new();
+    if !resolutions.is_empty() {
+        commit_messages.push(format!("{}\n\n{}", message, section(resolutions)));
+    }
+    commit_messages.push(message);
+    GitSuggestions {
+        branch_names: Vec::new(),
+        commit_messages,
+        commit_notes: Vec::new(),
+        commit_labels: Vec::new(),
+        analysis: None,
+        review: None,
+    }
+}
+
+/// rebase 中原 commit 的作者（取自 author-script），commit 時以環境變數保留
+pub fn original_author(repo: &Repository, operation: Operation) -> Vec<(String, String)> {
+    if operation != Operation::Rebase {
+        return Vec::new();
+    }
+    let script = ["rebase-merge", "rebase-apply"]
+        .iter()
+        .find_map(|dir| fs::read_to_string(repo.path().join(dir).join("author-script")).ok())
+        .unwrap_or_default();
+    script
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            // 值以 shell 單引號包住，內含的單引號寫作 '\''
+            let value = value.replace("'\\''", "'");
+            let value = value.strip_prefix('\'')?.strip_suffix('\'')?;
+            key.starts_with("GIT_AUTHOR_").then(|| (key.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// commit 後操作仍在進行中（例如 rebase 還有後續的 commit）時，詢問是否繼續
+pub fn offer_continue(repo: &Repository, operation: Operation) -> Result<()> {
+    if Operation::detect(repo).is_none() {
+        return Ok(());
+    }
+    let command = format!("git {} --continue", operation.name());
+    println!();
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt(format!("要繼續 {} 嗎？", operation.name()))
+        .items(&[format!("▶ 繼續（{}）", command), "稍後再說".to_string()])
+        .default(0)
+        .interact()?;
+    if selection != 0 {
+        return Ok(());
+    }
+
+    let status = Command::new("git")
+        .args([operation.name(), "--continue"])
+        .env("GIT_EDITOR", "true")
+        .status()
+        .context(format!("無法執行 {}", command))?;
+    if status.success() {
+        println!("{}", format!("✓ 已繼續 {}", operation.name()).green());
+    } else {
+        println!("{}", format!("⚠️  {} 尚未完成，解決衝突後可再次執行 git-auto-commit", operation.name()).yellow());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolution(path: &str, choice: Choice, operation: Operation, note: Option<&str>) -> Resolution {
+        Resolution {
+            path: path.to_string(),
+            choice,
+            operation,
+            note: note.map(String::from),
+        }
+    }
+
+    #[test]
+    fn has_conflict_markers_detects_each_marker() {
+        assert!(has_conflict_markers("a\n<<<<<<< HEAD\nb\n"));
+        assert!(has_conflict_markers(">>>>>>> feature\n"));
+        assert!(!has_conflict_markers("a <<<<<<< b\n"));
+    }
+
+    #[test]
+    fn has_conflict_markers_ignores_heading_underlines() {
+        assert!(!has_conflict_markers("Title\n=======\n\ntext\n"));
+    }
+
+    #[test]
+    fn first_marker_ignores_heading_underlines() {
+        assert_eq!(first_marker("Title\n=======\n\ntext\n"), None);
+        assert_eq!(first_marker("a\nb\n<<<<<<< HEAD\nc\n=======\nd\n>>>>>>> main\n"), Some(3));
+        assert_eq!(first_marker("a\n>>>>>>> main\n"), Some(2));
+    }
+
+    #[test]
+    fn section_describes_choice_for_operation() {
+        let resolutions = [
+            resolution("src/lib.rs", Choice::Ours, Operation::Rebase, None),
+            resolution("Cargo.lock", Choice::Theirs, Operation::Merge, Some("重新產生")),
+        ];
+        assert_eq!(
+            section(&resolutions),
+            "解決衝突：\n- src/lib.rs：保留目前分支的版本，捨棄此 commit 的變更\n- Cargo.lock：採用合併進來的版本；重新產生"
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashSet;
+
+/// 籠統、無法反映實際變更的描述（比對時忽略大小寫、空白與標點）
+const GENERIC_DESCRIPTIONS: &[&str] = &[
+    "更新檔案",
+    "更新專案檔案",
+    "更新程式碼",
+    "更新內容",
+    "修改檔案",
+    "修改程式碼",
+    "修正錯誤",
+    "修正程式錯誤",
+    "修正問題",
+    "日常維護更新",
+    "日常維護",
+    "維護更新",
+    "一般更新",
+    "小幅修改",
+    "新增檔案",
+    "移除不需要的檔案",
+    "改善程式效能",
+    "更新文檔內容",
+    "update",
+    "updates",
+    "updatefiles",
+    "updatecode",
+    "minorchanges",
+    "minorupdates",
+    "misc",
+    "wip",
+    "fixbug",
+    "fixbugs",
+    "bugfix",
+    "changes",
+    "cleanup",
+];
+
+/// 不具辨識度的英文單字，不列入關鍵字比對
+const STOP_WORDS: &[&str] = &[
+    "the", "and", "for", "with", "from", "into", "add", "new", "use", "update", "fix", "file",
+    "files", "code", "src", "lib", "main", "test", "tests", "mod", "pub", "let", "mut", "self",
+    "return", "true", "false", "none", "some", "string", "str", "this", "that",
+];
+
+/// commit 訊息與變更內容的對應程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// 提及了可從變更推導出的內容
+    Specific,
+    /// 描述過於籠統（例如「更新檔案」）
+    Generic,
+    /// 提到的名稱都不在變更的檔案或符號中
+    Unrelated,
+}
+
+impl Verdict {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Verdict::Specific => "",
+            Verdict::Generic => "描述過於籠統",
+            Verdict::Unrelated => "提及的名稱與變更內容不符",
+        }
+    }
+}
+
+/// 將識別字拆成小寫單字（支援 camelCase、snake_case、kebab-case 與路徑）
+fn split_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for c in text.chars() {
+        if c.is_ascii_alphanumeric() {
+            if c.is_ascii_uppercase() && prev_lower && !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+            current.push(c.to_ascii_lowercase());
+        } else {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+        }
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+
+    words
+}
+
+fn is_meaningful(word: &str) -> bool {
+    word.len() >= 3
+        && !word.chars().all(|c| c.is_ascii_digit())
+        && !STOP_WORDS.contains(&word)
+}
+
+/// 從變更的檔案路徑與 diff 內容收集關鍵字（檔名、目錄與變更行中的識別字）
+pub fn collect_keywords(files: &[String], diff: &str) -> HashSet<String> {
+    let mut keywords = HashSet::new();
+
+    for file in files {
+        keywords.extend(split_words(file).into_iter().filter(|w| is_meaningful(w)));
+    }
+
+    for line in diff.lines() {
+        let changed = (line.starts_with('+') && !line.starts_with("+++"))
+            || (line.starts_with('-') && !line.starts_with("---"))
+            || line.starts_with("@@");
+        if changed {
+            keywords.extend(split_words(line).into_iter().filter(|w| is_meaningful(w)));
+        }
+    }
+
+    keywords
+}
+
+/// 取得 commit 標題中「type(scope): 」之後的描述
+fn subject_description(subject: &str) -> &str {
+    match subject.find(':') {
+        Some(pos) => subject[pos + 1..].trim(),
+        None => subject.trim(),
+    }
+}
+
+/// 評估 commit 訊息的標題是否對應到變更內容
+pub fn assess(message: &str, keywords: &HashSet<String>) -> Verdict {
+    let subject = message.lines().next().unwrap_or("");
+    let description = subject_description(subject);
+
+    let normalized: String = description
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+
+    if normalized.chars().count() < 4 || GENERIC_DESCRIPTIONS.contains(&normalized.as_str()) {
+        return Verdict::Generic;
+    }
+
+    // 只有在描述中出現英文名稱時才能比對；純中文描述無法判斷，視為具體
+    let mentioned: Vec<String> = split_words(description)
+        .into_iter()
+        .filter(|w| is_meaningful(w))
+        .collect();
+    if mentioned.is_empty() {
+        return Verdict::Specific;
+    }
+
+    // 允許部分比對，例如 "login" 對應 "login_handler"
+    let overlaps = mentioned.iter().any(|word| {
+        keywords.iter().any(|k| {
+            k == word
+                || (k.len() >= 4 && word.contains(k.as_str()))
+                || (word.len() >= 4 && k.contains(word.as_str()))
+        })
+    });
+
+    if overlaps {
+        Verdict::Specific
+    } else {
+        Verdict::Unrelated
+    }
+}
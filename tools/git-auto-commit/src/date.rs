@@ -0,0 +1,83 @@
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+/// 解析時間字串（HH:MM、HH:MM:SS，或 12 小時制的 9am、6:30pm）
+fn parse_time(input: &str) -> Option<NaiveTime> {
+    if let Some((time, pm)) = input
+        .strip_suffix("am")
+        .map(|t| (t, false))
+        .or_else(|| input.strip_suffix("pm").map(|t| (t, true)))
+    {
+        let (hour, minute) = time.split_once(':').unwrap_or((time, "0"));
+        let hour: u32 = hour.parse().ok().filter(|h| (1..=12).contains(h))?;
+        let hour = hour % 12 + if pm { 12 } else { 0 };
+        return NaiveTime::from_hms_opt(hour, minute.parse().ok()?, 0);
+    }
+    NaiveTime::parse_from_str(input, "%H:%M")
+        .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M:%S"))
+        .ok()
+}
+
+/// 將本地日期與時間轉為 DateTime（遇到日光節約時間的模糊時刻取較早者）
+fn to_local(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Local>> {
+    Local.from_local_datetime(&date.and_time(time)).earliest()
+}
+
+/// 解析使用者輸入的 commit 時間
+///
+/// 支援格式：
+/// - `now`、`today 09:30`、`yesterday 18:00`、`18:00`、`9am`（今天）
+/// - `3 days ago`、`2 days ago 14:00`
+/// - `2024-01-01`、`2024-01-01 10:00`、`2024-01-01T10:00:00`
+/// - RFC 3339（例如 `2024-01-01T10:00:00+08:00`）
+pub fn parse_date(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let input = input.trim().to_lowercase();
+    let today = now.date_naive();
+    let parts: Vec<&str> = input.split_whitespace().collect();
+
+    let parsed = match parts.as_slice() {
+        ["now"] => Some(now),
+        ["today"] => Some(now),
+        ["yesterday"] => Some(now - Duration::days(1)),
+        ["today", time] => parse_time(time).and_then(|t| to_local(today, t)),
+        ["yesterday", time] => parse_time(time).and_then(|t| to_local(today - Duration::days(1), t)),
+        [n, "day" | "days", "ago"] => n.parse::<i64>().ok().map(|n| now - Duration::days(n)),
+        [n, "day" | "days", "ago", time] => n
+            .parse::<i64>()
+            .ok()
+            .zip(parse_time(time))
+            .and_then(|(n, t)| to_local(today - Duration::days(n), t)),
+        [n, "hour" | "hours", "ago"] => n.parse::<i64>().ok().map(|n| now - Duration::hours(n)),
+        [time] if parse_time(time).is_some() => parse_time(time).and_then(|t| to_local(today, t)),
+        _ => None,
+    };
+    if let Some(parsed) = parsed {
+        return Ok(parsed);
+    }
+
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(&input.to_uppercase()) {
+        return Ok(datetime.with_timezone(&Local));
+    }
+    for format in ["%Y-%m-%d %H:%M", "%Y-%m-%d %H:%M:%S", "%Y-%m-%dt%H:%M", "%Y-%m-%dt%H:%M:%S"] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(&input, format) {
+            if let Some(local) = Local.from_local_datetime(&datetime).earliest() {
+                return Ok(local);
+            }
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        if let Some(local) = to_local(date, now.time()) {
+            return Ok(local);
+        }
+    }
+
+    anyhow::bail!(
+        "無法解析時間「{}」，可使用例如 9am、yesterday 18:00、2 days ago、2024-01-01 10:00",
+        input
+    )
+}
+
+/// 格式化為 git 接受的日期格式（ISO 8601）
+pub fn to_git_date(date: &DateTime<Local>) -> String {
+    date.format("%Y-%m-%dT%H:%M:%S%z").to_string()
+}
@@ -0,0 +1,637 @@
+use crate::split::{FileChange, Group, Hunk, StagedChanges, Unit};
+use crate::GitSuggestions;
+use git2::Repository;
+use git_tools_core::git::file_versions;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// 套件生態系
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Go,
+    Pip,
+}
+
+impl Ecosystem {
+    pub fn name(self) -> &'static str {
+        match self {
+            Ecosystem::Cargo => "cargo",
+            Ecosystem::Npm => "npm",
+            Ecosystem::Go => "go",
+            Ecosystem::Pip => "pip",
+        }
+    }
+}
+
+/// 單一套件的版本變更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bump {
+    pub ecosystem: Ecosystem,
+    pub name: String,
+    pub from: String,
+    pub to: String,
+}
+
+impl Bump {
+    /// 是否為主版本升級（0.x 時以次版本判斷）
+    pub fn is_major(&self) -> bool {
+        let parts = |v: &str| -> Vec<u64> {
+            v.trim_start_matches(['^', '~', '=', '>', '<', 'v'])
+                .split(['.', '-', '+'])
+                .map_while(|p| p.parse().ok())
+                .collect()
+        };
+        let (from, to) = (parts(&self.from), parts(&self.to));
+        match (from.first(), to.first()) {
+            (Some(0), Some(0)) => from.get(1) != to.get(1),
+            (Some(a), Some(b)) => a != b,
+            _ => false,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("bump {} from {} to {}", self.name, self.from, self.to)
+    }
+}
+
+/// 依檔名判斷相依套件檔案的生態系（無法解析版本的 lock 檔也視為相依套件檔案）
+pub fn ecosystem_of(path: &str) -> Option<Ecosystem> {
+    let file_name = Path::new(path).file_name()?.to_str()?;
+    match file_name {
+        "Cargo.toml" | "Cargo.lock" => Some(Ecosystem::Cargo),
+        "package.json" | "package-lock.json" | "yarn.lock" | "pnpm-lock.yaml" => Some(Ecosystem::Npm),
+        "go.mod" | "go.sum" => Some(Ecosystem::Go),
+        "poetry.lock" | "Pipfile.lock" => Some(Ecosystem::Pip),
+        name if name.starts_with("requirements") && name.ends_with(".txt") => Some(Ecosystem::Pip),
+        _ => None,
+    }
+}
+
+static CARGO_TOML_DEP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*([A-Za-z0-9_-]+)\s*=\s*(?:"([^"]+)"|\{.*?\bversion\s*=\s*"([^"]+)")"#).unwrap()
+});
+static HUNK_START: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^@@ -(\d+)(?:,\d+)? \+(\d+)").unwrap());
+static CARGO_LOCK_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^name = "([^"]+)""#).unwrap());
+static CARGO_LOCK_VERSION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^version = "([^"]+)""#).unwrap());
+static PACKAGE_JSON_DEP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^\s*"([^"]+)"\s*:\s*"([~^>=<]*\d[^"]*)""#).unwrap());
+static JSON_OBJECT_START: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"^"([^"]+)"\s*:\s*\{$"#).unwrap());
+static GO_MOD_DEP: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*(?:require\s+)?([\w.~-]+(?:/[\w.~-]+)+)\s+(v\d[^\s]*)").unwrap());
+static REQUIREMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*([A-Za-z0-9_.\-\[\]]+)\s*==\s*([^\s;#]+)").unwrap());
+
+/// Cargo.toml 與 package.json 中不是相依套件的欄位
+const NON_DEPENDENCY_KEYS: &[&str] = &["version", "edition", "name", "rust-version", "license", "description"];
+
+/// Cargo.toml 中列出相依套件的 table：[dependencies]、[dev-dependencies]、[build-dependencies]、
+/// [workspace.dependencies] 與 [target.<平台>.dependencies] 等
+fn is_dependency_table(header: &str) -> bool {
+    const TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+    let name = header.trim().trim_start_matches('[').trim_end_matches(']').trim();
+    if TABLES.contains(&name) || name == "workspace.dependencies" {
+        return true;
+    }
+    name.strip_prefix("target.")
+        .and_then(|rest| rest.rsplit_once('.'))
+        .is_some_and(|(_, table)| TABLES.contains(&table))
+}
+
+/// Cargo.toml 中單一相依套件的 table（例如 [dependencies.serde]、[dev-dependencies.tokio]），回傳套件名稱
+fn dependency_subtable(header: &str) -> Option<&str> {
+    let name = header.trim().trim_start_matches('[').trim_end_matches(']').trim();
+    let (table, package) = name.rsplit_once('.')?;
+    is_dependency_table(table).then(|| package.trim().trim_matches('"'))
+}
+
+/// Cargo.toml 中一行的套件名稱與版本：相依套件 table 中的一行，或單一套件 table 中的 version
+fn cargo_entry(table: Option<&str>, line: &str) -> Option<(String, String)> {
+    let table = table?;
+    if is_dependency_table(table) {
+        return parse_line("Cargo.toml", line);
+    }
+    let package = dependency_subtable(table)?;
+    let caps = CARGO_TOML_DEP.captures(line)?;
+    let version = caps.get(2).filter(|_| &caps[1] == "version")?;
+    Some((package.to_string(), version.as_str().to_string()))
+}
+
+/// package.json 中第 line 行（從 1 起算）所在的物件，由外而內列出鍵（例如 ["dependencies"]）；
+/// 依 npm 的格式，每個物件的開頭與結尾各自一行
+fn json_path_at(content: &str, line: usize) -> Vec<&str> {
+    let mut path = Vec::new();
+    for l in content.lines().take(line.saturating_sub(1)).map(str::trim) {
+        if let Some(caps) = JSON_OBJECT_START.captures(l) {
+            path.push(caps.get(1).map_or("", |m| m.as_str()));
+        } else if l.starts_with('}') {
+            path.pop();
+        }
+    }
+    path
+}
+
+/// package.json 中列出相依套件的物件：dependencies、devDependencies、peerDependencies 等
+/// （不含 engines、overrides 等同樣是套件名稱對應版本的欄位）
+fn is_dependency_object(path: &[&str]) -> bool {
+    matches!(path, [key] if *key == "dependencies" || key.ends_with("Dependencies"))
+}
+
+/// 內容中第 line 行（從 1 起算）所在 table 的標頭
+fn table_at(content: &str, line: usize) -> Option<&str> {
+    content
+        .lines()
+        .take(line)
+        .map(str::trim)
+        .filter(|l| l.starts_with('[') && l.ends_with(']'))
+        .last()
+}
+
+/// 從 diff 的一行取出套件名稱與版本
+fn parse_line(file_name: &str, line: &str) -> Option<(String, String)> {
+    let caps = match file_name {
+        "Cargo.toml" => CARGO_TOML_DEP.captures(line)?,
+        "package.json" => PACKAGE_JSON_DEP.captures(line)?,
+        "go.mod" => GO_MOD_DEP.captures(line)?,
+        name if name.starts_with("requirements") => REQUIREMENT.captures(line)?,
+        _ => return None,
+    };
+    let name = caps.get(1)?.as_str().to_string();
+    if NON_DEPENDENCY_KEYS.contains(&name.as_str()) {
+        return None;
+    }
+    let version = caps.get(2).or_else(|| caps.get(3))?.as_str().to_string();
+    Some((name, version))
+}
+
+/// 比對同一個檔案中移除與新增的版本
+fn pair(ecosystem: Ecosystem, removed: Vec<(String, String)>, added: Vec<(String, String)>) -> Vec<Bump> {
+    let mut bumps = Vec::new();
+    let mut added = added;
+    for (name, from) in removed {
+        if let Some(pos) = added.iter().position(|(n, _)| *n == name) {
+            let (_, to) = added.remove(pos);
+            if from != to {
+                bumps.push(Bump { ecosystem, name, from, to });
+            }
+        }
+    }
+    bumps
+}
+
+/// 解析單一檔案的 diff 區塊；`versions` 為檔案在 HEAD 與 staging area 的內容，
+/// 用來判斷 Cargo.toml 的每一行位於哪個 table、package.json 的每一行位於哪個物件
+fn parse_file(path: &str, lines: &[&str], versions: (&str, &str)) -> Vec<Bump> {
+    let Some(ecosystem) = ecosystem_of(path) else {
+        return Vec::new();
+    };
+    let file_name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+
+    // Cargo.lock 的版本與套件名稱在不同行，需記住最近的 name
+    let mut current_name: Option<String> = None;
+    // 下一行在 HEAD 與 staging area 中的行號
+    let (mut old_line, mut new_line) = (0, 0);
+    for line in lines.iter().skip_while(|l| !l.starts_with("@@")) {
+        if let Some(caps) = HUNK_START.captures(line) {
+            old_line = caps[1].parse().unwrap_or(0);
+            new_line = caps[2].parse().unwrap_or(0);
+            continue;
+        }
+        let (sign, content) = match line.chars().next() {
+            Some(c @ ('+' | '-' | ' ')) => (c, &line[1..]),
+            _ => continue,
+        };
+        let (snapshot, line_no) = match sign {
+            '-' => (versions.0, old_line),
+            _ => (versions.1, new_line),
+        };
+        if sign != '+' {
+            old_line += 1;
+        }
+        if sign != '-' {
+            new_line += 1;
+        }
+
+        let entry = if file_name == "Cargo.lock" {
+            if let Some(caps) = CARGO_LOCK_NAME.captures(content) {
+                current_name = Some(caps[1].to_string());
+                continue;
+            }
+            match (CARGO_LOCK_VERSION.captures(content), &current_name) {
+                (Some(caps), Some(name)) => Some((name.clone(), caps[1].to_string())),
+                _ => None,
+            }
+        } else if file_name == "Cargo.toml" {
+            cargo_entry(table_at(snapshot, line_no), content)
+        } else if file_name == "package.json" {
+            if is_dependency_object(&json_path_at(snapshot, line_no)) {
+                parse_line(file_name, content)
+            } else {
+                None
+            }
+        } else {
+            parse_line(file_name, content)
+        };
+
+        match (sign, entry) {
+            ('-', Some(entry)) => removed.push(entry),
+            ('+', Some(entry)) => added.push(entry),
+            _ => {}
+        }
+    }
+
+    pair(ecosystem, removed, added)
+}
+
+/// 從 staged diff（--no-prefix 格式）找出所有套件版本變更；同一套件以 lock 檔的確切版本為準。
+/// `versions` 取得 Cargo.toml、package.json 在 HEAD 與 staging area 的內容
+fn detect_bumps(diff: &str, versions: impl Fn(&str) -> (String, String)) -> Vec<Bump> {
+    let mut bumps: Vec<Bump> = Vec::new();
+    let lines: Vec<&str> = diff.lines().collect();
+    let starts: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.starts_with("diff --git "))
+        .map(|(i, _)| i)
+        .collect();
+
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+        let section = &lines[start..end];
+        let Some(path) = section
+            .iter()
+            .take_while(|l| !l.starts_with("@@"))
+            .find_map(|l| l.strip_prefix("+++ "))
+        else {
+            continue;
+        };
+
+        let is_lock = path.ends_with(".lock");
+        let (old, new) = if path.ends_with("Cargo.toml") || path.ends_with("package.json") {
+            versions(path)
+        } else {
+            Default::default()
+        };
+        for bump in parse_file(path, section, (&old, &new)) {
+            match bumps.iter().position(|b| b.ecosystem == bump.ecosystem && b.name == bump.name) {
+                Some(pos) if is_lock => bumps[pos] = bump,
+                Some(_) => {}
+                None => bumps.push(bump),
+            }
+        }
+    }
+
+    bumps
+}
+
+/// 多個套件更新時的 commit 訊息：標題列出數量，內文逐一列出
+pub fn commit_message(bumps: &[Bump]) -> String {
+    match bumps {
+        [bump] => format!("chore(deps): {}", bump.describe()),
+        _ => {
+            let body: Vec<String> = bumps.iter().map(|b| format!("- {}", b.describe())).collect();
+            format!("chore(deps): bump {} dependencies\n\n{}", bumps.len(), body.join("\n"))
+        }
+    }
+}
+
+fn branch_name(bumps: &[Bump]) -> String {
+    match bumps {
+        [bump] => {
+            let name = crate::branch::slugify(&bump.name);
+            format!("chore/bump-{}-{}", name, bump.to.trim_start_matches(['^', '~', '=', 'v']))
+        }
+        _ => "chore/bump-dependencies".to_string(),
+    }
+}
+
+/// 所有 staged 檔案都是相依套件檔案且有版本變更時，回傳版本變更（可不呼叫 LLM 直接產生訊息）
+pub fn detect(repo: &Repository, diff: &str, files: &[String]) -> Option<Vec<Bump>> {
+    if files.is_empty() || !files.iter().all(|f| ecosystem_of(f).is_some()) {
+        return None;
+    }
+    let bumps = detect_bumps(diff, |path| file_versions(repo, path));
+    (!bumps.is_empty()).then_some(bumps)
+}
+
+/// 依版本變更產生的建議
+pub fn suggestions(bumps: &[Bump]) -> GitSuggestions {
+    GitSuggestions {
+        branch_names: vec![branch_name(bumps)],
+        commit_messages: vec![commit_message(bumps)],
+        commit_notes: Vec::new(),
+        commit_labels: Vec::new(),
+        analysis: None,
+        review: None,
+    }
+}
+
+/// 多個套件更新時的分組方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// 每個生態系（cargo、npm…）一個 commit
+    Ecosystem,
+    /// 每個主版本升級各一個 commit，其餘更新合併為一個 commit
+    Major,
+}
+
+/// lock 檔中標示套件名稱的行（hunk 本身不含名稱時往上尋找）
+static LOCK_PACKAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(?:name = "([^"]+)"|\s*"(?:.*node_modules/)?(@?[^"]+)": \{|"?(@?[^@\s"]+)@[^:]*:$|(\S+) v\d\S* )"#).unwrap()
+});
+
+fn lock_package(line: &str) -> Option<String> {
+    let caps = LOCK_PACKAGE.captures(line)?;
+    (1..=4).find_map(|i| caps.get(i)).map(|m| m.as_str().to_string())
+}
+
+/// 判斷 hunk 屬於哪個套件
+fn hunk_package(change: &FileChange, hunk: &Hunk) -> Option<String> {
+    let file_name = Path::new(&change.path).file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let lines: Vec<&str> = hunk.removed.iter().map(String::as_str).chain(hunk.added_lines()).collect();
+    if let Some((name, _)) = lines.iter().find_map(|line| parse_line(file_name, line)) {
+        return Some(name);
+    }
+    if !(file_name.contains("lock") || file_name == "go.sum") {
+        return None;
+    }
+    if let Some(name) = lines.iter().find_map(|line| lock_package(line)) {
+        return Some(name);
+    }
+
+    // 往上尋找最近的套件名稱（例如 Cargo.lock 的 name、package-lock.json 的 node_modules/<name>）
+    let original: Vec<&str> = change.original.lines().collect();
+    let from = hunk.old_start.min(original.len());
+    original[..from].iter().rev().find_map(|line| lock_package(line))
+}
+
+/// 依分組方式將 staged 變更分成多個 commit，每組的訊息列出組內的版本變更
+pub fn plan(grouping: Grouping, bumps: &[Bump], changes: &StagedChanges) -> Vec<Group> {
+    let message = |group_bumps: &[&Bump], fallback: String| {
+        if group_bumps.is_empty() {
+            fallback
+        } else {
+            let owned: Vec<Bump> = group_bumps.iter().map(|b| (*b).clone()).collect();
+            commit_message(&owned)
+        }
+    };
+
+    match grouping {
+        Grouping::Ecosystem => {
+            let mut ecosystems: Vec<Ecosystem> = Vec::new();
+            for change in &changes.files {
+                if let Some(ecosystem) = ecosystem_of(&change.path) {
+                    if !ecosystems.contains(&ecosystem) {
+                        ecosystems.push(ecosystem);
+                    }
+                }
+            }
+            ecosystems
+                .into_iter()
+                .map(|ecosystem| {
+                    let group_bumps: Vec<&Bump> = bumps.iter().filter(|b| b.ecosystem == ecosystem).collect();
+                    let units = changes
+                        .files
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, change)| ecosystem_of(&change.path) == Some(ecosystem))
+                        .map(|(file, _)| Unit { file, hunk: None })
+                        .collect();
+                    Group {
+                        message: message(&group_bumps, format!("chore(deps): update {} dependencies", ecosystem.name())),
+                        units,
+                    }
+                })
+                .collect()
+        }
+        Grouping::Major => {
+            let majors: Vec<&Bump> = bumps.iter().filter(|b| b.is_major()).collect();
+            let others: Vec<&Bump> = bumps.iter().filter(|b| !b.is_major()).collect();
+
+            // 每個 hunk 依所屬套件分到主版本升級的組，無法判斷或非主版本升級者歸入其他更新
+            let mut major_units: Vec<Vec<Unit>> = vec![Vec::new(); majors.len()];
+            let mut other_units: Vec<Unit> = Vec::new();
+            for (file, change) in changes.files.iter().enumerate() {
+                let ecosystem = ecosystem_of(&change.path);
+                if change.hunks.is_empty() {
+                    other_units.push(Unit { file, hunk: None });
+                }
+                for (i, hunk) in change.hunks.iter().enumerate() {
+                    let unit = Unit { file, hunk: Some(i) };
+                    let package = hunk_package(change, hunk);
+                    let major = majors
+                        .iter()
+                        .position(|b| Some(b.ecosystem) == ecosystem && Some(&b.name) == package.as_ref());
+                    match major {
+                        Some(index) => major_units[index].push(unit),
+                        None => other_units.push(unit),
+                    }
+                }
+            }
+
+            let mut groups = Vec::new();
+            if !other_units.is_empty() {
+                groups.push(Group {
+                    message: message(&others, "chore(deps): update lock files".to_string()),
+                    units: other_units,
+                });
+            }
+            for (bump, units) in majors.into_iter().zip(major_units) {
+                if !units.is_empty() {
+                    groups.push(Group {
+                        message: commit_message(std::slice::from_ref(bump)),
+                        units,
+                    });
+                }
+            }
+            groups
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bump(ecosystem: Ecosystem, name: &str, from: &str, to: &str) -> Bump {
+        Bump {
+            ecosystem,
+            name: name.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    #[test]
+    fn ecosystem_of_recognizes_manifests_and_lock_files() {
+        assert_eq!(ecosystem_of("crates/core/Cargo.toml"), Some(Ecosystem::Cargo));
+        assert_eq!(ecosystem_of("web/pnpm-lock.yaml"), Some(Ecosystem::Npm));
+        assert_eq!(ecosystem_of("go.sum"), Some(Ecosystem::Go));
+        assert_eq!(ecosystem_of("requirements-dev.txt"), Some(Ecosystem::Pip));
+        assert_eq!(ecosystem_of("src/main.rs"), None);
+    }
+
+    #[test]
+    fn is_major_uses_minor_version_below_one() {
+        assert!(bump(Ecosystem::Cargo, "serde", "1.0.200", "2.0.0").is_major());
+        assert!(!bump(Ecosystem::Npm, "react", "^18.2.0", "^18.3.1").is_major());
+        assert!(bump(Ecosystem::Cargo, "ureq", "0.11", "0.12").is_major());
+        assert!(!bump(Ecosystem::Go, "x/net", "v0.20.0", "v0.20.1").is_major());
+    }
+
+    #[test]
+    fn is_dependency_table_accepts_target_and_workspace_tables() {
+        assert!(is_dependency_table("[dependencies]"));
+        assert!(is_dependency_table("[workspace.dependencies]"));
+        assert!(is_dependency_table("[target.'cfg(unix)'.dev-dependencies]"));
+        assert!(!is_dependency_table("[package]"));
+        assert!(!is_dependency_table("[features]"));
+    }
+
+    #[test]
+    fn dependency_subtable_returns_package_name() {
+        assert_eq!(dependency_subtable("[dependencies.serde]"), Some("serde"));
+        assert_eq!(dependency_subtable("[dev-dependencies.tokio]"), Some("tokio"));
+        assert_eq!(dependency_subtable("[target.'cfg(unix)'.dependencies.libc]"), Some("libc"));
+        assert_eq!(dependency_subtable("[dependencies]"), None);
+        assert_eq!(dependency_subtable("[package.metadata]"), None);
+    }
+
+    #[test]
+    fn json_path_at_tracks_nested_objects() {
+        let content = "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"react\": \"^18.2.0\"\n  },\n  \"engines\": {\n    \"node\": \">=18\"\n  }\n}\n";
+        assert_eq!(json_path_at(content, 2), Vec::<&str>::new());
+        assert_eq!(json_path_at(content, 4), vec!["dependencies"]);
+        assert_eq!(json_path_at(content, 7), vec!["engines"]);
+        assert!(is_dependency_object(&["devDependencies"]));
+        assert!(!is_dependency_object(&["engines"]));
+        assert!(!is_dependency_object(&["overrides", "dependencies"]));
+    }
+
+    #[test]
+    fn table_at_returns_nearest_header() {
+        let content = "[package]\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n";
+        assert_eq!(table_at(content, 2), Some("[package]"));
+        assert_eq!(table_at(content, 5), Some("[dependencies]"));
+        assert_eq!(table_at("name = \"x\"", 1), None);
+    }
+
+    #[test]
+    fn parse_line_reads_each_format() {
+        let entry = |name: &str, version: &str| Some((name.to_string(), version.to_string()));
+        assert_eq!(parse_line("Cargo.toml", r#"tokio = { version = "1.38", features = ["full"] }"#), entry("tokio", "1.38"));
+        assert_eq!(parse_line("Cargo.toml", r#"version = "0.2.0""#), None);
+        assert_eq!(parse_line("package.json", r#"    "react": "^18.3.1","#), entry("react", "^18.3.1"));
+        assert_eq!(parse_line("go.mod", "\tgolang.org/x/net v0.21.0"), entry("golang.org/x/net", "v0.21.0"));
+        assert_eq!(parse_line("requirements.txt", "requests==2.32.0  # http"), entry("requests", "2.32.0"));
+    }
+
+    #[test]
+    fn detect_bumps_ignores_package_version() {
+        let diff = "\
+diff --git Cargo.toml Cargo.toml
+--- Cargo.toml
++++ Cargo.toml
+@@ -1,5 +1,5 @@
+ [package]
+-version = \"0.1.0\"
++version = \"0.2.0\"
+ 
+ [dependencies]
+-serde = \"1.0.100\"
++serde = \"1.0.200\"
+";
+        let old = "[package]\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1.0.100\"\n";
+        let new = "[package]\nversion = \"0.2.0\"\n\n[dependencies]\nserde = \"1.0.200\"\n";
+        let bumps = detect_bumps(diff, |_| (old.to_string(), new.to_string()));
+        assert_eq!(bumps, vec![bump(Ecosystem::Cargo, "serde", "1.0.100", "1.0.200")]);
+    }
+
+    #[test]
+    fn detect_bumps_reads_dependency_subtables() {
+        let diff = "\
+diff --git Cargo.toml Cargo.toml
+--- Cargo.toml
++++ Cargo.toml
+@@ -5,3 +5,3 @@
+ [dev-dependencies.tokio]
+-version = \"1.37\"
++version = \"1.38\"
+ features = [\"full\"]
+";
+        let old = "[package]\nname = \"app\"\nversion = \"0.1.0\"\n\n[dev-dependencies.tokio]\nversion = \"1.37\"\nfeatures = [\"full\"]\n";
+        let new = old.replace("1.37", "1.38");
+        let bumps = detect_bumps(diff, |_| (old.to_string(), new.clone()));
+        assert_eq!(bumps, vec![bump(Ecosystem::Cargo, "tokio", "1.37", "1.38")]);
+    }
+
+    #[test]
+    fn detect_bumps_limits_package_json_to_dependency_objects() {
+        let diff = "\
+diff --git package.json package.json
+--- package.json
++++ package.json
+@@ -3,11 +3,11 @@
+   \"dependencies\": {
+-    \"react\": \"^18.2.0\"
++    \"react\": \"^18.3.1\"
+   },
+   \"engines\": {
+-    \"node\": \"18\"
++    \"node\": \"20\"
+   },
+   \"overrides\": {
+-    \"semver\": \"7.5.2\"
++    \"semver\": \"7.5.4\"
+   }
+";
+        let old = "{\n  \"name\": \"app\",\n  \"dependencies\": {\n    \"react\": \"^18.2.0\"\n  },\n  \"engines\": {\n    \"node\": \"18\"\n  },\n  \"overrides\": {\n    \"semver\": \"7.5.2\"\n  }\n}\n";
+        let new = old.replace("^18.2.0", "^18.3.1").replace("\"18\"", "\"20\"").replace("7.5.2", "7.5.4");
+        let bumps = detect_bumps(diff, |_| (old.to_string(), new.clone()));
+        assert_eq!(bumps, vec![bump(Ecosystem::Npm, "react", "^18.2.0", "^18.3.1")]);
+    }
+
+    #[test]
+    fn detect_bumps_prefers_lock_file_versions() {
+        let diff = "\
+diff --git Cargo.toml Cargo.toml
+--- Cargo.toml
++++ Cargo.toml
+@@ -2,1 +2,1 @@
+-serde = \"1.0\"
++serde = \"1.1\"
+diff --git Cargo.lock Cargo.lock
+--- Cargo.lock
++++ Cargo.lock
+@@ -10,3 +10,3 @@
+ name = \"serde\"
+-version = \"1.0.100\"
++version = \"1.1.3\"
+";
+        let versions = |_: &str| ("[dependencies]\nserde = \"1.0\"\n".to_string(), "[dependencies]\nserde = \"1.1\"\n".to_string());
+        assert_eq!(detect_bumps(diff, versions), vec![bump(Ecosystem::Cargo, "serde", "1.0.100", "1.1.3")]);
+    }
+
+    #[test]
+    fn commit_message_lists_multiple_bumps() {
+        let single = [bump(Ecosystem::Cargo, "serde", "1.0.100", "1.0.200")];
+        assert_eq!(commit_message(&single), "chore(deps): bump serde from 1.0.100 to 1.0.200");
+        let multiple = [single[0].clone(), bump(Ecosystem::Npm, "react", "18.2.0", "18.3.1")];
+        assert_eq!(
+            commit_message(&multiple),
+            "chore(deps): bump 2 dependencies\n\n- bump serde from 1.0.100 to 1.0.200\n- bump react from 18.2.0 to 18.3.1"
+        );
+    }
+
+    #[test]
+    fn lock_package_reads_lock_file_headers() {
+        assert_eq!(lock_package(r#"name = "serde""#).as_deref(), Some("serde"));
+        assert_eq!(lock_package(r#"    "node_modules/@types/node": {"#).as_deref(), Some("@types/node"));
+        assert_eq!(lock_package("react@^18.2.0:").as_deref(), Some("react"));
+        assert_eq!(lock_package(r#"version = "1.0.0""#), None);
+    }
+}
@@ -0,0 +1,228 @@
+use crate::config::{get_config_path, LlmConfig};
+use crate::hooks;
+use crate::layout;
+use crate::progress::Timeline;
+use anyhow::Result;
+use colored::*;
+use git2::Repository;
+use regex::Regex;
+use std::env;
+use std::fs;
+use std::process::Command;
+
+/// 單項檢查的結果
+enum Status {
+    Ok(String),
+    /// 不影響使用，但可能不符合預期
+    Warn(String, String),
+    /// 需要修正，附上修正方式
+    Fail(String, String),
+}
+
+/// 顯示檢查結果，回傳是否失敗
+fn report(name: &str, status: Status) -> bool {
+    match status {
+        Status::Ok(detail) => {
+            println!("{}{}", format!("✓ {}：", name).green(), detail);
+            false
+        }
+        Status::Warn(detail, fix) => {
+            println!("{}{}", format!("⚠️  {}：", name).yellow(), detail);
+            println!("{}", format!("   → {}", fix).dimmed());
+            false
+        }
+        Status::Fail(detail, fix) => {
+            println!("{}{}", format!("✗ {}：", name).red(), detail);
+            println!("{}", format!("   → {}", fix).dimmed());
+            true
+        }
+    }
+}
+
+fn check_git() -> Status {
+    let libgit2 = git2::Version::get().libgit2_version();
+    let libgit2 = format!("libgit2 {}.{}.{}", libgit2.0, libgit2.1, libgit2.2);
+    match Command::new("git").arg("--version").output() {
+        Ok(output) if output.status.success() => Status::Ok(format!(
+            "{}，{}",
+            String::from_utf8_lossy(&output.stdout).trim(),
+            libgit2
+        )),
+        _ => Status::Fail(
+            format!("找不到 git 指令（{} 可用）", libgit2),
+            "請安裝 git 並確認已加入 PATH，commit 與分支操作需要 git 指令".to_string(),
+        ),
+    }
+}
+
+fn check_repo() -> (Status, Option<Repository>) {
+    let Ok(current_dir) = env::current_dir() else {
+        return (
+            Status::Fail("無法取得當前目錄".to_string(), "請確認當前目錄存在且有讀取權限".to_string()),
+            None,
+        );
+    };
+    match Repository::open(&current_dir) {
+        Ok(repo) if repo.is_bare() => (
+            Status::Fail(
+                "當前目錄是 bare repository".to_string(),
+                "請在有工作目錄的 repository 中執行".to_string(),
+            ),
+            None,
+        ),
+        Ok(repo) => {
+            let branch = repo
+                .head()
+                .ok()
+                .and_then(|h| h.shorthand().map(String::from))
+                .unwrap_or_else(|| "（尚無 commit）".to_string());
+            (Status::Ok(format!("{}（分支：{}）", current_dir.display(), branch)), Some(repo))
+        }
+        Err(_) => (
+            Status::Fail(
+                format!("{} 不是 Git repository 的根目錄", current_dir.display()),
+                "請切換到 repository 根目錄執行，或使用 git init 建立".to_string(),
+            ),
+            None,
+        ),
+    }
+}
+
+fn check_config() -> (Status, LlmConfig) {
+    let path = get_config_path();
+    if !path.exists() {
+        return (
+            Status::Warn(
+                format!("{} 不存在，使用預設設定", path.display()),
+                "執行 git-auto-commit setup 建立設定檔".to_string(),
+            ),
+            LlmConfig::default(),
+        );
+    }
+
+    let config = match fs::read_to_string(&path) {
+        Ok(content) => match toml::from_str::<LlmConfig>(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                return (
+                    Status::Fail(
+                        format!("{} 格式錯誤：{}", path.display(), e.message()),
+                        "請依 config.toml.example 修正，或執行 git-auto-commit setup 重新建立".to_string(),
+                    ),
+                    LlmConfig::default(),
+                )
+            }
+        },
+        Err(e) => {
+            return (
+                Status::Fail(
+                    format!("無法讀取 {}：{}", path.display(), e),
+                    "請確認檔案權限".to_string(),
+                ),
+                LlmConfig::default(),
+            )
+        }
+    };
+
+    if let Err(e) = Regex::new(&config.ticket_pattern) {
+        return (
+            Status::Fail(
+                format!("ticket_pattern 不是有效的正規表達式：{}", e),
+                "請修正設定檔中的 ticket_pattern".to_string(),
+            ),
+            config,
+        );
+    }
+    if !config.pricing.contains_key(&config.model) {
+        return (
+            Status::Warn(
+                format!("價格表中沒有模型 {} 的價格，無法估算花費", config.model),
+                format!("在設定檔加入 [pricing.\"{}\"]", config.model),
+            ),
+            config,
+        );
+    }
+
+    (Status::Ok(path.display().to_string()), config)
+}
+
+fn check_llm(config: &LlmConfig) -> Status {
+    let mut timeline = Timeline::new();
+    timeline.start(&format!("測試 {} 連線", config.command));
+    match crate::run_llm_command("請只回覆 OK", config) {
+        Ok(response) => {
+            timeline.finish();
+            let preview = layout::truncate(response.lines().next().unwrap_or(""), 40);
+            Status::Ok(format!("{}（{}）回應：{}", config.command, config.model, preview))
+        }
+        Err(e) => {
+            timeline.fail();
+            Status::Fail(
+                format!("{:#}", e),
+                format!(
+                    "請確認已安裝 {} 並完成登入，或在設定檔調整 command、model 等參數",
+                    config.command
+                ),
+            )
+        }
+    }
+}
+
+fn check_hooks(repo: &Repository) -> Status {
+    let hooks_dir = hooks::resolve_hooks_dir(repo);
+    if hooks_dir.from_config && !hooks_dir.path.is_dir() {
+        return Status::Fail(
+            format!("core.hooksPath 指向的目錄不存在：{}", hooks_dir.path.display()),
+            "請重新安裝 hooks（例如 npx husky、lefthook install），或移除 core.hooksPath 設定".to_string(),
+        );
+    }
+
+    let inactive = hooks::inactive_commit_hooks(&hooks_dir);
+    if !inactive.is_empty() {
+        return Status::Warn(
+            format!("{} 沒有執行權限，commit 時不會執行", inactive.join("、")),
+            format!(
+                "chmod +x {}",
+                inactive
+                    .iter()
+                    .map(|name| hooks_dir.path.join(name).display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+        );
+    }
+
+    let active = hooks::active_commit_hooks(&hooks_dir);
+    if active.is_empty() {
+        Status::Ok(format!("沒有 commit hooks（{}）", hooks_dir.path.display()))
+    } else {
+        Status::Ok(format!("{}（{}）", active.join("、"), hooks_dir.path.display()))
+    }
+}
+
+/// doctor 子指令：檢查執行環境並列出修正方式，有失敗項目時以結束代碼 1 結束
+pub fn run() -> Result<()> {
+    println!("\n{}\n", "🩺 環境檢查".cyan().bold());
+
+    let mut failed = report("Git", check_git());
+
+    let (status, repo) = check_repo();
+    failed |= report("Repository", status);
+
+    let (status, config) = check_config();
+    failed |= report("設定檔", status);
+
+    failed |= report("LLM", check_llm(&config));
+
+    if let Some(repo) = &repo {
+        failed |= report("Hooks", check_hooks(repo));
+    }
+
+    println!();
+    if failed {
+        println!("{}", "✗ 部分檢查未通過，請依上方說明修正".red());
+        std::process::exit(1);
+    }
+    println!("{}", "✓ 所有檢查通過".green());
+    Ok(())
+}
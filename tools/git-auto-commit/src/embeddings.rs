@@ -0,0 +1,234 @@
+use crate::config::EmbeddingsConfig;
+use crate::encoding;
+use crate::progress::Timeline;
+use anyhow::{Context, Result};
+use colored::*;
+use git2::{DiffFormat, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+/// 每個 commit 送出的 diff 字元數上限
+const MAX_DIFF_CHARS: usize = 2000;
+/// 每次 API 請求送出的 commit 數
+const BATCH_SIZE: usize = 32;
+
+/// 索引中的單一 commit
+#[derive(Deserialize, Serialize)]
+struct IndexEntry {
+    sha: String,
+    message: String,
+    vector: Vec<f32>,
+}
+
+/// commit 的 embeddings 索引（儲存於 .git/git-auto-commit/embeddings.json）
+#[derive(Deserialize, Serialize, Default)]
+struct Index {
+    /// 產生向量的模型，更換模型時重建索引
+    model: String,
+    entries: Vec<IndexEntry>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+fn get_index_path(repo: &Repository) -> PathBuf {
+    repo.path().join("git-auto-commit").join("embeddings.json")
+}
+
+fn load_index(repo: &Repository, model: &str) -> Index {
+    fs::read_to_string(get_index_path(repo))
+        .ok()
+        .and_then(|content| serde_json::from_str::<Index>(&content).ok())
+        .filter(|index| index.model == model)
+        .unwrap_or_else(|| Index {
+            model: model.to_string(),
+            entries: Vec::new(),
+        })
+}
+
+fn save_index(repo: &Repository, index: &Index) -> Result<()> {
+    let path = get_index_path(repo);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("無法建立索引目錄")?;
+    }
+    let content = serde_json::to_string(index).context("無法序列化 embeddings 索引")?;
+    fs::write(&path, content).context(format!("無法寫入 embeddings 索引：{}", path.display()))
+}
+
+/// 送去計算向量的內容：變更的檔案與前段的 diff
+fn document(files: &[String], diff: &str) -> String {
+    let diff: String = diff.chars().take(MAX_DIFF_CHARS).collect();
+    format!("{}\n\n{}", files.join("\n"), diff)
+}
+
+/// 向 OpenAI 相容的 /embeddings API（例如 OpenAI、Ollama）取得向量
+fn embed(config: &EmbeddingsConfig, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+    let url = format!("{}/embeddings", config.api_url.trim_end_matches('/'));
+    let mut request = ureq::post(&url).set("User-Agent", "git-auto-commit");
+    if let Some(name) = &config.api_key_env {
+        let key = env::var(name)
+            .ok()
+            .filter(|key| !key.is_empty())
+            .context(format!("未設定 {} 環境變數", name))?;
+        request = request.set("Authorization", &format!("Bearer {}", key));
+    }
+
+    let response: EmbeddingResponse = request
+        .send_json(json!({ "model": config.model, "input": inputs }))
+        .map_err(|e| match e {
+            ureq::Error::Status(code, response) => {
+                anyhow::anyhow!("embeddings API 錯誤（{}）：{}", code, response.into_string().unwrap_or_default())
+            }
+            ureq::Error::Transport(e) => anyhow::anyhow!("無法連線到 embeddings API：{}", e),
+        })?
+        .into_json()
+        .context("無法解析 embeddings API 回應")?;
+
+    let mut data = response.data;
+    data.sort_by_key(|d| d.index);
+    if data.len() != inputs.len() {
+        anyhow::bail!("embeddings API 回傳 {} 個向量，預期 {} 個", data.len(), inputs.len());
+    }
+    Ok(data.into_iter().map(|d| d.embedding).collect())
+}
+
+/// HEAD 上最近的 commit（不含合併 commit）中尚未建立索引者：(sha, 訊息, 送去計算向量的內容)；
+/// 淺層 clone 的邊界 commit（父 commit 不在本地）與 partial clone 中缺少 tree 的 commit 會略過
+fn unindexed_commits(repo: &Repository, index: &Index, limit: usize) -> Result<Vec<(String, String, String)>> {
+    let indexed: HashSet<&str> = index.entries.iter().map(|e| e.sha.as_str()).collect();
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    walk.set_sorting(Sort::TIME)?;
+
+    let mut commits = Vec::new();
+    for oid in walk.take(limit) {
+        let commit = repo.find_commit(oid?)?;
+        if commit.parent_count() > 1 || indexed.contains(commit.id().to_string().as_str()) {
+            continue;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => match parent.tree() {
+                Ok(tree) => Some(tree),
+                Err(_) => continue,
+            },
+            Err(_) if commit.parent_count() > 0 => continue,
+            Err(_) => None,
+        };
+        let Ok(tree) = commit.tree() else {
+            continue;
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        let files: Vec<String> = diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.display().to_string())
+            .collect();
+        let mut patch = String::new();
+        diff.print(DiffFormat::Patch, |_, _, line| {
+            if matches!(line.origin(), '+' | '-' | ' ') {
+                patch.push(line.origin());
+            }
+            patch.push_str(&String::from_utf8_lossy(line.content()));
+            patch.chars().count() < MAX_DIFF_CHARS
+        })
+        .ok();
+        commits.push((
+            commit.id().to_string(),
+            encoding::message(&commit).trim().to_string(),
+            document(&files, &patch),
+        ));
+    }
+    Ok(commits)
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+/// 更新索引後，找出與 staged 變更最相似的 top_k 個過去 commit，回傳其訊息
+pub fn similar_messages(
+    repo: &Repository,
+    config: &EmbeddingsConfig,
+    files: &[String],
+    diff: &str,
+    timeline: &mut Timeline,
+) -> Result<Vec<String>> {
+    let mut index = load_index(repo, &config.model);
+    let pending = unindexed_commits(repo, &index, config.index_limit)?;
+    if !pending.is_empty() {
+        timeline.start(&format!("更新 embeddings 索引（{} 個 commit）", pending.len()));
+        for batch in pending.chunks(BATCH_SIZE) {
+            let inputs: Vec<String> = batch.iter().map(|(_, _, text)| text.clone()).collect();
+            let vectors = match embed(config, &inputs) {
+                Ok(vectors) => vectors,
+                Err(e) => {
+                    timeline.fail();
+                    // 保留已完成的部分，下次從中斷處繼續
+                    save_index(repo, &index)?;
+                    return Err(e);
+                }
+            };
+            for ((sha, message, _), vector) in batch.iter().zip(vectors) {
+                index.entries.push(IndexEntry {
+                    sha: sha.clone(),
+                    message: message.clone(),
+                    vector,
+                });
+            }
+        }
+        save_index(repo, &index)?;
+        timeline.finish();
+    }
+    if index.entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    timeline.start("搜尋相似的 commit");
+    let query = match embed(config, &[document(files, diff)]) {
+        Ok(mut vectors) => vectors.remove(0),
+        Err(e) => {
+            timeline.fail();
+            return Err(e);
+        }
+    };
+    let mut scored: Vec<(f32, &IndexEntry)> = index
+        .entries
+        .iter()
+        .map(|entry| (cosine(&query, &entry.vector), entry))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    timeline.finish();
+
+    let mut messages: Vec<String> = Vec::new();
+    for (score, entry) in scored {
+        if messages.len() == config.top_k {
+            break;
+        }
+        if entry.message.is_empty() || messages.contains(&entry.message) {
+            continue;
+        }
+        let subject = entry.message.lines().next().unwrap_or("");
+        println!("{}", format!("  {} {}（相似度 {:.2}）", &entry.sha[..7], subject, score).dimmed());
+        messages.push(entry.message.clone());
+    }
+    Ok(messages)
+}
@@ -0,0 +1,150 @@
+use colored::*;
+use encoding_rs::{Encoding, UTF_8};
+use git2::Commit;
+use git_tools_core::git::config_value;
+use std::env;
+
+/// 無法表示的字元最多列出的數量
+const MAX_LISTED_CHARS: usize = 10;
+
+/// 依 i18n.commitEncoding 寫入 commit 物件的訊息
+pub enum Encoded {
+    /// UTF-8（git 的預設，不加 encoding 標頭）
+    Utf8,
+    /// 已轉為設定的編碼，git 會加上對應的 encoding 標頭
+    Converted(&'static Encoding, Vec<u8>),
+    /// 設定的編碼無法辨識或無法表示訊息中的字元，改以 UTF-8 寫入；
+    /// commit 時需覆寫設定，避免 encoding 標頭與實際內容不符
+    Fallback,
+}
+
+/// 依 i18n.commitEncoding 轉換訊息的編碼（git 不會轉換 -m 或 -F 的內容，只會照設定加上標頭）
+pub fn encode(message: &str) -> Encoded {
+    match config_value("i18n.commitEncoding") {
+        Some(label) => encode_as(&label, message),
+        None => Encoded::Utf8,
+    }
+}
+
+/// 將訊息轉為 label 指定的編碼
+fn encode_as(label: &str, message: &str) -> Encoded {
+    // UTF-16 等編碼的輸出會是 UTF-8，git 也不支援以這類編碼儲存訊息
+    let Some(encoding) = Encoding::for_label(label.trim().as_bytes()).filter(|e| e.output_encoding() == *e) else {
+        println!("{}", format!("⚠️  無法使用 i18n.commitEncoding 的編碼 {}，訊息改以 UTF-8 寫入", label).yellow());
+        return Encoded::Fallback;
+    };
+    if encoding == UTF_8 {
+        return Encoded::Utf8;
+    }
+
+    let (bytes, _, unmappable) = encoding.encode(message);
+    if unmappable {
+        let mut chars: Vec<char> = message
+            .chars()
+            .filter(|c| encoding.encode(c.encode_utf8(&mut [0; 4])).2)
+            .collect();
+        chars.dedup();
+        let listed: String = chars.iter().take(MAX_LISTED_CHARS).collect();
+        println!(
+            "{}",
+            format!("⚠️  {} 無法表示訊息中的「{}」，訊息改以 UTF-8 寫入", encoding.name(), listed).yellow()
+        );
+        return Encoded::Fallback;
+    }
+    Encoded::Converted(encoding, bytes.into_owned())
+}
+
+/// 目前的 locale（LC_ALL、LC_CTYPE、LANG 依序取第一個有設定者，都沒有時為 C）
+fn locale() -> String {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|key| env::var(key).ok().filter(|value| !value.is_empty()))
+        .unwrap_or_else(|| "C".to_string())
+}
+
+/// 訊息含非 ASCII 字元時，檢查終端機與 git log 能否正確顯示：
+/// locale 不是 UTF-8，或訊息以其他編碼寫入而 git log 會以該編碼輸出到 UTF-8 終端機時提醒
+pub fn check_display(message: &str, encoded: &Encoded) {
+    if message.is_ascii() {
+        return;
+    }
+    // Windows 的主控台不使用 locale 環境變數
+    if !cfg!(windows) {
+        let locale = locale();
+        let lowercase = locale.to_lowercase();
+        if !lowercase.contains("utf-8") && !lowercase.contains("utf8") {
+            println!(
+                "{}",
+                format!(
+                    "⚠️  目前的 locale（{}）不是 UTF-8，終端機與 git log 可能將訊息中的中日韓字元顯示為亂碼（可設定 LANG=zh_TW.UTF-8）",
+                    locale
+                )
+                .yellow()
+            );
+            return;
+        }
+    }
+    if let Encoded::Converted(encoding, _) = encoded {
+        if config_value("i18n.logOutputEncoding").is_none() {
+            println!(
+                "{}",
+                format!(
+                    "⚠️  訊息以 {} 寫入，git log 預設也以此編碼輸出，在 UTF-8 終端機中會顯示為亂碼（可設定 git config i18n.logOutputEncoding UTF-8）",
+                    encoding.name()
+                )
+                .yellow()
+            );
+        }
+    }
+}
+
+/// 依 commit 的 encoding 標頭解碼訊息（git2 的 message() 遇到非 UTF-8 的內容時回傳 None）
+pub fn message(commit: &Commit) -> String {
+    if let Some(message) = commit.message() {
+        return message.to_string();
+    }
+    let encoding = commit
+        .message_encoding()
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+    encoding.decode(commit.message_bytes()).0.into_owned()
+}
+
+/// commit 訊息的標題（第一個非空白行）
+pub fn subject(commit: &Commit) -> String {
+    message(commit)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_as_converts_to_configured_encoding() {
+        match encode_as("Big5", "feat: 新增登入") {
+            Encoded::Converted(encoding, bytes) => {
+                assert_eq!(encoding.name(), "Big5");
+                assert_eq!(encoding.decode(&bytes).0, "feat: 新增登入");
+            }
+            _ => panic!("應轉為 Big5"),
+        }
+    }
+
+    #[test]
+    fn encode_as_keeps_utf8() {
+        assert!(matches!(encode_as("utf-8", "feat: 新增登入"), Encoded::Utf8));
+    }
+
+    #[test]
+    fn encode_as_falls_back_for_unusable_encodings() {
+        // Big5 無法表示簡體字
+        assert!(matches!(encode_as("Big5", "feat: 新增登录"), Encoded::Fallback));
+        assert!(matches!(encode_as("UTF-16LE", "feat: add login"), Encoded::Fallback));
+        assert!(matches!(encode_as("no-such-encoding", "feat: add login"), Encoded::Fallback));
+    }
+}
@@ -0,0 +1,149 @@
+use crate::config::get_config_dir;
+use crate::GitSuggestions;
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use console::{pad_str, Alignment, Term};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// 提示詞模板檔案（只讀取 combined_prompt，其餘設定沿用主設定檔）
+#[derive(Deserialize)]
+struct PromptFile {
+    combined_prompt: String,
+}
+
+/// 參與比較的提示詞模板
+pub struct PromptVariant {
+    /// 顯示用標籤（A 或 B）
+    pub label: &'static str,
+    pub path: PathBuf,
+    pub combined_prompt: String,
+}
+
+/// 使用者對比較結果的選擇
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preference {
+    A,
+    B,
+    Tie,
+}
+
+/// 實驗紀錄（JSON Lines，儲存於 ~/.config/git-auto-commit/experiments.jsonl）
+#[derive(Serialize)]
+struct ExperimentRecord<'a> {
+    timestamp: String,
+    repo: Option<String>,
+    prompt_a: String,
+    prompt_b: String,
+    preference: Preference,
+    commits_a: &'a [String],
+    commits_b: &'a [String],
+}
+
+/// 從 TOML 檔讀取提示詞模板
+pub fn load_variant(label: &'static str, path: &Path) -> Result<PromptVariant> {
+    let content = fs::read_to_string(path)
+        .context(format!("無法讀取提示詞模板：{}", path.display()))?;
+    let file: PromptFile = toml::from_str(&content)
+        .context(format!("提示詞模板格式錯誤（需包含 combined_prompt）：{}", path.display()))?;
+
+    Ok(PromptVariant {
+        label,
+        path: path.to_path_buf(),
+        combined_prompt: file.combined_prompt,
+    })
+}
+
+/// 將一組建議展開成顯示用的行（分支名稱、commit 訊息完整內容）
+fn suggestion_lines(suggestions: &GitSuggestions) -> Vec<String> {
+    let mut lines = vec!["分支名稱：".to_string()];
+    for branch in &suggestions.branch_names {
+        lines.push(format!("  {}", branch));
+    }
+    lines.push(String::new());
+    lines.push("Commit 訊息：".to_string());
+    for (i, message) in suggestions.commit_messages.iter().enumerate() {
+        for (j, line) in message.lines().enumerate() {
+            if j == 0 {
+                lines.push(format!("{}. {}", i + 1, line));
+            } else {
+                lines.push(format!("   {}", line));
+            }
+        }
+        lines.push(String::new());
+    }
+    lines
+}
+
+/// 並排顯示兩組建議
+pub fn print_side_by_side(
+    a: (&PromptVariant, &GitSuggestions),
+    b: (&PromptVariant, &GitSuggestions),
+) {
+    let (_, width) = Term::stdout().size();
+    let column = ((width as usize).saturating_sub(3) / 2).max(20);
+
+    let header_a = format!("[{}] {}", a.0.label, a.0.path.display());
+    let header_b = format!("[{}] {}", b.0.label, b.0.path.display());
+    let lines_a = suggestion_lines(a.1);
+    let lines_b = suggestion_lines(b.1);
+
+    println!();
+    println!(
+        "{} │ {}",
+        pad_str(&header_a, column, Alignment::Left, Some("…")).cyan().bold(),
+        pad_str(&header_b, column, Alignment::Left, Some("…")).cyan().bold()
+    );
+    println!("{}", "─".repeat(column * 2 + 3).dimmed());
+
+    for i in 0..lines_a.len().max(lines_b.len()) {
+        let left = lines_a.get(i).map(String::as_str).unwrap_or("");
+        let right = lines_b.get(i).map(String::as_str).unwrap_or("");
+        println!(
+            "{} │ {}",
+            pad_str(left, column, Alignment::Left, Some("…")),
+            pad_str(right, column, Alignment::Left, Some("…"))
+        );
+    }
+    println!();
+}
+
+/// 記錄使用者偏好的提示詞模板
+pub fn record_preference(
+    a: (&PromptVariant, &GitSuggestions),
+    b: (&PromptVariant, &GitSuggestions),
+    preference: Preference,
+) -> Result<()> {
+    let path = get_config_dir().join("experiments.jsonl");
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("無法建立設定目錄")?;
+    }
+
+    let record = ExperimentRecord {
+        timestamp: Local::now().to_rfc3339(),
+        repo: env::current_dir().ok().map(|dir| dir.display().to_string()),
+        prompt_a: a.0.path.display().to_string(),
+        prompt_b: b.0.path.display().to_string(),
+        preference,
+        commits_a: &a.1.commit_messages,
+        commits_b: &b.1.commit_messages,
+    };
+
+    let mut line = serde_json::to_string(&record).context("無法序列化實驗紀錄")?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!("無法開啟實驗紀錄檔：{}", path.display()))?;
+    file.write_all(line.as_bytes()).context("無法寫入實驗紀錄")?;
+
+    println!("{}", format!("📝 已記錄選擇至 {}", path.display()).dimmed());
+    Ok(())
+}
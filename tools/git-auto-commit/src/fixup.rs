@@ -0,0 +1,87 @@
+use crate::config::LlmConfig;
+use crate::scope::Scope;
+use crate::{commit_changes, encoding, identity, plain, porcelain, CommitOptions};
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Confirm;
+use git2::Repository;
+use git_tools_core::input;
+
+/// 要建立的 commit 形式（同 git commit --fixup / --squash）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// rebase --autosquash 時併入目標 commit，並捨棄這個 commit 的訊息
+    Fixup,
+    /// rebase --autosquash 時併入目標 commit，並合併兩者的訊息
+    Squash,
+}
+
+impl Kind {
+    fn prefix(self) -> &'static str {
+        match self {
+            Kind::Fixup => "fixup!",
+            Kind::Squash => "squash!",
+        }
+    }
+}
+
+/// 不生成訊息，直接為 target 建立 fixup! 或 squash! commit（目標需在目前分支的歷史中）
+pub fn run(
+    repo: &Repository,
+    kind: Kind,
+    target: &str,
+    config: &LlmConfig,
+    quick: bool,
+    scope: &Scope,
+    options: &mut CommitOptions,
+) -> Result<()> {
+    let commit = repo
+        .revparse_single(target)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("找不到 commit：{}", target))?;
+    let head = repo.head()?.peel_to_commit().context("目前分支還沒有任何 commit")?;
+    if commit.id() != head.id() && !repo.graph_descendant_of(head.id(), commit.id())? {
+        anyhow::bail!("{} 不在目前分支的歷史中，rebase --autosquash 無法套用", target);
+    }
+
+    let subject = encoding::subject(&commit);
+    let short_id = commit.as_object().short_id()?;
+    println!(
+        "{}",
+        format!("🔧 目標 commit：{} {}", short_id.as_str().unwrap_or_default(), subject).cyan()
+    );
+
+    let mut message = format!("{} {}", kind.prefix(), subject);
+    // squash 可附上併入時要加到目標 commit 的說明
+    if kind == Kind::Squash && !quick {
+        if let Some(body) = input::message("squash 時要併入的說明（Esc 略過）")? {
+            message = format!("{}\n\n{}", message, body);
+        }
+    }
+
+    println!();
+    println!("{}", message.bright_white());
+    println!();
+    if !quick
+        && !Confirm::with_theme(plain::theme())
+            .with_prompt("以此訊息 commit？")
+            .default(true)
+            .interact()?
+    {
+        println!("{}", "已取消 commit".yellow());
+        porcelain::result("cancelled");
+        return Ok(());
+    }
+
+    if options.author_env.is_empty() {
+        options.identity = identity::select_identity(repo, &config.identities)?;
+    }
+    let summary = scope.commit(repo, || commit_changes(repo, &message, options))?;
+    porcelain::committed(&summary);
+    let upstream = match commit.parent_count() {
+        0 => "--root".to_string(),
+        _ => format!("{}~1", short_id.as_str().unwrap_or_default()),
+    };
+    println!("{}", format!("  之後可執行 git rebase -i --autosquash {} 套用", upstream).dimmed());
+    Ok(())
+}
@@ -0,0 +1,388 @@
+use crate::bitbucket;
+use crate::config::{BitbucketConfig, ForgeKind, GitHubConfig, GitLabConfig, LlmConfig};
+use crate::github::{self, Issue, PullRequest};
+use crate::gitlab;
+use anyhow::{Context, Result};
+use git2::Repository;
+use std::process::{Command, Stdio};
+
+/// remote URL 的組成：主機、連接埠與 repository 路徑
+struct RemoteUrl {
+    host: String,
+    port: Option<u16>,
+    /// 去除 .git 與前後斜線的路徑（例如 owner/name、group/subgroup/name）
+    path: String,
+}
+
+/// 解析 remote URL，支援 scp 形式（git@host:path）與 scheme://[user@]host[:port]/path
+fn parse_remote_url(url: &str) -> Option<RemoteUrl> {
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        None => url.split_once(':')?,
+    };
+    let authority = authority.rsplit('@').next()?;
+    let (host, port) = match authority.split_once(':') {
+        Some((host, port)) => (host, port.parse().ok()),
+        None => (authority, None),
+    };
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(RemoteUrl {
+        host: host.to_string(),
+        port,
+        path: path.to_string(),
+    })
+}
+
+/// 判斷代管服務：設定的主機對應優先，其次依主機名稱；其他主機視為 GitHub（含 GitHub Enterprise）
+fn detect_kind(config: &LlmConfig, remote: &RemoteUrl) -> ForgeKind {
+    if let Some(kind) = config.forge.hosts.get(&remote.host) {
+        return *kind;
+    }
+    let host = remote.host.to_ascii_lowercase();
+    if config.gerrit.enabled || remote.port == Some(29418) || host.contains("gerrit") {
+        ForgeKind::Gerrit
+    } else if host.contains("gitlab") {
+        ForgeKind::GitLab
+    } else if host == "bitbucket.org" || host.ends_with(".bitbucket.org") {
+        ForgeKind::Bitbucket
+    } else {
+        ForgeKind::GitHub
+    }
+}
+
+/// 以 percent-encoding 編碼查詢參數的值
+fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// 將路徑拆成 owner（可含子群組）與 repository 名稱
+fn split_repo(path: &str) -> Option<RemoteRepo> {
+    let (owner, name) = path.rsplit_once('/')?;
+    if owner.is_empty() || name.is_empty() {
+        return None;
+    }
+    Some(RemoteRepo {
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+/// 代管服務上的 repository（owner/name，GitLab 的 owner 可包含子群組）
+#[derive(Debug, Clone)]
+pub struct RemoteRepo {
+    pub owner: String,
+    pub name: String,
+}
+
+/// 代管服務的共同操作：pull request（GitLab 為 merge request）、issue 查詢與 commit 連結
+pub trait Forge {
+    /// 服務名稱（顯示用）
+    fn name(&self) -> &'static str;
+
+    /// commit 的網頁連結
+    fn commit_url(&self, sha: &str) -> String;
+
+    /// 建立 pull request 的網頁連結，預先填入標題與說明（服務不支援時回傳 None）
+    fn new_pull_request_url(&self, head: &str, base: &str, title: &str, body: &str) -> Option<String>;
+
+    /// 從 fork（owner 為 head_owner 的 repository）的分支建立 pull request 的網頁連結（服務不支援時回傳 None）
+    fn new_fork_pull_request_url(
+        &self,
+        _head_owner: &str,
+        _head: &str,
+        _base: &str,
+        _title: &str,
+        _body: &str,
+    ) -> Option<String> {
+        None
+    }
+
+    /// 查詢指定分支目前開啟中的 pull request
+    fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>>;
+
+    /// 建立 pull request
+    fn create_pull_request(&self, head: &str, base: &str, title: &str, body: &str) -> Result<PullRequest>;
+
+    /// 修改 pull request 的目標分支
+    fn update_base(&self, number: u64, base: &str) -> Result<PullRequest>;
+
+    /// 查詢 issue（不存在或服務沒有 issue 時回傳 None）
+    fn find_issue(&self, number: u64) -> Result<Option<Issue>>;
+}
+
+struct GitHub {
+    config: GitHubConfig,
+    host: String,
+    repo: RemoteRepo,
+}
+
+impl GitHub {
+    fn client(&self) -> Result<github::Client> {
+        github::Client::new(&self.config, self.repo.clone())
+    }
+}
+
+impl Forge for GitHub {
+    fn name(&self) -> &'static str {
+        "GitHub"
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!("https://{}/{}/{}/commit/{}", self.host, self.repo.owner, self.repo.name, sha)
+    }
+
+    fn new_pull_request_url(&self, head: &str, base: &str, title: &str, body: &str) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/{}/compare/{}...{}?expand=1&title={}&body={}",
+            self.host,
+            self.repo.owner,
+            self.repo.name,
+            base,
+            head,
+            encode(title),
+            encode(body)
+        ))
+    }
+
+    fn new_fork_pull_request_url(&self, head_owner: &str, head: &str, base: &str, title: &str, body: &str) -> Option<String> {
+        self.new_pull_request_url(&format!("{}:{}", head_owner, head), base, title, body)
+    }
+
+    fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        self.client()?.find_open_pull_request(branch)
+    }
+
+    fn create_pull_request(&self, head: &str, base: &str, title: &str, body: &str) -> Result<PullRequest> {
+        self.client()?.create_pull_request(head, base, title, body)
+    }
+
+    fn update_base(&self, number: u64, base: &str) -> Result<PullRequest> {
+        self.client()?.update_base(number, base)
+    }
+
+    fn find_issue(&self, number: u64) -> Result<Option<Issue>> {
+        self.client()?.find_issue(number)
+    }
+}
+
+struct GitLab {
+    config: GitLabConfig,
+    host: String,
+    repo: RemoteRepo,
+}
+
+impl GitLab {
+    fn client(&self) -> Result<gitlab::Client> {
+        gitlab::Client::new(&self.config, &self.host, self.repo.clone())
+    }
+}
+
+impl Forge for GitLab {
+    fn name(&self) -> &'static str {
+        "GitLab"
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!("https://{}/{}/{}/-/commit/{}", self.host, self.repo.owner, self.repo.name, sha)
+    }
+
+    fn new_pull_request_url(&self, head: &str, base: &str, title: &str, body: &str) -> Option<String> {
+        let params = [
+            ("source_branch", head),
+            ("target_branch", base),
+            ("title", title),
+            ("description", body),
+        ];
+        let query: Vec<String> = params
+            .iter()
+            .map(|(key, value)| format!("merge_request%5B{}%5D={}", key, encode(value)))
+            .collect();
+        Some(format!(
+            "https://{}/{}/{}/-/merge_requests/new?{}",
+            self.host,
+            self.repo.owner,
+            self.repo.name,
+            query.join("&")
+        ))
+    }
+
+    fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        self.client()?.find_open_pull_request(branch)
+    }
+
+    fn create_pull_request(&self, head: &str, base: &str, title: &str, body: &str) -> Result<PullRequest> {
+        self.client()?.create_pull_request(head, base, title, body)
+    }
+
+    fn update_base(&self, number: u64, base: &str) -> Result<PullRequest> {
+        self.client()?.update_base(number, base)
+    }
+
+    fn find_issue(&self, number: u64) -> Result<Option<Issue>> {
+        self.client()?.find_issue(number)
+    }
+}
+
+struct Bitbucket {
+    config: BitbucketConfig,
+    host: String,
+    repo: RemoteRepo,
+}
+
+impl Bitbucket {
+    fn client(&self) -> Result<bitbucket::Client> {
+        bitbucket::Client::new(&self.config, self.repo.clone())
+    }
+}
+
+impl Forge for Bitbucket {
+    fn name(&self) -> &'static str {
+        "Bitbucket"
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!("https://{}/{}/{}/commits/{}", self.host, self.repo.owner, self.repo.name, sha)
+    }
+
+    /// Bitbucket 的建立頁面只支援預先選擇分支，標題與說明需在頁面上填寫
+    fn new_pull_request_url(&self, head: &str, base: &str, _title: &str, _body: &str) -> Option<String> {
+        Some(format!(
+            "https://{}/{}/{}/pull-requests/new?source={}&dest={}",
+            self.host,
+            self.repo.owner,
+            self.repo.name,
+            encode(head),
+            encode(base)
+        ))
+    }
+
+    fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        self.client()?.find_open_pull_request(branch)
+    }
+
+    fn create_pull_request(&self, head: &str, base: &str, title: &str, body: &str) -> Result<PullRequest> {
+        self.client()?.create_pull_request(head, base, title, body)
+    }
+
+    fn update_base(&self, number: u64, base: &str) -> Result<PullRequest> {
+        self.client()?.update_base(number, base)
+    }
+
+    fn find_issue(&self, number: u64) -> Result<Option<Issue>> {
+        self.client()?.find_issue(number)
+    }
+}
+
+/// Gerrit 以 change 審查，沒有 pull request 與 issue
+struct Gerrit {
+    host: String,
+}
+
+impl Forge for Gerrit {
+    fn name(&self) -> &'static str {
+        "Gerrit"
+    }
+
+    fn commit_url(&self, sha: &str) -> String {
+        format!("https://{}/q/{}", self.host, sha)
+    }
+
+    fn new_pull_request_url(&self, _head: &str, _base: &str, _title: &str, _body: &str) -> Option<String> {
+        None
+    }
+
+    fn find_open_pull_request(&self, _branch: &str) -> Result<Option<PullRequest>> {
+        anyhow::bail!("Gerrit 沒有 pull request，請改用 [gerrit] 模式推送到 refs/for/<branch>")
+    }
+
+    fn create_pull_request(&self, _head: &str, _base: &str, _title: &str, _body: &str) -> Result<PullRequest> {
+        anyhow::bail!("Gerrit 沒有 pull request，請改用 [gerrit] 模式推送到 refs/for/<branch>")
+    }
+
+    fn update_base(&self, _number: u64, _base: &str) -> Result<PullRequest> {
+        anyhow::bail!("Gerrit 沒有 pull request，請改用 [gerrit] 模式推送到 refs/for/<branch>")
+    }
+
+    fn find_issue(&self, _number: u64) -> Result<Option<Issue>> {
+        Ok(None)
+    }
+}
+
+/// 依 remote URL 判斷代管服務（API token 在實際呼叫 API 時才檢查）
+pub fn connect(config: &LlmConfig, url: &str) -> Result<Box<dyn Forge>> {
+    let remote = parse_remote_url(url).context(format!("無法解析 remote URL：{}", url))?;
+    let repo = || split_repo(&remote.path).context(format!("無法從 remote URL 解析 repository：{}", url));
+    Ok(match detect_kind(config, &remote) {
+        ForgeKind::GitHub => Box::new(GitHub {
+            config: config.github.clone(),
+            repo: repo()?,
+            host: remote.host,
+        }),
+        ForgeKind::GitLab => Box::new(GitLab {
+            config: config.gitlab.clone(),
+            repo: repo()?,
+            host: remote.host,
+        }),
+        ForgeKind::Bitbucket => Box::new(Bitbucket {
+            config: config.bitbucket.clone(),
+            repo: repo()?,
+            host: remote.host,
+        }),
+        ForgeKind::Gerrit => Box::new(Gerrit { host: remote.host }),
+    })
+}
+
+/// 依 origin 的 URL 判斷代管服務（沒有 origin 或無法解析時回傳 None）
+pub fn from_origin(repo: &Repository, config: &LlmConfig) -> Option<Box<dyn Forge>> {
+    from_remote(repo, config, "origin")
+}
+
+/// 依指定 remote 的 URL 判斷代管服務（remote 不存在或無法解析時回傳 None）
+pub fn from_remote(repo: &Repository, config: &LlmConfig, name: &str) -> Option<Box<dyn Forge>> {
+    let remote = repo.find_remote(name).ok()?;
+    connect(config, remote.url()?).ok()
+}
+
+/// remote 所指 repository 的 owner（例如 fork 的使用者名稱），無法解析時回傳 None
+pub fn remote_owner(repo: &Repository, name: &str) -> Option<String> {
+    let remote = repo.find_remote(name).ok()?;
+    let url = parse_remote_url(remote.url()?)?;
+    split_repo(&url.path).map(|repo| repo.owner)
+}
+
+/// 將 ticket 轉為 issue 編號（例如 123 或 #123），其他格式（例如 PROJ-123）回傳 None
+pub fn issue_number(ticket: &str) -> Option<u64> {
+    ticket.trim_start_matches('#').parse().ok()
+}
+
+/// 以系統預設的瀏覽器開啟網址
+pub fn open_in_browser(url: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command
+        .arg(url)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .context("無法開啟瀏覽器")?;
+    if !status.success() {
+        anyhow::bail!("無法開啟瀏覽器");
+    }
+    Ok(())
+}
@@ -0,0 +1,129 @@
+use crate::config::GerritConfig;
+use crate::plain;
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use dialoguer::Select;
+use git2::Repository;
+use sha2::{Digest, Sha256};
+use std::process::Command;
+
+/// 產生 Change-Id（I 加上 40 個十六進位字元，與 Gerrit commit-msg hook 的格式相同）
+fn change_id(repo: &Repository, message: &str) -> String {
+    let mut hasher = Sha256::new();
+    if let Ok(head) = repo.head().and_then(|h| h.peel_to_commit()) {
+        hasher.update(head.id().as_bytes());
+    }
+    if let Ok(tree) = repo.index().and_then(|mut index| index.write_tree()) {
+        hasher.update(tree.as_bytes());
+    }
+    hasher.update(Local::now().to_rfc3339().as_bytes());
+    hasher.update(message.as_bytes());
+    let hex: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("I{}", &hex[..40])
+}
+
+/// 是否為 trailer 行（例如 Signed-off-by: ...、Migration: ...）
+fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, _)| {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// 在訊息最後加上 Change-Id trailer（已有時維持原樣）；最後一段已是 trailer 時併入同一段
+pub fn with_change_id(repo: &Repository, message: &str) -> String {
+    if message.lines().any(|line| line.starts_with("Change-Id: ")) {
+        return message.to_string();
+    }
+    let message = message.trim_end();
+    let trailer = format!("Change-Id: {}", change_id(repo, message));
+
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or("");
+    let has_body = message.contains("\n\n");
+    if has_body && last_paragraph.lines().all(is_trailer) {
+        format!("{}\n{}", message, trailer)
+    } else {
+        format!("{}\n\n{}", message, trailer)
+    }
+}
+
+/// 依 Gerrit 的預設規則檢查訊息格式，回傳問題說明
+pub fn check_message(message: &str, config: &GerritConfig) -> Vec<String> {
+    let mut issues = Vec::new();
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or("");
+
+    let length = subject.chars().count();
+    if length > config.subject_max_length {
+        issues.push(format!("標題有 {} 個字元，超過 Gerrit 建議的 {} 個字元", length, config.subject_max_length));
+    }
+    if lines.next().is_some_and(|line| !line.trim().is_empty()) {
+        issues.push("標題與內文之間需要空一行".to_string());
+    }
+    let long_lines = message
+        .lines()
+        .skip(2)
+        .filter(|line| !is_trailer(line) && line.chars().count() > config.body_max_line_length)
+        .count();
+    if long_lines > 0 {
+        issues.push(format!("內文有 {} 行超過 {} 個字元", long_lines, config.body_max_line_length));
+    }
+    issues
+}
+
+/// 顯示訊息格式的問題並詢問是否仍要 commit（沒有問題時直接回傳 true）
+pub fn confirm_message(message: &str, config: &GerritConfig) -> Result<bool> {
+    let issues = check_message(message, config);
+    if issues.is_empty() {
+        return Ok(true);
+    }
+    println!("{}", "⚠️  commit 訊息不符合 Gerrit 的預設規則：".yellow());
+    for issue in &issues {
+        println!("{}", format!("  - {}", issue).yellow());
+    }
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("仍要使用此訊息？")
+        .items(&["仍要 commit", "✗ 取消 commit"])
+        .default(0)
+        .interact()?;
+    Ok(selection == 0)
+}
+
+/// 審查的目標分支：設定值，否則為目前分支的 upstream，再否則為目前分支
+fn target_branch(repo: &Repository, config: &GerritConfig, current_branch: &str) -> String {
+    if let Some(branch) = &config.branch {
+        return branch.clone();
+    }
+    repo.config()
+        .ok()
+        .and_then(|c| c.get_string(&format!("branch.{}.merge", current_branch)).ok())
+        .map(|merge| merge.trim_start_matches("refs/heads/").to_string())
+        .unwrap_or_else(|| current_branch.to_string())
+}
+
+/// commit 後詢問是否推送到 refs/for/<branch> 建立或更新 Gerrit change
+pub fn offer_push(repo: &Repository, config: &GerritConfig, current_branch: &str) -> Result<()> {
+    let refspec = format!("HEAD:refs/for/{}", target_branch(repo, config, current_branch));
+    let command = format!("git push {} {}", config.remote, refspec);
+
+    println!();
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("要推送到 Gerrit 審查嗎？")
+        .items(&[format!("🚀 推送（{}）", command), "稍後再說".to_string()])
+        .default(0)
+        .interact()?;
+    if selection != 0 {
+        return Ok(());
+    }
+
+    let status = Command::new("git")
+        .args(["push", &config.remote, &refspec])
+        .status()
+        .context("無法執行 git push")?;
+    if status.success() {
+        println!("{}", "✓ 已推送到 Gerrit".green());
+    } else {
+        println!("{}", format!("✗ 推送失敗，可稍後手動執行：{}", command).red());
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use crate::config::GitHubConfig;
+use crate::forge::RemoteRepo;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::env;
+
+/// Pull request 的基本資訊
+#[derive(Debug, Deserialize)]
+pub struct PullRequest {
+    pub number: u64,
+    pub html_url: String,
+    pub base: PullRequestRef,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequestRef {
+    #[serde(rename = "ref")]
+    pub name: String,
+}
+
+/// Issue 的基本資訊
+#[derive(Debug, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub html_url: String,
+    /// open 或 closed
+    pub state: String,
+}
+
+#[derive(Serialize)]
+struct CreatePullRequest<'a> {
+    title: &'a str,
+    head: &'a str,
+    base: &'a str,
+    body: &'a str,
+}
+
+#[derive(Serialize)]
+struct UpdatePullRequest<'a> {
+    base: &'a str,
+}
+
+/// GitHub REST API 用戶端（token 取自 GITHUB_TOKEN 或 GH_TOKEN 環境變數）
+pub struct Client {
+    api_url: String,
+    token: String,
+    repo: RemoteRepo,
+}
+
+impl Client {
+    pub fn new(config: &GitHubConfig, repo: RemoteRepo) -> Result<Self> {
+        let token = ["GITHUB_TOKEN", "GH_TOKEN"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|token| !token.is_empty())
+            .context("未設定 GITHUB_TOKEN 或 GH_TOKEN 環境變數")?;
+        Ok(Self {
+            api_url: config.api_url.trim_end_matches('/').to_string(),
+            token,
+            repo,
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!(
+            "{}/repos/{}/{}{}",
+            self.api_url, self.repo.owner, self.repo.name, path
+        )
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        ureq::request(method, url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "git-auto-commit")
+    }
+
+    /// 查詢指定分支目前開啟中的 pull request
+    pub fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        let url = self.url("/pulls");
+        let head = format!("{}:{}", self.repo.owner, branch);
+        let pulls: Vec<PullRequest> = self
+            .request("GET", &url)
+            .query("head", &head)
+            .query("state", "open")
+            .call()
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 GitHub 回應")?;
+        Ok(pulls.into_iter().next())
+    }
+
+    /// 建立 pull request
+    pub fn create_pull_request(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<PullRequest> {
+        self.request("POST", &self.url("/pulls"))
+            .send_json(CreatePullRequest {
+                title,
+                head,
+                base,
+                body,
+            })
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 GitHub 回應")
+    }
+
+    /// 修改 pull request 的 base 分支
+    pub fn update_base(&self, number: u64, base: &str) -> Result<PullRequest> {
+        self.request("PATCH", &self.url(&format!("/pulls/{}", number)))
+            .send_json(UpdatePullRequest { base })
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 GitHub 回應")
+    }
+
+    /// 查詢 issue（不存在時回傳 None）
+    pub fn find_issue(&self, number: u64) -> Result<Option<Issue>> {
+        match self.request("GET", &self.url(&format!("/issues/{}", number))).call() {
+            Ok(response) => Ok(Some(response.into_json().context("無法解析 GitHub 回應")?)),
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(api_error(e)),
+        }
+    }
+}
+
+/// 將 ureq 錯誤轉為包含 GitHub 錯誤訊息的錯誤
+fn api_error(error: ureq::Error) -> anyhow::Error {
+    match error {
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+                .unwrap_or(body);
+            anyhow::anyhow!("GitHub API 錯誤（{}）：{}", code, message)
+        }
+        ureq::Error::Transport(e) => anyhow::anyhow!("無法連線到 GitHub：{}", e),
+    }
+}
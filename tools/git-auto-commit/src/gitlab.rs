@@ -0,0 +1,155 @@
+use crate::config::GitLabConfig;
+use crate::forge::RemoteRepo;
+use crate::github::{Issue, PullRequest, PullRequestRef};
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::env;
+
+#[derive(Debug, Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    web_url: String,
+    target_branch: String,
+}
+
+impl From<MergeRequest> for PullRequest {
+    fn from(mr: MergeRequest) -> Self {
+        PullRequest {
+            number: mr.iid,
+            html_url: mr.web_url,
+            base: PullRequestRef {
+                name: mr.target_branch,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GitLabIssue {
+    iid: u64,
+    title: String,
+    web_url: String,
+    state: String,
+}
+
+impl From<GitLabIssue> for Issue {
+    fn from(issue: GitLabIssue) -> Self {
+        Issue {
+            number: issue.iid,
+            title: issue.title,
+            html_url: issue.web_url,
+            state: issue.state,
+        }
+    }
+}
+
+/// GitLab REST API 用戶端（token 取自 GITLAB_TOKEN 環境變數），merge request 以 PullRequest 表示
+pub struct Client {
+    api_url: String,
+    token: String,
+    /// URL 編碼後的專案路徑（group%2Fsubgroup%2Fname）
+    project: String,
+}
+
+impl Client {
+    pub fn new(config: &GitLabConfig, host: &str, repo: RemoteRepo) -> Result<Self> {
+        let token = env::var("GITLAB_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty())
+            .context("未設定 GITLAB_TOKEN 環境變數")?;
+        let api_url = config
+            .api_url
+            .clone()
+            .unwrap_or_else(|| format!("https://{}/api/v4", host));
+        Ok(Self {
+            api_url: api_url.trim_end_matches('/').to_string(),
+            token,
+            project: format!("{}/{}", repo.owner, repo.name).replace('/', "%2F"),
+        })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/projects/{}{}", self.api_url, self.project, path)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        ureq::request(method, url)
+            .set("PRIVATE-TOKEN", &self.token)
+            .set("Accept", "application/json")
+            .set("User-Agent", "git-auto-commit")
+    }
+
+    /// 查詢指定分支目前開啟中的 merge request
+    pub fn find_open_pull_request(&self, branch: &str) -> Result<Option<PullRequest>> {
+        let requests: Vec<MergeRequest> = self
+            .request("GET", &self.url("/merge_requests"))
+            .query("source_branch", branch)
+            .query("state", "opened")
+            .call()
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 GitLab 回應")?;
+        Ok(requests.into_iter().next().map(PullRequest::from))
+    }
+
+    /// 建立 merge request
+    pub fn create_pull_request(
+        &self,
+        head: &str,
+        base: &str,
+        title: &str,
+        body: &str,
+    ) -> Result<PullRequest> {
+        let mr: MergeRequest = self
+            .request("POST", &self.url("/merge_requests"))
+            .send_json(json!({
+                "source_branch": head,
+                "target_branch": base,
+                "title": title,
+                "description": body,
+            }))
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 GitLab 回應")?;
+        Ok(mr.into())
+    }
+
+    /// 修改 merge request 的目標分支
+    pub fn update_base(&self, number: u64, base: &str) -> Result<PullRequest> {
+        let mr: MergeRequest = self
+            .request("PUT", &self.url(&format!("/merge_requests/{}", number)))
+            .send_json(json!({ "target_branch": base }))
+            .map_err(api_error)?
+            .into_json()
+            .context("無法解析 GitLab 回應")?;
+        Ok(mr.into())
+    }
+
+    /// 查詢 issue（不存在時回傳 None）
+    pub fn find_issue(&self, number: u64) -> Result<Option<Issue>> {
+        match self.request("GET", &self.url(&format!("/issues/{}", number))).call() {
+            Ok(response) => {
+                let issue: GitLabIssue = response.into_json().context("無法解析 GitLab 回應")?;
+                Ok(Some(issue.into()))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(api_error(e)),
+        }
+    }
+}
+
+/// 將 ureq 錯誤轉為包含 GitLab 錯誤訊息的錯誤
+fn api_error(error: ureq::Error) -> anyhow::Error {
+    match error {
+        ureq::Error::Status(code, response) => {
+            let body = response.into_string().unwrap_or_default();
+            let message = serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|v| v.get("message").or_else(|| v.get("error")).map(|m| m.as_str().map(String::from).unwrap_or_else(|| m.to_string())))
+                .unwrap_or(body);
+            anyhow::anyhow!("GitLab API 錯誤（{}）：{}", code, message)
+        }
+        ureq::Error::Transport(e) => anyhow::anyhow!("無法連線到 GitLab：{}", e),
+    }
+}
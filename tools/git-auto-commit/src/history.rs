@@ -0,0 +1,216 @@
+use crate::config::{get_config_dir, load_llm_config, HistoryConfig};
+use crate::plain;
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use console::{pad_str, Alignment};
+use dialoguer::Select;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 歷史紀錄中的一筆 commit（JSON Lines 格式，一行一個 commit）
+#[derive(Debug, Deserialize, Serialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    /// repository 的工作目錄
+    pub repo: String,
+    /// 產生建議的提示詞模板（例如 combined:default、rewrite:3f2a1b9c）
+    pub variant: String,
+    pub model: String,
+    /// 實際使用的 commit 訊息
+    pub message: String,
+    /// 是否直接採用建議（未選擇自訂訊息）
+    pub accepted: bool,
+    /// 使用者的評分（1–5），略過時為 None
+    #[serde(default)]
+    pub rating: Option<u8>,
+}
+
+fn get_history_path(config: &HistoryConfig) -> PathBuf {
+    match &config.path {
+        Some(path) => PathBuf::from(path),
+        None => get_config_dir().join("history.jsonl"),
+    }
+}
+
+/// 提示詞模板的識別碼：與內建模板相同時為「種類:default」，否則為「種類:模板內容的雜湊」
+pub fn variant_id(kind: &str, template: &str, default: &str) -> String {
+    if template == default {
+        return format!("{}:default", kind);
+    }
+    let hex: String = Sha256::digest(template.as_bytes())
+        .iter()
+        .take(4)
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("{}:{}", kind, hex)
+}
+
+/// 詢問對這次建議的評分，按 Esc 或選擇略過時回傳 None
+fn ask_rating() -> Result<Option<u8>> {
+    let items = [
+        "5 ★★★★★ 直接可用",
+        "4 ★★★★☆ 小幅修改",
+        "3 ★★★☆☆ 普通",
+        "2 ★★☆☆☆ 需大幅修改",
+        "1 ★☆☆☆☆ 不可用",
+        "略過",
+    ];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("這次的建議如何？")
+        .items(&items)
+        .default(0)
+        .interact_opt()?;
+    Ok(selection.filter(|s| *s < 5).map(|s| 5 - s as u8))
+}
+
+/// commit 後依設定詢問評分，並將訊息、提示詞模板與模型寫入歷史紀錄
+pub fn record(config: &HistoryConfig, repo: &str, variant: &str, model: &str, message: &str, accepted: bool) -> Result<()> {
+    let rating = if config.ask_rating { ask_rating()? } else { None };
+
+    let path = get_history_path(config);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("無法建立設定目錄")?;
+    }
+    let entry = HistoryEntry {
+        timestamp: Local::now().to_rfc3339(),
+        repo: repo.to_string(),
+        variant: variant.to_string(),
+        model: model.to_string(),
+        message: message.to_string(),
+        accepted,
+        rating,
+    };
+    let line = serde_json::to_string(&entry).context("無法序列化歷史紀錄")?;
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .context(format!("無法開啟歷史紀錄檔：{}", path.display()))?;
+    writeln!(file, "{}", line).context("無法寫入歷史紀錄")?;
+    if rating.is_some() {
+        println!("{}", format!("📝 已記錄評分至 {}", path.display()).dimmed());
+    }
+    Ok(())
+}
+
+/// 讀取歷史紀錄（檔案不存在時為空，略過格式錯誤的行）
+pub fn load(config: &HistoryConfig) -> Vec<HistoryEntry> {
+    fs::read_to_string(get_history_path(config))
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 此 repository 最近採用的高評分 commit 訊息（由新到舊、不重複），作為提示詞的 few-shot 範例
+///
+/// 評分達 few_shot_min_rating 的訊息，以及未評分但直接採用建議的訊息才會列入。
+pub fn examples(config: &HistoryConfig, repo: &str) -> Vec<String> {
+    if config.few_shot == 0 {
+        return Vec::new();
+    }
+    let mut examples: Vec<String> = Vec::new();
+    for entry in load(config).into_iter().rev() {
+        let qualified = match entry.rating {
+            Some(rating) => rating >= config.few_shot_min_rating,
+            None => entry.accepted,
+        };
+        if entry.repo == repo && qualified && !examples.contains(&entry.message) {
+            examples.push(entry.message);
+            if examples.len() == config.few_shot {
+                break;
+            }
+        }
+    }
+    examples
+}
+
+/// 同一組提示詞模板與模型的統計
+#[derive(Default)]
+struct VariantStats {
+    commits: usize,
+    accepted: usize,
+    ratings: Vec<u8>,
+}
+
+impl VariantStats {
+    fn average(&self) -> Option<f64> {
+        (!self.ratings.is_empty()).then(|| self.ratings.iter().map(|&r| r as f64).sum::<f64>() / self.ratings.len() as f64)
+    }
+}
+
+/// stats prompts：依提示詞模板與模型彙整評分，平均評分高者在前
+pub fn print_prompt_stats() -> Result<()> {
+    let config = load_llm_config();
+    println!("\n{}\n", "📊 提示詞模板與模型的評分".cyan().bold());
+
+    let entries = load(&config.history);
+    if entries.is_empty() {
+        println!(
+            "{}",
+            format!("尚無紀錄：請在設定檔的 [history] 啟用 enabled（{}）", get_history_path(&config.history).display())
+                .yellow()
+        );
+        return Ok(());
+    }
+
+    let mut stats: BTreeMap<(String, String), VariantStats> = BTreeMap::new();
+    for entry in entries {
+        let stat = stats.entry((entry.variant, entry.model)).or_default();
+        stat.commits += 1;
+        if entry.accepted {
+            stat.accepted += 1;
+        }
+        stat.ratings.extend(entry.rating);
+    }
+    let mut rows: Vec<_> = stats.into_iter().collect();
+    rows.sort_by(|(_, a), (_, b)| {
+        b.average()
+            .unwrap_or(0.0)
+            .total_cmp(&a.average().unwrap_or(0.0))
+            .then(b.commits.cmp(&a.commits))
+    });
+
+    let variant_width = rows.iter().map(|((v, _), _)| v.len()).max().unwrap_or(0).max(10);
+    let model_width = rows.iter().map(|((_, m), _)| m.len()).max().unwrap_or(0).max(4);
+    println!(
+        "{}",
+        format!(
+            "{}  {}  {}  {}  {}  {}",
+            pad_str("提示詞模板", variant_width, Alignment::Left, None),
+            pad_str("模型", model_width, Alignment::Left, None),
+            pad_str("commit", 6, Alignment::Right, None),
+            pad_str("評分數", 6, Alignment::Right, None),
+            pad_str("平均", 6, Alignment::Right, None),
+            pad_str("直接採用", 8, Alignment::Right, None)
+        )
+        .bold()
+    );
+    for ((variant, model), stat) in &rows {
+        let average = stat
+            .average()
+            .map(|a| format!("{:.1}", a))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{}  {}  {:>6}  {:>6}  {:>6}  {:>7}%",
+            pad_str(variant, variant_width, Alignment::Left, None),
+            pad_str(model, model_width, Alignment::Left, None),
+            stat.commits,
+            stat.ratings.len(),
+            average,
+            stat.accepted * 100 / stat.commits
+        );
+    }
+    println!();
+    println!("{}", "提示詞模板為 default 表示內建模板，其餘為自訂模板內容的雜湊".dimmed());
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use git2::Repository;
+use git_tools_core::path::{expand_home, is_executable};
+use std::fs;
+use std::path::PathBuf;
+
+/// commit 流程中會觸發的 hooks（依執行順序）
+pub const COMMIT_HOOKS: &[&str] = &["pre-commit", "prepare-commit-msg", "commit-msg", "post-commit"];
+
+/// hooks 目錄的解析結果
+pub struct HooksDir {
+    pub path: PathBuf,
+    /// 是否來自 core.hooksPath 設定（例如 husky、lefthook 管理的目錄）
+    pub from_config: bool,
+}
+
+/// 解析 hooks 目錄：優先使用 core.hooksPath（local 或 global 設定），否則為 .git/hooks
+///
+/// 與 git 相同，相對路徑以工作目錄的根目錄為基準；worktree 使用共用的 git 目錄。
+pub fn resolve_hooks_dir(repo: &Repository) -> HooksDir {
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok());
+
+    if let Some(configured) = configured {
+        let expanded = expand_home(&configured.to_string_lossy());
+        let path = if expanded.is_absolute() {
+            expanded
+        } else {
+            repo.workdir()
+                .unwrap_or_else(|| repo.path())
+                .join(expanded)
+        };
+        return HooksDir {
+            path,
+            from_config: true,
+        };
+    }
+
+    HooksDir {
+        path: common_dir(repo).join("hooks"),
+        from_config: false,
+    }
+}
+
+/// 取得共用的 git 目錄（worktree 的 .git/worktrees/<name> 內有 commondir 檔案指向主要的 git 目錄）
+fn common_dir(repo: &Repository) -> PathBuf {
+    let git_dir = repo.path();
+    match fs::read_to_string(git_dir.join("commondir")) {
+        Ok(content) => git_dir.join(content.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    }
+}
+
+/// 列出 commit 時會執行的 hooks
+pub fn active_commit_hooks(hooks_dir: &HooksDir) -> Vec<&'static str> {
+    COMMIT_HOOKS
+        .iter()
+        .copied()
+        .filter(|name| is_executable(&hooks_dir.path.join(name)))
+        .collect()
+}
+
+/// 列出存在但沒有執行權限（因此不會被執行）的 commit hooks
+pub fn inactive_commit_hooks(hooks_dir: &HooksDir) -> Vec<&'static str> {
+    COMMIT_HOOKS
+        .iter()
+        .copied()
+        .filter(|name| {
+            let path = hooks_dir.path.join(name);
+            path.is_file() && !is_executable(&path)
+        })
+        .collect()
+}
@@ -0,0 +1,71 @@
+use crate::config::Identity;
+use crate::plain;
+use anyhow::Result;
+use colored::*;
+use dialoguer::Select;
+use git2::Repository;
+use std::collections::BTreeMap;
+
+/// 從 git 設定（含 global、system 與 includeIf 條件引入）解析目前的作者身分
+pub fn current_identity(repo: &Repository) -> (Option<String>, Option<String>) {
+    let Ok(config) = repo.config() else {
+        return (None, None);
+    };
+    (
+        config.get_string("user.name").ok(),
+        config.get_string("user.email").ok(),
+    )
+}
+
+fn format_identity(name: &str, email: &str) -> String {
+    format!("{} <{}>", name, email)
+}
+
+/// 顯示 git 設定中的作者身分（缺少 user.name 或 user.email 時提醒）
+pub fn print_current(repo: &Repository) {
+    let (name, email) = current_identity(repo);
+    let current = format_identity(
+        name.as_deref().unwrap_or("（未設定 user.name）"),
+        email.as_deref().unwrap_or("（未設定 user.email）"),
+    );
+    println!("{}", format!("👤 作者：{}", current).dimmed());
+    if name.is_none() || email.is_none() {
+        println!("{}", "⚠️  git 設定中缺少 user.name 或 user.email".yellow());
+    }
+}
+
+/// 顯示即將使用的作者身分，並在有設定其他身分時讓使用者選擇
+///
+/// 回傳 `None` 表示使用 git 設定中的身分。
+pub fn select_identity(
+    repo: &Repository,
+    identities: &BTreeMap<String, Identity>,
+) -> Result<Option<Identity>> {
+    let (name, email) = current_identity(repo);
+    let current = format_identity(
+        name.as_deref().unwrap_or("（未設定 user.name）"),
+        email.as_deref().unwrap_or("（未設定 user.email）"),
+    );
+
+    if identities.is_empty() {
+        print_current(repo);
+        return Ok(None);
+    }
+
+    let mut items = vec![format!("使用目前身分：{}", current)];
+    for (key, identity) in identities {
+        items.push(format!("{}：{}", key, format_identity(&identity.name, &identity.email)));
+    }
+
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("👤 選擇此 commit 的作者身分")
+        .items(&items)
+        .default(0)
+        .interact()?;
+
+    if selection == 0 {
+        return Ok(None);
+    }
+
+    Ok(identities.values().nth(selection - 1).cloned())
+}
@@ -0,0 +1,204 @@
+use colored::*;
+use git2::Repository;
+use git_tools_core::git::file_versions;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// 資源的變化
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    Added,
+    Modified,
+    Destroyed,
+}
+
+impl Change {
+    fn label(self) -> &'static str {
+        match self {
+            Change::Added => "新增",
+            Change::Modified => "修改",
+            Change::Destroyed => "移除",
+        }
+    }
+
+    fn symbol(self) -> ColoredString {
+        match self {
+            Change::Added => "+".green(),
+            Change::Modified => "~".yellow(),
+            Change::Destroyed => "-".red(),
+        }
+    }
+}
+
+/// staged 變更中基礎設施設定（Terraform、Kubernetes、Helm）的資源變化
+pub struct InfraSummary {
+    /// 所有 staged 檔案是否都是基礎設施設定
+    pub infra_only: bool,
+    changes: Vec<(Change, String)>,
+}
+
+static TF_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^(resource|data|module)\s+"([^"]+)"(?:\s+"([^"]+)")?\s*\{"#).unwrap()
+});
+static K8S_KIND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^kind:\s*(\S+)").unwrap());
+static K8S_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s+name:\s*(.+?)\s*$").unwrap());
+
+/// 是否為基礎設施設定檔（*.tf、*.tfvars，或 helm/、k8s/ 目錄下的檔案）
+pub fn is_infra_file(path: &str) -> bool {
+    let path = Path::new(path);
+    let extension = path.extension().and_then(|e| e.to_str());
+    if matches!(extension, Some("tf" | "tfvars")) {
+        return true;
+    }
+    path.components()
+        .any(|c| matches!(c.as_os_str().to_str(), Some("helm" | "k8s")))
+}
+
+/// 解析 Terraform 的頂層區塊（resource、data、module），回傳名稱與區塊內容
+fn terraform_blocks(content: &str) -> Vec<(String, String)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in content.lines() {
+        if let Some((_, lines)) = &mut current {
+            lines.push(line);
+            if line.trim_end() == "}" {
+                let (name, lines) = current.take().unwrap();
+                blocks.push((name, lines.join("\n")));
+            }
+            continue;
+        }
+        if let Some(caps) = TF_BLOCK.captures(line) {
+            let name = match (&caps[1], caps.get(3)) {
+                ("resource", Some(name)) => format!("{}.{}", &caps[2], name.as_str()),
+                ("data", Some(name)) => format!("data.{}.{}", &caps[2], name.as_str()),
+                (kind, _) => format!("{}.{}", kind, &caps[2]),
+            };
+            if line.trim_end().ends_with('}') {
+                blocks.push((name, line.to_string()));
+            } else {
+                current = Some((name, vec![line]));
+            }
+        }
+    }
+    blocks
+}
+
+/// 解析 Kubernetes manifest（含 Helm 模板）的各個文件，回傳「Kind/name」與文件內容
+fn kubernetes_resources(content: &str) -> Vec<(String, String)> {
+    let mut resources = Vec::new();
+    let documents = content.split("\n---").map(|doc| doc.trim_start_matches("---"));
+
+    for document in documents {
+        let Some(kind) = document.lines().find_map(|line| K8S_KIND.captures(line)) else {
+            continue;
+        };
+        // metadata 區塊下的第一個 name
+        let name = document
+            .lines()
+            .skip_while(|line| !line.starts_with("metadata:"))
+            .skip(1)
+            .take_while(|line| line.starts_with(' ') || line.is_empty())
+            .find_map(|line| K8S_NAME.captures(line).map(|caps| caps[1].trim_matches(['"', '\'']).to_string()))
+            .unwrap_or_else(|| "?".to_string());
+        resources.push((format!("{}/{}", &kind[1], name), document.trim().to_string()));
+    }
+    resources
+}
+
+fn resources(path: &str, content: &str) -> Vec<(String, String)> {
+    match Path::new(path).extension().and_then(|e| e.to_str()) {
+        Some("tf") => terraform_blocks(content),
+        Some("yaml" | "yml") => kubernetes_resources(content),
+        _ => Vec::new(),
+    }
+}
+
+/// 比對 staged 的基礎設施設定，找出新增、修改與移除的資源；沒有基礎設施檔案時回傳 None
+pub fn analyze(repo: &Repository, files: &[String]) -> Option<InfraSummary> {
+    let infra_files: Vec<&String> = files.iter().filter(|f| is_infra_file(f)).collect();
+    if infra_files.is_empty() {
+        return None;
+    }
+
+    let mut changes = Vec::new();
+    for path in &infra_files {
+        let (old, new) = file_versions(repo, path);
+        let before = resources(path, &old);
+        let after = resources(path, &new);
+
+        for (name, content) in &after {
+            match before.iter().find(|(n, _)| n == name) {
+                None => changes.push((Change::Added, name.clone())),
+                Some((_, previous)) if previous != content => changes.push((Change::Modified, name.clone())),
+                Some(_) => {}
+            }
+        }
+        for (name, _) in &before {
+            if !after.iter().any(|(n, _)| n == name) {
+                changes.push((Change::Destroyed, name.clone()));
+            }
+        }
+    }
+
+    Some(InfraSummary {
+        infra_only: infra_files.len() == files.len(),
+        changes,
+    })
+}
+
+impl InfraSummary {
+    /// 顯示資源變化
+    pub fn print(&self) {
+        println!("{}", "🏗️  偵測到基礎設施設定變更：".blue());
+        if self.changes.is_empty() {
+            println!("  {}", "（未解析到資源層級的變化）".dimmed());
+        }
+        for (change, name) in &self.changes {
+            println!("  {} {}", change.symbol(), name);
+        }
+    }
+
+    /// 提供給提示詞的摘要
+    pub fn prompt_note(&self, scope: &str) -> String {
+        let mut lines = vec!["這次變更包含基礎設施設定（Terraform/Kubernetes/Helm），資源層級的變化如下：".to_string()];
+        for change in [Change::Added, Change::Modified, Change::Destroyed] {
+            let names: Vec<&str> = self
+                .changes
+                .iter()
+                .filter(|(c, _)| *c == change)
+                .map(|(_, name)| name.as_str())
+                .collect();
+            if !names.is_empty() {
+                lines.push(format!("- {}：{}", change.label(), names.join(", ")));
+            }
+        }
+        if self.changes.is_empty() {
+            lines.push("- （未解析到資源層級的變化，例如只修改變數或 values）".to_string());
+        }
+        if !scope.is_empty() {
+            lines.push(format!("基礎設施相關的 commit 請使用 scope「{}」，例如 feat({}): ...", scope, scope));
+        }
+        lines.join("\n")
+    }
+}
+
+/// 為沒有 scope 的 Conventional Commits 標題加上 scope（例如 feat: → feat(infra): ）
+pub fn with_scope(message: &str, scope: &str) -> String {
+    let (subject, rest) = message.split_once('\n').map_or((message, None), |(s, r)| (s, Some(r)));
+    let Some((prefix, description)) = subject.split_once(':') else {
+        return message.to_string();
+    };
+    let breaking = prefix.ends_with('!');
+    let commit_type = prefix.trim_end_matches('!');
+    if scope.is_empty() || commit_type.is_empty() || !commit_type.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return message.to_string();
+    }
+
+    let subject = format!("{}({}){}:{}", commit_type, scope, if breaking { "!" } else { "" }, description);
+    match rest {
+        Some(rest) => format!("{}\n{}", subject, rest),
+        None => subject,
+    }
+}
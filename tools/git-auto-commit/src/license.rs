@@ -0,0 +1,158 @@
+use crate::config::LicenseHeaderConfig;
+use crate::plain;
+use anyhow::{Context, Result};
+use chrono::{Datelike, Local};
+use colored::*;
+use dialoguer::Select;
+use git2::Repository;
+use git_tools_core::git::git;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 檢查標頭時讀取的檔案開頭行數
+const HEADER_LINES: usize = 20;
+
+/// 依副檔名決定的行註解符號
+fn comment_prefix(path: &str) -> Option<&'static str> {
+    let extension = path.rsplit_once('.')?.1.to_lowercase();
+    match extension.as_str() {
+        "rs" | "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" | "go" | "java" | "kt" | "kts" | "swift" | "c" | "h"
+        | "cc" | "cpp" | "hpp" | "cs" | "scala" | "dart" | "php" => Some("//"),
+        "py" | "rb" | "sh" | "bash" | "zsh" | "pl" | "r" | "ps1" | "yaml" | "yml" | "toml" => Some("#"),
+        "sql" | "lua" | "hs" => Some("--"),
+        _ => None,
+    }
+}
+
+/// 授權標頭模板：repository 中的模板檔優先，否則使用設定中的 template，都沒有時回傳 None
+pub fn template(config: &LicenseHeaderConfig, workdir: Option<&Path>) -> Option<String> {
+    workdir
+        .and_then(|workdir| fs::read_to_string(workdir.join(&config.project_template)).ok())
+        .or_else(|| config.template.clone())
+        .map(|template| template.trim_end().to_string())
+        .filter(|template| !template.trim().is_empty())
+}
+
+/// 模板中每一行（不含空行）的比對規則，{year} 可對應任何年份或年份範圍
+fn line_patterns(template: &str) -> Vec<Regex> {
+    template
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            let pattern = regex::escape(line).replace(r"\{year\}", r"\d{4}(\s*-\s*\d{4})?");
+            Regex::new(&format!("^{}$", pattern)).ok()
+        })
+        .collect()
+}
+
+/// 檔案開頭是否已有標頭（忽略註解符號與前後空白）
+fn has_header(content: &str, patterns: &[Regex]) -> bool {
+    let lines: Vec<&str> = content
+        .lines()
+        .take(HEADER_LINES)
+        .map(|line| line.trim().trim_start_matches(['/', '*', '#', '-', ';', '!']).trim())
+        .collect();
+    patterns.iter().all(|pattern| lines.iter().any(|line| pattern.is_match(line)))
+}
+
+/// staged 的新增檔案中缺少授權標頭的檔案（依 staged 的內容判斷）
+pub fn missing(repo: &Repository, config: &LicenseHeaderConfig, template: &str) -> Result<Vec<String>> {
+    let output = git(&["diff", "--cached", "--name-only", "--diff-filter=A"])?;
+    let patterns = line_patterns(template);
+    let index = repo.index().context("無法讀取 index")?;
+    Ok(output
+        .lines()
+        .filter(|path| {
+            let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+            config.extensions.contains(&extension) && comment_prefix(path).is_some()
+        })
+        .filter(|path| {
+            let Some(blob) = index.get_path(Path::new(path), 0).and_then(|entry| repo.find_blob(entry.id).ok()) else {
+                return false;
+            };
+            !blob.is_binary() && !has_header(&String::from_utf8_lossy(blob.content()), &patterns)
+        })
+        .map(String::from)
+        .collect())
+}
+
+/// 以檔案的註解符號產生標頭，{year} 代入今年
+fn render(template: &str, prefix: &str) -> String {
+    let year = Local::now().year().to_string();
+    template
+        .replace("{year}", &year)
+        .lines()
+        .map(|line| if line.trim().is_empty() { prefix.to_string() } else { format!("{} {}", prefix, line) })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 在檔案開頭加上標頭（保留 shebang 與 <?php 開頭）
+fn insert(workdir: &Path, path: &str, template: &str) -> Result<()> {
+    let prefix = comment_prefix(path).context("不支援的檔案類型")?;
+    let full_path = workdir.join(path);
+    let content = fs::read_to_string(&full_path).with_context(|| format!("無法讀取 {}", path))?;
+    let header = render(template, prefix);
+    let updated = match content.split_once('\n') {
+        Some((first, rest)) if first.starts_with("#!") || first.starts_with("<?php") => {
+            format!("{}\n{}\n\n{}", first, header, rest)
+        }
+        _ => format!("{}\n\n{}", header, content),
+    };
+    fs::write(&full_path, updated).with_context(|| format!("無法寫入 {}", path))
+}
+
+/// 列出缺少授權標頭的新檔案，詢問是否加上並重新 git add，回傳是否繼續 commit
+pub fn offer_insert(repo: &Repository, files: &[String], template: &str) -> Result<bool> {
+    println!("{}", format!("📜 {} 個新檔案缺少授權標頭：", files.len()).yellow());
+    for file in files {
+        println!("{}", format!("  - {}", file).yellow());
+    }
+    println!();
+
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("請選擇")
+        .items(&["📜 加上授權標頭並重新 git add", "略過，直接繼續", "✗ 中止"])
+        .default(0)
+        .interact()?;
+    match selection {
+        0 => {}
+        1 => return Ok(true),
+        _ => return Ok(false),
+    }
+
+    let workdir = repo.workdir().context("bare repository 無法修改檔案")?;
+    let mut updated = Vec::new();
+    for file in files {
+        // 工作目錄還有未 staged 的修改時，重新 git add 會一併加入，因此略過
+        let unstaged = !Command::new("git")
+            .args(["diff", "--quiet", "--", file])
+            .status()
+            .context("無法執行 git diff")?
+            .success();
+        if unstaged {
+            println!("{}", format!("⚠️  {} 有尚未 staged 的修改，請手動加上標頭", file).yellow());
+            continue;
+        }
+        match insert(workdir, file, template) {
+            Ok(()) => updated.push(file.as_str()),
+            Err(e) => println!("{}", format!("⚠️  {:#}", e).yellow()),
+        }
+    }
+    if !updated.is_empty() {
+        let status = Command::new("git")
+            .args(["add", "--"])
+            .args(&updated)
+            .status()
+            .context("無法執行 git add")?;
+        if !status.success() {
+            anyhow::bail!("git add 執行失敗");
+        }
+        println!("{}", format!("✓ 已為 {} 個檔案加上授權標頭", updated.len()).green());
+    }
+    println!();
+    Ok(true)
+}
@@ -0,0 +1,116 @@
+use crate::porcelain;
+use crate::progress;
+use colored::*;
+use regex::Regex;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
+/// 已顯示過的警告（同一次執行多次呼叫 LLM 時只顯示一次）
+static SHOWN: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+
+thread_local! {
+    /// capture 執行期間收到的 stderr（此時不直接顯示）
+    static CAPTURED: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// LLM CLI 在 stderr 輸出的警告類別
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// 額度或速率限制即將用完
+    Quota,
+    /// 使用的模型即將停用
+    DeprecatedModel,
+    /// 登入或憑證即將過期
+    Auth,
+}
+
+impl Kind {
+    fn id(self) -> &'static str {
+        match self {
+            Kind::Quota => "quota",
+            Kind::DeprecatedModel => "deprecated-model",
+            Kind::Auth => "auth",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Quota => "額度即將用完",
+            Kind::DeprecatedModel => "模型即將停用",
+            Kind::Auth => "登入狀態",
+        }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            Kind::Quota => "可稍後再試，或改用其他模型",
+            Kind::DeprecatedModel => "請更新設定檔中的 model",
+            Kind::Auth => "請重新登入 LLM CLI",
+        }
+    }
+}
+
+static PATTERNS: LazyLock<Vec<(Kind, Regex)>> = LazyLock::new(|| {
+    [
+        (
+            Kind::Quota,
+            r"(?i)\bquota\b|rate.?limit|resource.?exhausted|usage limit|\b429\b|credits? (are |is )?(running )?low|remaining (requests|credits)",
+        ),
+        (
+            Kind::DeprecatedModel,
+            r"(?i)deprecat|will be (retired|discontinued|removed|shut ?down)|end.of.life|\bsunset",
+        ),
+        (
+            Kind::Auth,
+            r"(?i)(token|credentials?|session|api key) (has |have |is )?(expir|invalid)|re-?authenticate|please (log ?in|sign in)|unauthori[sz]ed",
+        ),
+    ]
+    .into_iter()
+    .map(|(kind, pattern)| (kind, Regex::new(pattern).unwrap()))
+    .collect()
+});
+
+/// 找出 stderr 中符合已知警告的行（每行只歸入第一個符合的類別）
+fn classify(stderr: &str) -> Vec<(Kind, String)> {
+    stderr
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .filter_map(|line| {
+            PATTERNS
+                .iter()
+                .find(|(_, pattern)| pattern.is_match(line))
+                .map(|(kind, _)| (*kind, line.to_string()))
+        })
+        .collect()
+}
+
+/// 執行 f 期間不顯示警告，改為回傳收到的 stderr；供背景執行緒使用，
+/// 避免打斷主執行緒的互動式輸入，之後再由主執行緒以 surface 顯示
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, String) {
+    CAPTURED.with(|captured| *captured.borrow_mut() = Some(String::new()));
+    let result = f();
+    let stderr = CAPTURED.with(|captured| captured.borrow_mut().take()).unwrap_or_default();
+    (result, stderr)
+}
+
+/// LLM CLI 成功執行時，顯示 stderr 中的額度、模型停用等警告（其餘輸出視為雜訊忽略）
+pub fn surface(command: &str, stderr: &str) {
+    let captured = CAPTURED.with(|captured| captured.borrow_mut().as_mut().map(|buffer| buffer.push_str(stderr)).is_some());
+    if captured {
+        return;
+    }
+    let Ok(mut shown) = SHOWN.lock() else {
+        return;
+    };
+    let shown = shown.get_or_insert_with(HashSet::new);
+    for (kind, line) in classify(stderr) {
+        if !shown.insert(line.clone()) {
+            continue;
+        }
+        progress::println(&format!("⚠️  {}（{}）：{}", command, kind.label(), line).yellow().to_string());
+        progress::println(&format!("   {}", kind.hint()).dimmed().to_string());
+        porcelain::warning(kind.id(), &line);
+    }
+}
@@ -1,17 +1,178 @@
+/// 輸出一行文字；--plain 時將 emoji 與框線字元換成 ASCII 標記（覆寫標準的 println!，各模組共用）
+macro_rules! println {
+    () => { std::println!() };
+    ($($arg:tt)*) => {
+        if $crate::plain::enabled() {
+            std::println!("{}", $crate::plain::text(&format!($($arg)*)))
+        } else {
+            std::println!($($arg)*)
+        }
+    };
+}
+
+/// 輸出文字（不換行）；--plain 時同 println!
+macro_rules! print {
+    ($($arg:tt)*) => {
+        if $crate::plain::enabled() {
+            std::print!("{}", $crate::plain::text(&format!($($arg)*)))
+        } else {
+            std::print!($($arg)*)
+        }
+    };
+}
+
+mod annotate;
+mod assets;
+mod audit;
+mod bitbucket;
+mod branch;
+mod changelog;
+mod ci;
+mod cli;
+mod compliance;
+mod concerns;
+mod config;
+mod conflict;
+mod coverage;
+mod date;
+mod deps;
+mod doctor;
+mod embeddings;
+mod encoding;
+mod experiment;
+mod fixup;
+mod forge;
+mod gerrit;
+mod github;
+mod gitlab;
+mod history;
+mod hooks;
+mod identity;
+mod infra;
+mod license;
+mod llm_warnings;
+mod merge;
+mod migration;
+mod mode;
+mod model_policy;
+mod new_branch;
+mod new_files;
+mod notebook;
+mod notes;
+mod patch;
+mod plan;
+mod plugin;
+mod porcelain;
+mod preflight;
+mod pregen;
+mod rebase_plan;
+mod progress;
+mod ranking;
+mod release_checks;
+mod remote;
+mod repo_state;
+mod review;
+mod risk;
+mod scope;
+#[cfg(feature = "lua")]
+mod script;
+#[cfg(not(feature = "lua"))]
+#[path = "script_disabled.rs"]
+mod script;
+mod session;
+mod setup;
+mod shallow;
+mod side_by_side;
+mod split;
+mod stash;
+mod stack;
+mod summary;
+mod tag;
+mod terminology;
+mod trailers;
+mod update;
+mod usage;
+mod version_files;
+mod whitespace;
+mod workflow;
+
 use anyhow::{Context, Result};
-use chrono::Local;
+use chrono::{DateTime, Local};
+use clap::Parser;
+use cli::{Cli, Commands, StatsView};
 use colored::*;
-use dialoguer::{theme::ColorfulTheme, Input, Select};
-use git2::{Repository, StatusOptions};
-use serde::{Deserialize, Serialize};
+use config::{load_llm_config, ConfirmMode, CoverageMode, Identity, LlmConfig, PluginsConfig, PreflightMode, PromptVia, SplitMode};
+use coverage::Verdict;
+use dialoguer::{Confirm, Input, Select};
+use experiment::{Preference, PromptVariant};
+use git2::Repository;
+use git_tools_core::git::{get_current_branch, get_staged_files, is_valid_branch_name, stage_tracked_changes};
+use git_tools_core::{input, layout, menu, plain, text};
+use plugin::{PluginContext, PluginHost};
+use progress::Timeline;
+use remote::FetchOutcome;
+use serde::Serialize;
 use std::env;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// 掃描工作目錄超過此時間時，提示可用 --paths 縮小範圍
+const SLOW_SCAN: Duration = Duration::from_secs(1);
 
 fn main() -> Result<()> {
+    let cli = Cli::parse();
+    if cli.plain {
+        plain::enable();
+    }
+
+    match &cli.command {
+        Some(Commands::Stack(args)) => return stack::run(args),
+        Some(Commands::Setup) => return setup::run(),
+        Some(Commands::Doctor) => return doctor::run(),
+        Some(Commands::SelfUpdate(args)) => return update::run(args),
+        Some(Commands::Summary(args)) => return summary::run(args),
+        Some(Commands::Stats(args)) => match args.view {
+            StatsView::Prompts => return history::print_prompt_stats(),
+        },
+        Some(Commands::Branch(args)) => return new_branch::run(args),
+        Some(Commands::Plan(args)) => return plan::run(args),
+        Some(Commands::Tag(args)) => return tag::run(args),
+        Some(Commands::Stash(args)) => return stash::run(args),
+        Some(Commands::RebasePlan(args)) => return rebase_plan::run(args),
+        Some(Commands::Annotate(args)) => return annotate::run(args),
+        None => {}
+    }
+
+    if let Some(target) = &cli.porcelain {
+        porcelain::init(target)?;
+    }
+
+    let result = run(&cli);
+    if let Err(e) = &result {
+        porcelain::failed(&format!("{:#}", e));
+    }
+    result
+}
+
+/// commit 流程：分析 staged 變更、選擇分支與 commit 訊息並執行 commit
+fn run(cli: &Cli) -> Result<()> {
+    // 先解析 --date，格式錯誤時立即結束
+    let mut commit_options = CommitOptions::default();
+    if let Some(input) = &cli.date {
+        let date = date::parse_date(input, Local::now())?;
+        warn_if_future(&date);
+        commit_options.date = Some(date);
+    }
+
     println!("\n{}\n", "🚀 Git 自動 Commit 工具".cyan().bold());
 
+    // 首次執行時啟動設定精靈
+    setup::run_if_first_run()?;
+
     // 檢查是否在 git repository 中
     // 使用當前工作目錄而非執行檔所在目錄
     let current_dir = env::current_dir().context("無法取得當前目錄")?;
@@ -21,410 +182,1932 @@ fn main() -> Result<()> {
     let current_branch = get_current_branch(&repo)?;
     println!("{}", format!("當前分支：{}\n", current_branch).dimmed());
 
-    // 檢查 staged 變更
-    let staged_files = get_staged_files(&repo)?;
-    if staged_files.is_empty() {
-        println!(
-            "{}",
-            "⚠️  沒有 staged 的檔案變更，請先使用 git add 加入檔案"
-                .yellow()
-        );
-        std::process::exit(1);
-    }
-
-    // 顯示 staged 檔案
-    println!("{}", "📝 Staged 檔案：".blue());
-    for file in &staged_files {
-        println!("{}", format!("  - {}", file).dimmed());
+    // 合併、rebase、cherry-pick 或 revert 進行中：確認衝突都已解決並加入 staged
+    let operation = conflict::Operation::detect(&repo);
+    if let Some(operation) = operation {
+        if !conflict::ensure_resolved(&repo, operation)? {
+            porcelain::result("conflicts");
+            std::process::exit(1);
+        }
+        commit_options.author_env = conflict::original_author(&repo, operation);
     }
-    println!();
-
-    // 取得 diff 內容用於分析
-    let diff_content = get_staged_diff(&repo)?;
 
     // 載入設定（只載入一次）
-    let config = load_llm_config();
+    let mut config = load_llm_config();
+    if !cli.offline {
+        update::notify_if_due(&config.update);
+    }
 
-    // 生成建議（單次 LLM 請求）
-    let suggestions = generate_suggestions(&diff_content, &staged_files, &config);
+    stage_working_tree(&repo, cli, &config)?;
 
-    // 詢問是否要切換分支
-    let branch_choice = select_branch(&current_branch, &suggestions.branch_names)?;
+    let (mut staged_files, scope) = collect_staged_files(&repo, cli)?;
 
-    // 處理分支切換
-    if let Some(new_branch) = branch_choice {
-        switch_branch(&new_branch)?;
+    // 顯示 staged 檔案（圖片、字型附上大小與尺寸的變化；檔案很多時依頂層目錄摺疊）
+    let assets = assets::analyze(&repo, &staged_files);
+    print_staged_files(&staged_files, assets.as_ref(), !cli.quick)?;
+    for file in &staged_files {
+        porcelain::staged(file);
     }
-
     println!();
 
-    // 詢問 commit 訊息（內含預覽和確認循環）
-    let commit_message = select_commit_message(&suggestions.commit_messages)?;
+    reject_conflict_markers(&repo, &staged_files);
 
-    // 執行 commit
-    commit_changes(&commit_message)?;
+    // 顯示 commit 時會執行的 hooks（依 core.hooksPath 解析）
+    print_commit_hooks(&repo);
 
-    println!();
-    Ok(())
-}
-
-/// 取得當前分支名稱
-fn get_current_branch(repo: &Repository) -> Result<String> {
-    let head = repo.head()?;
-    let branch_name = head
-        .shorthand()
-        .unwrap_or("main")
-        .to_string();
-    Ok(branch_name)
-}
-
-/// 取得 staged 的檔案列表
-fn get_staged_files(repo: &Repository) -> Result<Vec<String>> {
-    let mut opts = StatusOptions::new();
-    opts.include_untracked(false);
-    
-    let statuses = repo.statuses(Some(&mut opts))?;
-    let mut staged_files = Vec::new();
-
-    for entry in statuses.iter() {
-        let status = entry.status();
-        if status.is_index_new()
-            || status.is_index_modified()
-            || status.is_index_deleted()
-            || status.is_index_renamed()
-            || status.is_index_typechange()
-        {
-            if let Some(path) = entry.path() {
-                staged_files.push(path.to_string());
-            }
-        }
+    // --fixup / --squash：不生成訊息，直接建立 fixup! / squash! commit
+    let fixup_target = match (&cli.fixup, &cli.squash) {
+        (Some(target), _) => Some((fixup::Kind::Fixup, target)),
+        (_, Some(target)) => Some((fixup::Kind::Squash, target)),
+        _ => None,
+    };
+    if let Some((kind, target)) = fixup_target {
+        fixup::run(&repo, kind, target, &config, cli.quick, &scope, &mut commit_options)?;
+        println!();
+        return Ok(());
     }
 
-    Ok(staged_files)
-}
-
-/// 取得 staged 的 diff 內容（優化版，減少 token 使用但保留關鍵資訊）
-fn get_staged_diff(_repo: &Repository) -> Result<String> {
-    // 優化參數說明：
-    // --inter-hunk-context=1: 減少 hunk 之間的空白行
-    // --ignore-space-change: 忽略空白變更（減少雜訊）
-    // --ignore-blank-lines: 忽略空白行變更
-    // --no-prefix: 移除 a/ 和 b/ 前綴（節省 token）
-    // --no-color: 確保沒有 ANSI 顏色碼
-    let output = Command::new("git")
-        .args(&[
-            "diff",
-            "--staged",
-            "--inter-hunk-context=1",
-            "--ignore-space-change",
-            "--ignore-blank-lines",
-            "--no-prefix",
-            "--no-color"
-        ])
-        .output()
-        .context("無法執行 git diff")?;
+    apply_run_options(&repo, cli, &mut config);
+    check_license_headers(&repo, &config, cli.quick)?;
+    let whitespace_only = review_whitespace_only(cli.quick, &mut staged_files)?;
+    let mode_changes = review_mode_changes(cli.quick, &mut staged_files)?;
+    let prompt_files = prompt_files_for(&staged_files, &whitespace_only);
 
-    if !output.status.success() {
-        anyhow::bail!("git diff 執行失敗");
-    }
+    let replay = operation.filter(|_| config.merge.detect);
+    let (merge, resolutions) = review_resolutions(&repo, &config, replay, &current_branch)?;
 
-    let diff = String::from_utf8_lossy(&output.stdout).to_string();
-    
-    Ok(diff)
-}
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path()).display().to_string();
+    let plugins = load_plugins(&config, cli.offline, &workdir, &current_branch, &staged_files);
 
-/// 取得檔案的簡要資訊
-fn get_file_summary(files: &[String]) -> String {
-    let mut summary = String::new();
-    
-    for file in files {
-        let path = std::path::Path::new(file);
-        
-        // 判斷檔案類型
-        let file_type = if let Some(ext) = path.extension() {
-            match ext.to_str() {
-                Some("rs") => "Rust 程式碼",
-                Some("js") | Some("ts") => "JavaScript/TypeScript",
-                Some("py") => "Python 程式碼",
-                Some("java") => "Java 程式碼",
-                Some("go") => "Go 程式碼",
-                Some("md") => "Markdown 文檔",
-                Some("toml") | Some("yaml") | Some("yml") | Some("json") => "設定檔",
-                Some("html") | Some("css") => "前端檔案",
-                _ => "其他檔案",
-            }
-        } else {
-            "無副檔名"
-        };
-        
-        summary.push_str(&format!("- {}: {}\n", file, file_type));
+    // 執行 lefthook / lint-staged 的預先檢查
+    if let Some(workdir) = repo.workdir() {
+        if !run_preflight(&config, &staged_files, workdir)? {
+            println!("{}", "已中止".yellow());
+            porcelain::result("aborted");
+            std::process::exit(1);
+        }
     }
-    
-    summary
-}
-
-/// LLM 建議結果
-#[derive(Debug, Clone)]
-struct GitSuggestions {
-    branch_names: Vec<String>,
-    commit_messages: Vec<String>,
-}
-
-/// LLM CLI 設定
-#[derive(Debug, Deserialize, Serialize, Clone)]
-struct LlmConfig {
-    /// LLM CLI 指令（例如：gemini）
-    #[serde(default = "default_command")]
-    command: String,
-    /// 提示參數標記（例如：-p）
-    #[serde(default = "default_prompt_flag")]
-    prompt_flag: String,
-    /// 模型參數標記（例如：--model）
-    #[serde(default = "default_model_flag")]
-    model_flag: String,
-    /// 模型名稱（例如：gemini-2.5-flash）
-    #[serde(default = "default_model")]
-    model: String,
-    /// 額外參數（例如：--yolo）
-    #[serde(default = "default_extra_args")]
-    extra_args: Vec<String>,
-    /// 合併的提示詞模板
-    #[serde(default = "default_combined_prompt")]
-    combined_prompt: String,
-}
-
-fn default_command() -> String {
-    "gemini".to_string()
-}
-
-fn default_prompt_flag() -> String {
-    "-p".to_string()
-}
-
-fn default_model_flag() -> String {
-    "--model".to_string()
-}
-
-fn default_model() -> String {
-    "gemini-2.5-flash".to_string()
-}
-
-fn default_extra_args() -> Vec<String> {
-    vec![]
-}
 
-fn default_combined_prompt() -> String {
-    r#"你是一個 Git 專家。請根據以下資訊，生成分支名稱和 commit 訊息建議。
+    let mut timeline = Timeline::new();
 
-變更統計：
-{stats}
+    sync_remote(&repo, &config, cli, &current_branch, operation, &mut staged_files, &mut timeline)?;
 
-檔案列表與類型：
-{file_summary}
+    let diff_content = collect_diff(&repo, &mut config, &scope, staged_files.len(), &mut timeline)?;
+    let risks = check_risks(&config, cli, &diff_content, &mode_changes)?;
+    let (mut change_notes, changes) = analyze_changes(&repo, &config, (&staged_files, &prompt_files), assets, &mode_changes);
 
-詳細變更（Git diff with context）：
-```
-{diff}
-```
+    // plan 子指令為此分支擬定的 commit 標題草稿：加入提示詞，並在產生建議後列為其中一個建議
+    let draft = branch::draft_commit(&repo, &current_branch).filter(|_| replay.is_none());
+    if let Some(draft) = &draft {
+        println!("{}", format!("🗺️  此分支規劃時的 commit 標題草稿：{}", draft).dimmed());
+        change_notes.push(format!(
+            "開始這項工作前擬定的 commit 標題草稿：{}（可參考其意圖，但請以實際的變更為準）",
+            draft
+        ));
+    }
 
-Determine the best branch naming prefixes.
+    // 關聯的 issue：ticket 為 issue 編號時向代管服務查詢標題並加入提示詞
+    if config.forge.issue_lookup && !cli.offline {
+        if let Some(note) = issue_note(&repo, &config, &current_branch, cli.ticket.as_deref()) {
+            change_notes.push(note);
+        }
+    }
 
-Here are the prefixes you can choose from:
+    // 合併、rebase 等不呼叫 LLM 時不搜尋相似的 commit
+    let search_similar = replay.is_none() && !cli.offline;
+    let examples = few_shot_examples(&repo, &config, &workdir, search_similar, (&prompt_files, &diff_content), &mut timeline);
 
-- feature/: For new features (e.g., feature/add-login-page, feat/add-login-page)
-- bugfix/: For bug fixes (e.g., bugfix/fix-header-bug, fix/header-bug)
-- hotfix/: For urgent fixes (e.g., hotfix/security-patch)
-- release/: For branches preparing a release (e.g., release/v1.2.0)
-- chore/: For non-code tasks like dependency, docs updates (e.g., chore/update-dependencies)
+    let mut prompt_context = PromptContext {
+        existing_branches: branch::existing_branch_names(&repo),
+        description: cli.describe.as_deref().map(str::trim).filter(|d| !d.is_empty()).map(String::from),
+        change_notes,
+        examples,
+    };
 
-Determine the best label for the commit.
+    // 使用者對變更目的的說明（--describe 未提供內容或設定 ask_description 時詢問）
+    let ask_description = match &cli.describe {
+        Some(text) => text.trim().is_empty(),
+        None => config.ask_description && cli.rewrite.is_none() && !cli.quick && !cli.offline,
+    };
+    if ask_description {
+        prompt_context.description = ask_change_description()?;
+    }
 
-Here are the labels you can choose from:
+    // 在背景預先生成建議，檢查關注點（可能請 LLM 判斷）時同時進行
+    let pregen = should_pregenerate(&repo, &config, cli, replay.is_some(), &diff_content, &staged_files)
+        .then(|| {
+            let prompt = build_combined_prompt(&diff_content, &prompt_files, &prompt_context, &config);
+            pregen::Pregen::start(prompt, &config)
+        })
+        .flatten();
+
+    // 生成建議（單次 LLM 請求；A/B 比較模式則分別以兩個模板各請求一次；--rewrite 則改寫使用者的描述）
+    // 多個相依套件更新或混合多個關注點時，可選擇拆成多個 commit
+    let mut split_groups = None;
+    // 由 LLM 產生建議時所用的提示詞模板，記錄於歷史紀錄
+    let mut variant = None;
+    let mut suggestions = match (&cli.rewrite, &cli.compare_prompts, &merge, replay) {
+        (Some(text), _, _, _) => {
+            let text = if text.trim().is_empty() {
+                Input::with_theme(plain::theme())
+                    .with_prompt("用自己的話描述這次的變更（任何語言皆可）")
+                    .interact_text()?
+            } else {
+                text.clone()
+            };
+            variant = Some(history::variant_id("rewrite", &config.rewrite_prompt, &LlmConfig::default().rewrite_prompt));
+            rewrite_message(text.trim(), &diff_content, &prompt_files, &prompt_context, &config, &mut timeline)
+        }
+        (None, Some(paths), _, _) => {
+            let (suggestions, chosen) =
+                compare_prompts(paths, &diff_content, &prompt_files, &prompt_context, &config, &mut timeline)?;
+            variant = chosen;
+            suggestions
+        }
+        (None, None, Some(merge), _) => {
+            println!("{}", "🔀 依合併的分支與衝突檔案產生訊息（不呼叫 LLM）".blue());
+            merge.suggestions(&resolutions)
+        }
+        (None, None, None, Some(operation)) => {
+            println!("{}", format!("🔀 沿用 {} 原本的訊息並加上衝突的解決方式（不呼叫 LLM）", operation.name()).blue());
+            conflict::replay_suggestions(&repo, operation, &resolutions)
+        }
+        (None, None, None, None) => {
+            let (suggestions, groups, chosen) = generate_for_staged(
+                &repo,
+                cli,
+                &config,
+                (&staged_files, &prompt_files, &diff_content),
+                &prompt_context,
+                pregen,
+                &mut timeline,
+            )?;
+            split_groups = groups;
+            variant = chosen;
+            suggestions
+        }
+    };
 
-- build: Changes that affect the build system or external dependencies (example scopes: gulp, broccoli, npm)
-- chore: Updating libraries, copyrights, or other repo settings, includes updating dependencies.
-- ci: Changes to our CI configuration files and scripts (example scopes: Travis, Circle, GitHub Actions)
-- docs: Non-code changes, such as fixing typos or adding new documentation (example scopes: Markdown files)
-- feat: A commit of the type feat introduces a new feature to the codebase
-- fix: A commit of the type fix patches a bug in your codebase
-- perf: A code change that improves performance
-- refactor: A code change that neither fixes a bug nor adds a feature
-- style: Changes that do not affect the meaning of the code (white-space, formatting, missing semi-colons, etc.)
-- test: Adding missing tests or correcting existing tests
+    if cli.verbose {
+        timeline.print_summary();
+    }
 
-請按照以下格式回覆：
+    // LLM 產生的建議依評分由高至低排列並移除相近的建議（A/B 比較維持交錯的順序，避免影響比較結果）
+    if config.ranking.enabled && variant.is_some() && cli.compare_prompts.is_none() {
+        let removed = ranking::rank(&mut suggestions, &config.ranking, &prompt_files, &diff_content);
+        if removed > 0 {
+            println!("{}", format!("🧮 已移除 {} 個相近的建議", removed).dimmed());
+        }
+    }
 
-[BRANCHES]
-feature/example-feature
-fix/example-bug
-chore/example-task
+    // 交由外掛與腳本處理建議
+    plugins.post_process(&mut suggestions);
+    script::transform_suggestions(&mut suggestions);
 
-[COMMITS]
-feat: 新增使用者登入功能
+    adjust_suggestions(&mut suggestions, &config, &changes, draft.as_deref());
 
-實作完整的使用者登入流程，包含密碼驗證與 session 管理。
+    filter_branch_names(
+        &repo,
+        &config,
+        &current_branch,
+        cli.ticket.as_deref(),
+        &prompt_context.existing_branches,
+        &mut suggestions,
+    );
 
+    for (i, name) in suggestions.branch_names.iter().enumerate() {
+        porcelain::suggestion("branch", i, name);
+    }
+    for (i, message) in suggestions.commit_messages.iter().enumerate() {
+        porcelain::suggestion("commit", i, message);
+    }
 
-fix: 修正資料庫連線錯誤
+    // 禁止出現在分支名稱與 commit 訊息中的字詞
+    let compliance = compliance::Filter::new(&config.compliance);
+
+    // 詢問是否要切換分支（記錄新建立的分支，commit 取消時可復原）；合併、rebase 等操作進行中時留在目前分支
+    // 快速模式下 workflow 允許直接 commit 時不詢問；匯出 patch 不建立 commit，因此不切換分支
+    let quick = cli.quick && workflow::allows_direct_commit(config.workflow, &current_branch);
+    let created_branch = if operation.is_none() && !quick && cli.export_patch.is_none() {
+        choose_branch(
+            &repo,
+            &config,
+            cli.offline,
+            &current_branch,
+            &suggestions.branch_names,
+            (&diff_content, &prompt_files),
+            &compliance,
+        )?
+    } else {
+        None
+    };
 
-修正了在高並發情況下資料庫連線池耗盡的問題。
+    println!();
 
+    // 記錄本次執行建立的 commit，結束時顯示摘要
+    let mut session = session::Session::new(&repo);
+
+    // 詢問 commit 訊息（內含預覽和確認循環）並執行 commit
+    let terms = terminology::Checker::new(&config.terminology, repo.workdir(), &config.language);
+    let mut chosen_message = None;
+    // 快速模式：預覽第一個建議並確認一次，選否時改用一般的選擇流程
+    let quick_message = if quick && split_groups.is_none() {
+        confirm_quick_commit(
+            &suggestions,
+            &get_current_branch(&repo)?,
+            config.confirm,
+            &terms,
+            &risks,
+            &compliance,
+        )?
+    } else {
+        None
+    };
+    let commit_result = match &split_groups {
+        Some(split) => commit_split_groups(
+            &repo,
+            split,
+            &config,
+            cli.ticket.as_deref(),
+            &scope,
+            &MessageChecks {
+                plugins: &plugins,
+                terms: &terms,
+                compliance: &compliance,
+            },
+            &mut commit_options,
+        )
+        .map(CommitOutcome::from),
+        None => quick_message
+            .map(|message| Ok(Some(message)))
+            .unwrap_or_else(|| {
+                select_commit_message(
+                    &suggestions,
+                    &MenuPreferences {
+                        confirm: config.confirm,
+                        commit_type: repo_state::load(&repo, &config).commit_type.as_deref(),
+                    },
+                    &plugins,
+                    &terms,
+                    &risks,
+                    &compliance,
+                    &mut commit_options,
+                )
+            })
+            .and_then(|message| match message {
+                Some(_) if !changes.migrations.is_empty() && !confirm_migrations(&changes.migrations)? => {
+                    Ok(CommitOutcome::Cancelled)
+                }
+                Some(message) => {
+                    chosen_message = Some((message.clone(), suggestions.commit_messages.contains(&message)));
+                    let Some(message) =
+                        finish_message(&repo, &config, cli.ticket.as_deref(), message, (&risks, &changes.migrations))?
+                    else {
+                        return Ok(CommitOutcome::Cancelled);
+                    };
+                    select_author(&repo, &config, quick, &mut commit_options)?;
+                    create_commit(&repo, &scope, &message, &commit_options, cli.export_patch.as_deref())
+                }
+                None => Ok(CommitOutcome::Cancelled),
+            }),
+    };
 
-chore: 更新專案依賴套件
+    match commit_result {
+        Ok(CommitOutcome::Committed(mut summaries)) => {
+            if let [summary] = summaries.as_mut_slice() {
+                annotate_commit(&repo, &config, summary, &suggestions, operation.is_none());
+            }
+            // 草稿已完成任務，在該分支 commit 後移除
+            if draft.is_some() && summaries.iter().any(|summary| summary.branch == current_branch) {
+                branch::clear_draft_commit(&repo, &current_branch);
+            }
+            // 記住這次選擇的 commit 類型，下次選單預設選擇同類型的建議
+            if let Some(commit_type) = chosen_message.as_ref().and_then(|(message, _)| conventional_type(message)) {
+                repo_state::update(&repo, &config, |state| state.commit_type = Some(commit_type.to_string()));
+            }
+            for summary in summaries {
+                porcelain::committed(&summary);
+                session.record(summary);
+            }
+            // 依設定詢問評分（快速模式不詢問）並寫入歷史紀錄
+            if let (true, Some(variant), Some(chosen)) = (config.history.enabled, &variant, &chosen_message) {
+                record_history(&config, cli.quick, &workdir, variant, chosen, &suggestions);
+            }
+            offer_follow_up(&repo, &config, cli, operation, &compliance)?;
+        }
+        Ok(CommitOutcome::Exported(path)) => {
+            println!("{}", format!("📨 已匯出 patch：{}", path.display()).green());
+            println!("{}", "  staged 的變更維持不變，未建立 commit".dimmed());
+            porcelain::exported(&path.display().to_string());
+        }
+        Ok(CommitOutcome::Cancelled) => {
+            println!("{}", "已取消 commit".yellow());
+            porcelain::result("cancelled");
+            if let Some(new_branch) = &created_branch {
+                offer_branch_rollback(&current_branch, new_branch)?;
+            }
+        }
+        Err(e) => {
+            if let Some(new_branch) = &created_branch {
+                offer_branch_rollback(&current_branch, new_branch)?;
+            }
+            return Err(e);
+        }
+    }
 
-更新所有依賴套件至最新穩定版本，提升安全性。
+    session.finish(&config.session);
 
-要求：
-1. 仔細分析 diff 的完整上下文，理解變更的真實意圖
-2. [BRANCHES] 區塊包含 3 個分支名稱建議，格式為「type/description」
-   - type 可選：請依據 naming prefixes 選擇最合適的類型
-   - description 使用英文小寫，單字之間用連字號 - 連接，不超過 30 字元
-3. [COMMITS] 區塊包含 3 個 commit 訊息建議
-   - **重要**：每個 commit 訊息必須以「type:」開頭（type 為英文）
-   - 第一行格式：「type: 簡短描述」，type 使用英文，描述使用繁體中文
-   - type 可選：請依據上述 labels 選擇最合適的類型
-   - 描述要精確反映實際變更內容，不超過 50 字
-   - 並補充說明，在第二行之後使用繁體中文詳細說明（限 5 行內）
-   - **重要**：每個 commit 訊息之間必須用空行分隔
-4. 不要使用 markdown 格式，不要編號
-5. 善用函數名稱、變數名稱等上下文資訊來理解變更目的
-6. 確保每個 commit 訊息都是完整且獨立的，不要將說明文字誤認為獨立的 commit"#
-        .to_string()
+    println!();
+    Ok(())
 }
 
-impl Default for LlmConfig {
-    fn default() -> Self {
-        Self {
-            command: default_command(),
-            prompt_flag: default_prompt_flag(),
-            model_flag: default_model_flag(),
-            model: default_model(),
-            extra_args: default_extra_args(),
-            combined_prompt: default_combined_prompt(),
-        }
+/// 合併、rebase、cherry-pick 或 revert 進行中：顯示合併的雙方，並詢問各衝突檔案的解決方式，
+/// 用來依衝突的解決方式產生訊息
+fn review_resolutions(
+    repo: &Repository,
+    config: &LlmConfig,
+    replay: Option<conflict::Operation>,
+    current_branch: &str,
+) -> Result<(Option<merge::MergeInfo>, Vec<conflict::Resolution>)> {
+    let merge = replay
+        .filter(|op| *op == conflict::Operation::Merge)
+        .and_then(|_| merge::detect(repo, current_branch));
+    if let Some(merge) = &merge {
+        merge.print();
     }
+    let resolutions = match replay {
+        Some(operation) => conflict::resolutions(repo, operation, config.merge.ask_resolution)?,
+        None => Vec::new(),
+    };
+    conflict::print(&resolutions);
+    Ok((merge, resolutions))
 }
 
-/// 取得設定檔路徑
-fn get_config_path() -> PathBuf {
-    let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
-    PathBuf::from(home).join(".config").join("git-auto-commit").join("config.toml")
-}
-
-/// 載入 LLM 設定
-fn load_llm_config() -> LlmConfig {
-    let config_path = get_config_path();
-    
-    if config_path.exists() {
-        match fs::read_to_string(&config_path) {
-            Ok(content) => {
-                match toml::from_str::<LlmConfig>(&content) {
-                    Ok(config) => {
-                        println!("{}", format!("📝 已載入設定檔：{}", config_path.display()).dimmed());
-                        return config;
-                    }
-                    Err(e) => {
-                        println!("{}", format!("⚠️  設定檔格式錯誤：{}，使用預設設定", e).yellow());
+/// 選擇要切換或建立的分支，直到 workflow 允許的選擇為止；回傳新建立的分支（commit 取消時可復原）。
+/// `changes` 為 diff 與變更的檔案，用來產生新分支的說明
+fn choose_branch(
+    repo: &Repository,
+    config: &LlmConfig,
+    offline: bool,
+    current_branch: &str,
+    suggestions: &[String],
+    (diff, files): (&str, &[String]),
+    compliance: &compliance::Filter,
+) -> Result<Option<String>> {
+    loop {
+        let Some(new_branch) = select_branch(current_branch, suggestions)? else {
+            if workflow::allows_direct_commit(config.workflow, current_branch) {
+                return Ok(None);
+            }
+            println!(
+                "{}",
+                format!("⚠️  目前的 workflow 不允許直接 commit 到 {}，請選擇或建立分支", current_branch).yellow()
+            );
+            continue;
+        };
+        if !check_compliance("分支名稱", &new_branch, compliance) {
+            continue;
+        }
+        match resolve_branch_collision(repo, &new_branch)? {
+            BranchAction::Create(name) => {
+                switch_branch(&name, branch_start_point(repo, config, current_branch).as_deref())?;
+                porcelain::branch("created", &name);
+                if config.branch_description.enabled && !offline {
+                    if let Some(description) = describe_branch(&name, diff, files, config) {
+                        save_branch_description(repo, &name, &description);
                     }
                 }
+                return Ok(Some(name));
             }
-            Err(e) => {
-                println!("{}", format!("⚠️  無法讀取設定檔：{}，使用預設設定", e).yellow());
+            BranchAction::SwitchExisting(name, remote) => {
+                checkout_existing_branch(&name, remote.as_deref())?;
+                porcelain::branch("switched", &name);
+                return Ok(None);
             }
+            BranchAction::Reselect => continue,
         }
     }
-    
-    LlmConfig::default()
 }
 
-/// 使用 LLM CLI 生成建議
-fn call_llm_cli(prompt: &str, config: &LlmConfig) -> Result<String> {
-    
-    // 建立指令
-    let mut cmd = Command::new(&config.command);
-    
-    // 添加提示參數
-    cmd.arg(&config.prompt_flag).arg(prompt);
-    
-    // 添加模型參數
-    cmd.arg(&config.model_flag).arg(&config.model);
-    
-    // 添加額外參數
-    for arg in &config.extra_args {
-        cmd.arg(arg);
+/// 為選定的訊息加上待辦事項、migration 與 trailer；啟用 Gerrit 時確認訊息並加上 Change-Id，
+/// 使用者取消時回傳 None
+fn finish_message(
+    repo: &Repository,
+    config: &LlmConfig,
+    ticket: Option<&str>,
+    message: String,
+    (risks, migrations): (&risk::Report, &[migration::Migration]),
+) -> Result<Option<String>> {
+    let message = if config.todo.append_section {
+        risk::with_todo_section(&message, risks)
+    } else {
+        message
+    };
+    let message = migration::with_footer(&message, migrations);
+    let trailers = trailers::resolve(repo, config, ticket)?;
+    trailers::print(&trailers);
+    let message = trailers::apply(&message, &trailers)?;
+    if !config.gerrit.enabled {
+        return Ok(Some(message));
     }
-    
-    // 執行指令
-    let output = cmd
-        .output()
-        .context(format!("無法執行 {} 指令，請確認已安裝 {} CLI 工具", config.command, config.command))?;
-    
-    if !output.status.success() {
-        let error = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("{} 執行失敗：{}", config.command, error);
+    if !gerrit::confirm_message(&message, &config.gerrit)? {
+        return Ok(None);
     }
-    
-    let response = String::from_utf8_lossy(&output.stdout).to_string();
-    Ok(response.trim().to_string())
+    Ok(Some(gerrit::with_change_id(repo, &message)))
 }
 
-/// 生成分支和 commit 建議（使用 LLM，單次請求）
-fn generate_suggestions(diff: &str, files: &[String], config: &LlmConfig) -> GitSuggestions {
-    println!("{}", "🤖 正在使用 LLM 生成建議...".dimmed());
-    
-    // 增加檔案類型摘要，提供更多上下文
-    let file_summary = get_file_summary(files);
-    
-    // 計算 diff 的統計資訊
-    let stats = get_diff_stats(diff);
-    
-    // 根據 diff 大小動態調整限制（增加到 8000 字元以保留更多上下文）
-    let diff_preview = if diff.len() > 8000 {
-        // 如果超過限制，優先保留前面和後面的部分
-        let front = &diff[..4000];
-        let back_start = diff.len().saturating_sub(4000);
-        let back = &diff[back_start..];
-        format!("{}\n\n... (中間省略) ...\n\n{}", front, back)
-    } else {
-        diff.to_string()
-    };
+/// 建立 commit，或以 --export-patch 匯出為 patch 檔案（不建立 commit）
+fn create_commit(
+    repo: &Repository,
+    scope: &scope::Scope,
+    message: &str,
+    options: &CommitOptions,
+    export_dir: Option<&Path>,
+) -> Result<CommitOutcome> {
+    match export_dir {
+        Some(dir) => scope
+            .commit(repo, || {
+                patch::export(repo, message, options.identity.as_ref(), options.date.as_ref(), dir)
+            })
+            .map(CommitOutcome::Exported),
+        None => scope
+            .commit(repo, || commit_changes(repo, message, options))
+            .map(|summary| vec![summary].into()),
+    }
+}
 
-    let files_list = files.join(", ");
-    
-    // 使用合併的提示詞模板，加入更多上下文資訊
-    let prompt = config.combined_prompt
-        .replace("{files}", &files_list)
-        .replace("{file_summary}", &file_summary)
-        .replace("{stats}", &stats)
-        .replace("{diff}", &diff_preview);
+/// commit 後的後續動作：rebase 等操作進行中時詢問是否繼續，否則（非快速模式）
+/// 依設定推送到 Gerrit 並詢問是否開始下一個分支
+fn offer_follow_up(
+    repo: &Repository,
+    config: &LlmConfig,
+    cli: &Cli,
+    operation: Option<conflict::Operation>,
+    compliance: &compliance::Filter,
+) -> Result<()> {
+    if let Some(operation) = operation.filter(|op| *op != conflict::Operation::Merge) {
+        return conflict::offer_continue(repo, operation);
+    }
+    if cli.quick {
+        return Ok(());
+    }
+    if config.gerrit.enabled {
+        gerrit::offer_push(repo, &config.gerrit, &get_current_branch(repo)?)?;
+    }
+    offer_next_branch(repo, config, cli, compliance)
+}
 
-    match call_llm_cli(&prompt, config) {
-        Ok(response) => {
-            // 解析 LLM 回應
-            if let Some(suggestions) = parse_llm_response(&response) {
-                return suggestions;
-            }
+/// 單一 commit 建立後的附加紀錄：CHANGELOG 項目（合併、rebase 等進行中時不加入）、
+/// 完整分析的 note 與審查筆記（皆對應整份 staged 變更）
+fn annotate_commit(
+    repo: &Repository,
+    config: &LlmConfig,
+    summary: &mut CommitSummary,
+    suggestions: &GitSuggestions,
+    changelog: bool,
+) {
+    // CHANGELOG 的項目可能併入剛建立的 commit，需在寫入 note 等使用 SHA 的步驟之前
+    if changelog && config.changelog.enabled {
+        if let Err(e) = changelog::offer(repo, &config.changelog, summary) {
+            println!("{}", format!("⚠️  無法更新 CHANGELOG：{:#}", e).yellow());
         }
-        Err(e) => {
-            println!("{}", format!("⚠️  LLM 生成失敗：{}", e).yellow());
-            println!("{}", "使用備用建議...".dimmed());
+    }
+    if let (true, Some(analysis)) = (config.notes.enabled, &suggestions.analysis) {
+        match notes::write(&summary.sha, analysis) {
+            Ok(()) => println!(
+                "{}",
+                format!("📎 已將完整分析存為 note，查看：git log --notes={} -1", notes::NOTES_REF).dimmed()
+            ),
+            Err(e) => println!("{}", format!("⚠️  無法寫入 note：{:#}", e).yellow()),
+        }
+    }
+    if let Some(notes) = &suggestions.review {
+        match review::save(repo, &config.review, &summary.sha, &summary.subject, notes) {
+            Ok(Some(location)) => println!("{}", format!("🧐 已保存審查筆記，查看：{}", location).dimmed()),
+            Ok(None) => {}
+            Err(e) => println!("{}", format!("⚠️  無法保存審查筆記：{:#}", e).yellow()),
         }
     }
+}
 
-    // 備用建議（如果 LLM 失敗）
-    GitSuggestions {
-        branch_names: generate_fallback_branch_suggestions(files),
-        commit_messages: generate_fallback_commit_suggestions(diff, files),
+/// 詢問評分（快速模式不詢問）並將選擇的訊息寫入歷史紀錄；`chosen` 為訊息與是否為原本的建議
+fn record_history(
+    config: &LlmConfig,
+    quick: bool,
+    workdir: &str,
+    variant: &str,
+    (message, accepted): &(String, bool),
+    suggestions: &GitSuggestions,
+) {
+    let mut history_config = config.history.clone();
+    history_config.ask_rating &= !quick;
+    // 同時以兩個模型生成時，記錄產生所選建議的模型（兩者都產生時記錄主要模型）
+    let model = suggestions
+        .commit_messages
+        .iter()
+        .position(|m| m == message)
+        .and_then(|i| suggestions.commit_labels.get(i).cloned().flatten())
+        .filter(|label| !label.contains(" + "))
+        .unwrap_or_else(|| config.model.clone());
+    if let Err(e) = history::record(&history_config, workdir, variant, &model, message, *accepted) {
+        println!("{}", format!("⚠️  歷史紀錄寫入失敗：{:#}", e).yellow());
     }
 }
 
-/// 取得 diff 的統計資訊
-fn get_diff_stats(diff: &str) -> String {
-    let mut additions = 0;
-    let mut deletions = 0;
-    let mut files_changed = 0;
-    
-    for line in diff.lines() {
+/// 依 -a 與 new_files.ask_untracked 將工作目錄的變更加入 staged；掃描過久時提示 --paths
+fn stage_working_tree(repo: &Repository, cli: &Cli, config: &LlmConfig) -> Result<()> {
+    let mut scan_time = Duration::ZERO;
+
+    // -a：同 git commit -a，先將已追蹤檔案的修改與刪除加入 staged
+    if cli.all {
+        let started = Instant::now();
+        let updated = progress::spin("將已追蹤檔案的變更加入 staged", || stage_tracked_changes(repo, &cli.paths))
+            .context("無法將已追蹤檔案的變更加入 staged")?;
+        scan_time += started.elapsed();
+        if !updated.is_empty() {
+            println!("{}", format!("➕ 已將 {} 個已追蹤檔案的變更加入 staged\n", updated.len()).dimmed());
+        }
+    }
+
+    // 未追蹤的檔案：列出並詢問是否加入 staged 或標記為 intent-to-add（快速模式只列出）
+    if config.new_files.ask_untracked {
+        let started = Instant::now();
+        let untracked = progress::spin("掃描未追蹤的檔案", || new_files::untracked(repo, &cli.paths))?;
+        scan_time += started.elapsed();
+        if !untracked.is_empty() {
+            new_files::print(&untracked);
+            if !cli.quick {
+                new_files::offer_add(&untracked)?;
+            }
+        }
+    }
+
+    if scan_time > SLOW_SCAN && cli.paths.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "⏳ 掃描工作目錄花了 {:.1} 秒；大型 repository 可用 --paths 只掃描正在修改的目錄，或設定 new_files.ask_untracked = false\n",
+                scan_time.as_secs_f64()
+            )
+            .dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// 讀取 staged 的檔案並套用 --only 的範圍；沒有可 commit 的變更時結束
+fn collect_staged_files(repo: &Repository, cli: &Cli) -> Result<(Vec<String>, scope::Scope)> {
+    let mut staged_files = progress::spin("讀取 staged 的檔案", || get_staged_files(repo))?;
+    if staged_files.is_empty() {
+        println!(
+            "{}",
+            "⚠️  沒有 staged 的檔案變更，請先使用 git add 加入檔案"
+                .yellow()
+        );
+        porcelain::result("nothing-staged");
+        std::process::exit(1);
+    }
+
+    // --only：只 commit 符合 pathspec 的 staged 變更，其餘的檔案維持 staged
+    let scope = scope::Scope::select(&cli.only, &mut staged_files)?;
+    if staged_files.is_empty() {
+        println!("{}", format!("⚠️  沒有符合 {} 的 staged 變更", cli.only.join(" ")).yellow());
+        porcelain::result("nothing-staged");
+        std::process::exit(1);
+    }
+    scope.print();
+    Ok((staged_files, scope))
+}
+
+/// 未解決的衝突標記被加入 staged：在生成訊息前列出並結束
+fn reject_conflict_markers(repo: &Repository, staged_files: &[String]) {
+    let markers = conflict::staged_markers(repo, staged_files);
+    if markers.is_empty() {
+        return;
+    }
+    println!("{}", "⛔ 以下 staged 的檔案仍有衝突標記（<<<<<<<、>>>>>>>），請解決後重新 git add：".red());
+    for (path, line) in &markers {
+        println!("{}", format!("  ✗ {}:{}", path, line).red());
+    }
+    println!();
+    porcelain::result("conflicts");
+    std::process::exit(1);
+}
+
+/// 套用 --reproducible 與 --language；以 --language 指定的語言記在此 repository，之後未指定時沿用
+fn apply_run_options(repo: &Repository, cli: &Cli, config: &mut LlmConfig) {
+    // 可重現模式：temperature 固定為 0、seed 未設定時使用 0
+    if cli.reproducible {
+        config.temperature = Some(0.0);
+        config.seed = config.seed.or(Some(0));
+        if config.temperature_flag.is_empty() || config.seed_flag.is_empty() {
+            println!(
+                "{}",
+                "⚠️  未設定 temperature_flag 或 seed_flag，LLM CLI 無法固定輸出，建議仍可能每次不同".yellow()
+            );
+        }
+    }
+
+    match &cli.language {
+        Some(language) => {
+            config.language = language.clone();
+            repo_state::update(repo, config, |state| state.language = Some(language.clone()));
+        }
+        None => {
+            if let Some(language) = repo_state::load(repo, config).language {
+                println!("{}", format!("🌐 使用此 repository 上次指定的語言：{}", language).dimmed());
+                config.language = language;
+            }
+        }
+    }
+}
+
+/// 新增的原始碼檔案缺少授權標頭：詢問是否加上並重新 git add（快速模式只提醒）
+fn check_license_headers(repo: &Repository, config: &LlmConfig, quick: bool) -> Result<()> {
+    let Some(template) = license::template(&config.license_header, repo.workdir()) else {
+        return Ok(());
+    };
+    let missing = license::missing(repo, &config.license_header, &template)?;
+    if !missing.is_empty() && quick {
+        println!("{}", format!("⚠️  {} 個新檔案缺少授權標頭：{}", missing.len(), missing.join(", ")).yellow());
+    } else if !missing.is_empty() && !license::offer_insert(repo, &missing, &template)? {
+        println!("{}", "已中止".yellow());
+        porcelain::result("aborted");
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// 只有換行字元或行尾空白變更的檔案：可移出 staged；保留時也不放入提示詞（快速模式不詢問）
+fn review_whitespace_only(quick: bool, staged_files: &mut Vec<String>) -> Result<Vec<whitespace::WhitespaceChange>> {
+    let whitespace_only = whitespace::detect(staged_files)?;
+    if whitespace_only.is_empty() {
+        return Ok(whitespace_only);
+    }
+    whitespace::print(&whitespace_only);
+    if !quick && whitespace::offer_unstage(&whitespace_only)? {
+        staged_files.retain(|file| !whitespace_only.iter().any(|change| &change.path == file));
+        if staged_files.is_empty() {
+            println!("{}", "⚠️  移出後已沒有 staged 的變更".yellow());
+            porcelain::result("nothing-staged");
+            std::process::exit(1);
+        }
+    }
+    Ok(whitespace_only)
+}
+
+/// 檔案權限變更：只有權限變更（例如 Windows 掛載目錄造成的 755 → 644）時可還原 index 中的權限（快速模式不詢問）
+fn review_mode_changes(quick: bool, staged_files: &mut Vec<String>) -> Result<Vec<mode::ModeChange>> {
+    let mut mode_changes = mode::detect()?;
+    mode_changes.retain(|change| staged_files.contains(&change.path));
+    if !mode_changes.is_empty() {
+        mode::print(&mode_changes);
+    }
+    if !quick {
+        let reverted = mode::offer_revert(&mode_changes)?;
+        mode_changes.retain(|change| !reverted.contains(&change.path));
+        staged_files.retain(|file| !reverted.contains(file));
+        if staged_files.is_empty() {
+            println!("{}", "⚠️  還原後已沒有 staged 的變更".yellow());
+            porcelain::result("nothing-staged");
+            std::process::exit(1);
+        }
+    }
+    Ok(mode_changes)
+}
+
+/// 提示詞與規則式建議使用的檔案：排除只有空白變更的檔案（全部都只有空白變更時維持原清單）
+fn prompt_files_for(staged_files: &[String], whitespace_only: &[whitespace::WhitespaceChange]) -> Vec<String> {
+    let files: Vec<String> = staged_files
+        .iter()
+        .filter(|file| !whitespace_only.iter().any(|change| &change.path == *file))
+        .cloned()
+        .collect();
+    if files.is_empty() {
+        staged_files.to_vec()
+    } else {
+        files
+    }
+}
+
+/// 載入 Lua 腳本與 PATH 上的 git-auto-commit-<name> 外掛（離線模式不載入，外掛與腳本可能連線到外部服務）
+fn load_plugins(
+    config: &LlmConfig,
+    offline: bool,
+    workdir: &str,
+    current_branch: &str,
+    staged_files: &[String],
+) -> PluginHost {
+    let plugin_context = PluginContext {
+        repo: workdir.to_string(),
+        current_branch: current_branch.to_string(),
+        files: staged_files.to_vec(),
+    };
+    if offline {
+        PluginHost::discover(&PluginsConfig::default(), plugin_context)
+    } else {
+        script::init(&config.script, &plugin_context);
+        PluginHost::discover(&config.plugins, plugin_context)
+    }
+}
+
+/// 收集 staged 的 diff，並依 diff 大小選擇模型：小型變更使用較快、便宜的模型，大型變更使用較強的模型
+fn collect_diff(
+    repo: &Repository,
+    config: &mut LlmConfig,
+    scope: &scope::Scope,
+    file_count: usize,
+    timeline: &mut Timeline,
+) -> Result<String> {
+    timeline.start("收集變更內容");
+    let mut diff = get_staged_diff(repo, &scope.pathspecs)?;
+    if config.notebook.strip_outputs {
+        diff = notebook::normalize_diff(repo, &diff);
+    }
+    timeline.finish();
+
+    if let Some(selection) = model_policy::select(&config.model_selection, &diff, file_count) {
+        println!(
+            "{}",
+            format!(
+                "🎚️  {}（{} 行、{} 個檔案），使用模型 {}",
+                selection.size, selection.lines, selection.files, selection.model
+            )
+            .dimmed()
+        );
+        config.model = selection.model;
+    }
+    Ok(diff)
+}
+
+/// 有風險的變更與新增的待辦註記：在 commit 預覽中提醒，嚴格模式下有待辦註記時拒絕 commit；
+/// 新增的除錯程式碼可先檢視所在的 hunk 再決定是否繼續（快速模式只在預覽中提醒）
+fn check_risks(config: &LlmConfig, cli: &Cli, diff: &str, mode_changes: &[mode::ModeChange]) -> Result<risk::Report> {
+    let mut risks = risk::analyze(diff, config);
+    risks.add_mode_changes(mode_changes);
+    if (cli.strict || config.todo.strict) && !risks.todos().is_empty() {
+        println!("{}", format!("⛔ 嚴格模式：staged 變更新增了 {} 個待辦註記，請處理後再 commit", risks.todos().len()).red());
+        risks.print_todos();
+        porcelain::result("aborted");
+        std::process::exit(1);
+    }
+    if !risks.debug_leftovers().is_empty() && !cli.quick && !review_debug_leftovers(&risks)? {
+        println!("{}", "已中止，移除除錯程式碼並重新 git add 後再執行".yellow());
+        porcelain::result("aborted");
+        std::process::exit(1);
+    }
+    Ok(risks)
+}
+
+/// 依檔案類型分析的 staged 變更，產生建議後用來調整建議與 commit 訊息
+struct ChangeAnalysis {
+    migrations: Vec<migration::Migration>,
+    infra: Option<infra::InfraSummary>,
+    assets: Option<assets::AssetSummary>,
+    ci: Option<ci::CiSummary>,
+}
+
+/// 分析 migration、基礎設施、靜態資源、CI 設定、權限變更與新檔案，回傳加入提示詞的變更摘要與分析結果；
+/// `files` 為 staged 的檔案與提示詞使用的檔案
+fn analyze_changes(
+    repo: &Repository,
+    config: &LlmConfig,
+    (staged_files, prompt_files): (&[String], &[String]),
+    assets: Option<assets::AssetSummary>,
+    mode_changes: &[mode::ModeChange],
+) -> (Vec<String>, ChangeAnalysis) {
+    let mut notes = Vec::new();
+
+    // 資料庫 migration：摘要 schema 操作並加入提示詞，commit 前需確認
+    let migrations = if config.migration.detect {
+        migration::detect(repo, staged_files)
+    } else {
+        Vec::new()
+    };
+    if !migrations.is_empty() {
+        migration::print(&migrations);
+        notes.push(migration::summary(&migrations));
+    }
+
+    // 基礎設施設定：摘要資源層級的變化並加入提示詞
+    let infra = config
+        .infra
+        .detect
+        .then(|| infra::analyze(repo, staged_files))
+        .flatten();
+    if let Some(infra) = &infra {
+        infra.print();
+        notes.push(infra.prompt_note(&config.infra.scope));
+    }
+
+    // 靜態資源：diff 中看不到內容，改提供大小與尺寸的變化
+    let assets = assets.filter(|_| config.assets.detect);
+    if let Some(assets) = &assets {
+        notes.push(assets.prompt_note());
+    }
+
+    // CI 設定：摘要 job 與步驟的增減並加入提示詞
+    let ci = config.ci.detect.then(|| ci::analyze(repo, staged_files)).flatten();
+    if let Some(ci) = &ci {
+        ci.print();
+        notes.push(ci.prompt_note());
+    }
+
+    if !mode_changes.is_empty() {
+        notes.push(mode::prompt_note(mode_changes));
+    }
+
+    // 新檔案的內容：diff 過長被截斷時仍能看到新檔案的用途
+    if config.new_files.include_content {
+        if let Some(note) = new_files::prompt_note(repo, prompt_files, &config.new_files) {
+            notes.push(note);
+        }
+    }
+
+    (notes, ChangeAnalysis { migrations, infra, assets, ci })
+}
+
+/// few-shot 範例：此 repository 過去採用的高評分訊息，以及（search_similar 時）與 staged 變更最相似的過去 commit 的訊息
+fn few_shot_examples(
+    repo: &Repository,
+    config: &LlmConfig,
+    workdir: &str,
+    search_similar: bool,
+    (files, diff): (&[String], &str),
+    timeline: &mut Timeline,
+) -> Vec<String> {
+    let mut examples = history::examples(&config.history, workdir);
+    if !examples.is_empty() {
+        println!("{}", format!("📚 附上 {} 則過去採用的 commit 訊息作為範例", examples.len()).dimmed());
+    }
+
+    if config.embeddings.enabled && search_similar {
+        match embeddings::similar_messages(repo, &config.embeddings, files, diff, timeline) {
+            Ok(similar) => {
+                for message in similar {
+                    if !examples.contains(&message) {
+                        examples.push(message);
+                    }
+                }
+            }
+            Err(e) => println!("{}", format!("⚠️  無法搜尋相似的 commit：{:#}", e).yellow()),
+        }
+    }
+    examples
+}
+
+/// 拆分成多個 commit 時的 staged 變更與分組
+type SplitPlan = (split::StagedChanges, Vec<split::Group>);
+
+/// 一般 commit 的建議：只更新相依套件時依版本變化直接產生訊息（可分組），混合多個關注點時可拆分，
+/// 其餘由 LLM 生成（離線模式依檔案與 diff 內容產生）；`files` 為 staged 的檔案、提示詞使用的檔案與 diff。
+/// 回傳建議、拆分的分組與所用的提示詞模板
+fn generate_for_staged(
+    repo: &Repository,
+    cli: &Cli,
+    config: &LlmConfig,
+    (staged_files, prompt_files, diff): (&[String], &[String], &str),
+    context: &PromptContext,
+    pregen: Option<pregen::Pregen>,
+    timeline: &mut Timeline,
+) -> Result<(GitSuggestions, Option<SplitPlan>, Option<String>)> {
+    let bumps = config.deps.detect.then(|| deps::detect(repo, diff, staged_files)).flatten();
+    if let Some(bumps) = bumps {
+        println!("{}", "📦 偵測到相依套件更新，直接依版本變化產生訊息（不呼叫 LLM）".blue());
+        let groups = if bumps.len() > 1 && cli.export_patch.is_none() && !cli.quick {
+            select_dependency_grouping(repo, &bumps, staged_files)?
+        } else {
+            None
+        };
+        return Ok((deps::suggestions(&bumps), groups, None));
+    }
+
+    if let Some((changes, groups, suggestions)) =
+        offer_concern_split(repo, staged_files, diff, context, config, cli, timeline)?
+    {
+        return Ok((suggestions, Some((changes, groups)), None));
+    }
+    if cli.offline {
+        println!("{}", "🔌 離線模式：依變更的檔案與 diff 內容產生建議（不呼叫 LLM）".blue());
+        return Ok((fallback_suggestions(diff, prompt_files), None, None));
+    }
+
+    let variant = history::variant_id("combined", &config.combined_prompt, &LlmConfig::default().combined_prompt);
+    let suggestions = match config.dual_model.model.as_deref().filter(|model| *model != config.model) {
+        Some(second_model) => generate_dual_suggestions(diff, prompt_files, context, config, second_model, timeline),
+        None => generate_suggestions(diff, prompt_files, context, config, timeline, pregen),
+    };
+    Ok((suggestions, None, Some(variant)))
+}
+
+/// 依規劃草稿與檔案類型調整建議：草稿列在最後（與建議的標題相同時不重複加入），
+/// 只變更靜態資源時確保有 chore(assets) 或 style 的建議，只變更 CI 設定時類型使用 ci，
+/// 只變更基礎設施設定時標題加上設定的 scope
+fn adjust_suggestions(suggestions: &mut GitSuggestions, config: &LlmConfig, changes: &ChangeAnalysis, draft: Option<&str>) {
+    if let Some(draft) = draft {
+        if !suggestions.commit_messages.iter().any(|m| m.lines().next() == Some(draft)) {
+            suggestions.commit_labels.resize(suggestions.commit_messages.len(), None);
+            suggestions.commit_messages.push(draft.to_string());
+            suggestions.commit_labels.push(Some("規劃草稿".to_string()));
+        }
+    }
+
+    if let Some(assets) = changes.assets.as_ref().filter(|a| a.assets_only) {
+        let suggested = suggestions
+            .commit_messages
+            .iter()
+            .any(|m| m.starts_with("chore(assets)") || m.starts_with("style"));
+        if !suggested {
+            suggestions.commit_messages.push(assets.fallback_message());
+        }
+    }
+
+    if changes.ci.as_ref().is_some_and(|ci| ci.ci_only) {
+        for message in &mut suggestions.commit_messages {
+            *message = ci::with_ci_type(message);
+        }
+    }
+
+    if changes.infra.as_ref().is_some_and(|infra| infra.infra_only) {
+        for message in &mut suggestions.commit_messages {
+            *message = infra::with_scope(message, &config.infra.scope);
+        }
+    }
+}
+
+/// 是否在背景預先生成建議：只適用一般的單一模型生成，相依套件更新不呼叫 LLM，
+/// 可能詢問拆分 commit 時提示詞尚未確定，不預先生成
+fn should_pregenerate(
+    repo: &Repository,
+    config: &LlmConfig,
+    cli: &Cli,
+    replaying: bool,
+    diff: &str,
+    staged_files: &[String],
+) -> bool {
+    config.pregenerate
+        && !cli.offline
+        && cli.rewrite.is_none()
+        && cli.compare_prompts.is_none()
+        && !replaying
+        && config.dual_model.model.as_deref().is_none_or(|model| model == config.model)
+        && !(config.deps.detect && deps::detect(repo, diff, staged_files).is_some())
+        && !may_offer_split(staged_files, config, cli)
+}
+
+/// 同步 remote 並顯示與 upstream 的領先/落後；落後時可先 pull --rebase，之後移除已包含在 upstream 中的 staged 檔案
+fn sync_remote(
+    repo: &Repository,
+    config: &LlmConfig,
+    cli: &Cli,
+    current_branch: &str,
+    operation: Option<conflict::Operation>,
+    staged_files: &mut Vec<String>,
+    timeline: &mut Timeline,
+) -> Result<()> {
+    // 讓分支衝突檢查與領先/落後資訊使用最新資料
+    if config.fetch.on_start && !cli.offline {
+        timeline.start("同步 remote（git fetch）");
+        match remote::fetch_with_timeout(&config.fetch) {
+            Ok(FetchOutcome::Updated) => timeline.finish(),
+            Ok(FetchOutcome::TimedOut) => {
+                timeline.fail();
+                println!("{}", "⚠️  git fetch 逾時，改用本地的 remote 資料".yellow());
+            }
+            Ok(FetchOutcome::Failed(error)) => {
+                timeline.fail();
+                println!("{}", format!("⚠️  git fetch 失敗（可能為離線狀態），改用本地的 remote 資料：{}", error).yellow());
+            }
+            Err(e) => {
+                timeline.fail();
+                println!("{}", format!("⚠️  {}，改用本地的 remote 資料", e).yellow());
+            }
+        }
+    }
+
+    let Some(status) = remote::ahead_behind(repo, current_branch) else {
+        return Ok(());
+    };
+    println!(
+        "{}",
+        format!("相對於 {}：領先 {} 個 commit，落後 {} 個 commit", status.upstream, status.ahead, status.behind).dimmed()
+    );
+    // 落後 upstream：commit 後推送會被拒絕，可先 pull --rebase 再產生建議（快速模式只提醒）
+    if status.behind == 0 || operation.is_some() || !config.fetch.offer_rebase {
+        return Ok(());
+    }
+    if cli.quick {
+        println!("{}", format!("⚠️  {} 有 {} 個新的 commit，推送前需先同步", status.upstream, status.behind).yellow());
+    } else if offer_pull_rebase(&status)? {
+        // 重新檢查 staged 的變更（可能已包含在 upstream 中）
+        let current = get_staged_files(repo)?;
+        staged_files.retain(|file| current.contains(file));
+        if staged_files.is_empty() {
+            println!("{}", "⚠️  同步後已沒有 staged 的變更（可能已包含在 upstream 中）".yellow());
+            porcelain::result("nothing-staged");
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+/// 套用分支名稱模板，並排除與現有分支相同或只有日期後綴不同的建議
+fn filter_branch_names(
+    repo: &Repository,
+    config: &LlmConfig,
+    current_branch: &str,
+    ticket: Option<&str>,
+    existing_branches: &[String],
+    suggestions: &mut GitSuggestions,
+) {
+    if let Some(template) = &config.branch_template {
+        let vars = branch::TemplateVars::detect(repo, current_branch, ticket, &config.ticket_pattern);
+        let mut rendered: Vec<String> = Vec::new();
+        for suggestion in &suggestions.branch_names {
+            let name = branch::apply_template(template, suggestion, &vars);
+            if is_valid_branch_name(&name) && !rendered.contains(&name) {
+                rendered.push(name);
+            }
+        }
+        suggestions.branch_names = rendered;
+    }
+
+    let before = suggestions.branch_names.len();
+    suggestions
+        .branch_names
+        .retain(|name| !branch::duplicates_existing(name, existing_branches));
+    let removed = before - suggestions.branch_names.len();
+    if removed > 0 {
+        println!("{}", format!("已排除 {} 個與現有分支重複的分支建議", removed).dimmed());
+    }
+}
+
+/// 決定 commit 的作者：rebase 等操作中沿用原 commit 的作者，快速模式顯示目前的身分，其餘依設定選擇
+fn select_author(repo: &Repository, config: &LlmConfig, quick: bool, commit_options: &mut CommitOptions) -> Result<()> {
+    if commit_options.author_env.is_empty() && quick {
+        identity::print_current(repo);
+    } else if commit_options.author_env.is_empty() {
+        commit_options.identity = identity::select_identity(repo, &config.identities)?;
+    } else {
+        let author = |key: &str| {
+            commit_options
+                .author_env
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
+                .unwrap_or_default()
+        };
+        println!(
+            "{}",
+            format!("👤 作者（沿用原 commit）：{} <{}>", author("GIT_AUTHOR_NAME"), author("GIT_AUTHOR_EMAIL"))
+                .dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// staged 檔案超過此數量時依頂層目錄摺疊顯示
+const STAGED_LIST_LIMIT: usize = 20;
+
+/// staged 檔案的一行說明（路徑過長時截斷中間，保留檔名）
+fn staged_file_line(file: &str, assets: Option<&assets::AssetSummary>) -> String {
+    match assets.and_then(|a| a.describe(file)) {
+        Some(change) => {
+            let path = layout::truncate_middle(file, layout::available(8 + console::measure_text_width(&change)));
+            format!("  - {}（{}）", path, change)
+        }
+        None => format!("  - {}", layout::truncate_middle(file, layout::available(4))),
+    }
+}
+
+/// 依頂層目錄分組（根目錄的檔案歸在空字串），檔案多的群組在前
+fn group_by_top_dir(files: &[String]) -> Vec<(String, Vec<&String>)> {
+    let mut groups: Vec<(String, Vec<&String>)> = Vec::new();
+    for file in files {
+        let dir = file.split_once('/').map(|(dir, _)| format!("{}/", dir)).unwrap_or_default();
+        match groups.iter_mut().find(|(d, _)| *d == dir) {
+            Some((_, files)) => files.push(file),
+            None => groups.push((dir, vec![file])),
+        }
+    }
+    groups.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+    groups
+}
+
+/// 顯示 staged 檔案；超過 STAGED_LIST_LIMIT 個時只列出各頂層目錄的檔案數，並可（`ask_pager` 時）在 pager 中查看完整列表
+fn print_staged_files(files: &[String], assets: Option<&assets::AssetSummary>, ask_pager: bool) -> Result<()> {
+    if files.len() <= STAGED_LIST_LIMIT {
+        println!("{}", "📝 Staged 檔案：".blue());
+        for file in files {
+            println!("{}", staged_file_line(file, assets).dimmed());
+        }
+        return Ok(());
+    }
+
+    println!("{}", format!("📝 Staged 檔案（共 {} 個）：", files.len()).blue());
+    let groups = group_by_top_dir(files);
+    let group_name = |dir: &str| if dir.is_empty() { "（根目錄）".to_string() } else { dir.to_string() };
+    let name_width = groups.iter().map(|(dir, _)| console::measure_text_width(&group_name(dir))).max().unwrap_or(0);
+    for (dir, group) in &groups {
+        let name = console::pad_str(&group_name(dir), name_width, console::Alignment::Left, None).to_string();
+        println!("{}", format!("  {}  {:>3} 個檔案", name, group.len()).dimmed());
+    }
+    if !ask_pager || !console::Term::stdout().is_term() {
+        return Ok(());
+    }
+
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("檔案較多，已依目錄摺疊")
+        .items(&["繼續", "📄 顯示所有檔案（pager）"])
+        .default(0)
+        .interact()?;
+    if selection == 1 {
+        let mut text = String::new();
+        for (dir, group) in &groups {
+            text.push_str(&format!("{}（{} 個檔案）\n", group_name(dir), group.len()));
+            for file in group {
+                match assets.and_then(|a| a.describe(file)) {
+                    Some(change) => text.push_str(&format!("  - {}（{}）\n", file, change)),
+                    None => text.push_str(&format!("  - {}\n", file)),
+                }
+            }
+            text.push('\n');
+        }
+        if let Err(e) = layout::page(&text) {
+            println!("{}", format!("⚠️  {:#}", e).yellow());
+            print!("{}", text);
+        }
+    }
+    Ok(())
+}
+
+/// 偵測 lefthook / lint-staged 設定並對 staged 檔案執行預先檢查，回傳是否繼續流程
+fn run_preflight(config: &LlmConfig, files: &[String], workdir: &Path) -> Result<bool> {
+    if config.preflight.mode == PreflightMode::Never {
+        return Ok(true);
+    }
+    let Some(checks) = preflight::detect(workdir) else {
+        return Ok(true);
+    };
+
+    if config.preflight.mode == PreflightMode::Ask {
+        let items = vec!["執行檢查", "略過"];
+        let selection = Select::with_theme(plain::theme())
+            .with_prompt(format!("偵測到 {}，是否先對 staged 檔案執行 pre-commit 檢查？", checks.source))
+            .items(&items)
+            .default(0)
+            .interact()?;
+        if selection != 0 {
+            return Ok(true);
+        }
+    }
+
+    println!("{}", format!("🧪 執行 {} 的 pre-commit 檢查", checks.source).blue());
+    if preflight::run_checks(&checks, files, workdir)? {
+        println!("{}", "✓ 所有檢查通過".green());
+        println!();
+        return Ok(true);
+    }
+
+    println!();
+    let items = vec!["仍然繼續", "中止"];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("部分檢查未通過")
+        .items(&items)
+        .default(1)
+        .interact()?;
+    println!();
+    Ok(selection == 0)
+}
+
+/// 顯示 commit 時會執行的 hooks 與其所在目錄
+fn print_commit_hooks(repo: &Repository) {
+    let hooks_dir = hooks::resolve_hooks_dir(repo);
+
+    if hooks_dir.from_config && !hooks_dir.path.is_dir() {
+        println!(
+            "{}",
+            format!("⚠️  core.hooksPath 指向的目錄不存在：{}", hooks_dir.path.display()).yellow()
+        );
+        return;
+    }
+
+    let active = hooks::active_commit_hooks(&hooks_dir);
+    if active.is_empty() {
+        return;
+    }
+
+    let location = match repo.workdir() {
+        Some(workdir) => hooks_dir
+            .path
+            .strip_prefix(workdir)
+            .unwrap_or(&hooks_dir.path)
+            .display()
+            .to_string(),
+        None => hooks_dir.path.display().to_string(),
+    };
+    println!(
+        "{}",
+        format!("🪝 commit 時將執行 hooks（{}）：{}", location, active.join("、")).dimmed()
+    );
+    println!();
+}
+
+/// 取得 staged 的 diff 內容（優化版，減少 token 使用但保留關鍵資訊）；paths 為空時包含所有檔案
+fn get_staged_diff(_repo: &Repository, paths: &[String]) -> Result<String> {
+    // 優化參數說明：
+    // --inter-hunk-context=1: 減少 hunk 之間的空白行
+    // --ignore-space-change: 忽略空白變更（減少雜訊）
+    // --ignore-blank-lines: 忽略空白行變更
+    // --no-prefix: 移除 a/ 和 b/ 前綴（節省 token）
+    // --no-color: 確保沒有 ANSI 顏色碼
+    let output = Command::new("git")
+        .args([
+            "diff",
+            "--staged",
+            "--inter-hunk-context=1",
+            "--ignore-space-change",
+            "--ignore-blank-lines",
+            "--no-prefix",
+            "--no-color"
+        ])
+        .args(if paths.is_empty() { &[][..] } else { &["--"][..] })
+        .args(paths)
+        .output()
+        .context("無法執行 git diff")?;
+
+    if !output.status.success() {
+        anyhow::bail!("git diff 執行失敗");
+    }
+
+    let diff = String::from_utf8_lossy(&output.stdout).to_string();
+    
+    Ok(diff)
+}
+
+/// 取得檔案的簡要資訊
+fn get_file_summary(files: &[String]) -> String {
+    let mut summary = String::new();
+    
+    for file in files {
+        let path = std::path::Path::new(file);
+        
+        // 判斷檔案類型
+        let file_type = if let Some(ext) = path.extension() {
+            match ext.to_str() {
+                Some("rs") => "Rust 程式碼",
+                Some("js") | Some("ts") => "JavaScript/TypeScript",
+                Some("py") => "Python 程式碼",
+                Some("java") => "Java 程式碼",
+                Some("go") => "Go 程式碼",
+                Some("md") => "Markdown 文檔",
+                Some("toml") | Some("yaml") | Some("yml") | Some("json") => "設定檔",
+                Some("html") | Some("css") => "前端檔案",
+                _ => "其他檔案",
+            }
+        } else {
+            "無副檔名"
+        };
+        
+        summary.push_str(&format!("- {}: {}\n", file, file_type));
+    }
+    
+    summary
+}
+
+/// 提供給提示詞模板的額外上下文
+#[derive(Debug, Default)]
+struct PromptContext {
+    /// 已存在的分支名稱（避免建議重複的分支）
+    existing_branches: Vec<String>,
+    /// 使用者對變更目的的說明
+    description: Option<String>,
+    /// 依檔案內容自動分析的變更摘要（例如 migration 的 schema 操作）
+    change_notes: Vec<String>,
+    /// 此 repository 過去採用的 commit 訊息（few-shot 範例）
+    examples: Vec<String>,
+}
+
+/// 詢問使用者對這次變更的說明（留空表示不提供）
+fn ask_change_description() -> Result<Option<String>> {
+    let input: String = Input::with_theme(plain::theme())
+        .with_prompt("用自己的話說明這次變更的目的（留空略過）")
+        .allow_empty(true)
+        .interact_text()?;
+    let input = input.trim();
+    Ok((!input.is_empty()).then(|| input.to_string()))
+}
+
+/// 將使用者的說明加入提示詞：模板有 {description} 時取代，否則附加在最後
+fn with_change_description(prompt: String, description: Option<&str>) -> String {
+    let Some(description) = description else {
+        return prompt.replace("{description}", "");
+    };
+    if prompt.contains("{description}") {
+        prompt.replace("{description}", description)
+    } else {
+        format!(
+            "{}\n\n使用者對這次變更的說明（diff 無法完整表達時以此理解變更目的，訊息應反映這些意圖，但不要加入 diff 中沒有的變更）：\n{}",
+            prompt, description
+        )
+    }
+}
+
+/// 將過去採用的 commit 訊息作為範例加入提示詞：模板有 {examples} 時取代，否則附加在最後
+fn with_examples(prompt: String, examples: &[String]) -> String {
+    if examples.is_empty() {
+        return prompt.replace("{examples}", "");
+    }
+    let examples = examples.join("\n---\n");
+    if prompt.contains("{examples}") {
+        prompt.replace("{examples}", &examples)
+    } else {
+        format!(
+            "{}\n\n此 repository 過去採用的 commit 訊息範例（以 --- 分隔；請參考其格式、用語與詳細程度，但內容必須依這次的變更撰寫）：\n{}",
+            prompt, examples
+        )
+    }
+}
+
+/// 查詢 ticket 對應的 issue 並產生提示詞說明（ticket 不是 issue 編號、找不到或查詢失敗時回傳 None）
+fn issue_note(repo: &Repository, config: &LlmConfig, current_branch: &str, ticket: Option<&str>) -> Option<String> {
+    let vars = branch::TemplateVars::detect(repo, current_branch, ticket, &config.ticket_pattern);
+    let number = forge::issue_number(vars.ticket.as_deref()?)?;
+    let forge = forge::from_origin(repo, config)?;
+    match forge.find_issue(number) {
+        Ok(Some(issue)) => {
+            println!("{}", format!("🔗 關聯的 issue #{}：{}（{}）", issue.number, issue.title, issue.state).dimmed());
+            Some(format!(
+                "這次變更關聯的 issue #{}：{}（{}）\ncommit 訊息請與此 issue 的目的一致",
+                issue.number, issue.title, issue.html_url
+            ))
+        }
+        Ok(None) => None,
+        Err(e) => {
+            println!("{}", format!("⚠️  無法向 {} 查詢 issue #{}：{}", forge.name(), number, e).yellow());
+            None
+        }
+    }
+}
+
+/// 將自動分析的變更摘要附加在提示詞最後
+fn with_change_notes(prompt: String, notes: &[String]) -> String {
+    if notes.is_empty() {
+        return prompt;
+    }
+    format!("{}\n\n{}", prompt, notes.join("\n\n"))
+}
+
+/// 啟用 notes 時，要求 LLM 在建議之後附上完整分析
+fn with_analysis_request(prompt: String, config: &LlmConfig) -> String {
+    if !config.notes.enabled {
+        return prompt;
+    }
+    format!("{}\n\n{}", prompt, config.notes.prompt.replace("{language}", &config.language))
+}
+
+/// 啟用審查筆記時，要求 LLM 在建議之後附上邊界情況與測試缺口
+fn with_review_request(prompt: String, config: &LlmConfig) -> String {
+    if !config.review.enabled {
+        return prompt;
+    }
+    format!("{}\n\n{}", prompt, config.review.prompt.replace("{language}", &config.language))
+}
+
+/// LLM 建議結果
+#[derive(Debug, Clone, Default)]
+struct GitSuggestions {
+    branch_names: Vec<String>,
+    commit_messages: Vec<String>,
+    /// 各 commit 建議的附註（依索引對應，例如內容檢查的警告）
+    commit_notes: Vec<Option<String>>,
+    /// 各 commit 建議的來源（依索引對應，同時以兩個模型生成時為產生該建議的模型）
+    commit_labels: Vec<Option<String>>,
+    /// LLM 的完整分析（啟用 notes 時，commit 後存為 git note）
+    analysis: Option<String>,
+    /// LLM 的審查筆記（啟用 review 時，顯示在預覽中）
+    review: Option<String>,
+}
+
+/// 使用 LLM CLI 生成建議（若啟用稽核紀錄，會一併記錄提示詞與回應）
+fn call_llm_cli(prompt: &str, config: &LlmConfig) -> Result<String> {
+    // 由使用者腳本改寫提示詞（未設定腳本時維持原樣）
+    let prompt = &script::transform_prompt(prompt);
+    usage::check_budget(config, prompt)?;
+
+    let result = run_llm_command(prompt, config);
+    record_llm_call(prompt, config, &result);
+    result
+}
+
+/// 寫入 LLM 呼叫的稽核紀錄與用量紀錄
+fn record_llm_call(prompt: &str, config: &LlmConfig, result: &Result<String>) {
+    if config.audit.enabled {
+        if let Err(e) = audit::record(config, prompt, result) {
+            progress::println(&format!("⚠️  稽核紀錄寫入失敗：{}", e).yellow().to_string());
+        }
+    }
+
+    if let Ok(response) = result {
+        if let Err(e) = usage::record_call(config, prompt, response) {
+            progress::println(&format!("⚠️  用量紀錄寫入失敗：{}", e).yellow().to_string());
+        }
+    }
+}
+
+/// 執行 LLM CLI 指令並取得回應
+fn run_llm_command(prompt: &str, config: &LlmConfig) -> Result<String> {
+    // 建立指令
+    let mut cmd = Command::new(&config.command);
+    
+    // 添加提示參數（提示詞過長可能超過作業系統的命令列長度限制，可改由 stdin 或暫存檔傳遞）
+    let prompt_file = match config.prompt_via {
+        PromptVia::Arg => {
+            cmd.arg(&config.prompt_flag).arg(prompt).stdin(Stdio::null());
+            None
+        }
+        PromptVia::Stdin => {
+            cmd.stdin(Stdio::piped());
+            None
+        }
+        PromptVia::File => {
+            let path = write_prompt_file(prompt)?;
+            if !config.extra_args.iter().any(|arg| arg.contains("{prompt_file}")) {
+                cmd.arg(&config.prompt_flag).arg(&path);
+            }
+            cmd.stdin(Stdio::null());
+            Some(path)
+        }
+    };
+    
+    // 添加模型參數
+    cmd.arg(&config.model_flag).arg(&config.model);
+    
+    // 添加 temperature 與 seed 參數（LLM CLI 不支援時標記為空字串，不傳遞）
+    if let Some(temperature) = config.temperature.filter(|_| !config.temperature_flag.is_empty()) {
+        cmd.arg(&config.temperature_flag).arg(temperature.to_string());
+    }
+    if let Some(seed) = config.seed.filter(|_| !config.seed_flag.is_empty()) {
+        cmd.arg(&config.seed_flag).arg(seed.to_string());
+    }
+    
+    // 添加額外參數
+    for arg in &config.extra_args {
+        match &prompt_file {
+            Some(path) => cmd.arg(arg.replace("{prompt_file}", &path.to_string_lossy())),
+            None => cmd.arg(arg),
+        };
+    }
+    
+    // 執行指令（stdin 模式下另開執行緒寫入，避免 CLI 的輸出塞滿 pipe 而互相等待）
+    let output = cmd
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            std::thread::scope(|scope| {
+                if let Some(mut stdin) = child.stdin.take() {
+                    scope.spawn(move || {
+                        let _ = stdin.write_all(prompt.as_bytes());
+                    });
+                }
+                child.wait_with_output()
+            })
+        });
+    if let Some(path) = &prompt_file {
+        let _ = fs::remove_file(path);
+    }
+    let output =
+        output.context(format!("無法執行 {} 指令，請確認已安裝 {} CLI 工具", config.command, config.command))?;
+    
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{} 執行失敗：{}", config.command, error);
+    }
+    
+    // 成功時 stderr 仍可能有額度或模型停用等警告
+    llm_warnings::surface(&config.command, &String::from_utf8_lossy(&output.stderr));
+
+    let response = String::from_utf8_lossy(&output.stdout).to_string();
+    Ok(response.trim().to_string())
+}
+
+/// 將提示詞寫入暫存檔（prompt_via = "file"），回傳檔案路徑；呼叫結束後刪除
+fn write_prompt_file(prompt: &str) -> Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let path = env::temp_dir().join(format!(
+        "git-auto-commit-prompt-{}-{}.txt",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&path, prompt).with_context(|| format!("無法寫入提示詞暫存檔：{}", path.display()))?;
+    Ok(path)
+}
+
+/// 已存在的分支列表（最多 100 個，避免提示詞過長）
+fn format_existing_branches(branches: &[String]) -> String {
+    if branches.is_empty() {
+        "（無）".to_string()
+    } else {
+        branches
+            .iter()
+            .take(100)
+            .map(|b| format!("- {}", b))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// 建立生成建議的提示詞（合併的提示詞模板加上說明、變更摘要與範例）
+fn build_combined_prompt(diff: &str, files: &[String], context: &PromptContext, config: &LlmConfig) -> String {
+    // 增加檔案類型摘要，提供更多上下文
+    let file_summary = get_file_summary(files);
+    
+    // 計算 diff 的統計資訊
+    let stats = get_diff_stats(diff);
+    
+    // 根據 diff 大小動態調整限制（增加到 8000 字元以保留更多上下文）
+    let diff_preview = if diff.chars().count() > 8000 {
+        // 如果超過限制，優先保留前面和後面的部分（依字元截取，避免切在多位元組字元中間）
+        let front = text::head(diff, 4000);
+        let back = text::tail(diff, 4000);
+        format!("{}\n\n... (中間省略) ...\n\n{}", front, back)
+    } else {
+        diff.to_string()
+    };
+
+    let files_list = files.join(", ");
+
+    let existing_branches = format_existing_branches(&context.existing_branches);
+    
+    // 使用合併的提示詞模板，加入更多上下文資訊
+    let prompt = config.combined_prompt
+        .replace("{files}", &files_list)
+        .replace("{file_summary}", &file_summary)
+        .replace("{stats}", &stats)
+        .replace("{diff}", &diff_preview)
+        .replace("{existing_branches}", &existing_branches)
+        .replace("{branch_prefixes}", workflow::branch_prefixes(config.workflow))
+        .replace("{language}", &config.language);
+    let prompt = with_change_description(prompt, context.description.as_deref());
+    let prompt = with_change_notes(prompt, &context.change_notes);
+    let prompt = with_examples(prompt, &context.examples);
+    let prompt = with_analysis_request(prompt, config);
+    with_review_request(prompt, config)
+}
+
+/// 生成分支和 commit 建議（使用 LLM，單次請求）；pregen 為背景預先送出的請求，提示詞相同時直接等待其結果
+fn generate_suggestions(
+    diff: &str,
+    files: &[String],
+    context: &PromptContext,
+    config: &LlmConfig,
+    timeline: &mut Timeline,
+    pregen: Option<pregen::Pregen>,
+) -> GitSuggestions {
+    timeline.start("建立提示詞");
+    let prompt = build_combined_prompt(diff, files, context, config);
+
+    let result = match pregen.and_then(|pregen| pregen.reuse(&prompt, config)) {
+        Some(pregen) => {
+            timeline.start("等待背景生成的建議");
+            pregen.wait()
+        }
+        None => {
+            timeline.start("呼叫 LLM 生成建議");
+            call_llm_cli(&prompt, config)
+        }
+    };
+    match result {
+        Ok(response) => {
+            // 解析 LLM 回應
+            timeline.start("解析回應");
+            if let Some(mut suggestions) = parse_llm_response(&response) {
+                timeline.finish();
+                if config.coverage.mode != CoverageMode::Off {
+                    check_coverage(&mut suggestions, &prompt, diff, files, config, timeline);
+                }
+                return suggestions;
+            }
+            timeline.fail();
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 生成失敗：{}", e).yellow());
+            println!("{}", "使用備用建議...".dimmed());
+        }
+    }
+
+    // 備用建議（如果 LLM 失敗）
+    fallback_suggestions(diff, files)
+}
+
+/// 同時以兩個模型生成建議，交錯合併兩組建議並標示產生的模型（相同的建議只保留一個）
+fn generate_dual_suggestions(
+    diff: &str,
+    files: &[String],
+    context: &PromptContext,
+    config: &LlmConfig,
+    second_model: &str,
+    timeline: &mut Timeline,
+) -> GitSuggestions {
+    timeline.start("建立提示詞");
+    let prompt = build_combined_prompt(diff, files, context, config);
+    let mut second_config = config.clone();
+    second_config.model = second_model.to_string();
+
+    // 腳本只在主執行緒載入，先改寫提示詞再平行呼叫；紀錄同樣在主執行緒依序寫入
+    let transformed = script::transform_prompt(&prompt);
+    if let Err(e) = usage::check_budget(config, &transformed) {
+        timeline.fail();
+        println!("{}", format!("⚠️  LLM 生成失敗：{}", e).yellow());
+        println!("{}", "使用備用建議...".dimmed());
+        return fallback_suggestions(diff, files);
+    }
+
+    timeline.start(&format!("呼叫 LLM 生成建議（{}、{}）", config.model, second_model));
+    let results = std::thread::scope(|scope| {
+        let handles = [config, &second_config].map(|model_config| {
+            let transformed = &transformed;
+            scope.spawn(move || run_llm_command(transformed, model_config))
+        });
+        handles.map(|handle| handle.join().unwrap_or_else(|_| Err(anyhow::anyhow!("執行緒異常結束"))))
+    });
+
+    let mut parsed = Vec::new();
+    let mut failures = Vec::new();
+    for (model_config, result) in [config, &second_config].into_iter().zip(results) {
+        record_llm_call(&transformed, model_config, &result);
+        match result.map(|response| parse_llm_response(&response)) {
+            Ok(Some(suggestions)) => parsed.push((model_config.model.clone(), suggestions)),
+            Ok(None) => failures.push(format!("無法解析 {} 的回應", model_config.model)),
+            Err(e) => failures.push(format!("{} 生成失敗：{}", model_config.model, e)),
+        }
+    }
+    if parsed.is_empty() {
+        timeline.fail();
+    } else {
+        timeline.finish();
+    }
+    for failure in &failures {
+        println!("{}", format!("⚠️  {}", failure).yellow());
+    }
+    if parsed.is_empty() {
+        println!("{}", "使用備用建議...".dimmed());
+        return fallback_suggestions(diff, files);
+    }
+
+    let mut suggestions = merge_model_suggestions(parsed);
+    if config.coverage.mode != CoverageMode::Off {
+        check_coverage(&mut suggestions, &prompt, diff, files, config, timeline);
+    }
+    suggestions
+}
+
+/// 交錯合併各模型的建議（第一個模型在前），標題相同的建議只保留一個並標示所有產生它的模型
+fn merge_model_suggestions(parsed: Vec<(String, GitSuggestions)>) -> GitSuggestions {
+    let mut merged = GitSuggestions {
+        branch_names: Vec::new(),
+        commit_messages: Vec::new(),
+        commit_notes: Vec::new(),
+        commit_labels: Vec::new(),
+        analysis: None,
+        review: None,
+    };
+    let longest = parsed.iter().map(|(_, s)| s.commit_messages.len().max(s.branch_names.len())).max().unwrap_or(0);
+    for i in 0..longest {
+        for (model, suggestions) in &parsed {
+            if let Some(branch) = suggestions.branch_names.get(i) {
+                if !merged.branch_names.contains(branch) {
+                    merged.branch_names.push(branch.clone());
+                }
+            }
+            let Some(message) = suggestions.commit_messages.get(i) else {
+                continue;
+            };
+            let subject = |m: &str| m.lines().next().unwrap_or_default().trim().to_string();
+            match merged.commit_messages.iter().position(|m| subject(m) == subject(message)) {
+                Some(index) => {
+                    if let Some(label) = &mut merged.commit_labels[index] {
+                        label.push_str(&format!(" + {}", model));
+                    }
+                }
+                None => {
+                    merged.commit_messages.push(message.clone());
+                    merged.commit_labels.push(Some(model.clone()));
+                }
+            }
+        }
+    }
+    merged.analysis = parsed.iter().find_map(|(_, s)| s.analysis.clone());
+    merged.review = parsed.into_iter().find_map(|(_, s)| s.review);
+    merged
+}
+
+/// 不呼叫 LLM，依檔案與 diff 內容以規則產生的建議（LLM 失敗時與 --offline 使用）
+fn fallback_suggestions(diff: &str, files: &[String]) -> GitSuggestions {
+    GitSuggestions {
+        branch_names: generate_fallback_branch_suggestions(files),
+        commit_messages: generate_fallback_commit_suggestions(diff, files),
+        commit_notes: Vec::new(),
+        commit_labels: Vec::new(),
+        analysis: None,
+        review: None,
+    }
+}
+
+/// 請 LLM 將使用者的描述改寫為符合格式與語言的 commit 訊息（不送出 diff 內容），失敗時直接使用原描述
+fn rewrite_message(
+    message: &str,
+    diff: &str,
+    files: &[String],
+    context: &PromptContext,
+    config: &LlmConfig,
+    timeline: &mut Timeline,
+) -> GitSuggestions {
+    timeline.start("建立提示詞");
+    let prompt = config
+        .rewrite_prompt
+        .replace("{message}", message)
+        .replace("{stats}", &get_diff_stats(diff))
+        .replace("{files}", &files.join(", "))
+        .replace("{existing_branches}", &format_existing_branches(&context.existing_branches))
+        .replace("{branch_prefixes}", workflow::branch_prefixes(config.workflow))
+        .replace("{language}", &config.language);
+    let prompt = with_examples(prompt, &context.examples);
+
+    timeline.start("呼叫 LLM 改寫訊息");
+    match call_llm_cli(&prompt, config) {
+        Ok(response) => {
+            timeline.start("解析回應");
+            if let Some(suggestions) = parse_llm_response(&response).filter(|s| !s.commit_messages.is_empty()) {
+                timeline.finish();
+                return suggestions;
+            }
+            timeline.fail();
+            println!("{}", "⚠️  無法解析 LLM 改寫的回應，改用原本的描述".yellow());
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 改寫失敗：{}，改用原本的描述", e).yellow());
+        }
+    }
+
+    GitSuggestions {
+        branch_names: generate_fallback_branch_suggestions(files),
+        commit_messages: vec![message.to_string()],
+        commit_notes: Vec::new(),
+        commit_labels: Vec::new(),
+        analysis: None,
+        review: None,
+    }
+}
+
+/// A/B 比較兩個提示詞模板：對同一份 diff 各生成一次建議並排顯示，記錄使用者偏好後回傳所選的建議
+/// 與其提示詞模板的識別碼（選擇差不多時合併兩組建議，不對應單一模板）
+fn compare_prompts(
+    paths: &[PathBuf],
+    diff: &str,
+    files: &[String],
+    context: &PromptContext,
+    config: &LlmConfig,
+    timeline: &mut Timeline,
+) -> Result<(GitSuggestions, Option<String>)> {
+    let variant_a = experiment::load_variant("A", &paths[0])?;
+    let variant_b = experiment::load_variant("B", &paths[1])?;
+
+    let mut generate = |variant: &PromptVariant| {
+        println!("{}", format!("[{}] {}", variant.label, variant.path.display()).cyan());
+        let mut variant_config = config.clone();
+        variant_config.combined_prompt = variant.combined_prompt.clone();
+        generate_suggestions(diff, files, context, &variant_config, timeline, None)
+    };
+    let suggestions_a = generate(&variant_a);
+    let suggestions_b = generate(&variant_b);
+
+    experiment::print_side_by_side((&variant_a, &suggestions_a), (&variant_b, &suggestions_b));
+
+    let items = ["A 較好", "B 較好", "差不多"];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("哪一組建議較好？")
+        .items(&items)
+        .default(0)
+        .interact()?;
+    let preference = match selection {
+        0 => Preference::A,
+        1 => Preference::B,
+        _ => Preference::Tie,
+    };
+
+    if let Err(e) = experiment::record_preference(
+        (&variant_a, &suggestions_a),
+        (&variant_b, &suggestions_b),
+        preference,
+    ) {
+        println!("{}", format!("⚠️  實驗紀錄寫入失敗：{}", e).yellow());
+    }
+
+    let default_prompt = LlmConfig::default().combined_prompt;
+    Ok(match preference {
+        Preference::A => (
+            suggestions_a,
+            Some(history::variant_id("combined", &variant_a.combined_prompt, &default_prompt)),
+        ),
+        Preference::B => (
+            suggestions_b,
+            Some(history::variant_id("combined", &variant_b.combined_prompt, &default_prompt)),
+        ),
+        Preference::Tie => {
+            // 合併兩組建議，A 在前、B 在後
+            let mut merged = suggestions_a;
+            merged.commit_notes.resize(merged.commit_messages.len(), None);
+            for branch in suggestions_b.branch_names {
+                if !merged.branch_names.contains(&branch) {
+                    merged.branch_names.push(branch);
+                }
+            }
+            let mut notes_b = suggestions_b.commit_notes;
+            notes_b.resize(suggestions_b.commit_messages.len(), None);
+            merged.commit_messages.extend(suggestions_b.commit_messages);
+            merged.commit_notes.extend(notes_b);
+            (merged, None)
+        }
+    })
+}
+
+/// 檢查 commit 建議是否對應到變更內容，依設定標示或重新生成籠統的建議
+fn check_coverage(
+    suggestions: &mut GitSuggestions,
+    prompt: &str,
+    diff: &str,
+    files: &[String],
+    config: &LlmConfig,
+    timeline: &mut Timeline,
+) {
+    timeline.start("檢查建議內容");
+    let keywords = coverage::collect_keywords(files, diff);
+    let assess_all = |messages: &[String]| -> Vec<(usize, Verdict)> {
+        messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (i, coverage::assess(m, &keywords)))
+            .filter(|(_, verdict)| *verdict != Verdict::Specific)
+            .collect()
+    };
+    let mut flagged = assess_all(&suggestions.commit_messages);
+    timeline.finish();
+
+    if !flagged.is_empty() && config.coverage.mode == CoverageMode::Regenerate {
+        timeline.start("重新生成籠統的建議");
+        let listed: Vec<String> = flagged
+            .iter()
+            .map(|(i, _)| format!("- {}", suggestions.commit_messages[*i].lines().next().unwrap_or("")))
+            .collect();
+        let retry_prompt = format!(
+            "{}\n\n注意：以下 commit 訊息過於籠統或與變更內容無關，請重新生成更具體的建議，\
+             描述中應提及實際變更的模組、函數或檔案：\n{}",
+            prompt,
+            listed.join("\n")
+        );
+
+        match call_llm_cli(&retry_prompt, config)
+            .ok()
+            .and_then(|response| parse_llm_response(&response))
+        {
+            Some(retry) => {
+                let mut replacements = retry
+                    .commit_messages
+                    .into_iter()
+                    .filter(|m| coverage::assess(m, &keywords) == Verdict::Specific);
+                for (index, _) in &flagged {
+                    if let Some(replacement) = replacements.next() {
+                        suggestions.commit_messages[*index] = replacement;
+                        if let Some(label) = suggestions.commit_labels.get_mut(*index) {
+                            *label = Some(config.model.clone());
+                        }
+                    }
+                }
+                timeline.finish();
+            }
+            None => timeline.fail(),
+        }
+
+        flagged = assess_all(&suggestions.commit_messages);
+    }
+
+    suggestions.commit_notes = vec![None; suggestions.commit_messages.len()];
+    for (index, verdict) in flagged {
+        suggestions.commit_notes[index] = Some(verdict.describe().to_string());
+    }
+}
+
+/// 取得 diff 的統計資訊
+fn get_diff_stats(diff: &str) -> String {
+    let mut additions = 0;
+    let mut deletions = 0;
+    let mut files_changed = 0;
+    
+    for line in diff.lines() {
         if line.starts_with("+++") || line.starts_with("---") {
             if !line.contains("/dev/null") {
                 files_changed += 1;
@@ -437,7 +2120,7 @@ fn get_diff_stats(diff: &str) -> String {
     }
     
     // 修正檔案數量（每個檔案會有 +++ 和 --- 兩行）
-    files_changed = files_changed / 2;
+    files_changed /= 2;
     
     format!(
         "{} 個檔案變更，新增 {} 行，刪除 {} 行",
@@ -463,8 +2146,24 @@ fn parse_llm_response(response: &str) -> Option<GitSuggestions> {
         }
     }
     
-    // 提取 commit 訊息區塊
-    let commits_section = &response[commits_start + 9..];
+    // 提取 commit 訊息區塊（之後可能有 [ANALYSIS] 完整分析與 [REVIEW] 審查筆記，順序不拘）
+    let rest = &response[commits_start + 9..];
+    let markers = ["[ANALYSIS]", "[REVIEW]"];
+    let next_marker = |from: usize| {
+        markers
+            .iter()
+            .filter_map(|marker| rest[from..].find(marker).map(|offset| from + offset))
+            .min()
+            .unwrap_or(rest.len())
+    };
+    let commits_section = &rest[..next_marker(0)];
+    let section = |marker: &str| {
+        let start = rest.find(marker)? + marker.len();
+        let text = rest[start..next_marker(start)].trim();
+        (!text.is_empty()).then(|| text.to_string())
+    };
+    let analysis = section("[ANALYSIS]");
+    let review = section("[REVIEW]");
     
     // 使用更智能的方式解析 commit 訊息
     // 符合 "word:" 格式的行被視為新 commit 的開始（允許任何類型）
@@ -490,7 +2189,7 @@ fn parse_llm_response(response: &str) -> Option<GitSuggestions> {
             // 檢查：1) 不是空的，2) 只包含英文字母、數字、連字號，3) 以字母開頭
             !before_colon.is_empty() 
                 && before_colon.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
-                && before_colon.chars().next().map_or(false, |c| c.is_ascii_alphabetic())
+                && before_colon.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
         } else {
             false
         };
@@ -530,6 +2229,10 @@ fn parse_llm_response(response: &str) -> Option<GitSuggestions> {
         Some(GitSuggestions {
             branch_names: branch_names.into_iter().take(3).collect(),
             commit_messages: commit_messages.into_iter().take(3).collect(),
+            commit_notes: Vec::new(),
+            commit_labels: Vec::new(),
+            analysis,
+            review,
         })
     } else {
         None
@@ -586,133 +2289,716 @@ fn select_branch(current: &str, suggestions: &[String]) -> Result<Option<String>
     let mut items = vec![format!("保持當前分支 ({})", current)];
 
     for (i, suggestion) in suggestions.iter().enumerate() {
-        items.push(format!("{}. {}", i + 1, suggestion));
+        let prefix = format!("{}. ", i + 1);
+        let name = layout::truncate(suggestion, layout::available(layout::MENU_INDENT + prefix.len()));
+        items.push(format!("{}{}", prefix, name));
+    }
+
+    items.push("自訂分支名稱".to_string());
+
+    // 可按數字鍵直接選擇建議；按下 Esc 視同保持當前分支
+    let selection = menu::select("請選擇", &items, 0)?.unwrap_or(0);
+
+    // 保持當前分支
+    if selection == 0 {
+        return Ok(None);
+    }
+
+    // 自訂分支名稱
+    if selection == items.len() - 1 {
+        let custom_branch: String = Input::with_theme(plain::theme())
+            .with_prompt("請輸入自訂分支名稱")
+            .validate_with(|input: &String| {
+                if input.trim().is_empty() {
+                    Err("分支名稱不能為空")
+                } else if !is_valid_branch_name(input) {
+                    Err("分支名稱包含無效字元")
+                } else {
+                    Ok(())
+                }
+            })
+            .interact_text()?;
+        return Ok(Some(custom_branch.trim().to_string()));
+    }
+
+    // 選擇建議的分支
+    let index = selection - 1; // 減去 "保持當前分支"
+    if index < suggestions.len() {
+        edit_branch_slug(&suggestions[index]).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// 編輯所選分支名稱的描述部分（最後一個 / 之後），前綴保持不變；直接按 Enter 即沿用建議
+fn edit_branch_slug(branch_name: &str) -> Result<String> {
+    let (prefix, slug) = match branch_name.rfind('/') {
+        Some(pos) => branch_name.split_at(pos + 1),
+        None => ("", branch_name),
+    };
+
+    let edited: String = Input::with_theme(plain::theme())
+        .with_prompt(format!("分支名稱 {}", prefix))
+        .with_initial_text(slug)
+        .validate_with(|input: &String| {
+            let slug = input.trim();
+            if slug.is_empty() {
+                Err("描述不能為空".to_string())
+            } else if slug.contains('/') {
+                Err("描述不能包含 /（前綴已固定）".to_string())
+            } else if !is_valid_branch_name(&format!("{}{}", prefix, slug)) {
+                Err("分支名稱包含無效字元".to_string())
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+
+    Ok(format!("{}{}", prefix, edited.trim()))
+}
+
+/// 顯示完整的 commit 訊息預覽
+fn print_commit_preview(message: &str) {
+    println!();
+    println!("{}", "📋 Commit 預覽".blue().bold());
+    println!("{}", layout::rule().dimmed());
+    // 依終端機寬度在空白處折行（只影響顯示，不改變訊息）
+    for line in message.lines() {
+        for wrapped in layout::wrap(line, layout::width()) {
+            println!("{}", wrapped);
+        }
+    }
+    println!("{}", layout::rule().dimmed());
+    println!();
+}
+
+/// 顯示 commit 預覽、變更的風險與用語檢查結果，回傳是否發現用語問題
+fn preview_with_checks(
+    message: &str,
+    terms: &terminology::Checker,
+    risks: &risk::Report,
+    review: Option<&str>,
+) -> bool {
+    print_commit_preview(message);
+    risks.print();
+    if let Some(notes) = review {
+        review::print(notes);
+    }
+    let has_issues = print_term_issues(message, terms);
+    if has_issues {
+        println!();
+    }
+    has_issues
+}
+
+/// 顯示訊息中可能的錯字或不建議的用語，有則回傳 true
+fn print_term_issues(message: &str, terms: &terminology::Checker) -> bool {
+    let issues = terms.check(message);
+    if issues.is_empty() {
+        return false;
+    }
+    println!("{}", "🔤 可能的錯字或不建議的用語：".yellow());
+    for issue in &issues {
+        println!("{}", format!("  「{}」→「{}」", issue.found, issue.preferred).yellow());
+    }
+    true
+}
+
+/// 檢查是否包含禁止使用的字詞，有則顯示並回傳 false
+fn check_compliance(label: &str, text: &str, compliance: &compliance::Filter) -> bool {
+    let violations = compliance.violations(text);
+    if violations.is_empty() {
+        return true;
+    }
+    println!(
+        "{}",
+        format!("⛔ {}包含禁止使用的內容：{}，請修改後再繼續", label, violations.join("、")).red()
+    );
+    false
+}
+
+/// 詢問多個相依套件更新的分組方式，只有一個 commit 時回傳 None
+fn select_dependency_grouping(
+    repo: &Repository,
+    bumps: &[deps::Bump],
+    staged_files: &[String],
+) -> Result<Option<SplitPlan>> {
+    let changes = split::StagedChanges::load(repo, staged_files)?;
+
+    // 只列出會拆成多個 commit 的分組方式
+    let mut options = vec![("全部合併為一個 commit".to_string(), None)];
+    for (grouping, label) in [
+        (deps::Grouping::Ecosystem, "依生態系分組"),
+        (deps::Grouping::Major, "主版本升級各自獨立"),
+    ] {
+        let groups = deps::plan(grouping, bumps, &changes);
+        if groups.len() > 1 {
+            options.push((format!("{}（{} 個 commit）", label, groups.len()), Some(groups)));
+        }
+    }
+    if options.len() == 1 {
+        return Ok(None);
+    }
+
+    let labels: Vec<&str> = options.iter().map(|(label, _)| label.as_str()).collect();
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt(format!("共 {} 個套件更新，要如何分組 commit？", bumps.len()))
+        .items(&labels)
+        .default(0)
+        .interact()?;
+
+    Ok(options.swap_remove(selection).1.map(|groups| (changes, groups)))
+}
+
+/// offer_concern_split 是否可能詢問拆成多個 commit（LLM 之後可能判斷為相關而不詢問）
+fn may_offer_split(files: &[String], config: &LlmConfig, cli: &Cli) -> bool {
+    config.split.mode == SplitMode::Offer
+        && !cli.quick
+        && cli.export_patch.is_none()
+        && concerns::plan(files, config.split.strictness).is_ok_and(|concerns| concerns.len() >= 2)
+}
+
+/// staged 變更混合多個關注點時提醒並詢問是否拆成多個 commit，選擇拆分時回傳分組與各組的建議
+fn offer_concern_split(
+    repo: &Repository,
+    files: &[String],
+    diff: &str,
+    context: &PromptContext,
+    config: &LlmConfig,
+    cli: &Cli,
+    timeline: &mut Timeline,
+) -> Result<Option<(split::StagedChanges, Vec<split::Group>, GitSuggestions)>> {
+    if config.split.mode == SplitMode::Off {
+        return Ok(None);
+    }
+    let concerns = concerns::plan(files, config.split.strictness)?;
+    if concerns.len() < 2 {
+        return Ok(None);
+    }
+
+    // 依檔案判斷為混合時，再請 LLM 確認；LLM 判斷為相關時不提醒
+    let mut reason = None;
+    if config.split.llm_check && !cli.offline {
+        timeline.start("判斷變更是否屬於同一個目的");
+        match concerns::judge(&concerns, diff, config) {
+            Ok(Some(concerns::Judgment::Related(why))) => {
+                timeline.finish();
+                println!("{}", format!("✂️  LLM 判斷 staged 變更屬於同一個目的：{}", why).dimmed());
+                return Ok(None);
+            }
+            Ok(judgment) => {
+                timeline.finish();
+                if let Some(concerns::Judgment::Separate(why)) = judgment {
+                    reason = Some(why);
+                }
+            }
+            Err(e) => {
+                timeline.fail();
+                println!("{}", format!("⚠️  無法請 LLM 判斷，依檔案分組提醒：{:#}", e).yellow());
+            }
+        }
+    }
+    concerns::print(&concerns, reason.as_deref());
+
+    if config.split.mode == SplitMode::Warn || cli.quick || cli.export_patch.is_some() {
+        println!("{}", "  可先以 git reset 移出部分檔案，分成多次 commit".dimmed());
+        println!();
+        return Ok(None);
+    }
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("要如何 commit？")
+        .items(&[format!("✂️  依關注點拆成 {} 個 commit", concerns.len()), "保持為一個 commit".to_string()])
+        .default(0)
+        .interact()?;
+    if selection != 0 {
+        return Ok(None);
+    }
+
+    // 每組各自產生建議，以第一個 commit 訊息作為該組的訊息；分支名稱合併各組的建議
+    let changes = split::StagedChanges::load(repo, files)?;
+    let mut groups = Vec::new();
+    let mut suggestions = GitSuggestions {
+        branch_names: Vec::new(),
+        commit_messages: Vec::new(),
+        commit_notes: Vec::new(),
+        commit_labels: Vec::new(),
+        analysis: None,
+        review: None,
+    };
+    for concern in &concerns {
+        let message = if concern.formatting_only() {
+            concerns::formatting_message(concern)
+        } else {
+            let diff = get_staged_diff(repo, &concern.files)?;
+            let group_suggestions = if cli.offline {
+                fallback_suggestions(&diff, &concern.files)
+            } else {
+                generate_suggestions(&diff, &concern.files, context, config, timeline, None)
+            };
+            for name in group_suggestions.branch_names {
+                if !suggestions.branch_names.contains(&name) {
+                    suggestions.branch_names.push(name);
+                }
+            }
+            group_suggestions
+                .commit_messages
+                .into_iter()
+                .next()
+                .unwrap_or_else(|| "chore: 更新專案檔案".to_string())
+        };
+        let units = changes
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, change)| concern.files.contains(&change.path))
+            .map(|(file, _)| split::Unit { file, hunk: None })
+            .collect();
+        suggestions.commit_messages.push(message.clone());
+        groups.push(split::Group { message, units });
+    }
+    Ok(Some((changes, groups, suggestions)))
+}
+
+/// 包含資料庫 migration 的 commit 需再次確認
+fn confirm_migrations(migrations: &[migration::Migration]) -> Result<bool> {
+    let names: Vec<&str> = migrations.iter().map(|m| m.name.as_str()).collect();
+    println!(
+        "{}",
+        format!("⚠️  這個 commit 包含資料庫 migration：{}", names.join(", ")).yellow()
+    );
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("確認 migration 內容無誤並 commit？")
+        .items(&["✓ 確認（加上 Migration: 註記）", "✗ 取消 commit"])
+        .default(0)
+        .interact()?;
+    Ok(selection == 0)
+}
+
+/// 列出新增的除錯程式碼，可檢視所在的 hunk，回傳是否繼續
+fn review_debug_leftovers(risks: &risk::Report) -> Result<bool> {
+    let leftovers = risks.debug_leftovers();
+    println!("{}", format!("🐛 staged 變更新增了 {} 處除錯程式碼：", leftovers.len()).yellow());
+    for finding in &leftovers {
+        let head = format!("  {}  ", finding.location);
+        let used = console::measure_text_width(&head);
+        println!("{}{}", head, layout::truncate(&finding.text, layout::available(used)).dimmed());
+    }
+    println!();
+
+    loop {
+        let selection = Select::with_theme(plain::theme())
+            .with_prompt("請選擇")
+            .items(&["✓ 繼續（保留這些程式碼）", "📄 檢視所在的 hunk", "✗ 中止"])
+            .default(0)
+            .interact()?;
+        match selection {
+            0 => return Ok(true),
+            1 => {
+                let hunks = risks.debug_hunks();
+                if let Err(e) = layout::page(&hunks) {
+                    println!("{}", format!("⚠️  {:#}", e).yellow());
+                    print!("{}", hunks);
+                }
+            }
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// 預覽並依序建立拆分後的多個 commit（相依套件更新的分組或依關注點拆分）
+fn commit_split_groups(
+    repo: &Repository,
+    (changes, groups): &(split::StagedChanges, Vec<split::Group>),
+    config: &LlmConfig,
+    ticket: Option<&str>,
+    scope: &scope::Scope,
+    checks: &MessageChecks,
+    options: &mut CommitOptions,
+) -> Result<Vec<CommitSummary>> {
+    // 與一般流程相同，各組的訊息先交由外掛與腳本處理
+    let groups: Vec<split::Group> = groups
+        .iter()
+        .map(|group| {
+            let mut suggestions = GitSuggestions {
+                commit_messages: vec![group.message.clone()],
+                ..Default::default()
+            };
+            checks.plugins.post_process(&mut suggestions);
+            script::transform_suggestions(&mut suggestions);
+            split::Group {
+                message: suggestions.commit_messages.into_iter().next().unwrap_or_else(|| group.message.clone()),
+                units: group.units.clone(),
+            }
+        })
+        .collect();
+
+    println!("{}", format!("✂️  將建立 {} 個 commit：", groups.len()).cyan());
+    let mut allowed = true;
+    for (i, group) in groups.iter().enumerate() {
+        println!();
+        println!("{}", format!("[{}/{}]", i + 1, groups.len()).dimmed());
+        println!("{}", group.message.bright_white());
+        print_term_issues(&group.message, checks.terms);
+        allowed &= check_compliance(&format!("第 {} 個 commit 的訊息", i + 1), &group.message, checks.compliance);
+        let mut files: Vec<&str> = group.units.iter().map(|u| changes.files[u.file].path.as_str()).collect();
+        files.dedup();
+        for file in files {
+            println!("  {}", file.dimmed());
+        }
+    }
+    println!();
+    if !allowed {
+        println!("{}", "✗ 拆分後的訊息包含禁止使用的內容，已取消 commit".red());
+        return Ok(Vec::new());
     }
 
-    items.push("自訂分支名稱".to_string());
-
-    let selection = Select::with_theme(&ColorfulTheme::default())
-        .with_prompt("請選擇")
-        .items(&items)
+    let confirm = Select::with_theme(plain::theme())
+        .with_prompt("確認建立這些 commit？")
+        .items(&["✓ 確認", "✗ 取消 commit"])
         .default(0)
         .interact()?;
+    if confirm != 0 {
+        return Ok(Vec::new());
+    }
 
-    // 保持當前分支
-    if selection == 0 {
+    options.identity = identity::select_identity(repo, &config.identities)?;
+    let trailers = trailers::resolve(repo, config, ticket)?;
+    trailers::print(&trailers);
+    // 範圍外的檔案在整個拆分期間移出 staged（拆分時 index 會依序重建）
+    scope.commit(repo, || {
+        split::commit_groups(repo, changes, &groups, |group| {
+            let message = trailers::apply(&group.message, &trailers)?;
+            let message = if config.gerrit.enabled {
+                gerrit::with_change_id(repo, &message)
+            } else {
+                message
+            };
+            commit_changes(repo, &message, options)
+        })
+    })
+}
+
+/// 預覽第一個建議並確認是否直接 commit（依 confirm 設定可不需確認），未確認時回傳 `None`
+fn confirm_quick_commit(
+    suggestions: &GitSuggestions,
+    branch: &str,
+    confirm: ConfirmMode,
+    terms: &terminology::Checker,
+    risks: &risk::Report,
+    compliance: &compliance::Filter,
+) -> Result<Option<String>> {
+    let Some(message) = suggestions.commit_messages.first() else {
+        return Ok(None);
+    };
+    let has_issues = preview_with_checks(message, terms, risks, suggestions.review.as_deref());
+    if !check_compliance("commit 訊息", message, compliance) {
         return Ok(None);
     }
-
-    // 自訂分支名稱
-    if selection == items.len() - 1 {
-        let custom_branch: String = Input::with_theme(&ColorfulTheme::default())
-            .with_prompt("請輸入自訂分支名稱")
-            .validate_with(|input: &String| {
-                if input.trim().is_empty() {
-                    Err("分支名稱不能為空")
-                } else if !is_valid_branch_name(input) {
-                    Err("分支名稱包含無效字元")
-                } else {
-                    Ok(())
-                }
-            })
-            .interact_text()?;
-        return Ok(Some(custom_branch.trim().to_string()));
+    // 有用語問題或風險時仍需確認，讓使用者有機會改用一般流程修正
+    if confirm != ConfirmMode::Always && !has_issues && !risks.needs_confirmation() {
+        return Ok(Some(message.clone()));
     }
+    let confirmed = Confirm::with_theme(plain::theme())
+        .with_prompt(format!("以此訊息 commit 到 {}？（選否改為手動選擇）", branch))
+        .default(true)
+        .interact()?;
+    Ok(confirmed.then(|| message.clone()))
+}
 
-    // 選擇建議的分支
-    let index = selection - 1; // 減去 "保持當前分支"
-    if index < suggestions.len() {
-        Ok(Some(suggestions[index].clone()))
-    } else {
-        Ok(None)
-    }
+/// 對 commit 訊息執行的改寫與檢查：外掛與腳本、用語檢查與禁止使用的字詞
+struct MessageChecks<'a> {
+    plugins: &'a PluginHost,
+    terms: &'a terminology::Checker,
+    compliance: &'a compliance::Filter,
 }
 
-/// 選擇 commit 訊息（包含預覽和確認循環）
-fn select_commit_message(suggestions: &[String]) -> Result<String> {
+/// commit 訊息選單的確認方式與預設選項
+struct MenuPreferences<'a> {
+    confirm: ConfirmMode,
+    /// 此 repository 上次選擇的 commit 類型，選單預設選擇同類型的第一個建議
+    commit_type: Option<&'a str>,
+}
+
+/// 選擇 commit 訊息（包含預覽和確認循環，依 confirm 設定可略過確認）
+///
+/// 使用者選擇取消時回傳 `None`。
+fn select_commit_message(
+    suggestions: &GitSuggestions,
+    preferences: &MenuPreferences,
+    plugins: &PluginHost,
+    terms: &terminology::Checker,
+    risks: &risk::Report,
+    compliance: &compliance::Filter,
+    options: &mut CommitOptions,
+) -> Result<Option<String>> {
     loop {
         // 顯示標題
         println!("\n{}", "--- 建議的 Commit 訊息 ---".cyan());
         
         let mut items = Vec::new();
 
-        // 只顯示每個建議的第一行（標題），避免選單過長
-        for (i, suggestion) in suggestions.iter().enumerate() {
+        // 只顯示每個建議的第一行（標題），避免選單過長；過長的標題依終端機寬度截斷，避免折行打亂選單
+        for (i, suggestion) in suggestions.commit_messages.iter().enumerate() {
             let first_line = suggestion.lines().next().unwrap_or(suggestion);
-            items.push(format!("{}. {}", i + 1, first_line));
+            let prefix = format!("{}. ", i + 1);
+            let label = suggestions.commit_labels.get(i).cloned().flatten().map(|label| format!("[{}]", label));
+            let note = suggestions.commit_notes.get(i).cloned().flatten().map(|note| format!("⚠ {}", note));
+            if label.is_none() && note.is_none() {
+                let subject = layout::truncate(first_line, layout::available(layout::MENU_INDENT + prefix.len()));
+                items.push(format!("{}{}", prefix, subject));
+                continue;
+            }
+            let suffix_width: usize =
+                [&label, &note].into_iter().flatten().map(|s| 1 + console::measure_text_width(s)).sum();
+            let used = layout::MENU_INDENT + prefix.len() + suffix_width;
+            let mut item = format!("{}{}", prefix, layout::truncate(first_line, layout::available(used).max(20)));
+            if let Some(label) = label {
+                item.push_str(&format!(" {}", label.dimmed()));
+            }
+            if let Some(note) = note {
+                item.push_str(&format!(" {}", note.yellow()));
+            }
+            items.push(item);
         }
 
+        let custom_index = items.len();
         items.push("自訂 Commit 訊息".to_string());
+        let editor_index = items.len();
+        items.push("📝 在編輯器中撰寫 Commit 訊息".to_string());
+        // 有多個建議時可並排比較完整內容
+        let compare_index = (suggestions.commit_messages.len() > 1).then(|| {
+            items.push("🔍 比較建議".to_string());
+            items.len() - 1
+        });
+        items.push("✗ 取消 commit".to_string());
+
+        // 可按數字鍵直接選擇建議；預設選擇與上次相同類型的建議
+        let default = preferences
+            .commit_type
+            .and_then(|preferred| {
+                suggestions
+                    .commit_messages
+                    .iter()
+                    .position(|message| conventional_type(message) == Some(preferred))
+            })
+            .unwrap_or(0);
+        let selection = menu::select("請選擇", &items, default)?;
 
-        let selection = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("請選擇")
-            .items(&items)
-            .default(0)
-            .interact()?;
+        // 按下 Esc 或選擇取消
+        let Some(selection) = selection.filter(|s| *s <= editor_index || Some(*s) == compare_index) else {
+            return Ok(None);
+        };
+        if Some(selection) == compare_index {
+            side_by_side::show(&suggestions.commit_messages)?;
+            continue;
+        }
 
         // 處理選擇
-        let message = if selection == items.len() - 1 {
-            // 自訂 commit 訊息
-            let custom_message: String = Input::with_theme(&ColorfulTheme::default())
-                .with_prompt("請輸入自訂 Commit 訊息")
-                .validate_with(|input: &String| {
-                    if input.trim().is_empty() {
-                        Err("Commit 訊息不能為空")
-                    } else {
-                        Ok(())
-                    }
-                })
-                .interact_text()?;
-            
-            custom_message.trim().to_string()
-        } else if selection < suggestions.len() {
+        let mut message = if selection == custom_index {
+            // 自訂 commit 訊息（可輸入多行，按 Esc 回到選單）
+            match input::message("請輸入自訂 Commit 訊息")? {
+                Some(custom_message) => custom_message,
+                None => continue,
+            }
+        } else if selection == editor_index {
+            // 在編輯器中撰寫（未存檔或內容為空時回到選單）
+            match input::editor("")? {
+                Some(custom_message) => custom_message,
+                None => continue,
+            }
+        } else if selection < suggestions.commit_messages.len() {
             // 選擇建議的訊息
-            suggestions[selection].clone()
+            suggestions.commit_messages[selection].clone()
         } else {
             continue;
         };
 
         // 顯示完整預覽
-        println!();
-        println!("{}", "📋 Commit 預覽".blue().bold());
-        println!("{}", "─────────────────────────────────────".dimmed());
-        println!("{}", message);
-        println!("{}", "─────────────────────────────────────".dimmed());
-        println!();
+        let mut has_issues = preview_with_checks(&message, terms, risks, suggestions.review.as_deref());
 
-        // 確認或重新選擇
-        let confirm_items = vec!["✓ 確認使用此訊息", "← 重新選擇"];
-        let confirmed = Select::with_theme(&ColorfulTheme::default())
-            .with_prompt("請選擇")
-            .items(&confirm_items)
-            .default(0)
-            .interact()?;
+        // 依設定略過確認；有用語問題、風險或包含禁止使用的字詞時仍顯示確認選單
+        let needs_confirm = match preferences.confirm {
+            ConfirmMode::Always => true,
+            ConfirmMode::CustomOnly => selection >= custom_index,
+            ConfirmMode::Never => false,
+        };
+        if !needs_confirm && !has_issues && !risks.needs_confirmation() && check_compliance("commit 訊息", &message, compliance) {
+            return Ok(Some(message));
+        }
 
-        if confirmed == 0 {
-            // 確認，返回訊息
-            return Ok(message);
+        // 確認、設定 commit 時間、執行外掛動作、套用建議用語或重新選擇
+        let actions = plugins.actions();
+        loop {
+            let date_label = match &options.date {
+                Some(date) => format!("🕒 設定 commit 時間（目前：{}）", date.format("%Y-%m-%d %H:%M")),
+                None => "🕒 設定 commit 時間（目前：現在）".to_string(),
+            };
+            let mut confirm_items = vec!["✓ 確認使用此訊息".to_string(), date_label];
+            for (_, action) in &actions {
+                confirm_items.push(format!("🔌 {}", action.label));
+            }
+            let fix_index = confirm_items.len();
+            if has_issues {
+                confirm_items.push("🔤 套用建議的寫法".to_string());
+            }
+            confirm_items.push("← 重新選擇".to_string());
+
+            let confirmed = Select::with_theme(plain::theme())
+                .with_prompt("請選擇")
+                .items(&confirm_items)
+                .default(0)
+                .interact()?;
+
+            match confirmed {
+                // 確認，返回訊息（包含禁止使用的字詞時需先修改）
+                0 => {
+                    if check_compliance("commit 訊息", &message, compliance) {
+                        return Ok(Some(message));
+                    }
+                }
+                1 => options.date = prompt_commit_date()?,
+                n if n - 2 < actions.len() => {
+                    let (plugin, action) = actions[n - 2];
+                    match plugins.run_action(plugin, action, &message) {
+                        Ok(updated) => {
+                            message = updated;
+                            has_issues = preview_with_checks(&message, terms, risks, suggestions.review.as_deref());
+                        }
+                        Err(e) => println!("{}", format!("✗ {:#}", e).red()),
+                    }
+                }
+                n if has_issues && n == fix_index => {
+                    message = terms.apply(&message);
+                    has_issues = preview_with_checks(&message, terms, risks, suggestions.review.as_deref());
+                }
+                // 否則跳出，重新選擇
+                _ => break,
+            }
         }
-        // 否則繼續循環，重新選擇
     }
 }
 
-/// 驗證分支名稱
-fn is_valid_branch_name(name: &str) -> bool {
-    // Git 分支名稱規則：不能包含空格、~、^、:、?、*、[、]、\
-    // 以及不能以 / 或 . 開頭
-    let invalid_chars = [' ', '~', '^', ':', '?', '*', '[', ']', '\\'];
-    
-    if name.starts_with('/') || name.starts_with('.') {
-        return false;
+/// 詢問 commit 時間（留空表示使用現在時間）
+fn prompt_commit_date() -> Result<Option<DateTime<Local>>> {
+    let input: String = Input::with_theme(plain::theme())
+        .with_prompt("commit 時間（例如 yesterday 18:00、2024-01-01 10:00，留空為現在）")
+        .allow_empty(true)
+        .validate_with(|input: &String| {
+            if input.trim().is_empty() {
+                return Ok(());
+            }
+            date::parse_date(input, Local::now())
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()?;
+
+    if input.trim().is_empty() {
+        return Ok(None);
+    }
+    let parsed = date::parse_date(&input, Local::now())?;
+    warn_if_future(&parsed);
+    Ok(Some(parsed))
+}
+
+fn warn_if_future(date: &DateTime<Local>) {
+    if *date > Local::now() {
+        println!("{}", "⚠️  指定的 commit 時間在未來".yellow());
+    }
+}
+
+/// 選定分支名稱後的處理方式
+enum BranchAction {
+    /// 建立並切換到新分支
+    Create(String),
+    /// 切換到已存在的分支（若只存在於 remote，則附上 remote 名稱以建立追蹤分支）
+    SwitchExisting(String, Option<String>),
+    /// 回到分支選單重新選擇
+    Reselect,
+}
+
+/// 檢查分支是否已存在於本地或 remote，若已存在則詢問處理方式
+fn resolve_branch_collision(repo: &Repository, name: &str) -> Result<BranchAction> {
+    let Some(collision) = branch::find_collision(repo, name) else {
+        return Ok(BranchAction::Create(name.to_string()));
+    };
+
+    let location = if collision.local {
+        "本地".to_string()
+    } else {
+        collision.remotes.join("、")
+    };
+    println!("{}", format!("⚠️  分支 {} 已存在於 {}", name, location).yellow());
+
+    let suffixed = branch::next_available_name(repo, name);
+    let switch_label = if collision.local {
+        format!("切換到現有分支 {}", name)
+    } else {
+        format!("從 {}/{} 建立追蹤分支並切換", collision.remotes[0], name)
+    };
+    let items = vec![
+        switch_label,
+        format!("改用 {}", suffixed),
+        "選擇其他分支名稱".to_string(),
+    ];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("請選擇")
+        .items(&items)
+        .default(1)
+        .interact()?;
+
+    Ok(match selection {
+        0 if collision.local => BranchAction::SwitchExisting(name.to_string(), None),
+        0 => BranchAction::SwitchExisting(name.to_string(), collision.remotes.first().cloned()),
+        1 => BranchAction::Create(suffixed),
+        _ => BranchAction::Reselect,
+    })
+}
+
+/// 切換到已存在的分支（staged 的變更會一併帶過去）
+fn checkout_existing_branch(name: &str, remote: Option<&str>) -> Result<()> {
+    let mut cmd = Command::new("git");
+    match remote {
+        Some(remote) => cmd.args(["checkout", "-b", name, "--track", &format!("{}/{}", remote, name)]),
+        None => cmd.args(["checkout", name]),
+    };
+    let output = cmd.output().context("無法執行 git checkout")?;
+
+    if output.status.success() {
+        println!("{}", format!("✓ 已切換到分支：{}", name).green());
+        Ok(())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        println!("{}", format!("✗ 切換分支失敗：{}", error).red());
+        anyhow::bail!("切換分支失敗")
     }
+}
 
-    !name.chars().any(|c| invalid_chars.contains(&c))
+/// 新分支的起點：workflow 有預設的 base 分支（例如 git-flow 的 develop）且不是目前分支時使用它，
+/// 本地沒有時改用 remote-tracking 分支；其他情況為 None（從目前的 HEAD 建立）
+fn branch_start_point(repo: &Repository, config: &LlmConfig, current_branch: &str) -> Option<String> {
+    let base = workflow::base_branch(config.workflow).filter(|base| *base != current_branch)?;
+    stack::trunk_ref(repo, base)
 }
 
-/// 切換分支
-fn switch_branch(branch_name: &str) -> Result<()> {
+/// 建立並切換到新分支；指定起點但 staged 的變更無法帶過去時，改從目前的 HEAD 建立
+fn switch_branch(branch_name: &str, start: Option<&str>) -> Result<()> {
+    if let Some(start) = start {
+        let output = Command::new("git")
+            .args(["checkout", "--no-track", "-b", branch_name, start])
+            .output()
+            .context("無法執行 git checkout")?;
+        if output.status.success() {
+            println!("{}", format!("✓ 已從 {} 建立並切換到新分支：{}", start, branch_name).green());
+            return Ok(());
+        }
+        let error = String::from_utf8_lossy(&output.stderr);
+        let reason = error.lines().find(|line| !line.trim().is_empty()).unwrap_or("").trim();
+        println!("{}", format!("⚠️  無法從 {} 建立分支（{}），改從目前的 HEAD 建立", start, reason).yellow());
+    }
+
     let output = Command::new("git")
-        .args(&["checkout", "-b", branch_name])
+        .args(["checkout", "-b", branch_name])
         .output()
         .context("無法執行 git checkout")?;
 
@@ -726,20 +3012,544 @@ fn switch_branch(branch_name: &str) -> Result<()> {
     }
 }
 
-/// 執行 git commit
-fn commit_changes(message: &str) -> Result<()> {
+/// 請 LLM 以一句話說明新分支的用途，失敗時回傳 None
+fn describe_branch(name: &str, diff: &str, files: &[String], config: &LlmConfig) -> Option<String> {
+    let diff_preview: String = diff.chars().take(4000).collect();
+    let prompt = config
+        .branch_description
+        .prompt
+        .replace("{branch}", name)
+        .replace("{stats}", &get_diff_stats(diff))
+        .replace("{file_summary}", &get_file_summary(files))
+        .replace("{diff}", &diff_preview)
+        .replace("{language}", &config.language);
+
+    let mut timeline = Timeline::new();
+    timeline.start("生成分支用途說明");
+    match call_llm_cli(&prompt, config) {
+        Ok(response) => {
+            let description = response
+                .lines()
+                .map(|line| line.trim().trim_matches(['"', '「', '」']))
+                .find(|line| !line.is_empty())
+                .map(String::from);
+            match description {
+                Some(_) => timeline.finish(),
+                None => timeline.fail(),
+            }
+            description
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  無法生成分支用途說明：{}", e).yellow());
+            None
+        }
+    }
+}
+
+/// 將用途說明寫入 branch.<name>.description，失敗時只顯示警告
+fn save_branch_description(repo: &Repository, name: &str, description: &str) {
+    match branch::set_description(repo, name, description) {
+        Ok(()) => println!("{}", format!("📝 分支用途：{}", description).dimmed()),
+        Err(e) => println!("{}", format!("⚠️  {:#}", e).yellow()),
+    }
+}
+
+/// 目前分支落後 upstream 時詢問是否先執行 git pull --rebase，回傳是否已同步
+fn offer_pull_rebase(status: &remote::AheadBehind) -> Result<bool> {
+    println!(
+        "{}",
+        format!("⚠️  {} 有 {} 個新的 commit，直接 commit 後推送會被拒絕", status.upstream, status.behind).yellow()
+    );
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("要先同步 upstream 嗎？")
+        .items(&["先執行 git pull --rebase（暫存並還原目前的變更）", "直接 commit（推送前再同步）"])
+        .default(0)
+        .interact()?;
+    if selection != 0 {
+        return Ok(false);
+    }
+    remote::pull_rebase()?;
+    println!("{}", format!("✓ 已同步 {} 的最新 commit", status.upstream).green());
+    println!();
+    Ok(true)
+}
+
+/// commit 未完成時，詢問是否切換回原分支並刪除剛建立的空分支
+fn offer_branch_rollback(previous: &str, created: &str) -> Result<()> {
+    println!();
+    let items = vec![
+        format!("切換回 {} 並刪除分支 {}", previous, created),
+        format!("保留分支 {}", created),
+    ];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt(format!("已建立的分支 {} 尚未有新的 commit", created))
+        .items(&items)
+        .default(0)
+        .interact()?;
+
+    if selection != 0 {
+        return Ok(());
+    }
+
+    // 新分支建立在原本的 HEAD 上，staged 的變更會隨切換保留
     let output = Command::new("git")
-        .args(&["commit", "-m", message])
+        .args(["checkout", "-q", previous])
         .output()
-        .context("無法執行 git commit")?;
+        .context("無法執行 git checkout")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        println!("{}", format!("✗ 切換回 {} 失敗：{}", previous, error).red());
+        anyhow::bail!("切換分支失敗");
+    }
 
+    let output = Command::new("git")
+        .args(["branch", "-d", created])
+        .output()
+        .context("無法執行 git branch")?;
     if output.status.success() {
-        println!("{}", "✓ Commit 成功！".green());
-        println!("{}", format!("  訊息：{}", message).dimmed());
+        println!("{}", format!("✓ 已切換回 {} 並刪除分支 {}", previous, created).green());
         Ok(())
     } else {
         let error = String::from_utf8_lossy(&output.stderr);
-        println!("{}", format!("✗ Commit 失敗：{}", error).red());
+        println!("{}", format!("✗ 刪除分支失敗：{}", error).red());
+        anyhow::bail!("刪除分支失敗")
+    }
+}
+
+/// commit 成功後，詢問是否從剛完成的 commit 分出下一個任務的分支（stacked 工作流程）
+fn offer_next_branch(
+    repo: &Repository,
+    config: &LlmConfig,
+    cli: &Cli,
+    compliance: &compliance::Filter,
+) -> Result<()> {
+    // Gerrit 模式已有推送到 refs/for 的流程；沒有 origin 時依預設推送的 remote 判斷
+    let forge = remote::default_push_remote(repo, &get_current_branch(repo)?, &remote::names(repo))
+        .and_then(|name| forge::from_remote(repo, config, &name))
+        .filter(|_| !config.gerrit.enabled);
+
+    println!();
+    let mut items = vec!["完成", "🌿 為下一個任務建立分支"];
+    if forge.is_some() {
+        items.push("🚀 推送並取得 PR 連結");
+    }
+    // 上次選擇推送時預設為推送
+    let push_by_default = forge.is_some() && repo_state::load(repo, config).push_after_commit == Some(true);
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("接下來要做什麼？")
+        .items(&items)
+        .default(if push_by_default { 2 } else { 0 })
+        .interact()?;
+    if forge.is_some() {
+        repo_state::update(repo, config, |state| state.push_after_commit = Some(selection == 2));
+    }
+    if selection == 0 {
+        return Ok(());
+    }
+    if let (2, Some(forge)) = (selection, &forge) {
+        return push_and_link(repo, config, forge.as_ref());
+    }
+
+    let description: String = Input::with_theme(plain::theme())
+        .with_prompt("下一個任務的描述")
+        .interact_text()?;
+
+    let current_branch = get_current_branch(repo)?;
+    let existing_branches = branch::existing_branch_names(repo);
+    let suggestions = if cli.offline {
+        slug_branch_suggestions(description.trim())
+    } else {
+        suggest_branches(&config.next_branch_prompt, description.trim(), &current_branch, &existing_branches, config)
+    };
+
+    // 下一個任務堆疊在目前分支上，因此從目前的 HEAD 建立
+    select_and_switch_branch(
+        repo,
+        config,
+        cli.ticket.as_deref(),
+        compliance,
+        suggestions,
+        Some(description.trim()),
+        false,
+    )
+}
+
+/// 套用分支名稱模板並排除重複的建議後，讓使用者選擇並切換（或建立）分支；`description` 為分支用途，
+/// `from_base` 為 true 時新分支從 workflow 預設的 base 分支建立
+fn select_and_switch_branch(
+    repo: &Repository,
+    config: &LlmConfig,
+    ticket: Option<&str>,
+    compliance: &compliance::Filter,
+    mut suggestions: Vec<String>,
+    description: Option<&str>,
+    from_base: bool,
+) -> Result<()> {
+    let current_branch = get_current_branch(repo)?;
+    let existing_branches = branch::existing_branch_names(repo);
+
+    if let Some(template) = &config.branch_template {
+        let vars = branch::TemplateVars::detect(repo, &current_branch, ticket, &config.ticket_pattern);
+        suggestions = suggestions
+            .iter()
+            .map(|suggestion| branch::apply_template(template, suggestion, &vars))
+            .collect();
+    }
+    let mut unique: Vec<String> = Vec::new();
+    for name in suggestions {
+        if is_valid_branch_name(&name)
+            && !unique.contains(&name)
+            && !branch::duplicates_existing(&name, &existing_branches)
+        {
+            unique.push(name);
+        }
+    }
+
+    while let Some(new_branch) = select_branch(&current_branch, &unique)? {
+        if !check_compliance("分支名稱", &new_branch, compliance) {
+            continue;
+        }
+        match resolve_branch_collision(repo, &new_branch)? {
+            BranchAction::Create(name) => {
+                let start = if from_base { branch_start_point(repo, config, &current_branch) } else { None };
+                switch_branch(&name, start.as_deref())?;
+                // 使用者輸入的任務描述即為分支用途
+                if let Some(description) = description.filter(|d| config.branch_description.enabled && !d.is_empty()) {
+                    save_branch_description(repo, &name, description);
+                }
+                break;
+            }
+            BranchAction::SwitchExisting(name, remote) => {
+                checkout_existing_branch(&name, remote.as_deref())?;
+                break;
+            }
+            BranchAction::Reselect => continue,
+        }
+    }
+
+    Ok(())
+}
+
+/// 有多個 remote 時選擇推送與 pull request 目標的 remote（預設依分支的追蹤設定），只有一個 remote 時直接使用
+fn select_push_target(repo: &Repository, branch: &str, trunk: Option<&str>) -> Result<remote::PushTarget> {
+    let remotes = remote::names(repo);
+    let push = remote::default_push_remote(repo, branch, &remotes).unwrap_or_else(|| "origin".to_string());
+    if remotes.len() < 2 {
+        return Ok(remote::PushTarget { base: push.clone(), push });
+    }
+
+    let labels: Vec<String> = remotes
+        .iter()
+        .map(|name| {
+            let url = repo.find_remote(name).ok().and_then(|r| r.url().map(str::to_string)).unwrap_or_default();
+            format!("{}  {}", name, url.dimmed())
+        })
+        .collect();
+    let index_of = |name: &str| remotes.iter().position(|remote| remote == name).unwrap_or(0);
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("推送到哪個 remote？")
+        .items(&labels)
+        .default(index_of(&push))
+        .interact()?;
+    let push = remotes[selection].clone();
+    let base = remote::default_base_remote(repo, trunk.unwrap_or(branch), &remotes, &push);
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("PR 要建立在哪個 remote 的 repository？")
+        .items(&labels)
+        .default(index_of(&base))
+        .interact()?;
+    Ok(remote::PushTarget {
+        push,
+        base: remotes[selection].clone(),
+    })
+}
+
+/// 推送目前分支（有多個 remote 時可選擇推送與 PR 目標的 remote），顯示建立 PR 的頁面（位於主幹時為 commit 頁面），
+/// 並依設定以瀏覽器開啟；目前分支為主幹或 remote 的預設分支時，需再次確認才推送
+fn push_and_link(repo: &Repository, config: &LlmConfig, forge: &dyn forge::Forge) -> Result<()> {
+    let branch = get_current_branch(repo)?;
+    let trunk = stack::detect_trunk(repo, None, config).ok();
+    let target = select_push_target(repo, &branch, trunk.as_deref())?;
+    let default_branch = remote::default_branch(repo, &target.push);
+    if trunk.as_deref() == Some(branch.as_str()) || default_branch.as_deref() == Some(branch.as_str()) {
+        let confirmed = Confirm::with_theme(plain::theme())
+            .with_prompt(format!("{} 是主幹分支，確定要直接推送到 {}？", branch, target.push))
+            .default(false)
+            .interact()?;
+        if !confirmed {
+            println!("{}", "已取消推送；可先建立分支再推送並建立 PR".dimmed());
+            return Ok(());
+        }
+    }
+    let output = Command::new("git")
+        .args(["push", "--quiet", "-u", &target.push, &branch])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .context("無法執行 git push")?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        println!("{}", format!("✗ 推送 {} 失敗：{}", branch, error.trim()).red());
+        return Ok(());
+    }
+    println!("{}", format!("✓ 已推送 {} 到 {}", branch, target.push).green());
+
+    // PR 建立在目標 remote 的 repository；從 fork 推送時以 fork 的 owner 指定來源分支
+    let base_forge = forge::from_remote(repo, config, &target.base);
+    let base_forge = base_forge.as_deref().unwrap_or(forge);
+    let from_fork = target.push != target.base;
+    let head = repo.head()?.peel_to_commit()?;
+    let pull_request_url = match &trunk {
+        Some(trunk) if *trunk != branch || from_fork => {
+            let message = encoding::message(&head);
+            let message = message.as_str();
+            let (title, body) = message.split_once('\n').unwrap_or((message, ""));
+            if from_fork {
+                forge::remote_owner(repo, &target.push).and_then(|owner| {
+                    base_forge.new_fork_pull_request_url(&owner, &branch, trunk, title.trim(), body.trim())
+                })
+            } else {
+                base_forge.new_pull_request_url(&branch, trunk, title.trim(), body.trim())
+            }
+        }
+        _ => None,
+    };
+    let url = pull_request_url.unwrap_or_else(|| {
+        let push_forge = forge::from_remote(repo, config, &target.push);
+        push_forge.as_deref().unwrap_or(forge).commit_url(&head.id().to_string())
+    });
+    println!("{}", format!("🔗 {}", url).cyan());
+
+    if config.forge.open_browser {
+        if let Err(e) = forge::open_in_browser(&url) {
+            println!("{}", format!("⚠️  {}", e).yellow());
+        }
+    }
+    Ok(())
+}
+
+/// 代入依工作描述建議分支名稱的提示詞模板變數
+fn fill_branch_prompt(
+    template: &str,
+    description: &str,
+    current_branch: &str,
+    existing_branches: &[String],
+    config: &LlmConfig,
+) -> String {
+    template
+        .replace("{description}", description)
+        .replace("{current_branch}", current_branch)
+        .replace("{existing_branches}", &format_existing_branches(existing_branches))
+        .replace("{branch_prefixes}", workflow::branch_prefixes(config.workflow))
+        .replace("{language}", &config.language)
+}
+
+/// 取出回應中看起來像分支名稱的行（含 / 且沒有空白），最多 3 個
+fn branch_lines(response: &str) -> Vec<String> {
+    response
+        .lines()
+        .map(|line| line.trim().trim_matches('`').to_string())
+        .filter(|line| line.contains('/') && !line.contains(' '))
+        .take(3)
+        .collect()
+}
+
+/// 依工作描述與提示詞模板請 LLM 建議分支名稱，失敗時改用描述轉換的名稱
+fn suggest_branches(
+    template: &str,
+    description: &str,
+    current_branch: &str,
+    existing_branches: &[String],
+    config: &LlmConfig,
+) -> Vec<String> {
+    let prompt = fill_branch_prompt(template, description, current_branch, existing_branches, config);
+
+    let mut timeline = Timeline::new();
+    timeline.start("呼叫 LLM 生成分支名稱建議");
+    let suggestions: Vec<String> = match call_llm_cli(&prompt, config) {
+        Ok(response) => {
+            timeline.finish();
+            branch_lines(&response)
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 呼叫失敗：{}", e).yellow());
+            Vec::new()
+        }
+    };
+
+    if !suggestions.is_empty() {
+        return suggestions;
+    }
+    slug_branch_suggestions(description)
+}
+
+/// 由描述轉換的分支名稱（無法轉換時使用備用建議）
+fn slug_branch_suggestions(description: &str) -> Vec<String> {
+    let slug = branch::slugify(description);
+    if slug.is_empty() {
+        generate_fallback_branch_suggestions(&[])
+    } else {
+        vec![format!("feature/{}", slug)]
+    }
+}
+
+/// 單次 commit 的覆寫選項
+#[derive(Debug, Default)]
+struct CommitOptions {
+    /// 作者身分（None 表示使用 git 設定）
+    identity: Option<Identity>,
+    /// 作者與 committer 時間（None 表示現在）
+    date: Option<DateTime<Local>>,
+    /// rebase 中保留原作者的環境變數（GIT_AUTHOR_NAME 等）
+    author_env: Vec<(String, String)>,
+}
+
+/// commit 流程的結果
+enum CommitOutcome {
+    Committed(Vec<CommitSummary>),
+    /// 以 --export-patch 匯出的 patch 檔案
+    Exported(PathBuf),
+    Cancelled,
+}
+
+impl From<Vec<CommitSummary>> for CommitOutcome {
+    fn from(summaries: Vec<CommitSummary>) -> Self {
+        if summaries.is_empty() {
+            CommitOutcome::Cancelled
+        } else {
+            CommitOutcome::Committed(summaries)
+        }
+    }
+}
+
+/// 已建立的 commit 摘要
+#[derive(Debug, Clone, Serialize)]
+struct CommitSummary {
+    sha: String,
+    branch: String,
+    /// commit 訊息的第一行（hooks 可能改寫訊息，因此從實際的 commit 讀取）
+    subject: String,
+    /// 變更的檔案路徑
+    files: Vec<String>,
+    files_changed: usize,
+    insertions: usize,
+    deletions: usize,
+}
+
+impl CommitSummary {
+    /// 讀取 HEAD 指向的 commit 與其相對於 parent 的變更統計
+    fn read_head(repo: &Repository) -> Result<Self> {
+        let head = repo.head().context("無法讀取 HEAD")?;
+        let commit = head.peel_to_commit().context("無法讀取新的 commit")?;
+        let tree = commit.tree()?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        let stats = diff.stats()?;
+        let files = diff
+            .deltas()
+            .filter_map(|delta| delta.new_file().path().or_else(|| delta.old_file().path()))
+            .map(|path| path.display().to_string())
+            .collect();
+
+        Ok(Self {
+            sha: commit.id().to_string(),
+            branch: head.shorthand().unwrap_or("HEAD").to_string(),
+            subject: encoding::subject(&commit),
+            files,
+            files_changed: stats.files_changed(),
+            insertions: stats.insertions(),
+            deletions: stats.deletions(),
+        })
+    }
+
+    /// Conventional Commits 的 type（例如 feat、fix），不符合格式時為 None
+    fn commit_type(&self) -> Option<&str> {
+        conventional_type(&self.subject)
+    }
+
+    fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+}
+
+/// commit 訊息第一行的 Conventional Commits type（例如 feat、fix），不符合格式時為 None
+fn conventional_type(message: &str) -> Option<&str> {
+    let (prefix, _) = message.lines().next()?.split_once(':')?;
+    let commit_type = prefix.split('(').next()?.trim_end_matches('!').trim();
+    let valid = !commit_type.is_empty() && commit_type.chars().all(|c| c.is_ascii_alphanumeric());
+    valid.then_some(commit_type)
+}
+
+/// 執行 git commit（可指定作者身分與時間），回傳新 commit 的摘要
+///
+/// 子程序直接繼承終端機的 stdout/stderr，讓 hooks（例如執行測試的 pre-commit）
+/// 的輸出與顏色即時顯示，而不是等到結束才一次輸出。
+fn commit_changes(repo: &Repository, message: &str, options: &CommitOptions) -> Result<CommitSummary> {
+    let mut cmd = Command::new("git");
+    cmd.envs(options.author_env.iter().map(|(key, value)| (key, value)));
+    if let Some(date) = &options.date {
+        let git_date = date::to_git_date(date);
+        cmd.env("GIT_AUTHOR_DATE", &git_date)
+            .env("GIT_COMMITTER_DATE", &git_date);
+    }
+    if let Some(identity) = &options.identity {
+        // 同時覆寫作者與 committer，只影響這次 commit
+        cmd.arg("-c")
+            .arg(format!("user.name={}", identity.name))
+            .arg("-c")
+            .arg(format!("user.email={}", identity.email));
+    }
+
+    let encoded = encoding::encode(message);
+    encoding::check_display(message, &encoded);
+    // 轉換編碼後的內容不一定是合法的命令列參數，改寫入檔案以 -F 傳入
+    let message_file = repo.path().join("AUTO_COMMIT_MSG");
+    match &encoded {
+        encoding::Encoded::Utf8 => cmd.args(["commit", "--quiet", "-m", message]),
+        encoding::Encoded::Fallback => cmd.args(["-c", "i18n.commitEncoding=UTF-8", "commit", "--quiet", "-m", message]),
+        encoding::Encoded::Converted(_, bytes) => {
+            fs::write(&message_file, bytes).context("無法寫入 commit 訊息檔")?;
+            cmd.args(["commit", "--quiet", "-F"]).arg(&message_file)
+        }
+    };
+
+    let status = cmd
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status();
+    if matches!(encoded, encoding::Encoded::Converted(..)) {
+        let _ = fs::remove_file(&message_file);
+    }
+    let status = status.context("無法執行 git commit")?;
+
+    if status.success() {
+        let summary = CommitSummary::read_head(repo)?;
+        println!(
+            "{} {}",
+            "✓ Commit 成功！".green(),
+            format!("{}（{}）", summary.short_sha(), summary.branch).bold()
+        );
+        println!("{}", format!("  訊息：{}", message).dimmed());
+        println!(
+            "{}",
+            format!(
+                "  {} 個檔案變更，+{} -{}",
+                summary.files_changed, summary.insertions, summary.deletions
+            )
+            .dimmed()
+        );
+        Ok(summary)
+    } else {
+        let code = status
+            .code()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "無".to_string());
+        println!("{}", format!("✗ Commit 失敗（結束代碼：{}），請參考上方輸出", code).red());
         anyhow::bail!("Commit 失敗")
     }
 }
@@ -0,0 +1,174 @@
+use crate::conflict::{self, Resolution};
+use crate::encoding;
+use crate::GitSuggestions;
+use colored::*;
+use git2::{Oid, Repository, RepositoryState};
+use regex::Regex;
+use std::fs;
+use std::sync::LazyLock;
+
+/// 訊息中每個分支最多列出的 commit 數
+const MAX_SUBJECTS: usize = 20;
+
+/// git 預設合併訊息的第一行（例如 Merge branch 'feature/login' of github.com:org/repo）
+static MERGE_SUBJECT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^Merge (?:remote-tracking branch|branch|tag|commit) '([^']+)'").unwrap());
+
+/// 進行中的合併
+pub struct MergeInfo {
+    /// 目前分支
+    pub ours: String,
+    /// 被合併的分支（取自 git 預設的合併訊息，否則為 commit 的短 SHA）
+    pub theirs: String,
+    /// git 預設訊息的第一行
+    subject: String,
+    /// 被合併進來的 commit 標題（由舊到新）
+    their_subjects: Vec<String>,
+    /// 目前分支在分岔後的 commit 標題（由舊到新）
+    our_subjects: Vec<String>,
+}
+
+/// 列出 include 可到達、exclude 不可到達的非合併 commit 標題（由舊到新）
+fn subjects(repo: &Repository, include: &[Oid], exclude: &[Oid]) -> Vec<String> {
+    let Ok(mut walk) = repo.revwalk() else {
+        return Vec::new();
+    };
+    for oid in include {
+        let _ = walk.push(*oid);
+    }
+    for oid in exclude {
+        let _ = walk.hide(*oid);
+    }
+    let mut subjects: Vec<String> = walk
+        .filter_map(|oid| repo.find_commit(oid.ok()?).ok())
+        .filter(|commit| commit.parent_count() <= 1)
+        .map(|commit| encoding::subject(&commit))
+        .filter(|subject| !subject.is_empty())
+        .collect();
+    subjects.reverse();
+    subjects
+}
+
+/// 偵測進行中的合併（MERGE_HEAD 存在），收集雙方的 commit
+pub fn detect(repo: &Repository, current_branch: &str) -> Option<MergeInfo> {
+    if repo.state() != RepositoryState::Merge {
+        return None;
+    }
+    let head = repo.head().ok()?.target()?;
+    let merge_heads: Vec<Oid> = fs::read_to_string(repo.path().join("MERGE_HEAD"))
+        .ok()?
+        .lines()
+        .filter_map(|line| Oid::from_str(line.trim()).ok())
+        .collect();
+    let first = *merge_heads.first()?;
+
+    let default_message = fs::read_to_string(repo.path().join("MERGE_MSG")).unwrap_or_default();
+    let subject = default_message.lines().next().unwrap_or("").trim().to_string();
+    let theirs = MERGE_SUBJECT
+        .captures(&subject)
+        .map(|caps| caps[1].to_string())
+        .unwrap_or_else(|| first.to_string()[..7].to_string());
+    let subject = if subject.is_empty() {
+        format!("Merge commit '{}'", theirs)
+    } else {
+        subject
+    };
+
+    Some(MergeInfo {
+        ours: current_branch.to_string(),
+        theirs,
+        subject,
+        their_subjects: subjects(repo, &merge_heads, &[head]),
+        our_subjects: subjects(repo, &[head], &merge_heads),
+    })
+}
+
+/// 以「- 標題」列出 commit，超過上限時註明省略的數量
+fn list(subjects: &[String]) -> String {
+    let mut lines: Vec<String> = subjects
+        .iter()
+        .take(MAX_SUBJECTS)
+        .map(|subject| format!("- {}", subject))
+        .collect();
+    if subjects.len() > MAX_SUBJECTS {
+        lines.push(format!("- …另外 {} 個 commit", subjects.len() - MAX_SUBJECTS));
+    }
+    lines.join("\n")
+}
+
+impl MergeInfo {
+    /// 顯示合併的摘要
+    pub fn print(&self) {
+        println!(
+            "{}",
+            format!(
+                "🔀 合併進行中：{} → {}（{} 個 commit）",
+                self.theirs,
+                self.ours,
+                self.their_subjects.len()
+            )
+            .blue()
+        );
+        println!();
+    }
+
+    /// 標題：git 預設的第一行，沒有「into <分支>」時補上
+    fn title(&self) -> String {
+        if self.subject.contains(" into ") {
+            self.subject.clone()
+        } else {
+            format!("{} into {}", self.subject, self.ours)
+        }
+    }
+
+    /// 詳細訊息：雙方的 commit 與衝突的解決方式
+    fn detailed_message(&self, resolutions: &[Resolution]) -> String {
+        let mut sections = vec![self.title()];
+        if !self.their_subjects.is_empty() {
+            sections.push(format!(
+                "合併 {} 的 {} 個 commit：\n{}",
+                self.theirs,
+                self.their_subjects.len(),
+                list(&self.their_subjects)
+            ));
+        }
+        if !self.our_subjects.is_empty() {
+            sections.push(format!(
+                "{} 在分岔後的 {} 個 commit：\n{}",
+                self.ours,
+                self.our_subjects.len(),
+                list(&self.our_subjects)
+            ));
+        }
+        if !resolutions.is_empty() {
+            sections.push(conflict::section(resolutions));
+        }
+        sections.join("\n\n")
+    }
+
+    /// 簡短訊息：標題與衝突的解決方式
+    fn short_message(&self, resolutions: &[Resolution]) -> String {
+        if resolutions.is_empty() {
+            self.title()
+        } else {
+            format!("{}\n\n{}", self.title(), conflict::section(resolutions))
+        }
+    }
+
+    /// 合併 commit 的訊息建議（不切換分支，因此沒有分支建議）
+    pub fn suggestions(&self, resolutions: &[Resolution]) -> GitSuggestions {
+        let mut commit_messages = vec![self.detailed_message(resolutions)];
+        let short = self.short_message(resolutions);
+        if !commit_messages.contains(&short) {
+            commit_messages.push(short);
+        }
+        GitSuggestions {
+            branch_names: Vec::new(),
+            commit_messages,
+            commit_notes: Vec::new(),
+            commit_labels: Vec::new(),
+            analysis: None,
+            review: None,
+        }
+    }
+}
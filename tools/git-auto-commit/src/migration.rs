@@ -0,0 +1,235 @@
+use colored::*;
+use git2::Repository;
+use regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// 資料庫 migration 工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+    Diesel,
+    Sqlx,
+    Flyway,
+}
+
+impl Framework {
+    fn name(self) -> &'static str {
+        match self {
+            Framework::Diesel => "diesel",
+            Framework::Sqlx => "sqlx",
+            Framework::Flyway => "flyway",
+        }
+    }
+}
+
+/// staged 變更中的一個 migration
+pub struct Migration {
+    /// migration 名稱（例如 20240101_add_users）
+    pub name: String,
+    pub framework: Framework,
+    /// 從 SQL 解析出的 schema 操作（例如 create table users）
+    pub operations: Vec<String>,
+}
+
+static SQLX_FILE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\d+_.+\.sql$").unwrap());
+static FLYWAY_FILE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^([VRU])\d[\d._]*__.+\.sql$").unwrap());
+
+static CREATE_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^create\s+(?:temporary\s+)?table\s+(?:if\s+not\s+exists\s+)?(\S+?)\s*\(").unwrap());
+static DROP_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^drop\s+table\s+(?:if\s+exists\s+)?(\S+)").unwrap());
+static CREATE_INDEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^create\s+(unique\s+)?index\s+(?:concurrently\s+)?(?:if\s+not\s+exists\s+)?(\S+)\s+on\s+(?:only\s+)?([^\s(]+)").unwrap()
+});
+static DROP_INDEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^drop\s+index\s+(?:concurrently\s+)?(?:if\s+exists\s+)?(\S+)").unwrap());
+static ALTER_TABLE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^alter\s+table\s+(?:if\s+exists\s+)?(?:only\s+)?(\S+)\s+(.+)$").unwrap());
+static ADD_CONSTRAINT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^add\s+(?:constraint|primary|foreign|unique|check|index|key)\b").unwrap());
+static ADD_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^add\s+(?:column\s+)?(?:if\s+not\s+exists\s+)?(\S+)").unwrap());
+static DROP_CONSTRAINT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^drop\s+constraint\s+(?:if\s+exists\s+)?(\S+)").unwrap());
+static DROP_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^drop\s+(?:column\s+)?(?:if\s+exists\s+)?(\S+)").unwrap());
+static RENAME_TABLE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)^rename\s+to\s+(\S+)").unwrap());
+static RENAME_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^rename\s+(?:column\s+)?(\S+)\s+to\s+(\S+)").unwrap());
+static ALTER_COLUMN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(?:alter|modify|change)\s+(?:column\s+)?(\S+)").unwrap());
+
+/// 依路徑判斷 migration 名稱與工具；回傳的 bool 表示是否為還原用的 down 檔案
+fn classify(path: &str) -> Option<(String, Framework, bool)> {
+    let path = Path::new(path);
+    let file_name = path.file_name()?.to_str()?;
+    let parent = path.parent();
+    let parent_name = parent.and_then(|p| p.file_name()).and_then(|n| n.to_str());
+    let grandparent_name = parent
+        .and_then(|p| p.parent())
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str());
+
+    // diesel：migrations/<名稱>/up.sql、down.sql
+    if matches!(file_name, "up.sql" | "down.sql") && grandparent_name == Some("migrations") {
+        return Some((parent_name?.to_string(), Framework::Diesel, file_name == "down.sql"));
+    }
+
+    // flyway：V1__init.sql、R__views.sql、U1__init.sql（undo）
+    if let Some(caps) = FLYWAY_FILE.captures(file_name) {
+        let name = file_name.trim_end_matches(".sql").to_string();
+        return Some((name, Framework::Flyway, &caps[1] == "U"));
+    }
+
+    // sqlx：migrations/<時間戳>_<名稱>.sql 或 .up.sql、.down.sql
+    if parent_name == Some("migrations") && SQLX_FILE.is_match(file_name) {
+        let stem = file_name.trim_end_matches(".sql");
+        let is_down = stem.ends_with(".down");
+        let name = stem.trim_end_matches(".down").trim_end_matches(".up").to_string();
+        return Some((name, Framework::Sqlx, is_down));
+    }
+
+    None
+}
+
+/// 以最外層的逗號切開 ALTER TABLE 的多個動作
+fn split_actions(actions: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in actions.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(actions[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(actions[start..].trim());
+    parts
+}
+
+fn alter_operations(table: &str, actions: &str) -> Vec<String> {
+    split_actions(actions)
+        .into_iter()
+        .filter_map(|action| {
+            if ADD_CONSTRAINT.is_match(action) {
+                Some(format!("add constraint on {}", table))
+            } else if let Some(caps) = ADD_COLUMN.captures(action) {
+                Some(format!("add column {}.{}", table, &caps[1]))
+            } else if let Some(caps) = DROP_CONSTRAINT.captures(action) {
+                Some(format!("drop constraint {} on {}", &caps[1], table))
+            } else if let Some(caps) = RENAME_TABLE.captures(action) {
+                Some(format!("rename table {} to {}", table, &caps[1]))
+            } else if let Some(caps) = RENAME_COLUMN.captures(action) {
+                Some(format!("rename column {}.{} to {}", table, &caps[1], &caps[2]))
+            } else if let Some(caps) = DROP_COLUMN.captures(action) {
+                Some(format!("drop column {}.{}", table, &caps[1]))
+            } else {
+                ALTER_COLUMN
+                    .captures(action)
+                    .map(|caps| format!("alter column {}.{}", table, &caps[1]))
+            }
+        })
+        .collect()
+}
+
+/// 從 SQL 內容解析 schema 操作（去除註解後逐一分析每個陳述式）
+fn parse_operations(sql: &str) -> Vec<String> {
+    let without_comments: String = sql
+        .lines()
+        .map(|line| line.split("--").next().unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let mut operations = Vec::new();
+    for statement in without_comments.split(';') {
+        // 去除識別字的引號（"users"、`users`），並將空白正規化
+        let statement = statement.replace(['"', '`'], "").split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if let Some(caps) = CREATE_TABLE.captures(&statement) {
+            operations.push(format!("create table {}", &caps[1]));
+        } else if let Some(caps) = DROP_TABLE.captures(&statement) {
+            operations.push(format!("drop table {}", &caps[1]));
+        } else if let Some(caps) = CREATE_INDEX.captures(&statement) {
+            let kind = if caps.get(1).is_some() { "unique index" } else { "index" };
+            operations.push(format!("create {} {} on {}", kind, &caps[2], &caps[3]));
+        } else if let Some(caps) = DROP_INDEX.captures(&statement) {
+            operations.push(format!("drop index {}", &caps[1]));
+        } else if let Some(caps) = ALTER_TABLE.captures(&statement) {
+            operations.extend(alter_operations(&caps[1], &caps[2]));
+        }
+    }
+    operations
+}
+
+/// 讀取 index 中（staged）的檔案內容
+fn staged_content(repo: &Repository, path: &str) -> Option<String> {
+    let index = repo.index().ok()?;
+    let entry = index.get_path(Path::new(path), 0)?;
+    let blob = repo.find_blob(entry.id).ok()?;
+    Some(String::from_utf8_lossy(blob.content()).to_string())
+}
+
+/// 找出 staged 檔案中的 migration，並從 up 檔案解析 schema 操作
+pub fn detect(repo: &Repository, files: &[String]) -> Vec<Migration> {
+    let mut migrations: Vec<Migration> = Vec::new();
+    for path in files {
+        let Some((name, framework, is_down)) = classify(path) else {
+            continue;
+        };
+        let operations = if is_down {
+            Vec::new()
+        } else {
+            staged_content(repo, path).map(|sql| parse_operations(&sql)).unwrap_or_default()
+        };
+
+        match migrations.iter_mut().find(|m| m.name == name) {
+            Some(migration) => migration.operations.extend(operations),
+            None => migrations.push(Migration { name, framework, operations }),
+        }
+    }
+    migrations
+}
+
+/// 提供給提示詞的摘要
+pub fn summary(migrations: &[Migration]) -> String {
+    let mut lines = vec!["這次變更包含資料庫 migration（commit 訊息應說明 schema 的變化）：".to_string()];
+    for migration in migrations {
+        lines.push(format!("- {}（{}）", migration.name, migration.framework.name()));
+        for operation in &migration.operations {
+            lines.push(format!("  - {}", operation));
+        }
+    }
+    lines.join("\n")
+}
+
+/// 顯示偵測到的 migration 與 schema 操作
+pub fn print(migrations: &[Migration]) {
+    println!("{}", "🗃️  偵測到資料庫 migration：".blue());
+    for migration in migrations {
+        println!("  {} {}", migration.name.bold(), format!("（{}）", migration.framework.name()).dimmed());
+        if migration.operations.is_empty() {
+            println!("    {}", "（未解析到 schema 操作）".dimmed());
+        }
+        for operation in &migration.operations {
+            println!("    {}", operation.dimmed());
+        }
+    }
+}
+
+/// 在 commit 訊息最後加上 Migration: 註記（已有相同註記者略過）
+pub fn with_footer(message: &str, migrations: &[Migration]) -> String {
+    let footers: Vec<String> = migrations
+        .iter()
+        .map(|m| format!("Migration: {}", m.name))
+        .filter(|footer| !message.lines().any(|line| line.trim() == footer))
+        .collect();
+    if footers.is_empty() {
+        return message.to_string();
+    }
+    format!("{}\n\n{}", message.trim_end(), footers.join("\n"))
+}
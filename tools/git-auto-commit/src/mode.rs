@@ -0,0 +1,132 @@
+use crate::plain;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{MultiSelect, Select};
+use git_tools_core::git::git;
+use std::process::Command;
+
+const REGULAR: &str = "100644";
+const EXECUTABLE: &str = "100755";
+
+/// staged 檔案的權限（mode）變更
+#[derive(Debug, Clone)]
+pub struct ModeChange {
+    pub path: String,
+    old_mode: String,
+    new_mode: String,
+    /// 內容沒有變更，只有權限不同
+    pub mode_only: bool,
+}
+
+impl ModeChange {
+    /// 一般檔案與可執行檔之間的切換（可用 git update-index --chmod 還原）
+    fn is_executable_toggle(&self) -> bool {
+        matches!((self.old_mode.as_str(), self.new_mode.as_str()), (REGULAR, EXECUTABLE) | (EXECUTABLE, REGULAR))
+    }
+
+    /// 例如「100644 → 100755（新增執行權限）」
+    pub fn describe(&self) -> String {
+        let meaning = match (self.old_mode.as_str(), self.new_mode.as_str()) {
+            (REGULAR, EXECUTABLE) => "新增執行權限",
+            (EXECUTABLE, REGULAR) => "移除執行權限",
+            _ => "檔案類型變更",
+        };
+        let only = if self.mode_only { "，內容未變更" } else { "" };
+        format!("{} → {}（{}{}）", self.old_mode, self.new_mode, meaning, only)
+    }
+}
+
+/// 從 git diff --cached --raw 找出權限有變更的檔案（不含新增與刪除的檔案）
+pub fn detect() -> Result<Vec<ModeChange>> {
+    let output = git(&["diff", "--cached", "--raw", "--no-abbrev", "--no-renames"])?;
+    // :100644 100755 <舊 blob> <新 blob> M\t<路徑>
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let (meta, path) = line.strip_prefix(':')?.split_once('\t')?;
+            let fields: Vec<&str> = meta.split_whitespace().collect();
+            let [old_mode, new_mode, old_blob, new_blob, _status] = fields[..] else {
+                return None;
+            };
+            let added_or_deleted = old_mode == "000000" || new_mode == "000000";
+            (old_mode != new_mode && !added_or_deleted).then(|| ModeChange {
+                path: path.to_string(),
+                old_mode: old_mode.to_string(),
+                new_mode: new_mode.to_string(),
+                mode_only: old_blob == new_blob,
+            })
+        })
+        .collect())
+}
+
+/// 顯示權限變更的檔案
+pub fn print(changes: &[ModeChange]) {
+    println!("{}", "🔐 檔案權限變更：".blue());
+    for change in changes {
+        println!("  {}  {}", change.path, change.describe().dimmed());
+    }
+    println!();
+}
+
+/// 給 LLM 的權限變更摘要（diff 中只有 old mode / new mode 兩行，容易被忽略）
+pub fn prompt_note(changes: &[ModeChange]) -> String {
+    let lines: Vec<String> = changes
+        .iter()
+        .map(|change| format!("- {}：{}", change.path, change.describe()))
+        .collect();
+    format!(
+        "檔案權限變更（例如腳本新增執行權限；內容未變更的檔案在 diff 中只有 mode 行，commit 訊息應提及）：\n{}",
+        lines.join("\n")
+    )
+}
+
+/// 詢問是否還原只有權限變更的檔案（只還原 index，不修改工作目錄），回傳已還原的路徑
+pub fn offer_revert(changes: &[ModeChange]) -> Result<Vec<String>> {
+    let candidates: Vec<&ModeChange> = changes.iter().filter(|c| c.mode_only && c.is_executable_toggle()).collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let items = [
+        "保留權限變更".to_string(),
+        format!("還原只有權限變更的 {} 個檔案", candidates.len()),
+        "逐一選擇要還原的檔案".to_string(),
+    ];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("權限變更是否為預期的？（例如在 Windows 掛載的目錄中權限可能被意外改變）")
+        .items(&items)
+        .default(0)
+        .interact()?;
+    let chosen: Vec<&ModeChange> = match selection {
+        1 => candidates,
+        2 => {
+            let labels: Vec<String> = candidates.iter().map(|c| format!("{}  {}", c.path, c.describe())).collect();
+            let picked = MultiSelect::with_theme(plain::theme())
+                .with_prompt("選擇要還原的檔案（空白鍵選取，Enter 確認）")
+                .items(&labels)
+                .interact()?;
+            picked.into_iter().map(|i| candidates[i]).collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let mut reverted = Vec::new();
+    for change in chosen {
+        let flag = if change.old_mode == EXECUTABLE { "--chmod=+x" } else { "--chmod=-x" };
+        let status = Command::new("git")
+            .args(["update-index", flag, "--", &change.path])
+            .status()
+            .context("無法執行 git update-index")?;
+        if status.success() {
+            reverted.push(change.path.clone());
+        } else {
+            println!("{}", format!("⚠️  無法還原 {} 的權限", change.path).yellow());
+        }
+    }
+    if !reverted.is_empty() {
+        println!("{}", format!("✓ 已將 {} 個檔案的權限還原為 HEAD 的設定（工作目錄不受影響）", reverted.len()).green());
+        println!("{}", "  若不需要追蹤權限變更，可執行 git config core.fileMode false".dimmed());
+        println!();
+    }
+    Ok(reverted)
+}
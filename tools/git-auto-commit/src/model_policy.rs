@@ -0,0 +1,36 @@
+use crate::config::ModelSelectionConfig;
+
+/// 依變更大小選擇的模型
+pub struct Selection {
+    pub model: String,
+    /// 「小型變更」或「大型變更」
+    pub size: &'static str,
+    pub lines: usize,
+    pub files: usize,
+}
+
+/// diff 中新增與刪除的行數（不含檔頭）
+fn changed_lines(diff: &str) -> usize {
+    diff.lines()
+        .filter(|line| !line.starts_with("+++") && !line.starts_with("---"))
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .count()
+}
+
+/// 依 diff 大小選擇模型；介於兩個門檻之間或未設定對應的模型時回傳 None（使用 model）
+pub fn select(config: &ModelSelectionConfig, diff: &str, files: usize) -> Option<Selection> {
+    let lines = changed_lines(diff);
+    let (size, model) = if lines >= config.large_min_lines || files >= config.large_min_files {
+        ("大型變更", config.large_model.as_ref()?)
+    } else if lines <= config.small_max_lines {
+        ("小型變更", config.small_model.as_ref()?)
+    } else {
+        return None;
+    };
+    Some(Selection {
+        model: model.clone(),
+        size,
+        lines,
+        files,
+    })
+}
@@ -0,0 +1,76 @@
+use crate::cli::BranchArgs;
+use crate::compliance;
+use crate::config::{load_llm_config, CoverageMode};
+use crate::progress::Timeline;
+use crate::{branch, plain, PromptContext};
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Input;
+use git2::Repository;
+use git_tools_core::git::{get_current_branch, get_staged_files};
+use std::env;
+
+/// branch 子指令：依工作描述或 staged 變更建議分支名稱，讓使用者選擇後建立並切換（不建立 commit）
+pub fn run(args: &BranchArgs) -> Result<()> {
+    println!("\n{}\n", "🌿 建立分支".cyan().bold());
+
+    let current_dir = env::current_dir().context("無法取得當前目錄")?;
+    let repo = Repository::open(&current_dir).context("✗ 錯誤：當前目錄不是 Git repository")?;
+    let current_branch = get_current_branch(&repo)?;
+
+    let config = load_llm_config();
+    let compliance = compliance::Filter::new(&config.compliance);
+    let existing_branches = branch::existing_branch_names(&repo);
+    let staged_files = get_staged_files(&repo)?;
+
+    let suggest = |description: &str| {
+        if args.offline {
+            crate::slug_branch_suggestions(description)
+        } else {
+            crate::suggest_branches(&config.branch_prompt, description, &current_branch, &existing_branches, &config)
+        }
+    };
+
+    let description = args.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+    let (suggestions, description) = match description {
+        Some(description) => (suggest(description), Some(description.to_string())),
+        None if !staged_files.is_empty() => {
+            println!("{}", format!("依 {} 個 staged 檔案的變更建議分支名稱", staged_files.len()).dimmed());
+            let suggestions = if args.offline {
+                crate::generate_fallback_branch_suggestions(&staged_files)
+            } else {
+                // 只需要分支名稱，不檢查 commit 建議的內容
+                let mut config = config.clone();
+                config.coverage.mode = CoverageMode::Off;
+                let diff = crate::get_staged_diff(&repo, &[])?;
+                let context = PromptContext {
+                    existing_branches: existing_branches.clone(),
+                    description: None,
+                    change_notes: Vec::new(),
+                    examples: Vec::new(),
+                };
+                crate::generate_suggestions(&diff, &staged_files, &context, &config, &mut Timeline::new(), None).branch_names
+            };
+            (suggestions, None)
+        }
+        None => {
+            let description: String = Input::with_theme(plain::theme())
+                .with_prompt("要進行的工作描述（任何語言皆可）")
+                .interact_text()?;
+            let description = description.trim().to_string();
+            (suggest(&description), Some(description))
+        }
+    };
+
+    crate::select_and_switch_branch(
+        &repo,
+        &config,
+        args.ticket.as_deref(),
+        &compliance,
+        suggestions,
+        description.as_deref(),
+        true,
+    )?;
+    println!();
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use crate::config::NewFilesConfig;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{MultiSelect, Select};
+use git2::{Repository, Status, StatusOptions, StatusShow};
+use git_tools_core::git::file_blobs;
+use git_tools_core::{plain, text};
+use std::process::Command;
+
+/// 列出未追蹤的檔案時最多顯示的數量
+const MAX_LISTED: usize = 20;
+
+/// 未追蹤的檔案（不含 .gitignore 忽略的檔案）；指定 pathspecs 時只掃描符合的路徑。
+/// 掃描時順便更新 index 中的檔案狀態快取（同 git status），之後的掃描不必重新比對未變更檔案的內容
+pub fn untracked(repo: &Repository, pathspecs: &[String]) -> Result<Vec<String>> {
+    let mut opts = StatusOptions::new();
+    opts.show(StatusShow::Workdir)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .update_index(true);
+    for pathspec in pathspecs {
+        opts.pathspec(pathspec);
+    }
+    let statuses = repo.statuses(Some(&mut opts))?;
+    Ok(statuses
+        .iter()
+        .filter(|entry| entry.status() == Status::WT_NEW)
+        .filter_map(|entry| entry.path().map(str::to_string))
+        .collect())
+}
+
+/// 顯示未追蹤的檔案
+pub fn print(files: &[String]) {
+    println!("{}", format!("🆕 {} 個未追蹤的檔案（不會被 commit）：", files.len()).yellow());
+    for file in files.iter().take(MAX_LISTED) {
+        println!("  {}", file.dimmed());
+    }
+    if files.len() > MAX_LISTED {
+        println!("{}", format!("  …還有 {} 個檔案", files.len() - MAX_LISTED).dimmed());
+    }
+    println!();
+}
+
+/// 詢問是否將未追蹤的檔案完整加入 staged（git add）或只標記為 intent-to-add（git add -N），可逐一選擇檔案
+pub fn offer_add(files: &[String]) -> Result<()> {
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("要如何處理這些檔案？")
+        .items(&[
+            "維持未追蹤",
+            "加入 staged，一起 commit（git add）",
+            "標記為 intent-to-add（git add -N）：這次不 commit，之後可用 git add -p 或 -a 加入",
+        ])
+        .default(0)
+        .interact()?;
+    if selection == 0 {
+        return Ok(());
+    }
+
+    let chosen: Vec<&String> = if files.len() == 1 {
+        files.iter().collect()
+    } else {
+        let picked = MultiSelect::with_theme(plain::theme())
+            .with_prompt("選擇檔案（空白鍵選取，Enter 確認）")
+            .items(files)
+            .defaults(&vec![true; files.len()])
+            .interact()?;
+        picked.into_iter().map(|i| &files[i]).collect()
+    };
+    if chosen.is_empty() {
+        println!();
+        return Ok(());
+    }
+
+    let intent_to_add = selection == 2;
+    let status = Command::new("git")
+        .arg("add")
+        .args(if intent_to_add { &["-N"][..] } else { &[][..] })
+        .arg("--")
+        .args(&chosen)
+        .status()
+        .context("無法執行 git add")?;
+    if !status.success() {
+        anyhow::bail!("git add 執行失敗");
+    }
+    if intent_to_add {
+        println!("{}", format!("✓ 已將 {} 個檔案標記為 intent-to-add（這次不會 commit）", chosen.len()).green());
+    } else {
+        println!("{}", format!("✓ 已將 {} 個檔案加入 staged", chosen.len()).green());
+    }
+    println!();
+    Ok(())
+}
+
+/// staged 的新檔案內容（每個檔案依上限截斷，略過二進位檔），作為提示詞的補充；沒有新檔案時為 None
+pub fn prompt_note(repo: &Repository, files: &[String], config: &NewFilesConfig) -> Option<String> {
+    let mut sections = Vec::new();
+    for path in files {
+        if sections.len() >= config.max_files {
+            break;
+        }
+        let (None, Some(content)) = file_blobs(repo, path) else {
+            continue;
+        };
+        if content.contains(&0) {
+            continue;
+        }
+        let content = String::from_utf8_lossy(&content);
+        let length = content.chars().count();
+        let shown = if length > config.max_chars {
+            format!("{}\n…（已截斷，共 {} 字元）", text::head(&content, config.max_chars), length)
+        } else {
+            content.trim_end().to_string()
+        };
+        sections.push(format!("--- {} ---\n{}", path, shown));
+    }
+    (!sections.is_empty()).then(|| {
+        format!(
+            "新增的檔案內容（新檔案通常說明了這次變更的目的，撰寫訊息時請優先參考）：\n{}",
+            sections.join("\n\n")
+        )
+    })
+}
@@ -0,0 +1,94 @@
+use git2::{DiffOptions, Patch, Repository};
+use git_tools_core::git::file_versions;
+use serde_json::Value;
+use std::path::Path;
+
+/// 將 notebook 轉為只含 cell 原始碼的文字（類似 jupytext 的 percent 格式），去除輸出、執行次數與 metadata
+fn cells_text(content: &str) -> Option<String> {
+    if content.is_empty() {
+        return Some(String::new());
+    }
+    let notebook: Value = serde_json::from_str(content).ok()?;
+    let cells = notebook.get("cells")?.as_array()?;
+
+    let mut text = String::new();
+    for (i, cell) in cells.iter().enumerate() {
+        let cell_type = cell.get("cell_type").and_then(Value::as_str).unwrap_or("code");
+        let source = match cell.get("source") {
+            Some(Value::Array(lines)) => lines.iter().filter_map(Value::as_str).collect::<String>(),
+            Some(Value::String(source)) => source.clone(),
+            _ => String::new(),
+        };
+        text.push_str(&format!("# %% [{}] cell {}\n", cell_type, i + 1));
+        text.push_str(&source);
+        if !source.ends_with('\n') {
+            text.push('\n');
+        }
+    }
+    Some(text)
+}
+
+/// 以 cell 原始碼重新產生單一 notebook 的 diff 內容（不含檔頭）
+fn notebook_hunks(repo: &Repository, path: &str) -> Option<String> {
+    let (old, new) = file_versions(repo, path);
+    let (old, new) = (cells_text(&old)?, cells_text(&new)?);
+    if old == new {
+        return Some("（只有輸出、執行次數或 metadata 變更）\n".to_string());
+    }
+
+    let mut options = DiffOptions::new();
+    options.context_lines(3);
+    let mut patch = Patch::from_buffers(
+        old.as_bytes(),
+        Some(Path::new(path)),
+        new.as_bytes(),
+        Some(Path::new(path)),
+        Some(&mut options),
+    )
+    .ok()?;
+    let buf = patch.to_buf().ok()?;
+    let text = String::from_utf8_lossy(&buf);
+    let hunks: Vec<&str> = text.lines().skip_while(|line| !line.starts_with("@@")).collect();
+    Some(format!("{}\n", hunks.join("\n")))
+}
+
+/// 將 staged diff 中 .ipynb 檔案的區段改為只比對 cell 原始碼（類似 nbdime），避免輸出（例如 base64 圖片）塞滿提示詞；
+/// 無法解析的 notebook 維持原樣
+pub fn normalize_diff(repo: &Repository, diff: &str) -> String {
+    let mut result = String::with_capacity(diff.len());
+    let mut sections = diff.split_inclusive('\n').peekable();
+
+    while let Some(line) = sections.next() {
+        result.push_str(line);
+        if !line.starts_with("diff --git ") {
+            continue;
+        }
+
+        // 收集這個檔案的區段
+        let mut section: Vec<&str> = Vec::new();
+        while let Some(next) = sections.next_if(|l| !l.starts_with("diff --git ")) {
+            section.push(next);
+        }
+
+        let path = section
+            .iter()
+            .take_while(|l| !l.starts_with("@@"))
+            .filter_map(|l| l.strip_prefix("+++ ").or_else(|| l.strip_prefix("--- ")))
+            .map(str::trim_end)
+            .find(|p| *p != "/dev/null");
+        let hunks = path
+            .filter(|p| p.ends_with(".ipynb"))
+            .and_then(|p| notebook_hunks(repo, p));
+
+        match hunks {
+            Some(hunks) => {
+                for header in section.iter().take_while(|l| !l.starts_with("@@")) {
+                    result.push_str(header);
+                }
+                result.push_str(&hunks);
+            }
+            None => section.iter().for_each(|l| result.push_str(l)),
+        }
+    }
+    result
+}
@@ -0,0 +1,26 @@
+use anyhow::Result;
+use git_tools_core::git::git;
+
+/// 本工具使用的 git notes ref（與一般的 refs/notes/commits 分開）
+pub const NOTES_REF: &str = "refs/notes/auto-commit";
+
+/// 讀取 commit 的 note，沒有時回傳 None
+pub fn show(sha: &str) -> Option<String> {
+    git(&["notes", "--ref", NOTES_REF, "show", sha])
+        .ok()
+        .map(|note| note.trim().to_string())
+}
+
+/// 審查筆記使用的 git notes ref（與完整分析分開，兩者可同時啟用）
+pub const REVIEW_REF: &str = "refs/notes/auto-commit-review";
+
+/// 將文字寫入 commit 的 note（已有 note 時覆寫）
+pub fn write(sha: &str, text: &str) -> Result<()> {
+    write_ref(NOTES_REF, sha, text)
+}
+
+/// 將文字寫入指定 ref 中 commit 的 note（已有 note 時覆寫）
+pub fn write_ref(notes_ref: &str, sha: &str, text: &str) -> Result<()> {
+    git(&["notes", "--ref", notes_ref, "add", "-f", "-m", text, sha])?;
+    Ok(())
+}
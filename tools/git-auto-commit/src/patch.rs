@@ -0,0 +1,152 @@
+use crate::config::Identity;
+use crate::identity;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use git2::Repository;
+use git_tools_core::git::git;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// git format-patch 的檔名：將標題中的特殊字元換成 -（最多 52 個字元）
+fn patch_file_name(subject: &str) -> String {
+    let mut slug = String::new();
+    for c in subject.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            slug.push(c);
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug: String = slug.trim_matches(['-', '.']).chars().take(52).collect();
+    let slug = slug.trim_end_matches(['-', '.']);
+    if slug.is_empty() {
+        "0001.patch".to_string()
+    } else {
+        format!("0001-{}.patch", slug)
+    }
+}
+
+/// RFC 2047 encoded-word 的最大長度
+const ENCODED_WORD_MAX: usize = 75;
+
+/// 將含有非 ASCII 字元的標頭值編碼為 RFC 2047 的 encoded-word（Q 編碼，與 git format-patch 相同），
+/// 過長時拆成多個 encoded-word 並折行；純 ASCII 時維持原樣
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    const PREFIX: &str = "=?UTF-8?q?";
+    const SUFFIX: &str = "?=";
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for c in value.chars() {
+        let mut encoded = String::new();
+        if c == ' ' {
+            encoded.push('_');
+        } else if c.is_ascii_alphanumeric() || "!*+-/".contains(c) {
+            encoded.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                encoded.push_str(&format!("={:02X}", byte));
+            }
+        }
+        // 同一個字元的位元組不拆到不同的 encoded-word
+        if !word.is_empty() && PREFIX.len() + word.len() + encoded.len() + SUFFIX.len() > ENCODED_WORD_MAX {
+            words.push(format!("{}{}{}", PREFIX, word, SUFFIX));
+            word.clear();
+        }
+        word.push_str(&encoded);
+    }
+    words.push(format!("{}{}{}", PREFIX, word, SUFFIX));
+    words.join("\n ")
+}
+
+/// 將 staged 變更匯出為 git format-patch 格式的檔案（不建立 commit），回傳檔案路徑
+pub fn export(
+    repo: &Repository,
+    message: &str,
+    author: Option<&Identity>,
+    date: Option<&DateTime<Local>>,
+    dir: &Path,
+) -> Result<PathBuf> {
+    let (name, email) = match author {
+        Some(identity) => (identity.name.clone(), identity.email.clone()),
+        None => {
+            let (name, email) = identity::current_identity(repo);
+            (
+                name.context("git 設定中缺少 user.name")?,
+                email.context("git 設定中缺少 user.email")?,
+            )
+        }
+    };
+    let date = date.copied().unwrap_or_else(Local::now);
+
+    let (subject, body) = message.split_once('\n').unwrap_or((message, ""));
+    let body = body.trim();
+
+    let stat = git(&["diff", "--cached", "--stat", "--summary", "--no-color"])?;
+    let diff = git(&[
+        "diff",
+        "--cached",
+        "--binary",
+        "--no-color",
+        "--no-ext-diff",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+    ])?;
+    let version = git(&["--version"])?;
+    let version = version.trim().trim_start_matches("git version ");
+
+    let mut patch = String::new();
+    patch.push_str("From 0000000000000000000000000000000000000000 Mon Sep 17 00:00:00 2001\n");
+    patch.push_str(&format!("From: {} <{}>\n", encode_header(&name), email));
+    patch.push_str(&format!("Date: {}\n", date.to_rfc2822()));
+    patch.push_str(&format!("Subject: [PATCH] {}\n", encode_header(subject.trim())));
+    if !message.is_ascii() {
+        patch.push_str("MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n");
+    }
+    patch.push('\n');
+    if !body.is_empty() {
+        patch.push_str(body);
+        patch.push('\n');
+    }
+    patch.push_str("---\n");
+    patch.push_str(&stat);
+    patch.push('\n');
+    patch.push_str(&diff);
+    patch.push_str(&format!("-- \n{}\n\n", version));
+
+    fs::create_dir_all(dir).context(format!("無法建立目錄：{}", dir.display()))?;
+    let path = dir.join(patch_file_name(subject.trim()));
+    fs::write(&path, patch).context(format!("無法寫入 patch：{}", path.display()))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_header_keeps_ascii() {
+        assert_eq!(encode_header("feat: add login"), "feat: add login");
+    }
+
+    #[test]
+    fn encode_header_uses_q_encoding() {
+        assert_eq!(encode_header("王 a"), "=?UTF-8?q?=E7=8E=8B_a?=");
+    }
+
+    #[test]
+    fn encode_header_folds_long_values_without_splitting_characters() {
+        let encoded = encode_header(&"新增登入功能".repeat(5));
+        let words: Vec<&str> = encoded.split("\n ").collect();
+        assert!(words.len() > 1);
+        for word in &words {
+            assert!(word.len() <= ENCODED_WORD_MAX);
+            assert!(word.starts_with("=?UTF-8?q?") && word.ends_with("?="));
+            // 每個字元為 3 個位元組，各編碼為 =XX
+            assert_eq!((word.len() - 12) % 9, 0);
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::cli::PlanArgs;
+use crate::compliance;
+use crate::config::{load_llm_config, LlmConfig};
+use crate::progress::Timeline;
+use crate::{branch, plain};
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Input;
+use git2::Repository;
+use git_tools_core::git::get_current_branch;
+use std::env;
+
+/// 解析 [BRANCHES] 與 [COMMIT] 區塊：分支名稱與 commit 標題草稿
+fn parse_response(response: &str) -> (Vec<String>, Option<String>) {
+    let (branches, commit) = match response.split_once("[COMMIT]") {
+        Some((branches, commit)) => (branches, Some(commit)),
+        None => (response, None),
+    };
+    let title = commit
+        .and_then(|commit| commit.lines().map(str::trim).find(|line| !line.is_empty()))
+        .map(|line| line.trim_matches('`').to_string());
+    (crate::branch_lines(branches), title)
+}
+
+/// 請 LLM 依工作描述建議分支名稱與 commit 標題草稿；失敗或無法解析時改用描述轉換的名稱，草稿為 None
+fn suggest(
+    description: &str,
+    current_branch: &str,
+    existing_branches: &[String],
+    config: &LlmConfig,
+) -> (Vec<String>, Option<String>) {
+    let prompt = crate::fill_branch_prompt(&config.plan_prompt, description, current_branch, existing_branches, config);
+    let mut timeline = Timeline::new();
+    timeline.start("呼叫 LLM 生成分支名稱與 commit 標題草稿");
+    let (branches, title) = match crate::call_llm_cli(&prompt, config) {
+        Ok(response) => {
+            timeline.finish();
+            parse_response(&response)
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 呼叫失敗：{}", e).yellow());
+            (Vec::new(), None)
+        }
+    };
+    if branches.is_empty() {
+        (crate::slug_branch_suggestions(description), title)
+    } else {
+        (branches, title)
+    }
+}
+
+/// plan 子指令：開始寫程式前，依工作描述建議分支名稱與 commit 標題草稿，建立並切換分支；
+/// 草稿記錄在分支設定中，之後在該分支 commit 時加入提示詞並列為建議
+pub fn run(args: &PlanArgs) -> Result<()> {
+    println!("\n{}\n", "🗺️  規劃新工作".cyan().bold());
+
+    let current_dir = env::current_dir().context("無法取得當前目錄")?;
+    let repo = Repository::open(&current_dir).context("✗ 錯誤：當前目錄不是 Git repository")?;
+    let current_branch = get_current_branch(&repo)?;
+
+    let config = load_llm_config();
+    let compliance = compliance::Filter::new(&config.compliance);
+    let existing_branches = branch::existing_branch_names(&repo);
+
+    let description = match args.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        Some(description) => description.to_string(),
+        None => Input::<String>::with_theme(plain::theme())
+            .with_prompt("要進行的工作描述（任何語言皆可）")
+            .interact_text()?
+            .trim()
+            .to_string(),
+    };
+
+    let (suggestions, title) = if args.offline {
+        (crate::slug_branch_suggestions(&description), None)
+    } else {
+        suggest(&description, &current_branch, &existing_branches, &config)
+    };
+    let title = title.unwrap_or_else(|| format!("feat: {}", description.lines().next().unwrap_or_default()));
+    println!("{}", format!("📝 commit 標題草稿：{}", title).cyan());
+
+    crate::select_and_switch_branch(
+        &repo,
+        &config,
+        args.ticket.as_deref(),
+        &compliance,
+        suggestions,
+        Some(&description),
+        true,
+    )?;
+    let branch = get_current_branch(&repo)?;
+    if branch == current_branch {
+        println!("{}", "未建立分支，不記錄 commit 標題草稿".dimmed());
+        println!();
+        return Ok(());
+    }
+
+    let title: String = Input::with_theme(plain::theme())
+        .with_prompt("commit 標題草稿（之後 commit 時列為建議，留空則不記錄）")
+        .with_initial_text(title)
+        .allow_empty(true)
+        .interact_text()?;
+    if !title.trim().is_empty() {
+        branch::set_draft_commit(&repo, &branch, title.trim())?;
+        println!("{}", format!("✓ 已記錄草稿，之後在 {} 上 commit 時會列為建議", branch).green());
+    }
+    println!();
+    Ok(())
+}
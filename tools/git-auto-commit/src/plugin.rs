@@ -0,0 +1,304 @@
+use crate::config::PluginsConfig;
+use crate::progress;
+use crate::GitSuggestions;
+use anyhow::{Context, Result};
+use colored::*;
+use git_tools_core::path::is_executable;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 外掛執行檔名稱的前綴（例如 git-auto-commit-jira）
+const PREFIX: &str = "git-auto-commit-";
+
+/// 外掛在 describe 事件回傳的資訊
+#[derive(Debug, Deserialize)]
+struct Description {
+    #[serde(default)]
+    description: String,
+    /// 是否處理 suggestions 事件（修改 LLM 的建議）
+    #[serde(default)]
+    post_process: bool,
+    /// 加入 commit 預覽選單的動作
+    #[serde(default)]
+    actions: Vec<PluginAction>,
+}
+
+/// 外掛提供的選單動作
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginAction {
+    pub id: String,
+    pub label: String,
+}
+
+/// 已註冊的外掛
+struct Plugin {
+    name: String,
+    path: PathBuf,
+    description: String,
+    post_process: bool,
+    actions: Vec<PluginAction>,
+}
+
+/// 傳給外掛的執行環境資訊
+#[derive(Debug, Serialize)]
+pub struct PluginContext {
+    pub repo: String,
+    pub current_branch: String,
+    pub files: Vec<String>,
+}
+
+/// suggestions 事件的回應，未提供的欄位維持原樣
+#[derive(Debug, Deserialize)]
+struct SuggestionsResponse {
+    branch_names: Option<Vec<String>>,
+    commit_messages: Option<Vec<String>>,
+}
+
+/// action 事件的回應
+#[derive(Debug, Deserialize)]
+struct ActionResponse {
+    commit_message: String,
+}
+
+/// 在 PATH 中尋找 git-auto-commit-<name> 執行檔（同名者以 PATH 中較前面的為準）
+fn find_executables() -> Vec<(String, PathBuf)> {
+    let mut found: Vec<(String, PathBuf)> = Vec::new();
+    let Some(paths) = env::var_os("PATH") else {
+        return found;
+    };
+
+    for dir in env::split_paths(&paths) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let stem = file_name.strip_suffix(env::consts::EXE_SUFFIX).unwrap_or(&file_name);
+            let Some(name) = stem.strip_prefix(PREFIX) else {
+                continue;
+            };
+            let path = entry.path();
+            if !name.is_empty() && is_executable(&path) && !found.iter().any(|(n, _)| n == name) {
+                found.push((name.to_string(), path));
+            }
+        }
+    }
+
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    found
+}
+
+/// 在另一個執行緒讀取 pipe 直到結束
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut output = String::new();
+        let _ = pipe.read_to_string(&mut output);
+        output
+    })
+}
+
+/// 執行外掛：將 JSON 寫入 stdin，讀取 stdout 的 JSON，超過時間限制則中止
+fn invoke(path: &Path, request: &serde_json::Value, timeout_secs: u64) -> Result<serde_json::Value> {
+    let mut child = Command::new(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context(format!("無法執行 {}", path.display()))?;
+
+    // 另開執行緒讀取 stdout 與 stderr，避免任一邊輸出過多時填滿 pipe 而卡住
+    let reader = read_in_background(child.stdout.take().context("無法讀取外掛輸出")?);
+    let errors = read_in_background(child.stderr.take().context("無法讀取外掛輸出")?);
+
+    // stdin 同樣另開執行緒寫入：外掛不讀取 stdin 而輸入超過 pipe 的容量時，寫入會停住，
+    // 仍需由下方的時間限制中止；外掛結束後寫入失敗，交由回應判斷
+    let input = serde_json::to_vec(request).context("無法序列化外掛輸入")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        thread::spawn(move || {
+            let _ = stdin.write_all(&input);
+        });
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(timeout_secs);
+    let status = loop {
+        if let Some(status) = child.try_wait().context("無法取得外掛狀態")? {
+            break status;
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("執行超過 {} 秒，已中止", timeout_secs);
+        }
+        thread::sleep(Duration::from_millis(50));
+    };
+
+    let output = reader.join().unwrap_or_default();
+    let error = errors.join().unwrap_or_default();
+    if !status.success() {
+        anyhow::bail!("結束代碼 {:?}：{}", status.code(), error.trim());
+    }
+
+    serde_json::from_str(output.trim()).context("輸出不是有效的 JSON")
+}
+
+/// 已載入的外掛與執行環境
+pub struct PluginHost {
+    plugins: Vec<Plugin>,
+    context: PluginContext,
+    timeout_secs: u64,
+}
+
+impl PluginHost {
+    /// 尋找並註冊 PATH 上設定為允許的外掛（以 describe 事件取得外掛資訊）；沒有允許任何外掛時不搜尋 PATH
+    pub fn discover(config: &PluginsConfig, context: PluginContext) -> Self {
+        let mut plugins = Vec::new();
+        if !config.allowed.is_empty() {
+            let found = find_executables();
+            for name in config.allowed.iter().filter(|name| !found.iter().any(|(n, _)| n == *name)) {
+                println!("{}", format!("⚠️  PATH 上找不到外掛 {}{}", PREFIX, name).yellow());
+            }
+            for (name, path) in found {
+                if !config.allowed.contains(&name) {
+                    continue;
+                }
+                let description = invoke(&path, &json!({ "event": "describe" }), config.timeout_secs)
+                    .and_then(|v| {
+                        serde_json::from_value::<Description>(v).context("describe 回應格式錯誤")
+                    });
+                match description {
+                    Ok(description) => plugins.push(Plugin {
+                        name,
+                        path,
+                        description: description.description,
+                        post_process: description.post_process,
+                        actions: description.actions,
+                    }),
+                    Err(e) => println!("{}", format!("⚠️  外掛 {} 無法載入：{:#}", name, e).yellow()),
+                }
+            }
+        }
+
+        for plugin in &plugins {
+            let label = if plugin.description.is_empty() {
+                plugin.name.clone()
+            } else {
+                format!("{}（{}）", plugin.name, plugin.description)
+            };
+            println!("{}", format!("🔌 已載入外掛：{}", label).dimmed());
+        }
+
+        Self {
+            plugins,
+            context,
+            timeout_secs: config.timeout_secs,
+        }
+    }
+
+    /// 依序交由外掛處理建議（例如依公司規則改寫分支名稱、加上 ticket footer）
+    pub fn post_process(&self, suggestions: &mut GitSuggestions) {
+        for plugin in self.plugins.iter().filter(|p| p.post_process) {
+            let request = json!({
+                "event": "suggestions",
+                "context": self.context,
+                "branch_names": suggestions.branch_names,
+                "commit_messages": suggestions.commit_messages,
+            });
+            let response = invoke(&plugin.path, &request, self.timeout_secs).and_then(|v| {
+                serde_json::from_value::<SuggestionsResponse>(v).context("suggestions 回應格式錯誤")
+            });
+
+            match response {
+                Ok(response) => {
+                    if let Some(branch_names) = response.branch_names {
+                        suggestions.branch_names = branch_names;
+                    }
+                    if let Some(commit_messages) = response.commit_messages {
+                        // 訊息已被改寫，原本的內容檢查結果不再適用
+                        if commit_messages != suggestions.commit_messages {
+                            suggestions.commit_notes.clear();
+                            suggestions.commit_labels.clear();
+                        }
+                        suggestions.commit_messages = commit_messages;
+                    }
+                }
+                Err(e) => progress::println(
+                    &format!("⚠️  外掛 {} 處理建議失敗：{:#}", plugin.name, e)
+                        .yellow()
+                        .to_string(),
+                ),
+            }
+        }
+    }
+
+    /// 所有外掛提供的選單動作（外掛索引與動作）
+    pub fn actions(&self) -> Vec<(usize, &PluginAction)> {
+        self.plugins
+            .iter()
+            .enumerate()
+            .flat_map(|(i, plugin)| plugin.actions.iter().map(move |action| (i, action)))
+            .collect()
+    }
+
+    /// 執行外掛的選單動作，回傳新的 commit 訊息
+    pub fn run_action(&self, plugin: usize, action: &PluginAction, message: &str) -> Result<String> {
+        let plugin = &self.plugins[plugin];
+        let request = json!({
+            "event": "action",
+            "action": action.id,
+            "context": self.context,
+            "commit_message": message,
+        });
+        let response = invoke(&plugin.path, &request, self.timeout_secs)
+            .and_then(|v| serde_json::from_value::<ActionResponse>(v).context("action 回應格式錯誤"))
+            .context(format!("外掛 {} 執行失敗", plugin.name))?;
+        Ok(response.commit_message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 在暫存目錄建立可執行的外掛腳本
+    #[cfg(unix)]
+    fn script(name: &str, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = env::temp_dir().join(format!("git-auto-commit-plugin-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    /// 超過 pipe 容量的輸入，外掛不讀取時寫入會停住
+    fn large_request() -> serde_json::Value {
+        json!({ "event": "suggest", "diff": "x".repeat(1 << 20) })
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ignores_stdin() {
+        let path = script("ignores-stdin", "echo '{\"ok\":true}'");
+        let response = invoke(&path, &large_request(), 5).unwrap();
+        assert_eq!(response, json!({ "ok": true }));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn timeout_while_stdin_unread() {
+        // 外掛不讀取 stdin 也不結束時，仍應在時間限制內中止
+        let path = script("hangs", "sleep 10");
+        let start = Instant::now();
+        let error = invoke(&path, &large_request(), 1).unwrap_err();
+        assert!(error.to_string().contains("執行超過 1 秒"));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,88 @@
+use crate::progress;
+use crate::CommitSummary;
+use anyhow::{Context, Result};
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// 事件輸出目的地（未啟用 --porcelain 時為 None）
+static SINK: Mutex<Option<Box<dyn Write + Send>>> = Mutex::new(None);
+
+/// 啟用事件輸出：target 為 "-" 時寫入 stderr，否則寫入指定的檔案或 FIFO
+pub fn init(target: &str) -> Result<()> {
+    let sink: Box<dyn Write + Send> = if target == "-" {
+        Box::new(io::stderr())
+    } else {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(target)
+            .context(format!("無法開啟 porcelain 輸出：{}", target))?;
+        Box::new(file)
+    };
+    if let Ok(mut current) = SINK.lock() {
+        *current = Some(sink);
+    }
+    Ok(())
+}
+
+/// 輸出一行事件（每個事件一行，以空白分隔欄位，最後一個欄位可為 JSON）
+fn emit(line: &str) {
+    let Ok(mut sink) = SINK.lock() else {
+        return;
+    };
+    let Some(sink) = sink.as_mut() else {
+        return;
+    };
+    // 寫入 stderr 時避免與 spinner 重疊
+    progress::suspend(|| {
+        let _ = writeln!(sink, "{}", line);
+        let _ = sink.flush();
+    });
+}
+
+/// 將字串轉為 JSON（保留換行等特殊字元，讓每個事件維持單行）
+fn json(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// STAGED <file>
+pub fn staged(file: &str) {
+    emit(&format!("STAGED {}", file));
+}
+
+/// SUGGESTION <branch|commit> <序號> <json>（序號由 1 開始）
+pub fn suggestion(kind: &str, index: usize, value: &str) {
+    emit(&format!("SUGGESTION {} {} {}", kind, index + 1, json(value)));
+}
+
+/// BRANCH <created|switched> <name>
+pub fn branch(action: &str, name: &str) {
+    emit(&format!("BRANCH {} {}", action, name));
+}
+
+/// WARNING <類別> <json>（LLM CLI 在 stderr 輸出的警告）
+pub fn warning(kind: &str, message: &str) {
+    emit(&format!("WARNING {} {}", kind, json(message)));
+}
+
+/// RESULT committed <sha> <json>（JSON 包含 sha、branch、subject 與變更統計）
+pub fn committed(summary: &CommitSummary) {
+    let detail = serde_json::to_string(summary).unwrap_or_default();
+    emit(&format!("RESULT committed {} {}", summary.sha, detail));
+}
+
+/// RESULT exported <json>（patch 檔案路徑）
+pub fn exported(path: &str) {
+    emit(&format!("RESULT exported {}", json(path)));
+}
+
+/// RESULT <cancelled|aborted|nothing-staged>
+pub fn result(status: &str) {
+    emit(&format!("RESULT {}", status));
+}
+
+/// RESULT failed <json>
+pub fn failed(error: &str) {
+    emit(&format!("RESULT failed {}", json(error)));
+}
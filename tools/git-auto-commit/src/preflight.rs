@@ -0,0 +1,305 @@
+use anyhow::{Context, Result};
+use colored::*;
+use git_tools_core::git::git_in;
+use globset::{Glob, GlobMatcher};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 單一檢查項目（對應 lefthook 的 command 或 lint-staged 的一組 pattern）
+pub struct Check {
+    pub name: String,
+    /// 檔案比對 pattern（None 表示所有 staged 檔案）
+    pattern: Option<GlobMatcher>,
+    /// 要執行的指令
+    command: String,
+    /// 是否逐一對每個檔案執行
+    per_file: bool,
+    /// 通過後是否重新 git add 被指令修改的檔案（lint-staged 的行為、lefthook 的 stage_fixed）
+    restage: bool,
+}
+
+/// 偵測到的 pre-commit 檢查設定
+pub struct Preflight {
+    /// 設定來源（例如 lefthook.yml）
+    pub source: String,
+    pub checks: Vec<Check>,
+}
+
+/// lefthook 的 glob 可以是字串或字串陣列
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LefthookGlob {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct LefthookCommand {
+    run: String,
+    glob: Option<LefthookGlob>,
+    #[serde(default)]
+    stage_fixed: bool,
+}
+
+#[derive(Deserialize)]
+struct LefthookHook {
+    #[serde(default)]
+    commands: BTreeMap<String, LefthookCommand>,
+}
+
+#[derive(Deserialize)]
+struct LefthookConfig {
+    #[serde(rename = "pre-commit")]
+    pre_commit: Option<LefthookHook>,
+}
+
+/// lint-staged 的指令可以是字串或字串陣列
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LintStagedCommands {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+    #[serde(rename = "lint-staged")]
+    lint_staged: Option<BTreeMap<String, LintStagedCommands>>,
+}
+
+/// 建立 glob 比對器；不含 / 的 pattern 比對檔名（與 lint-staged 的 matchBase 行為相同）
+fn build_matcher(pattern: &str, match_base: bool) -> Option<GlobMatcher> {
+    let pattern = if match_base && !pattern.contains('/') {
+        format!("**/{}", pattern)
+    } else {
+        pattern.to_string()
+    };
+    Glob::new(&pattern).ok().map(|g| g.compile_matcher())
+}
+
+fn detect_lefthook(workdir: &Path) -> Option<Preflight> {
+    let (name, content) = ["lefthook.yml", "lefthook.yaml", ".lefthook.yml", ".lefthook.yaml"]
+        .iter()
+        .find_map(|name| {
+            fs::read_to_string(workdir.join(name))
+                .ok()
+                .map(|content| (name.to_string(), content))
+        })?;
+
+    let config: LefthookConfig = serde_yaml::from_str(&content).ok()?;
+    let mut checks = Vec::new();
+
+    for (command_name, command) in config.pre_commit?.commands {
+        let globs = match command.glob {
+            Some(LefthookGlob::One(glob)) => vec![glob],
+            Some(LefthookGlob::Many(globs)) => globs,
+            None => vec![],
+        };
+        let per_file = command.run.contains("{staged_files}") || command.run.contains("{files}");
+
+        if globs.is_empty() {
+            checks.push(Check {
+                name: command_name.clone(),
+                pattern: None,
+                command: command.run.clone(),
+                per_file,
+                restage: command.stage_fixed,
+            });
+        }
+        for glob in globs {
+            checks.push(Check {
+                name: command_name.clone(),
+                pattern: build_matcher(&glob, true),
+                command: command.run.clone(),
+                per_file,
+                restage: command.stage_fixed,
+            });
+        }
+    }
+
+    Some(Preflight {
+        source: name,
+        checks,
+    })
+}
+
+fn detect_lint_staged(workdir: &Path) -> Option<Preflight> {
+    let from_rc = [".lintstagedrc", ".lintstagedrc.json"].iter().find_map(|name| {
+        let content = fs::read_to_string(workdir.join(name)).ok()?;
+        let map: BTreeMap<String, LintStagedCommands> = serde_json::from_str(&content).ok()?;
+        Some((name.to_string(), map))
+    });
+    let from_package = || {
+        let content = fs::read_to_string(workdir.join("package.json")).ok()?;
+        let package: PackageJson = serde_json::from_str(&content).ok()?;
+        package
+            .lint_staged
+            .map(|map| ("package.json (lint-staged)".to_string(), map))
+    };
+    let (source, map) = from_rc.or_else(from_package)?;
+
+    let mut checks = Vec::new();
+    for (pattern, commands) in map {
+        let commands = match commands {
+            LintStagedCommands::One(command) => vec![command],
+            LintStagedCommands::Many(commands) => commands,
+        };
+        for command in commands {
+            checks.push(Check {
+                name: pattern.clone(),
+                pattern: build_matcher(&pattern, true),
+                // lint-staged 會將檔案路徑附加在指令後方
+                command: format!("{} {{staged_files}}", command),
+                per_file: true,
+                restage: true,
+            });
+        }
+    }
+
+    Some(Preflight { source, checks })
+}
+
+/// 偵測 lefthook 或 lint-staged 的 pre-commit 設定
+pub fn detect(workdir: &Path) -> Option<Preflight> {
+    detect_lefthook(workdir)
+        .or_else(|| detect_lint_staged(workdir))
+        .filter(|preflight| !preflight.checks.is_empty())
+}
+
+/// 建立跨平台的 shell 指令
+pub fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// 為路徑加上引號，避免空白等字元被 shell 拆開
+fn quote(path: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", path)
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+/// 執行指令並回傳是否成功與輸出內容
+fn run(command: &str, workdir: &Path) -> Result<(bool, String)> {
+    let output = shell_command(command)
+        .current_dir(workdir)
+        .output()
+        .context(format!("無法執行：{}", command))?;
+    let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Ok((output.status.success(), text))
+}
+
+/// 工作目錄與 staging area 內容不同的檔案（git diff --name-only）
+fn unstaged(files: &[&String], workdir: &Path) -> Result<Vec<String>> {
+    let args: Vec<&str> = ["diff", "--name-only", "--"]
+        .into_iter()
+        .chain(files.iter().map(|file| file.as_str()))
+        .collect();
+    Ok(git_in(workdir, &args)?.lines().map(String::from).collect())
+}
+
+/// 將指令修改的檔案重新加入 staging area（與 lint-staged 相同），讓 commit 包含修正後的內容；
+/// 執行前已有未 staged 修改的檔案不重新加入，避免把無關的修改一併 commit
+fn restage(files: &[&String], dirty_before: &[String], workdir: &Path) -> Result<()> {
+    let (skipped, fixed): (Vec<String>, Vec<String>) =
+        unstaged(files, workdir)?.into_iter().partition(|file| dirty_before.contains(file));
+    if !fixed.is_empty() {
+        let status = Command::new("git")
+            .args(["add", "--"])
+            .args(&fixed)
+            .current_dir(workdir)
+            .status()
+            .context("無法執行 git add")?;
+        if !status.success() {
+            anyhow::bail!("無法重新 git add 修正後的檔案");
+        }
+        println!("{}", format!("  ↻ 已重新加入修正後的檔案：{}", fixed.join("、")).dimmed());
+    }
+    if !skipped.is_empty() {
+        println!(
+            "{}",
+            format!("  ⚠️  {} 原本就有未 staged 的修改，修正的內容未加入，請手動 git add", skipped.join("、")).yellow()
+        );
+    }
+    Ok(())
+}
+
+/// 顯示失敗指令的輸出（最多 20 行）
+fn print_failure_output(output: &str) {
+    let lines: Vec<&str> = output.lines().collect();
+    for line in lines.iter().take(20) {
+        println!("{}", format!("      {}", line).dimmed());
+    }
+    if lines.len() > 20 {
+        println!("{}", format!("      ...（省略 {} 行）", lines.len() - 20).dimmed());
+    }
+}
+
+/// 對 staged 檔案執行所有檢查，逐一顯示每個檔案的結果，回傳是否全部通過
+pub fn run_checks(preflight: &Preflight, files: &[String], workdir: &Path) -> Result<bool> {
+    // 已刪除的檔案無法檢查
+    let existing: Vec<&String> = files.iter().filter(|f| workdir.join(f).exists()).collect();
+    let mut all_passed = true;
+
+    for check in &preflight.checks {
+        let matched: Vec<&String> = existing
+            .iter()
+            .copied()
+            .filter(|f| check.pattern.as_ref().is_none_or(|m| m.is_match(f.as_str())))
+            .collect();
+        if matched.is_empty() {
+            continue;
+        }
+
+        println!("{}", format!("▶ {}：{}", check.name, check.command).cyan());
+        let dirty_before = if check.restage { unstaged(&matched, workdir)? } else { Vec::new() };
+        let mut passed = true;
+
+        if check.per_file {
+            for file in &matched {
+                let command = check
+                    .command
+                    .replace("{staged_files}", &quote(file))
+                    .replace("{files}", &quote(file));
+                let (success, output) = run(&command, workdir)?;
+                if success {
+                    println!("{}", format!("  ✓ {}", file).green());
+                } else {
+                    passed = false;
+                    println!("{}", format!("  ✗ {}", file).red());
+                    print_failure_output(&output);
+                }
+            }
+        } else {
+            let (success, output) = run(&check.command, workdir)?;
+            let files_label = format!("{} 個檔案", matched.len());
+            if success {
+                println!("{}", format!("  ✓ {}", files_label).green());
+            } else {
+                passed = false;
+                println!("{}", format!("  ✗ {}", files_label).red());
+                print_failure_output(&output);
+            }
+        }
+        // 與 lint-staged 相同，只在指令成功時重新加入
+        if passed && check.restage {
+            restage(&matched, &dirty_before, workdir)?;
+        }
+        all_passed &= passed;
+    }
+
+    Ok(all_passed)
+}
@@ -0,0 +1,85 @@
+use crate::config::LlmConfig;
+use crate::{audit, llm_warnings, script, usage};
+use anyhow::Result;
+use colored::*;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// 在背景預先送出的 LLM 請求：提示詞的內容確定後立即開始，與之後的檢查同時進行，
+/// 實際的提示詞與預先送出的相同時直接使用其結果，不同時捨棄
+pub struct Pregen {
+    /// 預先送出時的提示詞（腳本改寫前，用於比對）
+    prompt: String,
+    /// 經腳本改寫、實際送出的提示詞（寫入紀錄用）
+    transformed: String,
+    config: LlmConfig,
+    /// 背景請求的結果與 LLM CLI 的 stderr；取用結果後為 None
+    receiver: Option<Receiver<(Result<String>, String)>>,
+}
+
+impl Pregen {
+    /// 在背景執行緒開始呼叫 LLM；超過預算時不預先呼叫，留待之後的正常流程處理
+    pub fn start(prompt: String, config: &LlmConfig) -> Option<Pregen> {
+        // 腳本只在主執行緒載入，先改寫提示詞再交給背景執行緒
+        let transformed = script::transform_prompt(&prompt);
+        usage::check_budget(config, &transformed).ok()?;
+
+        let (sender, receiver) = mpsc::channel();
+        let thread_prompt = transformed.clone();
+        let thread_config = config.clone();
+        thread::spawn(move || {
+            let _ = sender.send(llm_warnings::capture(|| crate::run_llm_command(&thread_prompt, &thread_config)));
+        });
+        println!("{}", "⚡ 已在背景開始生成建議".dimmed());
+        Some(Pregen {
+            prompt,
+            transformed,
+            config: config.clone(),
+            receiver: Some(receiver),
+        })
+    }
+
+    /// 提示詞與模型都與預先送出時相同時回傳自身以等待結果；否則捨棄
+    pub fn reuse(self, prompt: &str, config: &LlmConfig) -> Option<Pregen> {
+        if self.prompt == prompt && self.config.model == config.model {
+            return Some(self);
+        }
+        println!("{}", "提示詞在預先生成後有所變更，捨棄預先生成的結果".dimmed());
+        None
+    }
+
+    /// 等待背景請求完成，顯示 LLM CLI 的警告並寫入紀錄
+    pub fn wait(mut self) -> Result<String> {
+        let (result, stderr) = self
+            .receiver
+            .take()
+            .and_then(|receiver| receiver.recv().ok())
+            .unwrap_or_else(|| (Err(anyhow::anyhow!("背景執行緒異常結束")), String::new()));
+        llm_warnings::surface(&self.config.command, &stderr);
+        crate::record_llm_call(&self.transformed, &self.config, &result);
+        result
+    }
+}
+
+impl Drop for Pregen {
+    /// 捨棄的請求已經送出，交給另一個執行緒等待完成後寫入稽核與用量紀錄，不延遲之後的流程；
+    /// 此時畫面可能正顯示選單，寫入時不顯示任何訊息（程式在請求完成前結束時不會記錄）
+    fn drop(&mut self) {
+        let Some(receiver) = self.receiver.take() else {
+            return;
+        };
+        let prompt = std::mem::take(&mut self.transformed);
+        let config = self.config.clone();
+        thread::spawn(move || {
+            let Ok((result, _)) = receiver.recv() else {
+                return;
+            };
+            if config.audit.enabled {
+                let _ = audit::record(&config, &prompt, &result);
+            }
+            if let Ok(response) = &result {
+                let _ = usage::record_call_quietly(&config, &prompt, response);
+            }
+        });
+    }
+}
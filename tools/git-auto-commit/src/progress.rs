@@ -0,0 +1,160 @@
+use crate::plain;
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// 目前正在執行的步驟 spinner，供其他模組輸出訊息時暫停重繪
+static ACTIVE_BAR: Mutex<Option<ProgressBar>> = Mutex::new(None);
+
+/// 輸出一行訊息；若有 spinner 正在執行，會先暫停以免畫面錯亂
+pub fn println(line: &str) {
+    suspend(|| println!("{}", line));
+}
+
+/// 暫停 spinner 的重繪並執行 f（沒有 spinner 時直接執行）
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    let active = ACTIVE_BAR.lock().ok().and_then(|bar| bar.clone());
+    match active {
+        Some(bar) => bar.suspend(f),
+        None => f(),
+    }
+}
+
+fn set_active(bar: Option<ProgressBar>) {
+    if let Ok(mut active) = ACTIVE_BAR.lock() {
+        *active = bar;
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2}s", duration.as_secs_f64())
+}
+
+/// 執行 f 時顯示 spinner，完成後清除（純文字模式或不在終端機中時不顯示），用於通常很快、但在大型 repository 中可能較久的操作
+pub fn spin<R>(label: &str, f: impl FnOnce() -> R) -> R {
+    if plain::enabled() {
+        return f();
+    }
+    let bar = ProgressBar::new_spinner();
+    bar.set_style(
+        ProgressStyle::with_template("{spinner:.cyan} {msg} {elapsed:.dim}")
+            .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+    );
+    bar.set_message(label.to_string());
+    bar.enable_steady_tick(Duration::from_millis(100));
+    set_active(Some(bar.clone()));
+    let result = f();
+    set_active(None);
+    bar.finish_and_clear();
+    result
+}
+
+/// 進行中的步驟
+struct RunningStep {
+    label: String,
+    started: Instant,
+    bar: ProgressBar,
+}
+
+/// 多步驟進度顯示（收集 diff → 建立提示詞 → 呼叫 LLM → 解析回應），並記錄各步驟耗時
+pub struct Timeline {
+    steps: Vec<(String, Duration)>,
+    current: Option<RunningStep>,
+}
+
+impl Timeline {
+    pub fn new() -> Self {
+        Self {
+            steps: Vec::new(),
+            current: None,
+        }
+    }
+
+    /// 開始新步驟（若前一個步驟尚未結束，視為成功完成）
+    pub fn start(&mut self, label: &str) {
+        self.finish();
+
+        // 純文字模式不顯示動畫，只輸出步驟開始與結束
+        if plain::enabled() {
+            println!("{} ...", label);
+            self.current = Some(RunningStep {
+                label: label.to_string(),
+                started: Instant::now(),
+                bar: ProgressBar::hidden(),
+            });
+            return;
+        }
+
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(
+            ProgressStyle::with_template("{spinner:.cyan} {msg} {elapsed:.dim}")
+                .unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        bar.set_message(label.to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        set_active(Some(bar.clone()));
+
+        self.current = Some(RunningStep {
+            label: label.to_string(),
+            started: Instant::now(),
+            bar,
+        });
+    }
+
+    /// 以成功狀態結束目前步驟
+    pub fn finish(&mut self) {
+        self.end_current(true);
+    }
+
+    /// 以失敗狀態結束目前步驟
+    pub fn fail(&mut self) {
+        self.end_current(false);
+    }
+
+    fn end_current(&mut self, success: bool) {
+        let Some(step) = self.current.take() else {
+            return;
+        };
+
+        let elapsed = step.started.elapsed();
+        let line = if success {
+            format!("✓ {} ({})", step.label, format_duration(elapsed)).green()
+        } else {
+            format!("✗ {} ({})", step.label, format_duration(elapsed)).red()
+        };
+
+        set_active(None);
+        if plain::enabled() {
+            println!("{}", line);
+            self.steps.push((step.label, elapsed));
+            return;
+        }
+        step.bar.set_style(
+            ProgressStyle::with_template("{msg}").unwrap_or_else(|_| ProgressStyle::default_spinner()),
+        );
+        step.bar.finish_with_message(line.to_string());
+
+        self.steps.push((step.label, elapsed));
+    }
+
+    /// 輸出所有步驟的耗時摘要（--verbose 模式）
+    pub fn print_summary(&self) {
+        println!("{}", "⏱  步驟耗時：".blue());
+        let mut total = Duration::ZERO;
+        for (label, elapsed) in &self.steps {
+            println!("{}", format!("  - {}：{}", label, format_duration(*elapsed)).dimmed());
+            total += *elapsed;
+        }
+        println!("{}", format!("  總計：{}", format_duration(total)).dimmed());
+    }
+}
+
+impl Drop for Timeline {
+    fn drop(&mut self) {
+        if let Some(step) = self.current.take() {
+            set_active(None);
+            step.bar.abandon();
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use crate::config::RankingConfig;
+use crate::coverage::{self, Verdict};
+use crate::GitSuggestions;
+use console::measure_text_width;
+use git_tools_core::input::{LINE_LIMIT, SUBJECT_LIMIT};
+use std::collections::HashSet;
+
+/// Conventional Commits 常見的 type
+const CONVENTIONAL_TYPES: &[&str] = &[
+    "feat", "fix", "chore", "docs", "style", "refactor", "test", "build", "ci", "perf", "revert",
+];
+
+/// 標題過短（顯示寬度）時視為資訊不足
+const MIN_SUBJECT_WIDTH: usize = 12;
+
+/// 評分後的建議（附註與來源標示隨建議一起移動）
+struct Ranked {
+    score: f64,
+    message: String,
+    note: Option<String>,
+    label: Option<String>,
+    /// 正規化後的描述（比對相近的建議用）
+    subject: Vec<char>,
+}
+
+/// 具體程度：提及變更的檔案或符號為 1，與變更無關為 0.3，籠統的描述為 0
+fn specificity(message: &str, keywords: &HashSet<String>) -> f64 {
+    match coverage::assess(message, keywords) {
+        Verdict::Specific => 1.0,
+        Verdict::Unrelated => 0.3,
+        Verdict::Generic => 0.0,
+    }
+}
+
+/// 格式規則的符合比例：已知的 type、標題不超過 72 欄、標題結尾沒有句號、標題與內文之間有空行
+fn conformance(message: &str) -> f64 {
+    let mut lines = message.lines();
+    let subject = lines.next().unwrap_or_default().trim_end();
+    let rules = [
+        crate::conventional_type(message).is_some_and(|t| CONVENTIONAL_TYPES.contains(&t)),
+        measure_text_width(subject) <= LINE_LIMIT,
+        !subject.ends_with(['.', '。']),
+        lines.next().is_none_or(|line| line.trim().is_empty()),
+    ];
+    rules.iter().filter(|passed| **passed).count() as f64 / rules.len() as f64
+}
+
+/// 標題長度：50 欄內為 1，超過後遞減到 72 欄時為 0.5，再長為 0；過短的標題為 0.5
+fn length(message: &str) -> f64 {
+    let width = measure_text_width(message.lines().next().unwrap_or_default().trim());
+    if width < MIN_SUBJECT_WIDTH {
+        0.5
+    } else if width <= SUBJECT_LIMIT {
+        1.0
+    } else if width <= LINE_LIMIT {
+        1.0 - 0.5 * (width - SUBJECT_LIMIT) as f64 / (LINE_LIMIT - SUBJECT_LIMIT) as f64
+    } else {
+        0.0
+    }
+}
+
+/// 標題中「type(scope): 」之後的描述，只保留文字與數字並轉為小寫（type 不同但描述相同的建議也視為相近）
+fn normalize(message: &str) -> Vec<char> {
+    let subject = message.lines().next().unwrap_or_default();
+    let description = match crate::conventional_type(message) {
+        Some(_) => subject.split_once(':').map_or(subject, |(_, description)| description),
+        None => subject,
+    };
+    description
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// 兩個標題的相似度（字元 bigram 的 Dice 係數，0–1），中日韓文字與英文都適用
+fn similarity(a: &[char], b: &[char]) -> f64 {
+    if a == b {
+        return 1.0;
+    }
+    if a.len() < 2 || b.len() < 2 {
+        return 0.0;
+    }
+    let bigrams = |chars: &[char]| -> Vec<(char, char)> { chars.windows(2).map(|w| (w[0], w[1])).collect() };
+    let (left, mut right) = (bigrams(a), bigrams(b));
+    let total = left.len() + right.len();
+    let mut shared = 0;
+    for bigram in left {
+        if let Some(pos) = right.iter().position(|other| *other == bigram) {
+            right.swap_remove(pos);
+            shared += 1;
+        }
+    }
+    2.0 * shared as f64 / total as f64
+}
+
+/// 依設定的權重為 commit 建議評分，移除標題相近的建議（保留分數較高者）並由高至低排序；
+/// 附註與來源標示隨建議一起移動。回傳移除的建議數
+pub fn rank(suggestions: &mut GitSuggestions, config: &RankingConfig, files: &[String], diff: &str) -> usize {
+    let keywords = coverage::collect_keywords(files, diff);
+    let weights = &config.weights;
+    let count = suggestions.commit_messages.len();
+    suggestions.commit_notes.resize(count, None);
+    suggestions.commit_labels.resize(count, None);
+
+    let mut ranked: Vec<Ranked> = suggestions
+        .commit_messages
+        .drain(..)
+        .zip(suggestions.commit_notes.drain(..))
+        .zip(suggestions.commit_labels.drain(..))
+        .map(|((message, note), label)| Ranked {
+            score: weights.specificity * specificity(&message, &keywords)
+                + weights.conformance * conformance(&message)
+                + weights.length * length(&message),
+            subject: normalize(&message),
+            message,
+            note,
+            label,
+        })
+        .collect();
+    // 穩定排序：分數相同時維持 LLM 原本的順序
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    let mut kept: Vec<Ranked> = Vec::new();
+    for entry in ranked {
+        if !kept.iter().any(|other| similarity(&entry.subject, &other.subject) >= config.dedup_similarity) {
+            kept.push(entry);
+        }
+    }
+
+    let removed = count - kept.len();
+    for entry in kept {
+        suggestions.commit_messages.push(entry.message);
+        suggestions.commit_notes.push(entry.note);
+        suggestions.commit_labels.push(entry.label);
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conformance_counts_passed_rules() {
+        assert_eq!(conformance("feat(auth): add login form\n\nbody"), 1.0);
+        assert_eq!(conformance("feat(auth): add login form."), 0.75);
+        assert_eq!(conformance("Add login form.\nbody"), 0.25);
+    }
+
+    #[test]
+    fn length_prefers_subjects_within_limit() {
+        assert_eq!(length("fix: typo"), 0.5);
+        assert_eq!(length("feat(auth): add login form"), 1.0);
+        // 61 欄：超過 50 欄 11 欄，扣 0.5 × 11 / 22
+        assert_eq!(length(&format!("feat: {}", "a".repeat(55))), 0.75);
+        assert_eq!(length(&"a".repeat(LINE_LIMIT + 1)), 0.0);
+    }
+
+    #[test]
+    fn normalize_ignores_type_case_and_punctuation() {
+        assert_eq!(normalize("feat(auth): Add login-form"), normalize("fix: add LOGIN form"));
+        assert_eq!(normalize("新增登入表單。\n\n內文"), "新增登入表單".chars().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn similarity_is_dice_coefficient() {
+        let chars = |s: &str| s.chars().collect::<Vec<_>>();
+        assert_eq!(similarity(&chars("abcd"), &chars("abcd")), 1.0);
+        assert_eq!(similarity(&chars("abcd"), &chars("abce")), 2.0 * 2.0 / 6.0);
+        assert_eq!(similarity(&chars("a"), &chars("b")), 0.0);
+    }
+
+    #[test]
+    fn rank_sorts_by_score_and_removes_near_duplicates() {
+        let mut suggestions = GitSuggestions {
+            commit_messages: vec![
+                "update files".to_string(),
+                "feat(login): add login form validation".to_string(),
+                "fix(login): add login form validation".to_string(),
+            ],
+            commit_notes: vec![None, Some("note".to_string())],
+            ..Default::default()
+        };
+        let files = vec!["src/login.rs".to_string()];
+        let removed = rank(&mut suggestions, &RankingConfig::default(), &files, "+fn validate_form() {}\n");
+        assert_eq!(removed, 1);
+        assert_eq!(
+            suggestions.commit_messages,
+            vec!["feat(login): add login form validation".to_string(), "update files".to_string()]
+        );
+        assert_eq!(suggestions.commit_notes, vec![Some("note".to_string()), None]);
+        assert_eq!(suggestions.commit_labels, vec![None, None]);
+    }
+}
@@ -0,0 +1,296 @@
+use crate::cli::RebasePlanArgs;
+use crate::config::{load_llm_config, CoverageMode, LlmConfig};
+use crate::coverage::{self, Verdict};
+use crate::progress::Timeline;
+use crate::{repo_state, shallow, stack, PromptContext};
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Select;
+use git2::Repository;
+use git_tools_core::git::git;
+use git_tools_core::{layout, plain};
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 分支上的一個 commit
+struct PlanCommit {
+    sha: String,
+    subject: String,
+    files: Vec<String>,
+}
+
+impl PlanCommit {
+    fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+
+    /// fixup! / squash! / amend! commit 的動作與目標標題
+    fn autosquash(&self) -> Option<(Fold, &str)> {
+        [("fixup! ", Fold::Fixup), ("amend! ", Fold::Fixup), ("squash! ", Fold::Squash)]
+            .into_iter()
+            .find_map(|(prefix, fold)| self.subject.strip_prefix(prefix).map(|target| (fold, target.trim())))
+    }
+
+    fn is_wip(&self) -> bool {
+        let subject = self.subject.to_lowercase();
+        subject.starts_with("wip") || subject.starts_with("tmp") || subject.starts_with("temp")
+    }
+}
+
+/// 併入前一個 commit 的方式
+#[derive(Clone, Copy)]
+enum Fold {
+    /// 併入並捨棄訊息
+    Fixup,
+    /// 併入並合併訊息
+    Squash,
+}
+
+impl Fold {
+    fn command(self) -> &'static str {
+        match self {
+            Fold::Fixup => "fixup",
+            Fold::Squash => "squash",
+        }
+    }
+}
+
+/// todo 中的一組：保留的 commit 與併入它的 commit
+struct Group {
+    head: usize,
+    /// 標題過於籠統，需要改寫
+    generic: bool,
+    /// 改寫後的訊息
+    reword: Option<String>,
+    followers: Vec<(usize, Fold, &'static str)>,
+}
+
+/// base 以來的 commit（由舊到新）
+fn collect_commits(base: &str) -> Result<Vec<PlanCommit>> {
+    let output = git(&["log", "--reverse", "--format=%H%x1f%s", &format!("{}..HEAD", base)])?;
+    let mut commits = Vec::new();
+    for line in output.lines() {
+        let Some((sha, subject)) = line.split_once('\x1f') else {
+            continue;
+        };
+        let files = git(&["diff-tree", "--no-commit-id", "--name-only", "-r", sha])?
+            .lines()
+            .map(String::from)
+            .collect();
+        commits.push(PlanCommit {
+            sha: sha.to_string(),
+            subject: subject.to_string(),
+            files,
+        });
+    }
+    Ok(commits)
+}
+
+/// 依 autosquash 標記、WIP commit 與籠統的標題規劃 todo
+fn plan(commits: &[PlanCommit]) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    for (index, commit) in commits.iter().enumerate() {
+        // fixup! / squash! 移到目標 commit 之後
+        if let Some((fold, target)) = commit.autosquash().filter(|(_, target)| !target.is_empty()) {
+            let found = groups
+                .iter_mut()
+                .find(|g| commits[g.head].subject == target || commits[g.head].subject.starts_with(target));
+            if let Some(group) = found {
+                group.followers.push((index, fold, "autosquash 標記"));
+                continue;
+            }
+        }
+
+        // 只修改前一組已變更檔案的 WIP commit 併入前一組
+        if commit.is_wip() {
+            if let Some(group) = groups.last_mut() {
+                let touched: HashSet<&String> = std::iter::once(group.head)
+                    .chain(group.followers.iter().map(|(i, _, _)| *i))
+                    .flat_map(|i| commits[i].files.iter())
+                    .collect();
+                if !commit.files.is_empty() && commit.files.iter().all(|f| touched.contains(f)) {
+                    group.followers.push((index, Fold::Fixup, "WIP commit，只修改了前一個 commit 的檔案"));
+                    continue;
+                }
+            }
+        }
+
+        let generic = commit.is_wip() || coverage::assess(&commit.subject, &HashSet::new()) == Verdict::Generic;
+        groups.push(Group {
+            head: index,
+            generic,
+            reword: None,
+            followers: Vec::new(),
+        });
+    }
+    groups
+}
+
+/// 由 LLM 依 commit 的變更（含併入的 commit）改寫訊息
+fn reword_message(commits: &[PlanCommit], group: &Group, config: &LlmConfig) -> Option<String> {
+    let members: Vec<usize> = std::iter::once(group.head)
+        .chain(group.followers.iter().map(|(i, _, _)| *i))
+        .collect();
+    let mut diff = String::new();
+    let mut files: Vec<String> = Vec::new();
+    for &i in &members {
+        diff.push_str(&git(&["show", "--format=", "--no-color", "--no-prefix", &commits[i].sha]).ok()?);
+        for file in &commits[i].files {
+            if !files.contains(file) {
+                files.push(file.clone());
+            }
+        }
+    }
+
+    let mut config = config.clone();
+    config.coverage.mode = CoverageMode::Off;
+    let context = PromptContext {
+        existing_branches: Vec::new(),
+        description: None,
+        change_notes: Vec::new(),
+        examples: Vec::new(),
+    };
+    println!("{}", format!("✏️  改寫 {} {}", commits[group.head].short_sha(), commits[group.head].subject).dimmed());
+    let suggestions = crate::generate_suggestions(&diff, &files, &context, &config, &mut Timeline::new(), None);
+    suggestions.commit_messages.into_iter().next()
+}
+
+/// 產生 todo 內容；改寫的訊息寫入 dir 中的檔案，以 exec 執行 git commit --amend
+fn render_todo(commits: &[PlanCommit], groups: &[Group], dir: &Path) -> Result<String> {
+    let mut todo = String::new();
+    for group in groups {
+        let head = &commits[group.head];
+        todo.push_str(&format!("pick {} {}\n", head.short_sha(), head.subject));
+        for (index, fold, _) in &group.followers {
+            let commit = &commits[*index];
+            todo.push_str(&format!("{} {} {}\n", fold.command(), commit.short_sha(), commit.subject));
+        }
+        if let Some(message) = &group.reword {
+            let path = dir.join(format!("reword-{}.txt", head.short_sha()));
+            fs::write(&path, message).context("無法寫入改寫的訊息")?;
+            todo.push_str(&format!("exec git commit --amend --only --no-verify -F \"{}\"\n", path.display()));
+        }
+    }
+    Ok(todo)
+}
+
+/// 顯示整理計畫與原因
+fn print_plan(commits: &[PlanCommit], groups: &[Group]) {
+    println!("{}", "🧹 建議的 rebase 計畫：".blue());
+    for group in groups {
+        let head = &commits[group.head];
+        let subject_width = layout::available(2 + 7 + 8);
+        println!("  {} {} {}", format!("{:<6}", "pick").green(), head.short_sha().yellow(), layout::truncate(&head.subject, subject_width));
+        for (index, fold, reason) in &group.followers {
+            let commit = &commits[*index];
+            println!(
+                "  {} {} {}",
+                format!("{:<6}", fold.command()).cyan(),
+                commit.short_sha().yellow(),
+                layout::truncate(&commit.subject, subject_width)
+            );
+            println!("{}", format!("         ↳ 併入 {}（{}）", head.short_sha(), reason).dimmed());
+        }
+        match (&group.reword, group.generic) {
+            (Some(message), _) => {
+                let subject = message.lines().next().unwrap_or(message);
+                println!("{}", format!("         ✏️  改寫為：{}", subject).magenta());
+            }
+            (None, true) => println!("{}", "         ⚠ 標題過於籠統，建議改寫".yellow()),
+            (None, false) => {}
+        }
+    }
+    println!();
+}
+
+/// rebase-plan 子指令：分析分支上的 commit，建議 fixup、調整順序與改寫籠統的訊息，確認後執行 git rebase -i
+pub fn run(args: &RebasePlanArgs) -> Result<()> {
+    println!("\n{}\n", "🧹 Rebase 計畫".cyan().bold());
+
+    let current_dir = env::current_dir().context("無法取得當前目錄")?;
+    let repo = Repository::open(&current_dir).context("✗ 錯誤：當前目錄不是 Git repository")?;
+    let config = load_llm_config();
+
+    let trunk = stack::detect_trunk(&repo, args.base.as_deref(), &config)?;
+    if let Some(base) = &args.base {
+        repo_state::update(&repo, &config, |state| state.base_branch = Some(base.clone()));
+    }
+    let incomplete = shallow::check(&current_dir, &config.fetch, "分支上的 commit 分析", args.offline)?;
+    let base = git(&["merge-base", &trunk, "HEAD"])
+        .with_context(|| {
+            if incomplete {
+                format!("找不到與 {} 的共同祖先（淺層 clone 的歷史不足，請執行 git fetch --unshallow 後再試）", trunk)
+            } else {
+                format!("找不到與 {} 的共同祖先", trunk)
+            }
+        })?
+        .trim()
+        .to_string();
+    if !git(&["rev-list", "--merges", &format!("{}..HEAD", base)])?.trim().is_empty() {
+        anyhow::bail!("分支上有合併 commit，rebase 會將其攤平，請手動整理");
+    }
+    if !git(&["status", "--porcelain", "--untracked-files=no"])?.trim().is_empty() {
+        anyhow::bail!("工作目錄有尚未 commit 的變更，請先 commit 或 stash");
+    }
+
+    let commits = collect_commits(&base)?;
+    if commits.is_empty() {
+        println!("{}", format!("⚠️  目前分支相對於 {} 沒有 commit", trunk).yellow());
+        return Ok(());
+    }
+    println!("{}", format!("相對於 {} 共 {} 個 commit", trunk, commits.len()).dimmed());
+    println!();
+
+    let mut groups = plan(&commits);
+    let reorders = groups.iter().any(|g| !g.followers.is_empty());
+    if !reorders && !groups.iter().any(|g| g.generic) {
+        println!("{}", "✓ 沒有需要整理的 commit".green());
+        return Ok(());
+    }
+
+    if !args.offline {
+        for group in groups.iter_mut().filter(|g| g.generic) {
+            group.reword = reword_message(&commits, group, &config);
+        }
+        println!();
+    }
+    print_plan(&commits, &groups);
+
+    if !reorders && groups.iter().all(|g| g.reword.is_none()) {
+        println!("{}", "沒有可自動套用的變更".dimmed());
+        return Ok(());
+    }
+
+    let items = ["✓ 執行 git rebase -i（套用此計畫）", "✗ 取消"];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("請選擇")
+        .items(&items)
+        .default(0)
+        .interact()?;
+    if selection != 0 {
+        println!("{}", "已取消".yellow());
+        return Ok(());
+    }
+
+    let dir = repo.path().join("git-auto-commit");
+    fs::create_dir_all(&dir).context("無法建立暫存目錄")?;
+    let todo_path = dir.join("rebase-todo");
+    fs::write(&todo_path, render_todo(&commits, &groups, &dir)?).context("無法寫入 rebase todo")?;
+
+    // 以計畫取代 git 開啟編輯器時的 todo 內容
+    let status = Command::new("git")
+        .args(["rebase", "-i", &base])
+        .env("GIT_SEQUENCE_EDITOR", format!("cp \"{}\"", todo_path.display()))
+        .status()
+        .context("無法執行 git rebase")?;
+    if status.success() {
+        println!("{}", "✓ 已依計畫完成 rebase".green());
+    } else {
+        println!("{}", "⚠️  rebase 未完成：解決衝突後執行 git rebase --continue，或以 git rebase --abort 復原".yellow());
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,106 @@
+use crate::config::LlmConfig;
+use crate::progress::Timeline;
+use crate::remote::{self, FetchOutcome};
+use anyhow::{Context, Result};
+use colored::*;
+use git2::Repository;
+use git_tools_core::git::git;
+
+/// 最多驗證的 tag 數（由新到舊），避免歷史很長時逐一驗證過久
+const MAX_VERIFIED: usize = 20;
+
+/// 是否為已簽署的 annotated tag（lightweight tag 與未簽署的 tag 回傳 false）
+fn is_signed(tag: &str) -> bool {
+    git(&["cat-file", "tag", tag]).is_ok_and(|content| content.contains("-----BEGIN "))
+}
+
+/// 驗證 HEAD 歷史中最近的已簽署 tag，回傳驗證失敗的 tag 與原因
+fn failed_signatures() -> Result<Vec<(String, String)>> {
+    let tags = git(&["tag", "--merged", "HEAD", "--sort=-creatordate"])?;
+    let mut failed = Vec::new();
+    for tag in tags.lines().filter(|tag| is_signed(tag)).take(MAX_VERIFIED) {
+        if let Err(error) = git(&["verify-tag", tag]) {
+            // 錯誤訊息的最後一行為 gpg 或 ssh 的驗證結果
+            let error = error.to_string();
+            let reason = error.lines().last().unwrap_or("簽章無效").to_string();
+            failed.push((tag.to_string(), reason));
+        }
+    }
+    Ok(failed)
+}
+
+/// 建立 tag 前確認 release 的完整性：已簽署的 tag 都能通過驗證、沒有未 commit 的變更、
+/// 目前分支沒有落後 upstream；任一項未通過時列出原因與處理方式並中止。離線模式不 fetch，直接以本地的 remote 資料比較
+pub fn run(config: &LlmConfig, offline: bool) -> Result<()> {
+    let checks = &config.release_checks;
+    let mut problems = Vec::new();
+    let mut timeline = Timeline::new();
+
+    if checks.verify_signatures {
+        timeline.start("驗證已簽署的 tag");
+        let failed = failed_signatures()?;
+        if failed.is_empty() {
+            timeline.finish();
+        } else {
+            timeline.fail();
+            for (tag, reason) in failed {
+                problems.push(format!(
+                    "tag {} 的簽章驗證失敗（{}）：請確認 tag 未被竄改，並匯入簽署者的公鑰（gpg --recv-keys）或設定 gpg.ssh.allowedSignersFile",
+                    tag, reason
+                ));
+            }
+        }
+    }
+
+    if checks.require_clean {
+        let status = git(&["status", "--porcelain", "--untracked-files=no"])?;
+        if !status.trim().is_empty() {
+            let count = status.lines().count();
+            problems.push(format!(
+                "有 {} 個檔案的變更尚未 commit：請先 commit 或 git stash，確保 tag 對應的內容與工作目錄一致",
+                count
+            ));
+        }
+    }
+
+    if checks.require_up_to_date {
+        let repo = Repository::discover(".").context("無法開啟 Git repository")?;
+        let head = repo.head().ok();
+        match head.as_ref().filter(|head| head.is_branch()).and_then(|head| head.shorthand()) {
+            None => problems.push("目前不在任何分支上（detached HEAD）：請切換到要發布的分支".to_string()),
+            Some(branch) => {
+                // 以最新的 remote 資料比較；無法 fetch 時改用本地資料
+                if !offline {
+                    timeline.start("同步 remote（git fetch）");
+                    match remote::fetch_with_timeout(&config.fetch)? {
+                        FetchOutcome::Updated => timeline.finish(),
+                        FetchOutcome::TimedOut | FetchOutcome::Failed(_) => {
+                            timeline.fail();
+                            println!("{}", "⚠️  無法同步 remote，改用本地的 remote 資料".yellow());
+                        }
+                    }
+                }
+                match remote::ahead_behind(&repo, branch) {
+                    Some(status) if status.behind > 0 => problems.push(format!(
+                        "{} 落後 {} {} 個 commit：請先 git pull --rebase，避免在過時的版本上建立 tag",
+                        branch, status.upstream, status.behind
+                    )),
+                    Some(_) => {}
+                    None => println!("{}", format!("ℹ️  {} 沒有設定 upstream，略過同步檢查", branch).dimmed()),
+                }
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    println!();
+    println!("{}", "✗ 建立 tag 前的檢查未通過：".red().bold());
+    for problem in &problems {
+        println!("{}", format!("  - {}", problem).red());
+    }
+    println!("{}", "  可在設定檔的 [release_checks] 中關閉個別檢查".dimmed());
+    println!();
+    anyhow::bail!("發布前檢查未通過（{} 項）", problems.len())
+}
@@ -0,0 +1,158 @@
+use crate::config::FetchConfig;
+use anyhow::{Context, Result};
+use git2::{BranchType, Repository};
+use git_tools_core::git::git;
+use std::io::Read;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// fetch 的結果
+pub enum FetchOutcome {
+    /// 成功更新 remote-tracking 參照
+    Updated,
+    /// 超過時間限制，已中止
+    TimedOut,
+    /// 執行失敗（例如離線或驗證失敗），附上錯誤訊息
+    Failed(String),
+}
+
+/// 在時間限制內執行 git fetch（可選擇 --prune），逾時則中止並改用本地資料
+pub fn fetch_with_timeout(config: &FetchConfig) -> Result<FetchOutcome> {
+    let mut cmd = Command::new("git");
+    cmd.args(["fetch", "--quiet"]);
+    if config.prune {
+        cmd.arg("--prune");
+    }
+    // 避免在需要帳密時卡在互動式提示
+    cmd.env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped());
+
+    let mut child = cmd.spawn().context("無法執行 git fetch")?;
+    let deadline = Instant::now() + Duration::from_secs(config.timeout_secs);
+
+    // 在背景持續讀取 stderr，避免輸出填滿 pipe 時 git fetch 卡住
+    let stderr = child.stderr.take().map(|mut stderr| {
+        thread::spawn(move || {
+            let mut error = String::new();
+            let _ = stderr.read_to_string(&mut error);
+            error
+        })
+    });
+
+    loop {
+        if let Some(status) = child.try_wait().context("無法取得 git fetch 狀態")? {
+            if status.success() {
+                return Ok(FetchOutcome::Updated);
+            }
+            let error = stderr.and_then(|reader| reader.join().ok()).unwrap_or_default();
+            return Ok(FetchOutcome::Failed(error.trim().to_string()));
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(FetchOutcome::TimedOut);
+        }
+
+        thread::sleep(Duration::from_millis(100));
+    }
+}
+
+/// 先 stash 目前的變更，執行 git pull --rebase 後以 --index 還原（保留 staged 與未 staged 的區分）；
+/// pull 失敗時中止 rebase 並還原變更
+pub fn pull_rebase() -> Result<()> {
+    if let Err(error) = git(&["stash", "push", "--quiet", "--message", "git-auto-commit：pull --rebase 前暫存"]) {
+        anyhow::bail!("無法暫存目前的變更：{}", error);
+    }
+    let pulled = git(&["pull", "--rebase", "--quiet"]);
+    if pulled.is_err() {
+        let _ = git(&["rebase", "--abort"]);
+    }
+    if let Err(error) = git(&["stash", "pop", "--index", "--quiet"]) {
+        anyhow::bail!(
+            "還原暫存的變更時發生衝突，變更仍保留在 stash 中，請解決後執行 git stash pop --index：{}",
+            error
+        );
+    }
+    if let Err(error) = pulled {
+        anyhow::bail!("git pull --rebase 失敗，已還原原本的變更：{}", error);
+    }
+    Ok(())
+}
+
+/// 推送與建立 pull request 使用的 remote（fork 工作流程中兩者不同）
+pub struct PushTarget {
+    /// 推送分支的 remote（例如自己的 fork）
+    pub push: String,
+    /// pull request 目標 repository 的 remote（例如 upstream）
+    pub base: String,
+}
+
+/// 所有 remote 的名稱
+pub fn names(repo: &Repository) -> Vec<String> {
+    repo.remotes()
+        .map(|names| names.iter().flatten().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// 讀取 repository 的 git 設定值
+fn config_string(repo: &Repository, key: &str) -> Option<String> {
+    repo.config().ok()?.get_string(key).ok().filter(|value| !value.is_empty())
+}
+
+/// remote 的預設分支（refs/remotes/<remote>/HEAD 指向的分支），未記錄時為 None
+pub fn default_branch(repo: &Repository, remote: &str) -> Option<String> {
+    let reference = repo.find_reference(&format!("refs/remotes/{}/HEAD", remote)).ok()?;
+    let target = reference.symbolic_target()?;
+    target.strip_prefix(&format!("refs/remotes/{}/", remote)).map(String::from)
+}
+
+/// 推送的預設 remote：依 git push 的順序參考 branch.<name>.pushRemote、remote.pushDefault、branch.<name>.remote，
+/// 都沒有設定時為 origin 或第一個 remote
+pub fn default_push_remote(repo: &Repository, branch: &str, remotes: &[String]) -> Option<String> {
+    [
+        format!("branch.{}.pushRemote", branch),
+        "remote.pushDefault".to_string(),
+        format!("branch.{}.remote", branch),
+    ]
+    .iter()
+    .filter_map(|key| config_string(repo, key))
+    .find(|name| remotes.contains(name))
+    .or_else(|| remotes.iter().find(|name| *name == "origin").cloned())
+    .or_else(|| remotes.first().cloned())
+}
+
+/// pull request 目標的預設 remote：主幹分支追蹤的 remote，其次為 upstream，最後為推送的 remote
+pub fn default_base_remote(repo: &Repository, trunk: &str, remotes: &[String], push: &str) -> String {
+    config_string(repo, &format!("branch.{}.remote", trunk))
+        .filter(|name| remotes.contains(name))
+        .or_else(|| remotes.iter().find(|name| *name == "upstream").cloned())
+        .unwrap_or_else(|| push.to_string())
+}
+
+/// 當前分支相對於 upstream 的領先/落後 commit 數
+pub struct AheadBehind {
+    pub upstream: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// 計算當前分支與其 upstream 的領先/落後數量（沒有 upstream 時回傳 None）
+pub fn ahead_behind(repo: &Repository, branch_name: &str) -> Option<AheadBehind> {
+    let branch = repo.find_branch(branch_name, BranchType::Local).ok()?;
+    let upstream = branch.upstream().ok()?;
+    let upstream_name = upstream.name().ok()??.to_string();
+
+    let local_oid = branch.get().target()?;
+    let upstream_oid = upstream.get().target()?;
+    let (ahead, behind) = repo.graph_ahead_behind(local_oid, upstream_oid).ok()?;
+
+    Some(AheadBehind {
+        upstream: upstream_name,
+        ahead,
+        behind,
+    })
+}
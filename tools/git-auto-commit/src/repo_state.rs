@@ -0,0 +1,53 @@
+use crate::config::LlmConfig;
+use colored::*;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 每個 repository 記住的選項（儲存於 .git/auto-commit-state.toml），作為下次執行的預設值
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub struct RepoState {
+    /// 上次選擇的 commit 類型（例如 feat），commit 訊息選單預設選擇同類型的建議
+    #[serde(default)]
+    pub commit_type: Option<String>,
+    /// 上次 commit 後是否選擇推送
+    #[serde(default)]
+    pub push_after_commit: Option<bool>,
+    /// 上次以 --language 指定的語言
+    #[serde(default)]
+    pub language: Option<String>,
+    /// stack、rebase-plan 上次以 --base 指定的 base 分支
+    #[serde(default)]
+    pub base_branch: Option<String>,
+}
+
+fn get_state_path(repo: &Repository) -> PathBuf {
+    repo.path().join("auto-commit-state.toml")
+}
+
+/// 讀取記住的選項；停用、檔案不存在或格式錯誤時皆為空
+pub fn load(repo: &Repository, config: &LlmConfig) -> RepoState {
+    if !config.remember {
+        return RepoState::default();
+    }
+    fs::read_to_string(get_state_path(repo))
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 修改並寫回記住的選項；寫入失敗時只顯示警告，不影響主要流程
+pub fn update(repo: &Repository, config: &LlmConfig, change: impl FnOnce(&mut RepoState)) {
+    if !config.remember {
+        return;
+    }
+    let mut state = load(repo, config);
+    change(&mut state);
+    let result = toml::to_string(&state)
+        .map_err(anyhow::Error::from)
+        .and_then(|content| Ok(fs::write(get_state_path(repo), content)?));
+    if let Err(e) = result {
+        println!("{}", format!("⚠️  無法記住此 repository 的選項：{:#}", e).yellow());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::config::{ReviewConfig, ReviewSave};
+use crate::notes;
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use git2::Repository;
+use std::fs::OpenOptions;
+use std::io::Write;
+
+/// 在預覽下方顯示 LLM 的審查筆記
+pub fn print(notes: &str) {
+    println!("{}", "🧐 審查筆記：".cyan());
+    for line in notes.lines().filter(|line| !line.trim().is_empty()) {
+        println!("{}", format!("  {}", line.trim()).dimmed());
+    }
+    println!();
+}
+
+/// 依設定保存 commit 的審查筆記，回傳查看方式；設定為只顯示時回傳 None
+pub fn save(repo: &Repository, config: &ReviewConfig, sha: &str, subject: &str, text: &str) -> Result<Option<String>> {
+    match config.save {
+        ReviewSave::None => Ok(None),
+        ReviewSave::Note => {
+            notes::write_ref(notes::REVIEW_REF, sha, text)?;
+            Ok(Some(format!("git log --notes={} -1", notes::REVIEW_REF)))
+        }
+        ReviewSave::File => {
+            let workdir = repo.workdir().context("bare repository 無法寫入審查筆記檔案")?;
+            let path = workdir.join(&config.file);
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .with_context(|| format!("無法寫入 {}", path.display()))?;
+            // 每個 commit 一個段落，最新的在最後
+            let short = &sha[..sha.len().min(7)];
+            writeln!(
+                file,
+                "## {} {}\n\n_{}_\n\n{}\n",
+                short,
+                subject,
+                Local::now().format("%Y-%m-%d %H:%M"),
+                text.trim()
+            )?;
+            Ok(Some(config.file.clone()))
+        }
+    }
+}
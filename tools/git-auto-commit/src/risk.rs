@@ -0,0 +1,378 @@
+use crate::config::LlmConfig;
+use crate::mode::ModeChange;
+use colored::*;
+use git_tools_core::layout;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::LazyLock;
+
+/// 每種風險在預覽中最多列出的位置數
+const MAX_SHOWN: usize = 3;
+
+/// 錯誤處理的寫法（try/catch、except、if err != nil、map_err 等）
+static ERROR_HANDLING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b(catch|except|rescue)\b|\btry\s*[{:]|\bif err != nil\b|\.(map_err|context|with_context|catch)\(").unwrap()
+});
+
+/// 與驗證、權限、加解密相關的路徑
+static SENSITIVE_PATH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)(auth|crypto|security|permission|password|secret|session|oauth|jwt|acl|cipher|tls|ssl)").unwrap()
+});
+
+/// 與驗證、權限、加解密相關的程式碼
+static SENSITIVE_CODE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(password|passwd|secret|private_key|encrypt|decrypt|cipher|hmac|md5|sha1|bcrypt|jwt|oauth|csrf|verify_ssl|insecureskipverify|danger_accept_invalid_certs)\b|verify\s*=\s*false",
+    )
+    .unwrap()
+});
+
+/// 被註解掉的測試，或標記為略過的測試
+static DISABLED_TEST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*(//|#|--)\s*(#\[(tokio::)?test\]|@Test\b|def test_|func Test|(it|test|describe)\()|#\[ignore\]|\bx(it|describe)\(|\.skip\(|@pytest\.mark\.skip|@Disabled\b|@Ignore\b|\bt\.Skip\(",
+    )
+    .unwrap()
+});
+
+/// 內建的除錯程式碼規則：（語言, 副檔名, 正規表示式）
+const DEBUG_PATTERNS: &[(&str, &[&str], &[&str])] = &[
+    (
+        "rust",
+        &["rs"],
+        &[r"\bdbg!\(", r#"(?i)\be?println!\(\s*"\s*(debug|here|test|tmp|temp|xxx|asdf|>>>|!!!|\?\?\?|===)"#],
+    ),
+    (
+        "javascript",
+        &["js", "jsx", "ts", "tsx", "mjs", "cjs", "vue", "svelte"],
+        &[r"\bconsole\.(log|debug|trace|dir)\(", r"^\s*debugger\s*;?\s*$"],
+    ),
+    ("go", &["go"], &[r"\bfmt\.Println\(", r"\bspew\.Dump\("]),
+    (
+        "python",
+        &["py"],
+        &[r"\bbreakpoint\(\)", r"\b(i?pdb)\.set_trace\(", r#"(?i)^\s*print\(\s*f?["'](debug|here|test|>>>)"#],
+    ),
+    ("ruby", &["rb"], &[r"\bbinding\.(pry|irb)\b", r"^\s*byebug\b"]),
+    ("php", &["php"], &[r"\b(var_dump|dd|dump)\("]),
+    ("java", &["java", "kt"], &[r"\bSystem\.out\.println\(", r"\.printStackTrace\(\)"]),
+];
+
+/// 風險的種類（依預覽中顯示的順序）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    /// 新增 console.log、dbg! 等除錯程式碼
+    DebugLeftover,
+    /// 移除的錯誤處理多於新增的
+    RemovedErrorHandling,
+    /// 變更驗證、權限或加解密相關的程式碼
+    Security,
+    /// 停用或註解掉測試
+    DisabledTest,
+    /// 新增 TODO、FIXME 等待辦註記（全部列出）
+    Todo,
+    /// 檔案權限變更（全部列出，僅提醒、不需確認）
+    ModeChange,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::DebugLeftover => "新增的除錯程式碼",
+            Kind::RemovedErrorHandling => "移除了錯誤處理",
+            Kind::Security => "變更了驗證、權限或加解密相關的程式碼",
+            Kind::DisabledTest => "停用或註解掉測試",
+            Kind::Todo => "新增的待辦註記",
+            Kind::ModeChange => "變更了檔案權限",
+        }
+    }
+}
+
+/// 一處可能有風險的變更
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub kind: Kind,
+    /// 檔案路徑與行號（新檔案中的位置）
+    pub location: String,
+    /// 該行內容（已去除前後空白）；待辦註記為從標記開始的註解內容
+    pub text: String,
+}
+
+/// staged 變更的風險評估結果
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    findings: Vec<Finding>,
+    /// 包含除錯程式碼的 hunk（附檔案路徑）
+    debug_hunks: Vec<String>,
+}
+
+/// 一個檔案中新增與刪除的行（附新檔案中的行號）
+#[derive(Default)]
+struct FileChanges {
+    added: Vec<(usize, String)>,
+    removed: Vec<(usize, String)>,
+    /// 各 hunk 在新檔案中的起訖行號與內容
+    hunks: Vec<(usize, usize, String)>,
+}
+
+impl FileChanges {
+    /// 包含新檔案中第 line 行的 hunk
+    fn hunk_at(&self, line: usize) -> Option<&str> {
+        self.hunks
+            .iter()
+            .find(|(start, end, _)| (*start..=*end).contains(&line))
+            .map(|(_, _, text)| text.as_str())
+    }
+}
+
+/// 依檔案整理 diff 中新增與刪除的行
+fn collect_changes(diff: &str) -> BTreeMap<String, FileChanges> {
+    let mut files: BTreeMap<String, FileChanges> = BTreeMap::new();
+    let mut old_path = String::new();
+    let mut path = String::new();
+    let mut line_number = 0;
+    for line in diff.lines() {
+        if let Some(old) = line.strip_prefix("--- ") {
+            old_path = old.trim().to_string();
+        } else if let Some(new) = line.strip_prefix("+++ ") {
+            // 刪除的檔案以原路徑表示
+            path = if new.trim() == "/dev/null" { old_path.clone() } else { new.trim().to_string() };
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            // @@ -a,b +c,d @@：新檔案從第 c 行開始
+            line_number = header
+                .split_whitespace()
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|start| start.split(',').next())
+                .and_then(|start| start.parse().ok())
+                .unwrap_or(1);
+            if !path.is_empty() {
+                let hunks = &mut files.entry(path.clone()).or_default().hunks;
+                hunks.push((line_number, line_number, format!("{}\n", line)));
+            }
+        } else if path.is_empty() {
+            continue;
+        } else if line.starts_with("diff --git ") {
+            path.clear();
+        } else {
+            let changes = files.entry(path.clone()).or_default();
+            if let Some(added) = line.strip_prefix('+') {
+                changes.added.push((line_number, added.to_string()));
+                line_number += 1;
+            } else if let Some(removed) = line.strip_prefix('-') {
+                changes.removed.push((line_number, removed.to_string()));
+            } else if line.starts_with(' ') {
+                line_number += 1;
+            }
+            if let Some((_, end, text)) = changes.hunks.last_mut() {
+                *end = line_number.saturating_sub(1).max(*end);
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+    }
+    files
+}
+
+/// 各副檔名適用的除錯程式碼規則；設定中的語言名稱或副檔名覆寫內建的規則
+fn debug_patterns(overrides: &BTreeMap<String, Vec<String>>) -> Vec<(Vec<String>, Vec<Regex>)> {
+    let compile = |patterns: &[String]| -> Vec<Regex> {
+        patterns.iter().filter_map(|p| Regex::new(p).ok()).collect()
+    };
+    let mut rules: Vec<(Vec<String>, Vec<Regex>)> = DEBUG_PATTERNS
+        .iter()
+        .filter(|(language, _, _)| !overrides.contains_key(*language))
+        .map(|(_, extensions, patterns)| {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            (extensions.iter().map(|e| e.to_string()).collect(), compile(&patterns))
+        })
+        .collect();
+    for (key, patterns) in overrides {
+        let extensions = DEBUG_PATTERNS
+            .iter()
+            .find(|(language, _, _)| language == key)
+            .map(|(_, extensions, _)| extensions.iter().map(|e| e.to_string()).collect())
+            .unwrap_or_else(|| vec![key.clone()]);
+        // 副檔名同時出現在內建語言與覆寫設定時，以覆寫設定為準
+        for (existing, _) in &mut rules {
+            existing.retain(|e| !extensions.contains(e));
+        }
+        rules.push((extensions, compile(patterns)));
+    }
+    rules
+}
+
+/// 比對待辦註記的標記（例如 TODO、FIXME），未設定標記時回傳 None
+fn todo_pattern(markers: &[String]) -> Option<Regex> {
+    if markers.is_empty() {
+        return None;
+    }
+    let markers: Vec<String> = markers.iter().map(|m| regex::escape(m)).collect();
+    Regex::new(&format!(r"\b({})\b", markers.join("|"))).ok()
+}
+
+/// 待辦註記從標記開始的內容，去除區塊註解的結尾
+fn todo_text(text: &str, start: usize) -> &str {
+    text[start..].trim().trim_end_matches("*/").trim_end_matches("-->").trim_end()
+}
+
+/// 以規則檢查 staged diff 中可能有風險的變更，並收集新增的待辦註記
+pub fn analyze(diff: &str, config: &LlmConfig) -> Report {
+    let todo = todo_pattern(&config.todo.markers);
+    let debug = if config.debug_leftovers.detect {
+        debug_patterns(&config.debug_leftovers.patterns)
+    } else {
+        Vec::new()
+    };
+    let mut findings = Vec::new();
+    let mut debug_hunks: Vec<String> = Vec::new();
+    for (path, changes) in collect_changes(diff) {
+        let finding = |kind, line: usize, text: &str| Finding {
+            kind,
+            location: format!("{}:{}", path, line),
+            text: text.trim().to_string(),
+        };
+
+        let extension = path.rsplit_once('.').map(|(_, ext)| ext.to_lowercase()).unwrap_or_default();
+        if let Some((_, patterns)) = debug.iter().find(|(extensions, _)| extensions.contains(&extension)) {
+            for (line, text) in &changes.added {
+                if patterns.iter().any(|p| p.is_match(text)) {
+                    findings.push(finding(Kind::DebugLeftover, *line, text));
+                    if let Some(hunk) = changes.hunk_at(*line) {
+                        let hunk = format!("{}\n{}", path, hunk);
+                        if !debug_hunks.contains(&hunk) {
+                            debug_hunks.push(hunk);
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(todo) = &todo {
+            for (line, text) in &changes.added {
+                if let Some(found) = todo.find(text) {
+                    findings.push(finding(Kind::Todo, *line, todo_text(text, found.start())));
+                }
+            }
+        }
+        if !config.risk.detect {
+            continue;
+        }
+
+        // 錯誤處理只是搬移位置時，新增與刪除的數量相近，不列為風險
+        let removed_handling: Vec<&(usize, String)> =
+            changes.removed.iter().filter(|(_, text)| ERROR_HANDLING.is_match(text)).collect();
+        let added_handling = changes.added.iter().filter(|(_, text)| ERROR_HANDLING.is_match(text)).count();
+        if removed_handling.len() > added_handling {
+            for (line, text) in removed_handling.iter().skip(added_handling) {
+                findings.push(finding(Kind::RemovedErrorHandling, *line, text));
+            }
+        }
+
+        // 敏感的路徑只列出一次，其他檔案列出符合的行
+        let sensitive_lines = changes
+            .added
+            .iter()
+            .chain(&changes.removed)
+            .find(|(_, text)| SENSITIVE_CODE.is_match(text));
+        match sensitive_lines {
+            Some((line, text)) => findings.push(finding(Kind::Security, *line, text)),
+            None if SENSITIVE_PATH.is_match(&path) => {
+                let line = changes.added.first().or(changes.removed.first()).map(|(l, _)| *l).unwrap_or(1);
+                findings.push(finding(Kind::Security, line, "（檔案路徑）"));
+            }
+            None => {}
+        }
+
+        for (line, text) in &changes.added {
+            if DISABLED_TEST.is_match(text) {
+                findings.push(finding(Kind::DisabledTest, *line, text));
+            }
+        }
+    }
+    findings.sort_by_key(|f| f.kind);
+    Report { findings, debug_hunks }
+}
+
+impl Report {
+    /// 是否有需要在 commit 前確認的項目（權限變更只提醒）
+    pub fn needs_confirmation(&self) -> bool {
+        self.findings.iter().any(|f| f.kind != Kind::ModeChange)
+    }
+
+    /// 加入權限變更，在預覽中一併顯示
+    pub fn add_mode_changes(&mut self, changes: &[ModeChange]) {
+        self.findings.extend(changes.iter().map(|change| Finding {
+            kind: Kind::ModeChange,
+            location: change.path.clone(),
+            text: change.describe(),
+        }));
+    }
+
+    /// 新增的除錯程式碼
+    pub fn debug_leftovers(&self) -> Vec<&Finding> {
+        self.findings.iter().filter(|f| f.kind == Kind::DebugLeftover).collect()
+    }
+
+    /// 包含除錯程式碼的 hunk，供以 pager 檢視
+    pub fn debug_hunks(&self) -> String {
+        self.debug_hunks.join("\n")
+    }
+
+    /// 新增的待辦註記
+    pub fn todos(&self) -> Vec<&Finding> {
+        self.findings.iter().filter(|f| f.kind == Kind::Todo).collect()
+    }
+
+    /// 顯示新增的待辦註記（--strict 拒絕 commit 時）
+    pub fn print_todos(&self) {
+        for finding in self.todos() {
+            println!("{}", format!("  {}  {}", finding.location, finding.text).yellow());
+        }
+    }
+
+    /// 在 commit 預覽中顯示風險摘要
+    pub fn print(&self) {
+        if self.findings.is_empty() {
+            return;
+        }
+        println!("{}", "⚠️  提交前請再次確認：".yellow());
+        let mut kinds: Vec<Kind> = self.findings.iter().map(|f| f.kind).collect();
+        kinds.dedup();
+        for kind in kinds {
+            let findings: Vec<&Finding> = self.findings.iter().filter(|f| f.kind == kind).collect();
+            println!("{}", format!("  {}（{} 處）", kind.label(), findings.len()).yellow());
+            let shown = if matches!(kind, Kind::Todo | Kind::ModeChange) { findings.len() } else { MAX_SHOWN };
+            for finding in findings.iter().take(shown) {
+                let head = format!("    {}  ", finding.location);
+                let used = console::measure_text_width(&head);
+                println!("{}{}", head.dimmed(), layout::truncate(&finding.text, layout::available(used)).dimmed());
+            }
+            if findings.len() > shown {
+                println!("{}", format!("    … 另有 {} 處", findings.len() - shown).dimmed());
+            }
+        }
+        println!();
+    }
+}
+
+/// 在 commit 內文最後加上 TODO: 區塊，列出新增的待辦註記與位置（已有相同項目時略過）
+pub fn with_todo_section(message: &str, report: &Report) -> String {
+    let items: Vec<String> = report
+        .todos()
+        .iter()
+        .map(|finding| {
+            let text = finding.text.strip_prefix("TODO").unwrap_or(&finding.text);
+            let text = text.trim_start_matches([':', '：', ' ']);
+            if text.is_empty() {
+                format!("- {}", finding.location)
+            } else {
+                format!("- {} ({})", text, finding.location)
+            }
+        })
+        .filter(|item| !message.lines().any(|line| line.trim() == item))
+        .collect();
+    if items.is_empty() {
+        return message.to_string();
+    }
+    format!("{}\n\nTODO:\n{}", message.trim_end(), items.join("\n"))
+}
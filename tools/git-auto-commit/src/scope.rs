@@ -0,0 +1,124 @@
+use anyhow::{Context, Result};
+use colored::*;
+use git2::{IndexEntry, ObjectType, Pathspec, PathspecFlags, Repository};
+use std::path::Path;
+
+/// --only 指定的 commit 範圍：範圍外的 staged 檔案在 commit 期間暫時移出 staged，完成後恢復
+#[derive(Default)]
+pub struct Scope {
+    /// --only 的 pathspec（未指定時為空，表示全部的 staged 變更）
+    pub pathspecs: Vec<String>,
+    /// 範圍外的 staged 檔案
+    excluded: Vec<String>,
+}
+
+impl Scope {
+    /// 依 pathspec 將 staged 檔案分為範圍內（保留在 staged_files）與範圍外；未指定 pathspec 時不做任何限制
+    pub fn select(pathspecs: &[String], staged_files: &mut Vec<String>) -> Result<Scope> {
+        if pathspecs.is_empty() {
+            return Ok(Scope::default());
+        }
+        let pathspec = Pathspec::new(pathspecs).context("--only 的 pathspec 格式錯誤")?;
+        let (included, excluded): (Vec<String>, Vec<String>) = staged_files
+            .drain(..)
+            .partition(|file| pathspec.matches_path(Path::new(file), PathspecFlags::DEFAULT));
+        *staged_files = included;
+        Ok(Scope {
+            pathspecs: pathspecs.to_vec(),
+            excluded,
+        })
+    }
+
+    /// 顯示範圍外、這次不 commit 的檔案
+    pub fn print(&self) {
+        if self.excluded.is_empty() {
+            return;
+        }
+        println!(
+            "{}",
+            format!("🎯 只 commit 符合 {} 的變更，以下 {} 個檔案維持 staged：", self.pathspecs.join(" "), self.excluded.len())
+                .cyan()
+        );
+        for file in &self.excluded {
+            println!("  {}", file.dimmed());
+        }
+        println!();
+    }
+
+    /// 暫時將範圍外的檔案在 index 中還原為 HEAD 的內容後執行 action（commit 或匯出 patch），
+    /// 結束（或失敗）時恢復原本 staged 的內容
+    pub fn commit<T>(&self, repo: &Repository, action: impl FnOnce() -> Result<T>) -> Result<T> {
+        if self.excluded.is_empty() {
+            return action();
+        }
+
+        // 記錄原本 staged 的項目（None 表示檔案已刪除）
+        let index = repo.index().context("無法讀取 index")?;
+        let staged: Vec<Option<IndexEntry>> = self
+            .excluded
+            .iter()
+            .map(|path| index.get_path(Path::new(path), 0))
+            .collect();
+        let head = repo.head().ok().and_then(|head| head.peel(ObjectType::Commit).ok());
+        repo.reset_default(head.as_ref(), &self.excluded)
+            .context("無法暫時移出範圍外的 staged 變更")?;
+
+        let result = action();
+
+        let restore = || -> Result<()> {
+            let mut index = repo.index()?;
+            index.read(true)?;
+            for (path, entry) in self.excluded.iter().zip(&staged) {
+                match entry {
+                    Some(entry) => index.add(entry)?,
+                    None => {
+                        let _ = index.remove_path(Path::new(path));
+                    }
+                }
+            }
+            index.write()?;
+            Ok(())
+        };
+        restore().context("無法恢復範圍外的 staged 變更")?;
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(paths: &[&str]) -> Vec<String> {
+        paths.iter().map(|path| path.to_string()).collect()
+    }
+
+    #[test]
+    fn select_without_pathspec_keeps_all_files() {
+        let mut staged = files(&["src/main.rs", "README.md"]);
+        let scope = Scope::select(&[], &mut staged).unwrap();
+        assert_eq!(staged, files(&["src/main.rs", "README.md"]));
+        assert!(scope.excluded.is_empty());
+    }
+
+    #[test]
+    fn select_partitions_by_pathspec() {
+        let mut staged = files(&["src/main.rs", "src/cli.rs", "README.md", "docs/guide.md"]);
+        let scope = Scope::select(&files(&["src", "*.md"]), &mut staged).unwrap();
+        assert_eq!(staged, files(&["src/main.rs", "src/cli.rs", "README.md", "docs/guide.md"]));
+        assert!(scope.excluded.is_empty());
+
+        let mut staged = files(&["src/main.rs", "src/cli.rs", "README.md"]);
+        let scope = Scope::select(&files(&["src/"]), &mut staged).unwrap();
+        assert_eq!(staged, files(&["src/main.rs", "src/cli.rs"]));
+        assert_eq!(scope.excluded, files(&["README.md"]));
+        assert_eq!(scope.pathspecs, files(&["src/"]));
+    }
+
+    #[test]
+    fn select_matches_globs_in_subdirectories() {
+        let mut staged = files(&["src/main.rs", "crates/core/lib.rs", "Cargo.toml"]);
+        let scope = Scope::select(&files(&["*.rs"]), &mut staged).unwrap();
+        assert_eq!(staged, files(&["src/main.rs", "crates/core/lib.rs"]));
+        assert_eq!(scope.excluded, files(&["Cargo.toml"]));
+    }
+}
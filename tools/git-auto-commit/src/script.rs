@@ -0,0 +1,106 @@
+use crate::config::ScriptConfig;
+use crate::plugin::PluginContext;
+use crate::progress;
+use crate::GitSuggestions;
+use anyhow::{Context, Result};
+use colored::*;
+use git_tools_core::path::expand_home;
+use mlua::{Function, Lua, Table};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+thread_local! {
+    /// 已載入的 Lua 腳本（Lua 狀態無法跨執行緒共用）
+    static SCRIPT: RefCell<Option<Lua>> = const { RefCell::new(None) };
+}
+
+fn load(path: &PathBuf, context: &PluginContext) -> Result<Lua> {
+    let code = fs::read_to_string(path).context(format!("無法讀取腳本：{}", path.display()))?;
+    let lua = Lua::new();
+
+    // 腳本可透過全域變數 context 取得執行環境
+    let table = lua.create_table()?;
+    table.set("repo", context.repo.as_str())?;
+    table.set("current_branch", context.current_branch.as_str())?;
+    table.set("files", lua.create_sequence_from(context.files.iter().map(String::as_str))?)?;
+    lua.globals().set("context", table)?;
+
+    lua.load(&code)
+        .set_name(path.display().to_string())
+        .exec()
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .context(format!("腳本執行錯誤：{}", path.display()))?;
+    Ok(lua)
+}
+
+/// 載入設定的 Lua 腳本，失敗時顯示警告並略過
+pub fn init(config: &ScriptConfig, context: &PluginContext) {
+    let Some(path) = &config.path else {
+        return;
+    };
+    let path = expand_home(path);
+
+    match load(&path, context) {
+        Ok(lua) => {
+            println!("{}", format!("📜 已載入腳本：{}", path.display()).dimmed());
+            SCRIPT.with(|script| *script.borrow_mut() = Some(lua));
+        }
+        Err(e) => println!("{}", format!("⚠️  {:#}", e).yellow()),
+    }
+}
+
+/// 取得腳本中定義的函數並執行（腳本未載入或未定義該函數時回傳 None）
+fn call<T>(name: &str, f: impl FnOnce(&Lua, Function) -> mlua::Result<T>) -> Option<Result<T>> {
+    SCRIPT.with(|script| {
+        let script = script.borrow();
+        let lua = script.as_ref()?;
+        let function = lua.globals().get::<_, Option<Function>>(name).ok()??;
+        Some(f(lua, function).map_err(|e| anyhow::anyhow!("{}", e)))
+    })
+}
+
+/// 以腳本的 transform_prompt(prompt) 改寫送往 LLM 的提示詞，失敗時使用原本的提示詞
+pub fn transform_prompt(prompt: &str) -> String {
+    match call("transform_prompt", |_, f| f.call::<_, String>(prompt)) {
+        Some(Ok(transformed)) => transformed,
+        Some(Err(e)) => {
+            progress::println(&format!("⚠️  transform_prompt 執行失敗：{}", e).yellow().to_string());
+            prompt.to_string()
+        }
+        None => prompt.to_string(),
+    }
+}
+
+/// 以腳本的 transform_suggestions(suggestions) 改寫解析後的建議
+///
+/// 傳入與回傳的 table 皆包含 branch_names 與 commit_messages，省略的欄位維持原樣。
+pub fn transform_suggestions(suggestions: &mut GitSuggestions) {
+    let result = call("transform_suggestions", |lua, f| {
+        let input = lua.create_table()?;
+        input.set("branch_names", suggestions.branch_names.clone())?;
+        input.set("commit_messages", suggestions.commit_messages.clone())?;
+        let output: Table = f.call(input)?;
+        Ok((
+            output.get::<_, Option<Vec<String>>>("branch_names")?,
+            output.get::<_, Option<Vec<String>>>("commit_messages")?,
+        ))
+    });
+
+    match result {
+        Some(Ok((branch_names, commit_messages))) => {
+            if let Some(branch_names) = branch_names {
+                suggestions.branch_names = branch_names;
+            }
+            if let Some(commit_messages) = commit_messages {
+                if commit_messages != suggestions.commit_messages {
+                    suggestions.commit_notes.clear();
+                    suggestions.commit_labels.clear();
+                }
+                suggestions.commit_messages = commit_messages;
+            }
+        }
+        Some(Err(e)) => println!("{}", format!("⚠️  transform_suggestions 執行失敗：{}", e).yellow()),
+        None => {}
+    }
+}
@@ -0,0 +1,22 @@
+use crate::config::ScriptConfig;
+use crate::plugin::PluginContext;
+use crate::GitSuggestions;
+use colored::*;
+
+/// 未啟用 lua feature 時不載入腳本；設定了腳本時提示此版本不支援 Lua
+pub fn init(config: &ScriptConfig, _context: &PluginContext) {
+    if let Some(path) = &config.path {
+        println!(
+            "{}",
+            format!("⚠️  此版本編譯時未啟用 lua feature，略過腳本：{}", path).yellow()
+        );
+    }
+}
+
+/// 未啟用 Lua 時提示詞維持原樣
+pub fn transform_prompt(prompt: &str) -> String {
+    prompt.to_string()
+}
+
+/// 未啟用 Lua 時建議維持原樣
+pub fn transform_suggestions(_suggestions: &mut GitSuggestions) {}
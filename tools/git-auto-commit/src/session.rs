@@ -0,0 +1,107 @@
+use crate::config::{get_config_dir, SessionConfig};
+use crate::layout;
+use crate::CommitSummary;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local};
+use colored::*;
+use console::{measure_text_width, pad_str, Alignment};
+use git2::Repository;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// 單次執行建立的 commit
+pub struct Session {
+    started_at: DateTime<Local>,
+    repo: String,
+    commits: Vec<CommitSummary>,
+}
+
+/// session 紀錄檔中的一筆紀錄（JSON Lines 格式，一行一次執行）
+#[derive(Serialize)]
+struct SessionEntry<'a> {
+    started_at: String,
+    finished_at: String,
+    repo: &'a str,
+    commits: &'a [CommitSummary],
+}
+
+fn get_session_path(config: &SessionConfig) -> PathBuf {
+    match &config.path {
+        Some(path) => PathBuf::from(path),
+        None => get_config_dir().join("sessions.jsonl"),
+    }
+}
+
+impl Session {
+    pub fn new(repo: &Repository) -> Self {
+        Self {
+            started_at: Local::now(),
+            repo: repo.workdir().unwrap_or_else(|| repo.path()).display().to_string(),
+            commits: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, summary: CommitSummary) {
+        self.commits.push(summary);
+    }
+
+    /// 建立多個 commit 時顯示摘要表格，並依設定寫入 session 紀錄檔
+    pub fn finish(&self, config: &SessionConfig) {
+        if self.commits.len() > 1 {
+            self.print_summary();
+        }
+        if config.enabled && !self.commits.is_empty() {
+            match self.write(config) {
+                Ok(path) => println!("{}", format!("📒 已寫入 session 紀錄：{}", path.display()).dimmed()),
+                Err(e) => println!("{}", format!("⚠️  無法寫入 session 紀錄：{:#}", e).yellow()),
+            }
+        }
+    }
+
+    fn print_summary(&self) {
+        println!();
+        println!("{}", format!("📋 本次共建立 {} 個 commit：", self.commits.len()).cyan());
+        let type_width = self
+            .commits
+            .iter()
+            .map(|c| measure_text_width(c.commit_type().unwrap_or("-")))
+            .max()
+            .unwrap_or(1);
+        // SHA、type 與檔案數之後剩下的寬度
+        let subject_width = layout::available(2 + 7 + 2 + type_width + 2 + 10 + 2);
+        for commit in &self.commits {
+            println!(
+                "  {}  {}  {}  {}",
+                commit.short_sha().yellow(),
+                pad_str(commit.commit_type().unwrap_or("-"), type_width, Alignment::Left, None),
+                format!("{:>3} 個檔案", commit.files_changed).dimmed(),
+                layout::truncate(&commit.subject, subject_width),
+            );
+        }
+    }
+
+    fn write(&self, config: &SessionConfig) -> Result<PathBuf> {
+        let path = get_session_path(config);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("無法建立 session 紀錄目錄")?;
+        }
+
+        let entry = SessionEntry {
+            started_at: self.started_at.to_rfc3339(),
+            finished_at: Local::now().to_rfc3339(),
+            repo: &self.repo,
+            commits: &self.commits,
+        };
+        let line = serde_json::to_string(&entry).context("無法序列化 session 紀錄")?;
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .context(format!("無法開啟 session 紀錄檔：{}", path.display()))?;
+        writeln!(file, "{}", line).context("無法寫入 session 紀錄")?;
+        Ok(path)
+    }
+}
@@ -0,0 +1,212 @@
+use crate::config::{get_config_path, LlmConfig, Workflow};
+use crate::layout;
+use crate::plain;
+use crate::progress::Timeline;
+use anyhow::{Context, Result};
+use colored::*;
+use console::Term;
+use dialoguer::{Confirm, Input, Select};
+use serde::Serialize;
+use std::fs;
+
+/// 設定精靈寫入的設定（其餘設定使用預設值）
+#[derive(Serialize)]
+struct SetupFile {
+    command: String,
+    prompt_flag: String,
+    model_flag: String,
+    model: String,
+    extra_args: Vec<String>,
+    language: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    workflow: Option<Workflow>,
+}
+
+/// 可選擇的 LLM CLI：名稱、指令、提示參數、模型參數、預設模型
+const BACKENDS: &[(&str, &str, &str, &str, &str)] = &[
+    ("Gemini CLI", "gemini", "-p", "--model", "gemini-2.5-flash"),
+    ("Claude CLI", "claude", "-p", "--model", "sonnet"),
+];
+
+const LANGUAGES: &[&str] = &["繁體中文", "简体中文", "English", "日本語"];
+
+/// 首次執行（尚無設定檔）且在終端機中時啟動設定精靈
+pub fn run_if_first_run() -> Result<()> {
+    if get_config_path().exists() || !Term::stdout().is_term() {
+        return Ok(());
+    }
+
+    println!("{}", "👋 尚未建立設定檔，開始初次設定".cyan());
+    let items = vec!["開始設定", "略過（以預設值建立設定檔）"];
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("請選擇")
+        .items(&items)
+        .default(0)
+        .interact()?;
+
+    if selection == 0 {
+        run_wizard()
+    } else {
+        let defaults = LlmConfig::default();
+        write_config(&SetupFile {
+            command: defaults.command,
+            prompt_flag: defaults.prompt_flag,
+            model_flag: defaults.model_flag,
+            model: defaults.model,
+            extra_args: defaults.extra_args,
+            language: defaults.language,
+            workflow: None,
+        })
+    }
+}
+
+/// setup 子指令：重新執行設定精靈
+pub fn run() -> Result<()> {
+    println!("\n{}\n", "⚙️  設定精靈".cyan().bold());
+
+    let path = get_config_path();
+    if path.exists()
+        && !Confirm::with_theme(plain::theme())
+            .with_prompt(format!("將覆寫現有設定檔 {}，是否繼續？", path.display()))
+            .default(false)
+            .interact()?
+    {
+        return Ok(());
+    }
+
+    run_wizard()
+}
+
+/// 依序選擇 LLM CLI、測試連線、選擇語言與工作流程，並寫入設定檔
+fn run_wizard() -> Result<()> {
+    let mut config = LlmConfig::default();
+
+    loop {
+        choose_backend(&mut config)?;
+        if test_connection(&config) {
+            break;
+        }
+
+        let items = vec!["重新選擇 LLM CLI", "仍然使用此設定"];
+        let selection = Select::with_theme(plain::theme())
+            .with_prompt("連線測試失敗")
+            .items(&items)
+            .default(0)
+            .interact()?;
+        if selection == 1 {
+            break;
+        }
+    }
+
+    let language = Select::with_theme(plain::theme())
+        .with_prompt("commit 訊息使用的語言")
+        .items(LANGUAGES)
+        .default(0)
+        .interact()?;
+
+    let workflows = [
+        ("不使用（預設分支前綴）", None),
+        ("git-flow", Some(Workflow::GitFlow)),
+        ("github-flow", Some(Workflow::GithubFlow)),
+        ("trunk", Some(Workflow::Trunk)),
+    ];
+    let labels: Vec<&str> = workflows.iter().map(|(label, _)| *label).collect();
+    let workflow = Select::with_theme(plain::theme())
+        .with_prompt("工作流程預設")
+        .items(&labels)
+        .default(0)
+        .interact()?;
+
+    write_config(&SetupFile {
+        command: config.command,
+        prompt_flag: config.prompt_flag,
+        model_flag: config.model_flag,
+        model: config.model,
+        extra_args: config.extra_args,
+        language: LANGUAGES[language].to_string(),
+        workflow: workflows[workflow].1,
+    })
+}
+
+/// 選擇 LLM CLI，或輸入自訂的指令與參數
+fn choose_backend(config: &mut LlmConfig) -> Result<()> {
+    let mut items: Vec<String> = BACKENDS
+        .iter()
+        .map(|(name, command, ..)| format!("{}（{}）", name, command))
+        .collect();
+    items.push("自訂 LLM CLI".to_string());
+
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("選擇 LLM CLI")
+        .items(&items)
+        .default(0)
+        .interact()?;
+
+    let (command, prompt_flag, model_flag, model) = match BACKENDS.get(selection) {
+        Some((_, command, prompt_flag, model_flag, model)) => (
+            command.to_string(),
+            prompt_flag.to_string(),
+            model_flag.to_string(),
+            model.to_string(),
+        ),
+        None => (
+            Input::with_theme(plain::theme())
+                .with_prompt("指令")
+                .interact_text()?,
+            Input::with_theme(plain::theme())
+                .with_prompt("提示參數")
+                .default("-p".to_string())
+                .interact_text()?,
+            Input::with_theme(plain::theme())
+                .with_prompt("模型參數")
+                .default("--model".to_string())
+                .interact_text()?,
+            String::new(),
+        ),
+    };
+
+    config.model = Input::with_theme(plain::theme())
+        .with_prompt("模型名稱")
+        .with_initial_text(model)
+        .interact_text()?;
+    config.command = command;
+    config.prompt_flag = prompt_flag;
+    config.model_flag = model_flag;
+    Ok(())
+}
+
+/// 以簡短的提示詞測試 LLM CLI 是否可用
+fn test_connection(config: &LlmConfig) -> bool {
+    let mut timeline = Timeline::new();
+    timeline.start(&format!("測試 {} 連線", config.command));
+    match crate::run_llm_command("請只回覆 OK", config) {
+        Ok(response) => {
+            timeline.finish();
+            let preview = layout::truncate(response.trim(), 60);
+            println!("{}", format!("✓ 連線成功，回應：{}", preview).green());
+            true
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("✗ 連線失敗：{}", e).red());
+            false
+        }
+    }
+}
+
+fn write_config(file: &SetupFile) -> Result<()> {
+    let path = get_config_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("無法建立設定目錄")?;
+    }
+
+    let content = format!(
+        "# 由 git-auto-commit 設定精靈產生，其他設定請參考 config.toml.example\n\n{}",
+        toml::to_string(file).context("無法序列化設定")?
+    );
+    fs::write(&path, content).context(format!("無法寫入設定檔：{}", path.display()))?;
+
+    println!("{}", format!("✓ 已建立設定檔：{}", path.display()).green());
+    println!();
+    Ok(())
+}
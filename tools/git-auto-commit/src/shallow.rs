@@ -0,0 +1,88 @@
+use crate::config::{DeepenMode, FetchConfig};
+use crate::plain;
+use crate::progress::Timeline;
+use anyhow::Result;
+use colored::*;
+use console::Term;
+use dialoguer::Confirm;
+use git_tools_core::git::git_in;
+use std::path::Path;
+
+/// 是否為淺層 clone（以 --depth 等方式 clone，只有部分歷史）
+pub fn is_shallow(dir: &Path) -> bool {
+    git_in(dir, &["rev-parse", "--is-shallow-repository"]).is_ok_and(|output| output.trim() == "true")
+}
+
+/// partial clone 的過濾條件（例如 blob:none），不是 partial clone 時為 None
+fn partial_filter(dir: &Path) -> Option<String> {
+    let remote = git_in(dir, &["config", "--get", "extensions.partialClone"]).ok()?;
+    let remote = remote.trim();
+    Some(
+        git_in(dir, &["config", "--get", &format!("remote.{}.partialclonefilter", remote)])
+            .map_or_else(|_| format!("promisor remote：{}", remote), |filter| filter.trim().to_string()),
+    )
+}
+
+/// 加深淺層 clone 的歷史：by 為 0 時取得完整歷史（--unshallow），否則往前多取 by 個 commit
+fn deepen(dir: &Path, by: u32) -> Result<()> {
+    let depth = if by == 0 {
+        "--unshallow".to_string()
+    } else {
+        format!("--deepen={}", by)
+    };
+    git_in(dir, &["fetch", "--quiet", &depth])?;
+    Ok(())
+}
+
+/// 依賴歷史的功能（feature）開始前檢查 clone 是否完整：淺層 clone 時依 fetch.deepen 設定詢問、
+/// 自動或不加深歷史（離線模式一律不加深）；partial clone 時提醒舊版本的內容會在讀取時才從 remote 下載。
+/// 回傳歷史是否仍不完整（仍為淺層 clone），呼叫端據此調整結果或提示
+pub fn check(dir: &Path, config: &FetchConfig, feature: &str, offline: bool) -> Result<bool> {
+    if let Some(filter) = partial_filter(dir) {
+        println!(
+            "{}",
+            format!("ℹ️  這是 partial clone（{}），{}需要的舊版本內容會在讀取時從 remote 下載，離線時可能失敗", filter, feature)
+                .dimmed()
+        );
+    }
+    if !is_shallow(dir) {
+        return Ok(false);
+    }
+
+    println!("{}", format!("⚠️  這是淺層 clone（shallow），只有部分歷史，{}可能不完整", feature).yellow());
+    let action = if config.deepen_by == 0 {
+        "取得完整歷史（git fetch --unshallow）".to_string()
+    } else {
+        format!("往前多取 {} 個 commit（git fetch --deepen={}）", config.deepen_by, config.deepen_by)
+    };
+    let deepen_now = match config.deepen {
+        _ if offline => false,
+        DeepenMode::Never => false,
+        DeepenMode::Always => true,
+        // 不在終端機中（例如 CI）時無法詢問，視為不加深
+        DeepenMode::Ask => {
+            Term::stderr().is_term()
+                && Confirm::with_theme(plain::theme())
+                    .with_prompt(format!("要{}嗎？", action))
+                    .default(true)
+                    .interact()?
+        }
+    };
+    if !deepen_now {
+        println!("{}", "可執行 git fetch --unshallow 取得完整歷史".dimmed());
+        println!();
+        return Ok(true);
+    }
+
+    let mut timeline = Timeline::new();
+    timeline.start(&action);
+    match deepen(dir, config.deepen_by) {
+        Ok(()) => timeline.finish(),
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  {}，改用現有的歷史", e).yellow());
+        }
+    }
+    println!();
+    Ok(is_shallow(dir))
+}
@@ -0,0 +1,117 @@
+use anyhow::Result;
+use colored::*;
+use console::{pad_str, Alignment, Term};
+use git_tools_core::layout;
+use std::collections::HashSet;
+
+/// 每一欄的最小寬度，終端機太窄時改為上下排列
+const MIN_COLUMN_WIDTH: usize = 24;
+/// 欄與欄之間的分隔
+const GUTTER: &str = " │ ";
+
+/// 切成比對用的詞：連續的英數字為一個詞，其他字元（中日韓文字、標點、空白）各自為一個詞
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(s) = start.take() {
+            tokens.push(&text[s..i]);
+        }
+        tokens.push(&text[i..i + c.len_utf8()]);
+    }
+    if let Some(s) = start {
+        tokens.push(&text[s..]);
+    }
+    tokens
+}
+
+/// 以醒目的顏色標示共同的詞以外的部分（空白不標示）
+fn highlight(line: &str, common: &HashSet<&str>) -> String {
+    tokens(line)
+        .into_iter()
+        .map(|token| {
+            if token.trim().is_empty() || common.contains(token) {
+                token.to_string()
+            } else {
+                token.yellow().bold().to_string()
+            }
+        })
+        .collect()
+}
+
+/// 將每個建議依欄寬折行並標示差異
+fn columns(messages: &[String], width: usize, common: &HashSet<&str>) -> Vec<Vec<String>> {
+    messages
+        .iter()
+        .map(|message| {
+            message
+                .lines()
+                .flat_map(|line| layout::wrap(line, width))
+                .map(|line| highlight(&line, common))
+                .collect()
+        })
+        .collect()
+}
+
+/// 排列所有建議的完整內容：終端機夠寬時並排，否則上下排列；只出現在部分建議中的詞以黃色粗體標示
+pub fn render(messages: &[String]) -> String {
+    // 所有建議都有的詞視為相同的部分
+    let mut common: HashSet<&str> = messages.first().map(|m| tokens(m).into_iter().collect()).unwrap_or_default();
+    for message in messages.iter().skip(1) {
+        let present: HashSet<&str> = tokens(message).into_iter().collect();
+        common.retain(|token| present.contains(token));
+    }
+
+    let total = layout::width();
+    let count = messages.len().max(1);
+    let column_width = total.saturating_sub(GUTTER.chars().count() * (count - 1)) / count;
+    let mut output = Vec::new();
+
+    if column_width < MIN_COLUMN_WIDTH {
+        for (i, column) in columns(messages, total, &common).into_iter().enumerate() {
+            output.push(format!("── {} ──", i + 1).cyan().bold().to_string());
+            output.extend(column);
+            output.push(String::new());
+        }
+        return output.join("\n");
+    }
+
+    let headers: Vec<String> = (1..=count)
+        .map(|i| pad_str(&format!("{}.", i).cyan().bold().to_string(), column_width, Alignment::Left, None).into_owned())
+        .collect();
+    output.push(headers.join(&GUTTER.dimmed().to_string()));
+    output.push(vec!["─".repeat(column_width); count].join(&"─┼─".dimmed().to_string()).dimmed().to_string());
+
+    let columns = columns(messages, column_width, &common);
+    let rows = columns.iter().map(Vec::len).max().unwrap_or(0);
+    for row in 0..rows {
+        let cells: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                let cell = column.get(row).map(String::as_str).unwrap_or("");
+                pad_str(cell, column_width, Alignment::Left, None).into_owned()
+            })
+            .collect();
+        output.push(cells.join(&GUTTER.dimmed().to_string()).trim_end().to_string());
+    }
+    output.push(String::new());
+    output.join("\n")
+}
+
+/// 「比較建議」：顯示所有建議的完整內容並標示差異，超過一個畫面時在 pager 中顯示
+pub fn show(messages: &[String]) -> Result<()> {
+    let text = render(messages);
+    let height = Term::stdout().size_checked().map(|(rows, _)| rows as usize);
+    println!();
+    match height {
+        Some(height) if text.lines().count() + 2 > height => layout::page(&format!("{}\n", text)),
+        _ => {
+            println!("{}", text);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use crate::CommitSummary;
+use anyhow::{Context, Result};
+use git2::{IndexEntry, IndexTime, Oid, Repository};
+use git_tools_core::git::{blob_content, git, head_tree};
+use regex::Regex;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::LazyLock;
+
+static HUNK_HEADER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+\d+(?:,\d+)? @@").unwrap());
+
+/// staged 變更中的一個 hunk（以 -U0 產生，行號對應 HEAD 的內容）
+pub struct Hunk {
+    /// 被取代的第一行（由 1 開始；純新增時為插入位置的前一行）
+    pub old_start: usize,
+    pub old_lines: usize,
+    /// 刪除的行（不含 -）
+    pub removed: Vec<String>,
+    /// 新增的行（含換行字元）
+    added: Vec<String>,
+}
+
+impl Hunk {
+    /// 新增的行（不含換行字元）
+    pub fn added_lines(&self) -> impl Iterator<Item = &str> {
+        self.added.iter().map(|l| l.trim_end_matches('\n'))
+    }
+}
+
+/// 單一檔案的 staged 變更
+pub struct FileChange {
+    pub path: String,
+    pub hunks: Vec<Hunk>,
+    /// HEAD 中的內容（新檔案或二進位檔為空字串）
+    pub original: String,
+}
+
+/// 可分配到 commit 的最小單位：整個檔案或檔案中的一個 hunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Unit {
+    pub file: usize,
+    pub hunk: Option<usize>,
+}
+
+/// 拆分後的一個 commit
+pub struct Group {
+    pub message: String,
+    pub units: Vec<Unit>,
+}
+
+/// 所有 staged 變更（拆分 commit 時使用）
+pub struct StagedChanges {
+    pub files: Vec<FileChange>,
+}
+
+/// 解析 git diff -U0 的輸出
+fn parse_hunks(diff: &str) -> Vec<Hunk> {
+    let mut hunks: Vec<Hunk> = Vec::new();
+    let mut last_added = false;
+    for line in diff.lines() {
+        if let Some(caps) = HUNK_HEADER.captures(line) {
+            let old_start = caps[1].parse().unwrap_or(0);
+            let old_lines = caps.get(2).map_or(1, |m| m.as_str().parse().unwrap_or(1));
+            hunks.push(Hunk {
+                old_start,
+                old_lines,
+                removed: Vec::new(),
+                added: Vec::new(),
+            });
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+        if let Some(text) = line.strip_prefix('+') {
+            hunk.added.push(format!("{}\n", text));
+            last_added = true;
+        } else if let Some(text) = line.strip_prefix('-') {
+            hunk.removed.push(text.to_string());
+            last_added = false;
+        } else if line.starts_with("\\ No newline") && last_added {
+            // 新增的最後一行沒有換行（刪除的行內容取自原始檔案，不需處理）
+            if let Some(last) = hunk.added.last_mut() {
+                last.pop();
+            }
+        }
+    }
+
+    // 逐行取代的 hunk（刪除與新增行數相同）拆成單行，讓相鄰的變更可以分到不同 commit
+    hunks
+        .into_iter()
+        .flat_map(|hunk| {
+            if hunk.old_lines > 1 && hunk.removed.len() == hunk.old_lines && hunk.added.len() == hunk.old_lines {
+                hunk.removed
+                    .into_iter()
+                    .zip(hunk.added)
+                    .enumerate()
+                    .map(|(i, (removed, added))| Hunk {
+                        old_start: hunk.old_start + i,
+                        old_lines: 1,
+                        removed: vec![removed],
+                        added: vec![added],
+                    })
+                    .collect()
+            } else {
+                vec![hunk]
+            }
+        })
+        .collect()
+}
+
+impl StagedChanges {
+    /// 讀取 staged 的檔案與 hunks
+    pub fn load(repo: &Repository, files: &[String]) -> Result<Self> {
+        let tree = head_tree(repo);
+        let mut changes = Vec::new();
+
+        for path in files {
+            let diff = git(&["diff", "--cached", "-U0", "--no-color", "--no-ext-diff", "--no-renames", "--", path])?;
+            let original = blob_content(repo, tree.as_ref(), path)
+                .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+                .unwrap_or_default();
+
+            changes.push(FileChange {
+                path: path.clone(),
+                hunks: parse_hunks(&diff),
+                original,
+            });
+        }
+
+        Ok(Self { files: changes })
+    }
+
+    /// 以原始內容套用選取的 hunks，產生中間狀態的檔案內容
+    fn partial_content(&self, file: usize, applied: &HashSet<Unit>) -> Vec<u8> {
+        let change = &self.files[file];
+        let mut lines: Vec<String> = change.original.split_inclusive('\n').map(String::from).collect();
+
+        // 由後往前套用，避免行號位移
+        let mut selected: Vec<&Hunk> = change
+            .hunks
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| applied.contains(&Unit { file, hunk: Some(*i) }))
+            .map(|(_, hunk)| hunk)
+            .collect();
+        selected.sort_by_key(|hunk| std::cmp::Reverse(hunk.old_start));
+
+        for hunk in selected {
+            let (start, end) = if hunk.old_lines == 0 {
+                (hunk.old_start, hunk.old_start)
+            } else {
+                (hunk.old_start - 1, hunk.old_start - 1 + hunk.old_lines)
+            };
+            let end = end.min(lines.len());
+            let start = start.min(end);
+            lines.splice(start..end, hunk.added.iter().cloned());
+        }
+
+        lines.concat().into_bytes()
+    }
+
+    /// 檔案的所有變更是否都已套用
+    fn fully_applied(&self, file: usize, applied: &HashSet<Unit>) -> bool {
+        applied.contains(&Unit { file, hunk: None })
+            || (0..self.files[file].hunks.len()).all(|i| applied.contains(&Unit { file, hunk: Some(i) }))
+    }
+}
+
+/// 以 tree 的項目建立 index 項目（內容由 add_frombuffer 填入）
+fn index_entry(path: &str, mode: u32, id: Oid) -> IndexEntry {
+    IndexEntry {
+        ctime: IndexTime::new(0, 0),
+        mtime: IndexTime::new(0, 0),
+        dev: 0,
+        ino: 0,
+        mode,
+        uid: 0,
+        gid: 0,
+        file_size: 0,
+        id,
+        flags: 0,
+        flags_extended: 0,
+        path: path.as_bytes().to_vec(),
+    }
+}
+
+/// 依序將每一組變更寫入 index 並 commit；結束（或失敗）時恢復原本 staged 的內容，未分配的變更仍保持 staged
+pub fn commit_groups(
+    repo: &Repository,
+    changes: &StagedChanges,
+    groups: &[Group],
+    mut commit: impl FnMut(&Group) -> Result<CommitSummary>,
+) -> Result<Vec<CommitSummary>> {
+    // 記錄原本 staged 的項目（None 表示檔案已刪除）
+    let index = repo.index().context("無法讀取 index")?;
+    let staged: Vec<Option<IndexEntry>> = changes
+        .files
+        .iter()
+        .map(|f| index.get_path(Path::new(&f.path), 0))
+        .collect();
+    let original_tree = head_tree(repo);
+
+    let restore = || -> Result<()> {
+        let mut index = repo.index()?;
+        index.read(true)?;
+        for (change, entry) in changes.files.iter().zip(&staged) {
+            match entry {
+                Some(entry) => index.add(entry)?,
+                None => {
+                    let _ = index.remove_path(Path::new(&change.path));
+                }
+            }
+        }
+        index.write()?;
+        Ok(())
+    };
+
+    let mut applied: HashSet<Unit> = HashSet::new();
+    let mut summaries = Vec::new();
+
+    for group in groups {
+        applied.extend(group.units.iter().copied());
+
+        let result = (|| -> Result<CommitSummary> {
+            let mut index = repo.index()?;
+            index.read(true)?;
+            if let Some(tree) = head_tree(repo) {
+                index.read_tree(&tree)?;
+            } else {
+                index.clear()?;
+            }
+
+            for (file, change) in changes.files.iter().enumerate() {
+                if !applied.iter().any(|u| u.file == file) {
+                    continue;
+                }
+                let path = Path::new(&change.path);
+                if changes.fully_applied(file, &applied) {
+                    match &staged[file] {
+                        Some(entry) => index.add(entry)?,
+                        None => {
+                            let _ = index.remove_path(path);
+                        }
+                    }
+                } else {
+                    let mode = staged[file]
+                        .as_ref()
+                        .map(|e| e.mode)
+                        .or_else(|| {
+                            original_tree
+                                .as_ref()
+                                .and_then(|t| t.get_path(path).ok())
+                                .map(|e| e.filemode() as u32)
+                        })
+                        .unwrap_or(0o100644);
+                    let entry = index_entry(&change.path, mode, Oid::zero());
+                    index.add_frombuffer(&entry, &changes.partial_content(file, &applied))?;
+                }
+            }
+            index.write()?;
+            commit(group)
+        })();
+
+        match result {
+            Ok(summary) => summaries.push(summary),
+            Err(e) => {
+                restore().context("無法恢復 staged 的變更")?;
+                return Err(e);
+            }
+        }
+    }
+
+    restore().context("無法恢復 staged 的變更")?;
+    Ok(summaries)
+}
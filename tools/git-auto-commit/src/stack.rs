@@ -0,0 +1,378 @@
+use crate::cli::StackArgs;
+use crate::config::{load_llm_config, LlmConfig};
+use crate::forge;
+use crate::plain;
+use crate::progress::Timeline;
+use crate::remote;
+use crate::repo_state;
+use crate::shallow;
+use crate::workflow;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{Confirm, Select};
+use git2::{BranchType, Oid, Repository};
+use git_tools_core::git::git;
+use std::env;
+use std::process::Command;
+
+/// 堆疊中的一層：分支與其 base（下一層分支或主幹）
+pub struct Layer {
+    pub branch: String,
+    pub parent: String,
+    /// 比較 commit 時使用的 parent ref（主幹只存在於 remote 時為 remote-tracking 分支，例如 origin/main）
+    pub parent_ref: String,
+    /// parent..branch 的 commit 標題（由舊到新）
+    pub commits: Vec<String>,
+}
+
+/// 取得本地分支指向的 commit
+fn branch_tip(repo: &Repository, name: &str) -> Option<Oid> {
+    repo.find_branch(name, BranchType::Local)
+        .ok()
+        .and_then(|b| b.get().target())
+}
+
+/// 決定主幹分支：--base 指定值、此 repository 上次以 --base 指定且仍存在的分支、workflow 預設的 base 分支、
+/// origin/HEAD 指向的分支，或本地的 main / master
+pub fn detect_trunk(repo: &Repository, base: Option<&str>, config: &LlmConfig) -> Result<String> {
+    let remembered = repo_state::load(repo, config)
+        .base_branch
+        .filter(|name| branch_tip(repo, name).is_some());
+    if let Some(base) = base.or(remembered.as_deref()).or(workflow::base_branch(config.workflow)) {
+        return Ok(base.to_string());
+    }
+    if let Some(name) = remote::default_branch(repo, "origin") {
+        return Ok(name);
+    }
+    ["main", "master"]
+        .iter()
+        .find(|name| branch_tip(repo, name).is_some())
+        .map(|name| name.to_string())
+        .context("無法判斷主幹分支，請使用 --base 指定")
+}
+
+/// 主幹在本地可用的 ref：本地分支，沒有時改用 remote-tracking 分支（優先 origin），都不存在時為 None
+pub fn trunk_ref(repo: &Repository, trunk: &str) -> Option<String> {
+    if branch_tip(repo, trunk).is_some() {
+        return Some(trunk.to_string());
+    }
+    let remotes = repo.remotes().ok()?;
+    let mut names: Vec<&str> = remotes.iter().flatten().collect();
+    names.sort_by_key(|name| *name != "origin");
+    names
+        .into_iter()
+        .map(|remote| format!("{}/{}", remote, trunk))
+        .find(|name| repo.find_branch(name, BranchType::Remote).is_ok())
+}
+
+/// 找出分支的 parent：在 commit 歷史上最接近的祖先分支，找不到時為主幹
+fn find_parent(repo: &Repository, branch: &str, tips: &[(String, Oid)], trunk: &str) -> String {
+    let Some(tip) = branch_tip(repo, branch) else {
+        return trunk.to_string();
+    };
+
+    tips.iter()
+        .filter(|(name, oid)| {
+            name != branch
+                && name != trunk
+                && *oid != tip
+                && repo.graph_descendant_of(tip, *oid).unwrap_or(false)
+        })
+        .filter_map(|(name, oid)| {
+            repo.graph_ahead_behind(tip, *oid)
+                .ok()
+                .map(|(ahead, _)| (name, ahead))
+        })
+        .min_by_key(|(_, ahead)| *ahead)
+        .map(|(name, _)| name.to_string())
+        .unwrap_or_else(|| trunk.to_string())
+}
+
+/// 取得 parent..branch 的 commit 標題
+fn layer_commits(parent: &str, branch: &str) -> Result<Vec<String>> {
+    let output = git(&["log", "--reverse", "--format=%s", &format!("{}..{}", parent, branch)])?;
+    Ok(output
+        .lines()
+        .map(|line| line.to_string())
+        .collect())
+}
+
+/// 依 merge base 計算當前分支所在的堆疊（由靠近主幹的一層開始）
+///
+/// 往下沿 parent 走到主幹；往上只在剛好有一個子分支時繼續，避免分岔時猜錯方向。
+pub fn build_stack(repo: &Repository, current: &str, trunk: &str, trunk_ref: &str) -> Result<Vec<Layer>> {
+    let tips: Vec<(String, Oid)> = repo
+        .branches(Some(BranchType::Local))
+        .context("無法讀取本地分支")?
+        .flatten()
+        .filter_map(|(b, _)| {
+            let name = b.name().ok().flatten()?.to_string();
+            let oid = b.get().target()?;
+            Some((name, oid))
+        })
+        .collect();
+
+    let parents: Vec<(String, String)> = tips
+        .iter()
+        .filter(|(name, _)| name != trunk)
+        .map(|(name, _)| (name.clone(), find_parent(repo, name, &tips, trunk)))
+        .collect();
+    let parent_of = |branch: &str| {
+        parents
+            .iter()
+            .find(|(name, _)| name == branch)
+            .map(|(_, parent)| parent.clone())
+    };
+
+    // 往下走到主幹
+    let mut chain = vec![current.to_string()];
+    while let Some(parent) = parent_of(chain.last().map(String::as_str).unwrap_or(trunk)) {
+        if parent == trunk || chain.contains(&parent) {
+            break;
+        }
+        chain.push(parent);
+    }
+    chain.reverse();
+
+    // 往上走到堆疊頂端
+    loop {
+        let top = chain.last().cloned().unwrap_or_default();
+        let children: Vec<&String> = parents
+            .iter()
+            .filter(|(_, parent)| *parent == top)
+            .map(|(name, _)| name)
+            .collect();
+        if children.len() != 1 || chain.contains(children[0]) {
+            break;
+        }
+        chain.push(children[0].clone());
+    }
+
+    let mut layers = Vec::new();
+    let mut parent = trunk.to_string();
+    let mut parent_ref = trunk_ref.to_string();
+    for branch in chain {
+        if branch == trunk {
+            continue;
+        }
+        layers.push(Layer {
+            commits: layer_commits(&parent_ref, &branch)?,
+            parent: parent.clone(),
+            parent_ref: parent_ref.clone(),
+            branch: branch.clone(),
+        });
+        parent = branch.clone();
+        parent_ref = branch;
+    }
+
+    Ok(layers)
+}
+
+/// 顯示分支堆疊
+fn print_stack(trunk: &str, layers: &[Layer], current: &str) {
+    println!("{}", format!("📚 分支堆疊（base：{}）", trunk).cyan());
+    println!("  {}", trunk.dimmed());
+    for (depth, layer) in layers.iter().enumerate() {
+        let indent = "   ".repeat(depth);
+        let marker = if layer.branch == current { "  ← 目前" } else { "" };
+        println!(
+            "  {}└─ {} {}{}",
+            indent,
+            layer.branch.bold(),
+            format!("（{} 個 commit）", layer.commits.len()).dimmed(),
+            marker.green()
+        );
+    }
+    println!();
+}
+
+/// 取得分支在 remote 上的 commit（未推送時為 None）
+fn remote_tip(repo: &Repository, remote: &str, branch: &str) -> Option<Oid> {
+    repo.find_reference(&format!("refs/remotes/{}/{}", remote, branch))
+        .ok()
+        .and_then(|r| r.target())
+}
+
+/// 推送分支到 remote（堆疊在 rebase 後需要覆寫，因此使用 --force-with-lease）
+fn push_branch(remote: &str, branch: &str) -> Result<()> {
+    let output = Command::new("git")
+        .args(["push", "--quiet", "--force-with-lease", "-u", remote, branch])
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .output()
+        .context("無法執行 git push")?;
+    if output.status.success() {
+        println!("{}", format!("✓ 已推送 {}", branch).green());
+        Ok(())
+    } else {
+        let error = String::from_utf8_lossy(&output.stderr);
+        println!("{}", format!("✗ 推送 {} 失敗：{}", branch, error.trim()).red());
+        anyhow::bail!("推送失敗")
+    }
+}
+
+/// 以 LLM 生成 PR 標題與說明，失敗時以 commit 標題組成
+fn generate_description(layer: &Layer, config: &LlmConfig) -> (String, String) {
+    let fallback = || {
+        let title = layer
+            .commits
+            .last()
+            .cloned()
+            .unwrap_or_else(|| layer.branch.clone());
+        let body = layer
+            .commits
+            .iter()
+            .map(|c| format!("- {}", c))
+            .collect::<Vec<_>>()
+            .join("\n");
+        (title, body)
+    };
+
+    let Ok(diff) = git(&["diff", &format!("{}...{}", layer.parent_ref, layer.branch)]) else {
+        return fallback();
+    };
+    let diff_preview: String = diff.chars().take(8000).collect();
+
+    let prompt = config
+        .pr_prompt
+        .replace("{branch}", &layer.branch)
+        .replace("{base}", &layer.parent)
+        .replace("{commits}", &layer.commits.join("\n"))
+        .replace("{stats}", &crate::get_diff_stats(&diff))
+        .replace("{diff}", &diff_preview)
+        .replace("{language}", &config.language);
+
+    let mut timeline = Timeline::new();
+    timeline.start(&format!("生成 {} 的 PR 說明", layer.branch));
+    match crate::call_llm_cli(&prompt, config) {
+        Ok(response) => {
+            timeline.finish();
+            let mut lines = response.lines().skip_while(|l| l.trim().is_empty());
+            let title = lines.next().unwrap_or("").trim().to_string();
+            let body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+            if title.is_empty() {
+                fallback()
+            } else {
+                (title, body)
+            }
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 呼叫失敗：{}，改用 commit 標題", e).yellow());
+            fallback()
+        }
+    }
+}
+
+/// stack 子指令：顯示分支堆疊，並為每一層建立或更新 base 正確的 PR
+pub fn run(args: &StackArgs) -> Result<()> {
+    println!("\n{}\n", "📚 堆疊分支 PR".cyan().bold());
+
+    let current_dir = env::current_dir().context("無法取得當前目錄")?;
+    let repo = Repository::open(&current_dir).context("✗ 錯誤：當前目錄不是 Git repository")?;
+    let current = crate::get_current_branch(&repo)?;
+    let config = load_llm_config();
+    let trunk = detect_trunk(&repo, args.base.as_deref(), &config)?;
+    if let Some(base) = &args.base {
+        repo_state::update(&repo, &config, |state| state.base_branch = Some(base.clone()));
+    }
+
+    if current == trunk {
+        println!("{}", format!("⚠️  目前位於主幹分支 {}，沒有堆疊的分支", trunk).yellow());
+        return Ok(());
+    }
+
+    shallow::check(&current_dir, &config.fetch, "堆疊分支的判斷", false)?;
+    let Some(trunk_ref) = trunk_ref(&repo, &trunk) else {
+        println!(
+            "{}",
+            format!("⚠️  找不到主幹分支 {}（本地與 remote 都沒有），無法判斷堆疊；請先 git fetch 或以 --base 指定", trunk)
+                .yellow()
+        );
+        return Ok(());
+    };
+    if trunk_ref != trunk {
+        println!("{}", format!("ℹ️  本地沒有 {} 分支，改以 {} 比較", trunk, trunk_ref).dimmed());
+    }
+    let layers = build_stack(&repo, &current, &trunk, &trunk_ref)?;
+    print_stack(&trunk, &layers, &current);
+
+    if args.dry_run {
+        return Ok(());
+    }
+
+    if !Confirm::with_theme(plain::theme())
+        .with_prompt("為每一層建立或更新 PR？")
+        .default(true)
+        .interact()?
+    {
+        return Ok(());
+    }
+
+    let remote = &args.remote;
+    let url = repo
+        .find_remote(remote)
+        .ok()
+        .and_then(|r| r.url().map(String::from))
+        .context(format!("找不到 remote：{}", remote))?;
+    let client = forge::connect(&config, &url)?;
+
+    // 推送尚未推送或已變更的分支
+    let unpushed: Vec<&Layer> = layers
+        .iter()
+        .filter(|layer| remote_tip(&repo, remote, &layer.branch) != branch_tip(&repo, &layer.branch))
+        .collect();
+    if !unpushed.is_empty() {
+        let names: Vec<&str> = unpushed.iter().map(|l| l.branch.as_str()).collect();
+        println!("{}", format!("以下分支尚未推送或與 {} 不同：{}", remote, names.join("、")).yellow());
+        if !Confirm::with_theme(plain::theme())
+            .with_prompt(format!("推送到 {}？", remote))
+            .default(true)
+            .interact()?
+        {
+            anyhow::bail!("PR 需要分支存在於 remote，已中止");
+        }
+        for layer in unpushed {
+            push_branch(remote, &layer.branch)?;
+        }
+        println!();
+    }
+
+    for layer in &layers {
+        println!("{}", format!("▶ {} → {}", layer.branch, layer.parent).cyan());
+
+        if let Some(pr) = client.find_open_pull_request(&layer.branch)? {
+            if pr.base.name == layer.parent {
+                println!("{}", format!("  ✓ 已有 PR #{}：{}", pr.number, pr.html_url).green());
+            } else {
+                let pr = client.update_base(pr.number, &layer.parent)?;
+                println!(
+                    "{}",
+                    format!("  ✓ 已將 PR #{} 的 base 改為 {}：{}", pr.number, layer.parent, pr.html_url).green()
+                );
+            }
+            continue;
+        }
+
+        let (title, body) = generate_description(layer, &config);
+        println!("{}", format!("  標題：{}", title).bold());
+        for line in body.lines() {
+            println!("{}", format!("  {}", line).dimmed());
+        }
+
+        let items = vec!["建立 PR", "略過這一層"];
+        let selection = Select::with_theme(plain::theme())
+            .with_prompt("請選擇")
+            .items(&items)
+            .default(0)
+            .interact()?;
+        if selection != 0 {
+            continue;
+        }
+
+        let pr = client.create_pull_request(&layer.branch, &layer.parent, &title, &body)?;
+        println!("{}", format!("  ✓ 已建立 PR #{}：{}", pr.number, pr.html_url).green());
+    }
+
+    println!();
+    Ok(())
+}
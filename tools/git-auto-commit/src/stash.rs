@@ -0,0 +1,261 @@
+use crate::cli::{StashAction, StashArgs};
+use crate::config::{load_llm_config, LlmConfig};
+use crate::progress::Timeline;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{Input, Select};
+use git_tools_core::git::git;
+use git_tools_core::{layout, menu, plain};
+
+/// 送給 LLM 的 diff 最多字元數
+const MAX_DIFF_CHARS: usize = 6000;
+
+fn lines(output: &str) -> Vec<String> {
+    output.lines().filter(|line| !line.is_empty()).map(String::from).collect()
+}
+
+/// stash 子指令：未指定動作時以描述性的訊息 stash 目前的變更，list 則瀏覽並還原 stash
+pub fn run(args: &StashArgs) -> Result<()> {
+    git(&["rev-parse", "--verify", "HEAD"]).context("✗ 錯誤：當前目錄不是 Git repository，或還沒有任何 commit")?;
+    match &args.action {
+        Some(StashAction::List { query }) => browse(query.as_deref()),
+        None => save(args),
+    }
+}
+
+/// 依工作目錄的變更生成 stash 訊息，確認後執行 git stash push
+fn save(args: &StashArgs) -> Result<()> {
+    println!("\n{}\n", "📦 Stash 變更".cyan().bold());
+
+    let mut files = lines(&git(&["diff", "HEAD", "--name-only"])?);
+    let untracked = if args.include_untracked {
+        lines(&git(&["ls-files", "--others", "--exclude-standard"])?)
+    } else {
+        Vec::new()
+    };
+    files.extend(untracked.iter().cloned());
+    if files.is_empty() {
+        println!("{}", "⚠️  沒有可 stash 的變更".yellow());
+        return Ok(());
+    }
+
+    println!("{}", format!("📝 變更的檔案（{} 個）：", files.len()).blue());
+    for file in &files {
+        let mark = if untracked.contains(file) { "（未追蹤）" } else { "" };
+        println!("{}", format!("  - {}{}", layout::truncate_middle(file, layout::available(4 + 10)), mark).dimmed());
+    }
+    println!();
+
+    let branch = git(&["rev-parse", "--abbrev-ref", "HEAD"])?.trim().to_string();
+    let config = load_llm_config();
+    let fallback = fallback_message(&files);
+    let suggested = if args.offline {
+        fallback
+    } else {
+        let diff = git(&["diff", "HEAD", "--no-color", "--no-prefix", "--ignore-space-change"])?;
+        generate_message(&diff, &files, &branch, &config).unwrap_or(fallback)
+    };
+
+    // 直接按 Enter 即沿用建議
+    let message: String = Input::with_theme(plain::theme())
+        .with_prompt("stash 訊息")
+        .with_initial_text(&suggested)
+        .validate_with(|input: &String| {
+            if input.trim().is_empty() {
+                Err("stash 訊息不能為空")
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+
+    let mut push_args = vec!["stash", "push", "-m", message.trim()];
+    if args.include_untracked {
+        push_args.push("--include-untracked");
+    }
+    git(&push_args)?;
+    println!("{}", format!("✓ 已 stash：{}", message.trim()).green());
+    println!("{}", "  之後可用 git-auto-commit stash list 搜尋並還原".dimmed());
+    println!();
+    Ok(())
+}
+
+/// 不使用 LLM 時的 stash 訊息：列出變更的檔案
+fn fallback_message(files: &[String]) -> String {
+    let names: Vec<&str> = files
+        .iter()
+        .take(3)
+        .map(|file| file.rsplit('/').next().unwrap_or(file))
+        .collect();
+    let more = if files.len() > 3 { format!(" 等 {} 個檔案", files.len()) } else { String::new() };
+    format!("WIP: 修改 {}{}", names.join("、"), more)
+}
+
+/// 請 LLM 以一句話描述進行中的工作，失敗時回傳 None
+fn generate_message(diff: &str, files: &[String], branch: &str, config: &LlmConfig) -> Option<String> {
+    let diff_preview: String = diff.chars().take(MAX_DIFF_CHARS).collect();
+    let prompt = config
+        .stash_prompt
+        .replace("{branch}", branch)
+        .replace("{files}", &files.join(", "))
+        .replace("{file_summary}", &crate::get_file_summary(files))
+        .replace("{stats}", &crate::get_diff_stats(diff))
+        .replace("{diff}", &diff_preview)
+        .replace("{language}", &config.language);
+
+    let mut timeline = Timeline::new();
+    timeline.start("生成 stash 訊息");
+    match crate::call_llm_cli(&prompt, config) {
+        Ok(response) => {
+            let message = response
+                .lines()
+                .map(|line| line.trim().trim_matches(['"', '`', '「', '」']))
+                .find(|line| !line.is_empty())
+                .map(String::from);
+            match message {
+                Some(_) => timeline.finish(),
+                None => timeline.fail(),
+            }
+            message
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 呼叫失敗：{}，改用檔案名稱作為訊息", e).yellow());
+            None
+        }
+    }
+}
+
+/// 一筆 stash
+struct StashEntry {
+    /// 參照名稱（例如 stash@{0}）
+    reference: String,
+    /// 建立時間（YYYY-MM-DD HH:MM）
+    date: String,
+    /// git stash list 顯示的說明（例如 On main: 訊息）
+    subject: String,
+    /// 變更的檔案
+    files: Vec<String>,
+}
+
+impl StashEntry {
+    /// 說明或檔案路徑包含關鍵字（不分大小寫）
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        self.subject.to_lowercase().contains(&query) || self.files.iter().any(|f| f.to_lowercase().contains(&query))
+    }
+}
+
+fn load_stashes() -> Result<Vec<StashEntry>> {
+    let output = git(&["stash", "list", "--format=%gd%x1f%ci%x1f%gs"])?;
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        let mut fields = line.splitn(3, '\x1f');
+        let (Some(reference), Some(date), Some(subject)) = (fields.next(), fields.next(), fields.next()) else {
+            continue;
+        };
+        let files = git(&["stash", "show", "--name-only", "--include-untracked", reference])
+            .or_else(|_| git(&["stash", "show", "--name-only", reference]))
+            .map(|output| lines(&output))
+            .unwrap_or_default();
+        entries.push(StashEntry {
+            reference: reference.to_string(),
+            date: date.chars().take(16).collect(),
+            subject: subject.to_string(),
+            files,
+        });
+    }
+    Ok(entries)
+}
+
+/// 依關鍵字搜尋 stash（比對說明與檔案路徑），選擇後可套用、檢視或刪除
+fn browse(query: Option<&str>) -> Result<()> {
+    println!("\n{}\n", "📦 Stash 列表".cyan().bold());
+
+    let mut query = query.map(str::to_string);
+    loop {
+        let stashes = load_stashes()?;
+        if stashes.is_empty() {
+            println!("{}", "沒有任何 stash".dimmed());
+            return Ok(());
+        }
+        let keyword = match query.take() {
+            Some(keyword) => keyword,
+            None => Input::with_theme(plain::theme())
+                .with_prompt("搜尋（說明或檔案路徑，留空列出全部）")
+                .allow_empty(true)
+                .interact_text()?,
+        };
+        let matched: Vec<&StashEntry> = stashes.iter().filter(|s| s.matches(keyword.trim())).collect();
+        if matched.is_empty() {
+            println!("{}", format!("⚠️  沒有符合「{}」的 stash", keyword.trim()).yellow());
+            continue;
+        }
+
+        let mut items: Vec<String> = matched
+            .iter()
+            .map(|stash| {
+                let head = format!("{}  {}  ", stash.reference, stash.date);
+                let used = layout::MENU_INDENT + console::measure_text_width(&head);
+                format!("{}{}", head, layout::truncate(&stash.subject, layout::available(used)))
+            })
+            .collect();
+        items.push("🔍 重新搜尋".to_string());
+        let Some(selection) = menu::select(&format!("共 {} 個 stash", matched.len()), &items, 0)? else {
+            return Ok(());
+        };
+        if selection == matched.len() {
+            continue;
+        }
+
+        if !act_on(matched[selection])? {
+            return Ok(());
+        }
+    }
+}
+
+/// 對選定的 stash 執行動作，回傳是否回到列表
+fn act_on(stash: &StashEntry) -> Result<bool> {
+    println!();
+    println!("{}", stash.subject.bold());
+    for file in &stash.files {
+        println!("{}", format!("  - {}", layout::truncate_middle(file, layout::available(4))).dimmed());
+    }
+    println!();
+
+    loop {
+        let items = [
+            "套用並刪除（pop）",
+            "套用並保留（apply）",
+            "📄 檢視變更（pager）",
+            "🗑️  刪除（drop）",
+            "← 返回列表",
+        ];
+        let selection = Select::with_theme(plain::theme())
+            .with_prompt(stash.reference.as_str())
+            .items(&items)
+            .default(0)
+            .interact()?;
+        let result = match selection {
+            0 => git(&["stash", "pop", &stash.reference]).map(|_| format!("✓ 已套用並刪除 {}", stash.reference)),
+            1 => git(&["stash", "apply", &stash.reference]).map(|_| format!("✓ 已套用 {}", stash.reference)),
+            2 => {
+                let diff = git(&["stash", "show", "-p", "--no-color", &stash.reference])?;
+                if let Err(e) = layout::page(&diff) {
+                    println!("{}", format!("⚠️  {:#}", e).yellow());
+                    print!("{}", diff);
+                }
+                continue;
+            }
+            3 => git(&["stash", "drop", &stash.reference]).map(|_| format!("✓ 已刪除 {}", stash.reference)),
+            _ => return Ok(true),
+        };
+        match result {
+            Ok(done) => println!("{}", done.green()),
+            // 例如與工作目錄的變更衝突
+            Err(e) => println!("{}", format!("✗ {}", e).red()),
+        }
+        println!();
+        return Ok(false);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::cli::SummaryArgs;
+use crate::config::load_llm_config;
+use crate::date;
+use crate::identity;
+use crate::progress::Timeline;
+use crate::shallow;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Local};
+use colored::*;
+use git2::Repository;
+use git_tools_core::git::git_in;
+use std::env;
+use std::path::Path;
+
+/// 每個 commit 最多送出的內文行數
+const MAX_BODY_LINES: usize = 5;
+
+/// 摘要中的單一 commit
+struct CommitEntry {
+    /// commit 時間（MM-DD HH:MM）
+    time: String,
+    branch: String,
+    subject: String,
+    body: String,
+}
+
+impl CommitEntry {
+    /// 提示詞中的格式：「- 時間 [分支] 標題」，內文縮排列在下方
+    fn format(&self) -> String {
+        let mut text = format!("- {} [{}] {}", self.time, self.branch, self.subject);
+        for line in self.body.lines().filter(|l| !l.trim().is_empty()).take(MAX_BODY_LINES) {
+            text.push_str(&format!("\n  {}", line.trim()));
+        }
+        text
+    }
+}
+
+/// 單一 repository（專案）在期間內的工作
+struct Project {
+    /// 專案名稱（工作目錄的名稱）
+    name: String,
+    commits: Vec<CommitEntry>,
+    /// 尚未 commit 的變更（統計與前段的 diff）
+    uncommitted: Option<String>,
+}
+
+/// 取得所有本地分支上期間內的 commit（不含合併 commit，由舊到新），可只取指定作者
+fn collect_commits(
+    dir: &Path,
+    since: &DateTime<Local>,
+    until: Option<&DateTime<Local>>,
+    author: Option<&str>,
+) -> Result<Vec<CommitEntry>> {
+    let since = format!("--since={}", date::to_git_date(since));
+    let mut args = vec![
+        "log",
+        "--branches",
+        "--source",
+        "--no-merges",
+        "--reverse",
+        "--date=format:%m-%d %H:%M",
+        "--format=%ad%x1f%S%x1f%s%x1f%b%x1e",
+        since.as_str(),
+    ];
+    let until = until.map(|until| format!("--until={}", date::to_git_date(until)));
+    if let Some(until) = &until {
+        args.push(until);
+    }
+    let author = author.map(|email| format!("--author={}", email));
+    if let Some(author) = &author {
+        args.extend(["--fixed-strings", author.as_str()]);
+    }
+
+    let output = git_in(dir, &args)?;
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(4, '\x1f');
+            Some(CommitEntry {
+                time: fields.next()?.to_string(),
+                branch: fields.next()?.trim_start_matches("refs/heads/").to_string(),
+                subject: fields.next()?.to_string(),
+                body: fields.next().unwrap_or("").trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// 尚未 commit 的變更（統計與前段的 diff），沒有變更時回傳 None
+fn uncommitted_changes(dir: &Path) -> Result<Option<String>> {
+    let diff = git_in(dir, &["diff", "HEAD", "--no-color", "--no-ext-diff"])?;
+    if diff.trim().is_empty() {
+        return Ok(None);
+    }
+    let preview: String = diff.chars().take(6000).collect();
+    Ok(Some(format!("{}\n\n```\n{}\n```", crate::get_diff_stats(&diff), preview)))
+}
+
+/// 收集單一 repository 的工作，作者為該 repository 的 git 設定中的 user.email
+fn collect_project(
+    path: &Path,
+    since: &DateTime<Local>,
+    until: Option<&DateTime<Local>>,
+    uncommitted: bool,
+) -> Result<Project> {
+    let repo = Repository::discover(path).context(format!("✗ 錯誤：{} 不是 Git repository", path.display()))?;
+    let dir = repo.workdir().context(format!("✗ 錯誤：{} 是 bare repository", path.display()))?;
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| dir.display().to_string());
+
+    let (_, email) = identity::current_identity(&repo);
+    if email.is_none() {
+        println!("{}", format!("⚠️  {} 的 git 設定中缺少 user.email，將列出所有作者的 commit", name).yellow());
+    }
+    Ok(Project {
+        commits: collect_commits(dir, since, until, email.as_deref())?,
+        uncommitted: if uncommitted { uncommitted_changes(dir)? } else { None },
+        name,
+    })
+}
+
+/// 本週一 00:00
+fn start_of_week(now: DateTime<Local>) -> Result<DateTime<Local>> {
+    let days = now.weekday().num_days_from_monday() as i64;
+    date::parse_date(&format!("{} days ago 00:00", days), now)
+}
+
+/// 提示詞中的 commit 列表；多個專案時依專案分組
+fn format_commits(projects: &[Project], grouped: bool) -> String {
+    let sections: Vec<String> = projects
+        .iter()
+        .filter(|p| !p.commits.is_empty())
+        .map(|project| {
+            let lines: Vec<String> = project.commits.iter().map(CommitEntry::format).collect();
+            if grouped {
+                format!("[{}]\n{}", project.name, lines.join("\n"))
+            } else {
+                lines.join("\n")
+            }
+        })
+        .collect();
+    if sections.is_empty() {
+        "（沒有 commit）".to_string()
+    } else {
+        sections.join("\n\n")
+    }
+}
+
+/// 提示詞中尚未 commit 的變更；多個專案時依專案分組
+fn format_uncommitted(projects: &[Project], grouped: bool) -> String {
+    let sections: Vec<String> = projects
+        .iter()
+        .filter_map(|project| {
+            let changes = project.uncommitted.as_deref()?;
+            Some(if grouped {
+                format!("[{}]\n{}", project.name, changes)
+            } else {
+                changes.to_string()
+            })
+        })
+        .collect();
+    if sections.is_empty() {
+        "（未提供）".to_string()
+    } else {
+        sections.join("\n\n")
+    }
+}
+
+/// 整理期間內自己的 commit，由 LLM 產生站立會議用的工作摘要或依專案分組的週報
+pub fn run(args: &SummaryArgs) -> Result<()> {
+    let title = if args.weekly { "📋 週報" } else { "📋 工作摘要" };
+    println!("\n{}\n", title.cyan().bold());
+
+    let now = Local::now();
+    let since = match (&args.since, args.weekly) {
+        (Some(since), _) => date::parse_date(since, now)?,
+        (None, true) => start_of_week(now)?,
+        (None, false) => date::parse_date("today 00:00", now)?,
+    };
+    let until = args.until.as_deref().map(|until| date::parse_date(until, now)).transpose()?;
+    let config = load_llm_config();
+
+    let paths = if args.repos.is_empty() {
+        vec![env::current_dir().context("無法取得當前目錄")?]
+    } else {
+        args.repos.clone()
+    };
+    let mut projects: Vec<Project> = Vec::new();
+    for path in &paths {
+        shallow::check(path, &config.fetch, "期間內的 commit", false)?;
+        match collect_project(path, &since, until.as_ref(), args.uncommitted) {
+            Ok(project) => projects.push(project),
+            Err(e) if paths.len() > 1 => println!("{}", format!("⚠️  略過 {}：{}", path.display(), e).yellow()),
+            Err(e) => return Err(e),
+        }
+    }
+
+    let since_label = since.format("%Y-%m-%d %H:%M").to_string();
+    let until_label = until
+        .map(|until| until.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_else(|| "現在".to_string());
+    let total: usize = projects.iter().map(|p| p.commits.len()).sum();
+    if total == 0 && projects.iter().all(|p| p.uncommitted.is_none()) {
+        println!("{}", format!("{} 至 {} 沒有自己的 commit", since_label, until_label).yellow());
+        return Ok(());
+    }
+
+    let grouped = args.weekly || projects.len() > 1;
+    println!("{}", format!("{} 至 {} 的 commit（{} 個）：", since_label, until_label, total).blue());
+    for project in &projects {
+        if grouped {
+            println!("{}", format!("  {}（{} 個）", project.name, project.commits.len()).bold());
+        }
+        for entry in &project.commits {
+            println!("{}", format!("  {} [{}] {}", entry.time, entry.branch, entry.subject).dimmed());
+        }
+    }
+    println!();
+
+    let template = if args.weekly { &config.weekly_report_prompt } else { &config.summary_prompt };
+    let prompt = template
+        .replace("{since}", &since_label)
+        .replace("{until}", &until_label)
+        .replace("{commits}", &format_commits(&projects, grouped))
+        .replace("{uncommitted}", &format_uncommitted(&projects, grouped))
+        .replace("{language}", &config.language);
+
+    let mut timeline = Timeline::new();
+    timeline.start(if args.weekly { "生成週報" } else { "生成工作摘要" });
+    match crate::call_llm_cli(&prompt, &config) {
+        Ok(response) => {
+            timeline.finish();
+            println!();
+            println!("{}", response.trim());
+        }
+        Err(e) => {
+            timeline.fail();
+            println!("{}", format!("⚠️  LLM 呼叫失敗：{}，改為列出 commit 標題", e).yellow());
+            for project in projects.iter().filter(|p| !p.commits.is_empty()) {
+                println!();
+                if grouped {
+                    println!("{}", project.name);
+                }
+                for entry in &project.commits {
+                    println!("- {}", entry.subject);
+                }
+            }
+        }
+    }
+
+    println!();
+    Ok(())
+}
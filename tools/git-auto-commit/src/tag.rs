@@ -0,0 +1,362 @@
+use crate::cli::{BumpLevel, TagArgs};
+use crate::config::load_llm_config;
+use crate::progress::Timeline;
+use crate::release_checks;
+use crate::shallow;
+use crate::version_files;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::{Input, Select};
+use git_tools_core::git::git;
+use git_tools_core::{layout, plain};
+use regex::Regex;
+use std::env;
+use std::path::PathBuf;
+
+/// 沒有任何 tag 時的起始版本
+const INITIAL_VERSION: (u64, u64, u64) = (0, 1, 0);
+
+/// 上一個 tag 以來的 commit
+struct TagCommit {
+    subject: String,
+    body: String,
+}
+
+impl TagCommit {
+    /// conventional commit 的類型（例如 feat、fix）
+    fn commit_type(&self) -> Option<&str> {
+        let (head, _) = self.subject.split_once(':')?;
+        let head = head.trim_end_matches('!');
+        let kind = head.split('(').next().unwrap_or(head);
+        (!kind.is_empty() && kind.chars().all(|c| c.is_ascii_alphanumeric())).then_some(kind)
+    }
+
+    /// 是否為不相容的變更（type! 或內文含 BREAKING CHANGE）
+    fn is_breaking(&self) -> bool {
+        let breaking_subject = self.subject.split_once(':').is_some_and(|(head, _)| head.ends_with('!'));
+        breaking_subject || self.body.contains("BREAKING CHANGE") || self.body.contains("BREAKING-CHANGE")
+    }
+}
+
+/// 版本號（保留原 tag 的前綴，例如 v）
+struct Version {
+    prefix: String,
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+impl Version {
+    /// 解析 tag 名稱（例如 v1.2.3、release-1.2.3），忽略 -rc1 等後綴
+    fn parse(tag: &str) -> Option<Self> {
+        let re = Regex::new(r"^(\D*)(\d+)\.(\d+)\.(\d+)").ok()?;
+        let caps = re.captures(tag)?;
+        Some(Self {
+            prefix: caps[1].to_string(),
+            major: caps[2].parse().ok()?,
+            minor: caps[3].parse().ok()?,
+            patch: caps[4].parse().ok()?,
+        })
+    }
+
+    /// 升級後的版本；0.x 版的不相容變更只升級 minor
+    fn bump(&self, level: BumpLevel) -> Self {
+        let (major, minor, patch) = match level {
+            BumpLevel::Major if self.major == 0 => (0, self.minor + 1, 0),
+            BumpLevel::Major => (self.major + 1, 0, 0),
+            BumpLevel::Minor => (self.major, self.minor + 1, 0),
+            BumpLevel::Patch => (self.major, self.minor, self.patch + 1),
+        };
+        Self {
+            prefix: self.prefix.clone(),
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("{}{}.{}.{}", self.prefix, self.major, self.minor, self.patch)
+    }
+}
+
+/// HEAD 可追溯到的最近一個 tag
+fn previous_tag() -> Option<String> {
+    git(&["describe", "--tags", "--abbrev=0"])
+        .ok()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+}
+
+fn tag_exists(tag: &str) -> bool {
+    git(&["rev-parse", "--verify", "--quiet", &format!("refs/tags/{}", tag)]).is_ok()
+}
+
+/// 上一個 tag（沒有時為所有歷史）以來的 commit，不含合併 commit，由舊到新
+fn collect_commits(previous: Option<&str>) -> Result<Vec<TagCommit>> {
+    let range = match previous {
+        Some(tag) => format!("{}..HEAD", tag),
+        None => "HEAD".to_string(),
+    };
+    let output = git(&["log", "--no-merges", "--reverse", "--format=%s%x1f%b%x1e", &range])?;
+    Ok(output
+        .split('\x1e')
+        .filter_map(|record| {
+            let (subject, body) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(TagCommit {
+                subject: subject.trim().to_string(),
+                body: body.trim().to_string(),
+            })
+        })
+        .filter(|commit| !commit.subject.is_empty())
+        .collect())
+}
+
+/// 依 commit 類型推斷版本升級的層級與原因
+fn infer_bump(commits: &[TagCommit]) -> (BumpLevel, &'static str) {
+    if commits.iter().any(TagCommit::is_breaking) {
+        (BumpLevel::Major, "包含不相容的變更")
+    } else if commits.iter().any(|c| c.commit_type() == Some("feat")) {
+        (BumpLevel::Minor, "包含新功能")
+    } else {
+        (BumpLevel::Patch, "只有修正與維護")
+    }
+}
+
+fn bump_name(level: BumpLevel) -> &'static str {
+    match level {
+        BumpLevel::Major => "major",
+        BumpLevel::Minor => "minor",
+        BumpLevel::Patch => "patch",
+    }
+}
+
+/// 不使用 LLM 時的 tag 訊息：依 commit 類型分組列出標題
+fn fallback_message(tag: &str, commits: &[TagCommit]) -> String {
+    let sections = [
+        ("不相容的變更", commits.iter().filter(|c| c.is_breaking()).collect::<Vec<_>>()),
+        (
+            "新功能",
+            commits.iter().filter(|c| !c.is_breaking() && c.commit_type() == Some("feat")).collect(),
+        ),
+        (
+            "修正",
+            commits.iter().filter(|c| !c.is_breaking() && c.commit_type() == Some("fix")).collect(),
+        ),
+        (
+            "其他",
+            commits
+                .iter()
+                .filter(|c| !c.is_breaking() && !matches!(c.commit_type(), Some("feat" | "fix")))
+                .collect(),
+        ),
+    ];
+
+    let mut message = tag.to_string();
+    for (title, entries) in sections.iter().filter(|(_, entries)| !entries.is_empty()) {
+        message.push_str(&format!("\n\n{}：", title));
+        for commit in entries {
+            message.push_str(&format!("\n- {}", commit.subject));
+        }
+    }
+    message
+}
+
+/// tag 子指令：推斷下一個版本號、撰寫重點並建立 annotated tag
+pub fn run(args: &TagArgs) -> Result<()> {
+    println!("\n{}\n", "🏷️  建立 tag".cyan().bold());
+
+    git(&["rev-parse", "--verify", "HEAD"]).context("✗ 錯誤：當前目錄不是 Git repository，或還沒有任何 commit")?;
+    let config = load_llm_config();
+    let current_dir = env::current_dir().context("無法取得當前目錄")?;
+    let incomplete = shallow::check(&current_dir, &config.fetch, "版本號推斷與 commit 列表", args.offline)?;
+    let previous = previous_tag();
+    if previous.is_none() && incomplete {
+        // 上一個 tag 可能在本地歷史之外，從起始版本開始會產生錯誤的版本號
+        anyhow::bail!("淺層 clone 中找不到可追溯的 tag，無法推斷版本號；請執行 git fetch --unshallow 後再試");
+    }
+    let commits = collect_commits(previous.as_deref())?;
+    let previous_label = previous.clone().unwrap_or_else(|| "第一個 commit".to_string());
+    if commits.is_empty() {
+        println!("{}", format!("⚠️  自 {} 以來沒有新的 commit", previous_label).yellow());
+        return Ok(());
+    }
+    release_checks::run(&config, args.offline)?;
+
+    println!("{}", format!("自 {} 以來的 commit（{} 個）：", previous_label, commits.len()).blue());
+    for commit in &commits {
+        println!("{}", format!("  - {}", layout::truncate(&commit.subject, layout::available(4))).dimmed());
+    }
+    println!();
+
+    let (level, reason) = match args.bump {
+        Some(level) => (level, "手動指定"),
+        None => infer_bump(&commits),
+    };
+    let (major, minor, patch) = INITIAL_VERSION;
+    let initial = format!("v{}.{}.{}", major, minor, patch);
+    let mut tag = match (&previous, previous.as_deref().and_then(Version::parse)) {
+        (Some(previous), Some(version)) => {
+            let tag = version.bump(level).name();
+            let line = format!("版本升級：{}（{}）：{} → {}", bump_name(level), reason, previous, tag);
+            println!("{}", line.dimmed());
+            tag
+        }
+        (Some(previous), None) => {
+            println!("{}", format!("⚠️  無法從 {} 解析版本號，使用起始版本 {}", previous, initial).yellow());
+            initial
+        }
+        (None, _) => {
+            println!("{}", format!("尚無 tag，使用起始版本 {}", initial).dimmed());
+            initial
+        }
+    };
+
+    let fallback = || fallback_message(&tag, &commits);
+    let mut message = if args.offline {
+        fallback()
+    } else {
+        let listed: Vec<String> = commits
+            .iter()
+            .map(|commit| {
+                let body: Vec<&str> = commit.body.lines().take(3).collect();
+                if body.is_empty() {
+                    format!("- {}", commit.subject)
+                } else {
+                    format!("- {}\n  {}", commit.subject, body.join("\n  "))
+                }
+            })
+            .collect();
+        let prompt = config
+            .tag_prompt
+            .replace("{tag}", &tag)
+            .replace("{previous}", &previous_label)
+            .replace("{commits}", &listed.join("\n"))
+            .replace("{language}", &config.language);
+
+        let mut timeline = Timeline::new();
+        timeline.start("生成 tag 訊息");
+        match crate::call_llm_cli(&prompt, &config) {
+            Ok(response) if !response.trim().is_empty() => {
+                timeline.finish();
+                response.trim().to_string()
+            }
+            Ok(_) => {
+                timeline.fail();
+                fallback()
+            }
+            Err(e) => {
+                timeline.fail();
+                println!("{}", format!("⚠️  LLM 呼叫失敗：{}，改為依類型列出 commit 標題", e).yellow());
+                fallback()
+            }
+        }
+    };
+
+    let root = PathBuf::from(git(&["rev-parse", "--show-toplevel"])?.trim());
+    let update_files = config.version_files.enabled && !args.no_version_files;
+    let mut skip_files = false;
+    loop {
+        let version = version_files::version_of(&tag).unwrap_or(&tag).to_string();
+        let updates = if update_files {
+            version_files::plan(&config.version_files, &root, &version)?
+        } else {
+            Vec::new()
+        };
+
+        println!();
+        println!("{}", format!("🏷️  {}", tag).blue().bold());
+        println!("{}", layout::rule().dimmed());
+        for line in message.lines() {
+            for wrapped in layout::wrap(line, layout::width()) {
+                println!("{}", wrapped);
+            }
+        }
+        println!("{}", layout::rule().dimmed());
+        if !updates.is_empty() && !skip_files {
+            println!("{}", "📦 建立 tag 前更新版本檔案並建立 release commit：".blue());
+            for update in &updates {
+                println!("{}", format!("  {}  {} → {}", update.path, update.from, version).dimmed());
+            }
+        }
+        println!();
+
+        let mut items = vec![
+            format!("✓ 建立 annotated tag {}", tag),
+            "✏️  修改 tag 名稱".to_string(),
+            "✏️  修改 tag 訊息標題".to_string(),
+        ];
+        if !updates.is_empty() {
+            items.push(if skip_files { "📦 更新版本檔案" } else { "📦 不更新版本檔案" }.to_string());
+        }
+        items.push("✗ 取消".to_string());
+        let selection = Select::with_theme(plain::theme())
+            .with_prompt("請選擇")
+            .items(&items)
+            .default(0)
+            .interact()?;
+        match selection {
+            0 if tag_exists(&tag) => println!("{}", format!("⚠️  tag {} 已存在，請修改 tag 名稱", tag).yellow()),
+            0 => {
+                if !skip_files && !updates.is_empty() {
+                    // release commit 不另外呼叫 LLM：標題由模板產生，內文沿用 tag 訊息的重點
+                    let title = config
+                        .version_files
+                        .message
+                        .replace("{version}", &version)
+                        .replace("{tag}", &tag);
+                    let body = message.split_once('\n').map_or("", |(_, rest)| rest.trim());
+                    let release = format!("{}\n\n{}", title, body).trim_end().to_string();
+                    version_files::commit(&root, &updates, &release)?;
+                    println!("{}", format!("✓ 已建立 release commit：{}", title).green());
+                }
+                break;
+            }
+            1 => {
+                tag = Input::with_theme(plain::theme())
+                    .with_prompt("tag 名稱")
+                    .with_initial_text(&tag)
+                    .validate_with(|input: &String| {
+                        let name = input.trim();
+                        if name.is_empty() {
+                            Err("tag 名稱不能為空")
+                        } else if git(&["check-ref-format", &format!("refs/tags/{}", name)]).is_err() {
+                            Err("tag 名稱包含無效字元")
+                        } else {
+                            Ok(())
+                        }
+                    })
+                    .interact_text()?
+                    .trim()
+                    .to_string();
+            }
+            2 => {
+                let (title, rest) = message.split_once('\n').unwrap_or((&message, ""));
+                let title: String = Input::with_theme(plain::theme())
+                    .with_prompt("標題")
+                    .with_initial_text(title)
+                    .interact_text()?;
+                message = format!("{}\n{}", title.trim(), rest).trim_end().to_string();
+            }
+            3 if !updates.is_empty() => skip_files = !skip_files,
+            _ => {
+                println!("{}", "已取消".yellow());
+                return Ok(());
+            }
+        }
+    }
+
+    git(&["tag", "-a", &tag, "-m", &message])?;
+    println!("{}", format!("✓ 已建立 tag {}", tag).green());
+
+    if args.push {
+        match git(&["push", "origin", &tag]) {
+            Ok(_) => println!("{}", format!("✓ 已推送 {} 到 origin", tag).green()),
+            Err(e) => println!("{}", format!("✗ 推送失敗：{}", e).red()),
+        }
+    } else {
+        println!("{}", format!("  推送：git push origin {}", tag).dimmed());
+    }
+    println!();
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use crate::config::TerminologyConfig;
+use colored::*;
+use regex::Regex;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// 常見的英文拼字錯誤
+const TYPOS: &[(&str, &str)] = &[
+    ("accomodate", "accommodate"),
+    ("acheive", "achieve"),
+    ("adress", "address"),
+    ("agian", "again"),
+    ("aparent", "apparent"),
+    ("begining", "beginning"),
+    ("calender", "calendar"),
+    ("compatability", "compatibility"),
+    ("comit", "commit"),
+    ("commited", "committed"),
+    ("definately", "definitely"),
+    ("dependancy", "dependency"),
+    ("enviroment", "environment"),
+    ("existant", "existent"),
+    ("funtion", "function"),
+    ("garentee", "guarantee"),
+    ("initalize", "initialize"),
+    ("lenght", "length"),
+    ("occured", "occurred"),
+    ("paramter", "parameter"),
+    ("preformance", "performance"),
+    ("recieve", "receive"),
+    ("refered", "referred"),
+    ("reponse", "response"),
+    ("retreive", "retrieve"),
+    ("seperate", "separate"),
+    ("sucess", "success"),
+    ("succesful", "successful"),
+    ("teh", "the"),
+    ("thier", "their"),
+    ("threshhold", "threshold"),
+    ("udpate", "update"),
+    ("untill", "until"),
+    ("wierd", "weird"),
+];
+
+/// 繁體中文的慣用詞（commit 訊息語言為繁體中文時使用）
+const ZH_TW_TERMS: &[(&str, &str)] = &[
+    ("數據庫", "資料庫"),
+    ("數據", "資料"),
+    ("默認", "預設"),
+    ("信息", "資訊"),
+    ("代碼", "程式碼"),
+    ("軟件", "軟體"),
+    ("硬件", "硬體"),
+    ("服務器", "伺服器"),
+    ("網絡", "網路"),
+    ("接口", "介面"),
+    ("緩存", "快取"),
+    ("調用", "呼叫"),
+    ("內存", "記憶體"),
+    ("鏈接", "連結"),
+    ("文件夾", "資料夾"),
+    ("變量", "變數"),
+    ("用戶", "使用者"),
+];
+
+/// 用語檢查發現的問題
+pub struct Issue {
+    pub found: String,
+    pub preferred: String,
+}
+
+struct Rule {
+    pattern: Regex,
+    preferred: String,
+}
+
+/// 拼字與用語檢查（依序套用規則，較長的詞優先）
+#[derive(Default)]
+pub struct Checker {
+    rules: Vec<Rule>,
+}
+
+/// 讀取專案的用語表（repository 根目錄的 TOML，格式為 "不建議的詞" = "建議的詞"）
+fn load_project_glossary(path: &Path) -> BTreeMap<String, String> {
+    let Ok(content) = fs::read_to_string(path) else {
+        return BTreeMap::new();
+    };
+    match toml::from_str(&content) {
+        Ok(glossary) => glossary,
+        Err(e) => {
+            println!("{}", format!("⚠️  用語表格式錯誤（{}）：{}", path.display(), e).yellow());
+            BTreeMap::new()
+        }
+    }
+}
+
+/// 英數詞以 ASCII 字詞邊界比對（不分大小寫），其他（例如中文）直接比對子字串
+fn build_pattern(term: &str) -> Option<Regex> {
+    let escaped = regex::escape(term);
+    let pattern = if term.is_ascii() {
+        format!(r"(?i)(?-u:\b){}(?-u:\b)", escaped)
+    } else {
+        escaped
+    };
+    Regex::new(&pattern).ok()
+}
+
+/// 保留原文開頭的大寫（例如句首的 Recieve → Receive）
+fn match_case(found: &str, preferred: &str) -> String {
+    let found_upper = found.chars().next().is_some_and(|c| c.is_uppercase());
+    let mut chars = preferred.chars();
+    match chars.next() {
+        Some(first) if found_upper && first.is_lowercase() => first.to_uppercase().chain(chars).collect(),
+        _ => preferred.to_string(),
+    }
+}
+
+impl Checker {
+    pub fn new(config: &TerminologyConfig, workdir: Option<&Path>, language: &str) -> Self {
+        if !config.enabled {
+            return Self::default();
+        }
+
+        // 後加入的項目覆寫先前的：內建 → 設定檔 → 專案用語表
+        let mut terms: BTreeMap<String, String> = BTreeMap::new();
+        if config.builtin {
+            terms.extend(TYPOS.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+            if language.starts_with("繁體") {
+                terms.extend(ZH_TW_TERMS.iter().map(|(k, v)| (k.to_string(), v.to_string())));
+            }
+        }
+        terms.extend(config.glossary.clone());
+        if let Some(workdir) = workdir {
+            terms.extend(load_project_glossary(&workdir.join(&config.project_glossary)));
+        }
+
+        let mut entries: Vec<(String, String)> = terms.into_iter().collect();
+        entries.sort_by_key(|(term, _)| Reverse(term.chars().count()));
+
+        let rules = entries
+            .into_iter()
+            .filter(|(term, _)| !term.is_empty())
+            .filter_map(|(term, preferred)| {
+                build_pattern(&term).map(|pattern| Rule { pattern, preferred })
+            })
+            .collect();
+        Self { rules }
+    }
+
+    /// 套用所有規則，回傳修正後的訊息與發現的問題
+    fn run(&self, message: &str) -> (String, Vec<Issue>) {
+        let mut text = message.to_string();
+        let mut issues: Vec<Issue> = Vec::new();
+
+        for rule in &self.rules {
+            let mut changed = false;
+            let replaced = rule.pattern.replace_all(&text, |caps: &regex::Captures| {
+                let found = &caps[0];
+                let preferred = match_case(found, &rule.preferred);
+                if found != preferred {
+                    changed = true;
+                    if !issues.iter().any(|i| i.found == found) {
+                        issues.push(Issue {
+                            found: found.to_string(),
+                            preferred: preferred.clone(),
+                        });
+                    }
+                }
+                preferred
+            });
+            if changed {
+                text = replaced.into_owned();
+            }
+        }
+
+        (text, issues)
+    }
+
+    /// 檢查訊息中可能的錯字與不建議的用語
+    pub fn check(&self, message: &str) -> Vec<Issue> {
+        self.run(message).1
+    }
+
+    /// 將訊息中的錯字與用語替換為建議的寫法
+    pub fn apply(&self, message: &str) -> String {
+        self.run(message).0
+    }
+}
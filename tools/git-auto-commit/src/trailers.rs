@@ -0,0 +1,118 @@
+use crate::branch::TemplateVars;
+use crate::config::{LlmConfig, TrailerConfig};
+use crate::plain;
+use crate::preflight::shell_command;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Input;
+use git2::Repository;
+use git_tools_core::git::get_current_branch;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// 要加入訊息的 trailer
+pub struct Trailer {
+    key: String,
+    value: String,
+    if_exists: Option<String>,
+}
+
+/// 代入 {ticket}、{user}、{branch}；有變數沒有值時回傳 None
+fn render(template: &str, vars: &TemplateVars, branch: &str) -> Option<String> {
+    let mut value = template.replace("{branch}", branch);
+    for (name, var) in [("{ticket}", &vars.ticket), ("{user}", &vars.user)] {
+        if value.contains(name) {
+            value = value.replace(name, var.as_deref()?);
+        }
+    }
+    Some(value)
+}
+
+/// 執行指令並取得輸出的第一行，失敗或沒有輸出時為 None
+fn command_output(command: &str) -> Option<String> {
+    let output = shell_command(command).stderr(Stdio::null()).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    stdout.lines().next().map(|line| line.trim().to_string())
+}
+
+fn resolve_one(trailer: &TrailerConfig, vars: &TemplateVars, branch: &str) -> Result<Option<String>> {
+    let value = trailer
+        .value
+        .as_deref()
+        .and_then(|template| render(template, vars, branch))
+        .or_else(|| trailer.env.as_deref().and_then(|name| std::env::var(name).ok()))
+        .or_else(|| trailer.command.as_deref().and_then(command_output))
+        .filter(|value| !value.trim().is_empty());
+    if value.is_some() {
+        return Ok(value);
+    }
+    let Some(prompt) = &trailer.prompt else {
+        return Ok(None);
+    };
+    let input: String = Input::with_theme(plain::theme())
+        .with_prompt(format!("{}（{}，留空則不加入）", prompt, trailer.key))
+        .allow_empty(true)
+        .interact_text()?;
+    Ok(Some(input.trim().to_string()).filter(|value| !value.is_empty()))
+}
+
+/// 依設定取得各 trailer 的值（需要時詢問），沒有值的 trailer 不加入
+pub fn resolve(repo: &Repository, config: &LlmConfig, ticket: Option<&str>) -> Result<Vec<Trailer>> {
+    if config.trailers.is_empty() {
+        return Ok(Vec::new());
+    }
+    let branch = get_current_branch(repo)?;
+    let vars = TemplateVars::detect(repo, &branch, ticket, &config.ticket_pattern);
+    let mut trailers = Vec::new();
+    for trailer in &config.trailers {
+        if let Some(value) = resolve_one(trailer, &vars, &branch)? {
+            trailers.push(Trailer {
+                key: trailer.key.clone(),
+                value,
+                if_exists: trailer.if_exists.clone(),
+            });
+        }
+    }
+    Ok(trailers)
+}
+
+/// 以 git interpret-trailers 將 trailer 加在訊息最後（與既有的 trailer 併為同一段，並套用 git 的 trailer.* 設定）
+pub fn apply(message: &str, trailers: &[Trailer]) -> Result<String> {
+    if trailers.is_empty() {
+        return Ok(message.to_string());
+    }
+    let mut cmd = Command::new("git");
+    cmd.args(["interpret-trailers", "--no-divider"]);
+    for trailer in trailers {
+        match &trailer.if_exists {
+            Some(action) => cmd.arg(format!("--if-exists={}", action)),
+            None => cmd.arg("--no-if-exists"),
+        };
+        cmd.arg("--trailer").arg(format!("{}: {}", trailer.key, trailer.value));
+    }
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("無法執行 git interpret-trailers")?;
+    // 沒有結尾換行時，git 會把最後一行視為 trailer 區塊的一部分
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(format!("{}\n", message.trim_end()).as_bytes())?;
+    }
+    let output = child.wait_with_output().context("無法執行 git interpret-trailers")?;
+    if !output.status.success() {
+        anyhow::bail!("git interpret-trailers 執行失敗：{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+/// 顯示加入的 trailer
+pub fn print(trailers: &[Trailer]) {
+    for trailer in trailers {
+        println!("{}", format!("🏷️  加上 trailer：{}: {}", trailer.key, trailer.value).dimmed());
+    }
+}
@@ -0,0 +1,310 @@
+use crate::cli::SelfUpdateArgs;
+use crate::config::{get_config_dir, load_llm_config, UpdateConfig};
+use crate::plain;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local};
+use colored::*;
+use dialoguer::Confirm;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 此工具在 release tag 上的前綴（例如 git-auto-commit-v0.2.0），也接受單純的 v0.2.0
+const TAG_PREFIX: &str = "git-auto-commit-";
+
+/// 自動檢查的間隔
+const CHECK_INTERVAL_DAYS: i64 = 7;
+
+#[derive(Debug, Deserialize)]
+struct Release {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+/// 上次檢查的紀錄（儲存於 ~/.config/git-auto-commit/update-check.toml）
+#[derive(Debug, Deserialize, Serialize)]
+struct CheckState {
+    /// 上次檢查的時間（RFC 3339）
+    last_check: String,
+    latest_version: Option<String>,
+}
+
+fn get_state_path() -> PathBuf {
+    get_config_dir().join("update-check.toml")
+}
+
+/// 將 "1.2.3" 轉為可比較的數字（忽略 -beta 等後綴）
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+    Some((parts.next()??, parts.next().flatten().unwrap_or(0), parts.next().flatten().unwrap_or(0)))
+}
+
+fn is_newer(version: &str) -> bool {
+    match (parse_version(version), parse_version(CURRENT_VERSION)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
+
+/// 從 release tag 取出此工具的版本號，其他工具的 release 回傳 None
+fn tag_version(tag: &str) -> Option<&str> {
+    let tag = tag.strip_prefix(TAG_PREFIX).unwrap_or(tag);
+    let version = tag.strip_prefix('v')?;
+    parse_version(version).map(|_| version)
+}
+
+/// 此平台的執行檔名稱（例如 git-auto-commit-x86_64-linux、git-auto-commit-x86_64-windows.exe）
+fn asset_name() -> String {
+    format!(
+        "git-auto-commit-{}-{}{}",
+        env::consts::ARCH,
+        env::consts::OS,
+        env::consts::EXE_SUFFIX
+    )
+}
+
+fn agent(timeout_secs: u64) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(timeout_secs))
+        .user_agent("git-auto-commit")
+        .build()
+}
+
+/// 取得最新的正式 release
+fn latest_release(config: &UpdateConfig, timeout_secs: u64) -> Result<Option<(String, Release)>> {
+    let url = format!("https://api.github.com/repos/{}/releases?per_page=30", config.repository);
+    let releases: Vec<Release> = agent(timeout_secs)
+        .get(&url)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .context("無法取得 release 列表")?
+        .into_json()
+        .context("無法解析 release 列表")?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|r| !r.draft && !r.prerelease)
+        .filter_map(|r| tag_version(&r.tag_name).map(String::from).map(|v| (v, r)))
+        .max_by_key(|(version, _)| parse_version(version)))
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let response = agent(120).get(url).call().context(format!("無法下載：{}", url))?;
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut bytes)
+        .context("下載中斷")?;
+    Ok(bytes)
+}
+
+/// 從 SHA256SUMS 找出指定檔案的雜湊值（與執行檔來自同一個 release，只能確認下載完整，無法證明發布者）
+fn expected_checksum(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (hash, file) = line.split_once(char::is_whitespace)?;
+        (file.trim().trim_start_matches('*') == name).then(|| hash.to_lowercase())
+    })
+}
+
+/// 以新的執行檔取代目前的執行檔
+fn replace_executable(current: &Path, bytes: &[u8]) -> Result<()> {
+    let dir = current.parent().context("無法取得執行檔所在目錄")?;
+    let staged = dir.join(format!(".git-auto-commit-update{}", env::consts::EXE_SUFFIX));
+    fs::write(&staged, bytes).context(format!("無法寫入 {}", staged.display()))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))
+            .context("無法設定執行權限")?;
+    }
+
+    // Windows 無法覆寫執行中的檔案，但可以先改名
+    #[cfg(windows)]
+    let old = current.with_extension("old.exe");
+    #[cfg(windows)]
+    {
+        let _ = fs::remove_file(&old);
+        fs::rename(current, &old).context("無法移動目前的執行檔")?;
+    }
+
+    let replaced = fs::rename(&staged, current);
+    if replaced.is_err() {
+        // 取代失敗時把原本的執行檔改回來，避免執行檔消失
+        #[cfg(windows)]
+        let _ = fs::rename(&old, current);
+        let _ = fs::remove_file(&staged);
+    }
+    replaced.context(format!("無法取代 {}", current.display()))
+}
+
+/// self-update 子指令：下載最新版本、比對 SHA-256 確認下載完整後取代目前的執行檔
+pub fn run(args: &SelfUpdateArgs) -> Result<()> {
+    println!("\n{}\n", "⬆️  檢查更新".cyan().bold());
+
+    let config = &load_llm_config().update;
+
+    let Some((version, release)) = latest_release(config, 30)? else {
+        println!("{}", format!("⚠️  {} 沒有可用的 release", config.repository).yellow());
+        return Ok(());
+    };
+    save_state(Some(&version));
+
+    if !is_newer(&version) {
+        println!("{}", format!("✓ 已是最新版本（{}）", CURRENT_VERSION).green());
+        return Ok(());
+    }
+
+    println!("{}", format!("目前版本：{}，最新版本：{}", CURRENT_VERSION, version).bold());
+    println!("{}", release.html_url.dimmed());
+    if args.check {
+        return Ok(());
+    }
+
+    let name = asset_name();
+    let asset = release
+        .assets
+        .iter()
+        .find(|a| a.name == name)
+        .context(format!("release 中沒有此平台的執行檔：{}", name))?;
+    let sums = release
+        .assets
+        .iter()
+        .find(|a| a.name == "SHA256SUMS")
+        .context("release 中沒有 SHA256SUMS，無法確認下載內容完整")?;
+    println!(
+        "{}",
+        "SHA256SUMS 與執行檔來自同一個 release：只能偵測下載損毀，無法偵測 release 遭竄改".dimmed()
+    );
+
+    if !Confirm::with_theme(plain::theme())
+        .with_prompt(format!("更新至 {}？", version))
+        .default(true)
+        .interact()?
+    {
+        return Ok(());
+    }
+
+    let bytes = download(&asset.browser_download_url)?;
+    let sums = String::from_utf8(download(&sums.browser_download_url)?)
+        .context("SHA256SUMS 格式錯誤")?;
+    let expected = expected_checksum(&sums, &name)
+        .context(format!("SHA256SUMS 中沒有 {} 的雜湊值", name))?;
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        anyhow::bail!("SHA-256 與 SHA256SUMS 不符（預期 {}，實際 {}），下載可能不完整，已中止更新", expected, actual);
+    }
+    println!("{}", "✓ SHA-256 與 SHA256SUMS 相符（僅確認下載未損毀，未驗證發布者）".green());
+
+    let current = env::current_exe().context("無法取得目前執行檔的路徑")?;
+    replace_executable(&current, &bytes)?;
+    println!("{}", format!("✓ 已更新至 {}（{}）", version, current.display()).green());
+    Ok(())
+}
+
+fn load_state() -> Option<CheckState> {
+    let content = fs::read_to_string(get_state_path()).ok()?;
+    toml::from_str(&content).ok()
+}
+
+fn save_state(latest_version: Option<&str>) {
+    let state = CheckState {
+        last_check: Local::now().to_rfc3339(),
+        latest_version: latest_version.map(String::from),
+    };
+    if let Ok(content) = toml::to_string(&state) {
+        let path = get_state_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::write(path, content);
+    }
+}
+
+/// 每週最多檢查一次新版本，有新版本時顯示提示；任何錯誤都直接略過，不影響主要流程
+pub fn notify_if_due(config: &UpdateConfig) {
+    if !config.check {
+        return;
+    }
+
+    let state = load_state();
+    let due = state
+        .as_ref()
+        .and_then(|s| DateTime::parse_from_rfc3339(&s.last_check).ok())
+        .is_none_or(|last| Local::now().fixed_offset() - last >= Duration::days(CHECK_INTERVAL_DAYS));
+
+    let previous = state.and_then(|s| s.latest_version);
+    let latest = if due {
+        // 離線或失敗時沿用上次的結果，避免每次執行都等待逾時
+        let latest = match latest_release(config, 3) {
+            Ok(Some((version, _))) => Some(version),
+            _ => previous,
+        };
+        save_state(latest.as_deref());
+        latest
+    } else {
+        previous
+    };
+
+    if let Some(version) = latest.filter(|v| is_newer(v)) {
+        println!(
+            "{}",
+            format!(
+                "⬆️  有新版本 {}（目前 {}），執行 git-auto-commit self-update 更新",
+                version, CURRENT_VERSION
+            )
+            .yellow()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_checksum_finds_asset() {
+        let sums = "\
+ABCDEF01  git-auto-commit-x86_64-linux
+1234abcd *git-auto-commit-x86_64-windows.exe
+";
+        assert_eq!(expected_checksum(sums, "git-auto-commit-x86_64-linux").as_deref(), Some("abcdef01"));
+        assert_eq!(expected_checksum(sums, "git-auto-commit-x86_64-windows.exe").as_deref(), Some("1234abcd"));
+        assert_eq!(expected_checksum(sums, "git-auto-commit-aarch64-macos"), None);
+        assert_eq!(expected_checksum(sums, "git-auto-commit-x86_64"), None);
+    }
+
+    #[test]
+    fn parse_version_ignores_suffix() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.4.0-beta.1"), Some((1, 4, 0)));
+        assert_eq!(parse_version("2"), Some((2, 0, 0)));
+        assert_eq!(parse_version("next"), None);
+    }
+
+    #[test]
+    fn tag_version_accepts_tool_tags() {
+        assert_eq!(tag_version("git-auto-commit-v1.2.3"), Some("1.2.3"));
+        assert_eq!(tag_version("v0.5.0"), Some("0.5.0"));
+        assert_eq!(tag_version("other-tool-v1.0.0"), None);
+        assert_eq!(tag_version("git-auto-commit-latest"), None);
+    }
+}
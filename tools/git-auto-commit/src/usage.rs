@@ -0,0 +1,192 @@
+use crate::config::{get_config_dir, BudgetAction, LlmConfig};
+use crate::progress;
+use anyhow::{Context, Result};
+use chrono::Local;
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 當日累計用量（儲存於 ~/.config/git-auto-commit/usage.toml）
+#[derive(Debug, Deserialize, Serialize, Default)]
+struct DailyUsage {
+    /// 日期（YYYY-MM-DD），跨日時重新計算
+    date: String,
+    calls: u32,
+    input_tokens: u64,
+    output_tokens: u64,
+    cost: f64,
+}
+
+fn get_usage_path() -> PathBuf {
+    get_config_dir().join("usage.toml")
+}
+
+fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// 讀取今日用量，若紀錄為前一天或不存在則從零開始
+fn load_today_usage() -> DailyUsage {
+    let today = today();
+    fs::read_to_string(get_usage_path())
+        .ok()
+        .and_then(|content| toml::from_str::<DailyUsage>(&content).ok())
+        .filter(|usage| usage.date == today)
+        .unwrap_or(DailyUsage {
+            date: today,
+            ..Default::default()
+        })
+}
+
+/// 先寫入暫存檔再改名，寫到一半中止時不會留下損毀的紀錄
+fn save_usage(usage: &DailyUsage) -> Result<()> {
+    let path = get_usage_path();
+    let content = toml::to_string(usage).context("無法序列化用量紀錄")?;
+    let temp = path.with_extension(format!("toml.{}.tmp", std::process::id()));
+    fs::write(&temp, content).context("無法寫入用量紀錄")?;
+    fs::rename(&temp, &path).context("無法寫入用量紀錄")?;
+    Ok(())
+}
+
+/// 在鎖定檔的保護下讀取、修改並寫回今日用量
+///
+/// 同時執行多個 git-auto-commit（或背景預先產生的呼叫）時，避免彼此覆蓋累計的用量。
+fn update_usage(update: impl FnOnce(&mut DailyUsage)) -> Result<DailyUsage> {
+    let path = get_usage_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("無法建立設定目錄")?;
+    }
+    let lock = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("toml.lock"))
+        .context("無法開啟用量紀錄的鎖定檔")?;
+    // 鎖定在 lock 關閉時自動解除
+    lock.lock().context("無法鎖定用量紀錄")?;
+
+    let mut usage = load_today_usage();
+    update(&mut usage);
+    save_usage(&usage)?;
+    Ok(usage)
+}
+
+/// 估算文字的 token 數量
+///
+/// CJK 字元大約一個字一個 token，其他字元大約四個字元一個 token。
+pub fn estimate_tokens(text: &str) -> u64 {
+    let mut cjk = 0u64;
+    let mut other = 0u64;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3000..=0x303F   // CJK 標點
+        | 0x3040..=0x30FF // 日文假名
+        | 0x3400..=0x4DBF // CJK 擴充 A
+        | 0x4E00..=0x9FFF // CJK 統一表意文字
+        | 0xAC00..=0xD7AF // 韓文
+        | 0xF900..=0xFAFF // CJK 相容表意文字
+        | 0xFF00..=0xFFEF // 全形字元
+    )
+}
+
+/// 依價格表估算花費（美元），未知模型回傳 None
+fn estimate_cost(config: &LlmConfig, input_tokens: u64, output_tokens: u64) -> Option<f64> {
+    let pricing = config.pricing.get(&config.model)?;
+    Some(
+        input_tokens as f64 / 1_000_000.0 * pricing.input_per_million
+            + output_tokens as f64 / 1_000_000.0 * pricing.output_per_million,
+    )
+}
+
+/// 呼叫 LLM 前檢查每日預算與呼叫次數
+///
+/// 超過上限時依 `on_exceed` 設定顯示警告或回傳錯誤。
+pub fn check_budget(config: &LlmConfig, prompt: &str) -> Result<()> {
+    let budget = &config.budget;
+    if budget.daily_limit.is_none() && budget.daily_call_limit.is_none() {
+        return Ok(());
+    }
+
+    let usage = load_today_usage();
+    let mut reasons = Vec::new();
+
+    if let Some(limit) = budget.daily_call_limit {
+        if usage.calls >= limit {
+            reasons.push(format!("今日已呼叫 {} 次（上限 {} 次）", usage.calls, limit));
+        }
+    }
+
+    if let Some(limit) = budget.daily_limit {
+        // 只能估算輸入部分，回應長度在呼叫前無法得知
+        let estimated = estimate_cost(config, estimate_tokens(prompt), 0).unwrap_or(0.0);
+        if usage.cost + estimated > limit {
+            reasons.push(format!(
+                "今日估計花費 ${:.4} 加上本次約 ${:.4} 將超過預算 ${:.2}",
+                usage.cost, estimated, limit
+            ));
+        }
+    }
+
+    if reasons.is_empty() {
+        return Ok(());
+    }
+
+    let reason = reasons.join("；");
+    match budget.on_exceed {
+        BudgetAction::Warn => {
+            progress::println(&format!("⚠️  {}", reason).yellow().to_string());
+            Ok(())
+        }
+        BudgetAction::Refuse => anyhow::bail!("已超過每日預算：{}", reason),
+    }
+}
+
+/// 將一次 LLM 呼叫的用量加入今日累計，回傳本次的輸入、輸出 tokens、估計花費與更新後的累計
+fn add_call(config: &LlmConfig, prompt: &str, response: &str) -> Result<(u64, u64, Option<f64>, DailyUsage)> {
+    let input_tokens = estimate_tokens(prompt);
+    let output_tokens = estimate_tokens(response);
+    let cost = estimate_cost(config, input_tokens, output_tokens);
+
+    let usage = update_usage(|usage| {
+        usage.calls += 1;
+        usage.input_tokens += input_tokens;
+        usage.output_tokens += output_tokens;
+        usage.cost += cost.unwrap_or(0.0);
+    })?;
+    Ok((input_tokens, output_tokens, cost, usage))
+}
+
+/// 記錄一次 LLM 呼叫的用量，不顯示花費（背景完成的請求，避免打斷畫面上的選單）
+pub fn record_call_quietly(config: &LlmConfig, prompt: &str, response: &str) -> Result<()> {
+    add_call(config, prompt, response).map(|_| ())
+}
+
+/// 記錄一次 LLM 呼叫的用量並顯示花費估算
+pub fn record_call(config: &LlmConfig, prompt: &str, response: &str) -> Result<()> {
+    let (input_tokens, output_tokens, cost, usage) = add_call(config, prompt, response)?;
+
+    let summary = match cost {
+        Some(cost) => format!(
+            "💰 本次約 {} + {} tokens，估計 ${:.4}；今日累計 {} 次，${:.4}",
+            input_tokens, output_tokens, cost, usage.calls, usage.cost
+        ),
+        None => format!(
+            "💰 本次約 {} + {} tokens（價格表中沒有 {} 的價格）；今日累計 {} 次",
+            input_tokens, output_tokens, config.model, usage.calls
+        ),
+    };
+    progress::println(&summary.dimmed().to_string());
+
+    Ok(())
+}
@@ -0,0 +1,181 @@
+use crate::config::VersionFilesConfig;
+use anyhow::{Context, Result};
+use git_tools_core::git::git_in;
+use regex::Regex;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// 一個版本檔案的更新內容
+pub struct Update {
+    /// 檔案路徑（相對於 repository 根目錄）
+    pub path: String,
+    /// 目前的版本號
+    pub from: String,
+    /// 寫入新版本號後的完整內容
+    content: String,
+}
+
+/// 依設定找出需要更新的版本檔案：不存在、沒有符合的版本號或已是新版本的檔案略過；
+/// 同一個檔案的多個 pattern 依序套用在前一個 pattern 更新後的內容上
+pub fn plan(config: &VersionFilesConfig, root: &Path, version: &str) -> Result<Vec<Update>> {
+    let mut updates: Vec<Update> = Vec::new();
+    for file in &config.files {
+        let re = Regex::new(&file.pattern).with_context(|| format!("{} 的 pattern 不是有效的 regex", file.path))?;
+        match updates.iter_mut().find(|update| update.path == file.path) {
+            Some(update) => {
+                if let Some((_, content)) = rewrite(&re, &update.content, version) {
+                    update.content = content;
+                }
+            }
+            None => {
+                let Ok(content) = fs::read_to_string(root.join(&file.path)) else {
+                    continue;
+                };
+                if let Some((from, content)) = rewrite(&re, &content, version) {
+                    updates.push(Update {
+                        path: file.path.clone(),
+                        from,
+                        content,
+                    });
+                }
+            }
+        }
+    }
+    Ok(updates)
+}
+
+/// 以新版本號取代第一個符合處的第一個 capture group（沒有 group 時取代整個符合的文字），
+/// 回傳原本的版本號與取代後的內容；沒有符合處或已是新版本時回傳 None
+fn rewrite(re: &Regex, content: &str, version: &str) -> Option<(String, String)> {
+    let caps = re.captures(content)?;
+    let found = caps.get(1).or_else(|| caps.get(0))?;
+    if found.as_str() == version {
+        return None;
+    }
+    Some((
+        found.as_str().to_string(),
+        format!("{}{}{}", &content[..found.start()], version, &content[found.end()..]),
+    ))
+}
+
+/// tag 名稱去掉前綴後的版本號（例如 v1.2.0-rc.1 為 1.2.0-rc.1）
+pub fn version_of(tag: &str) -> Option<&str> {
+    let start = tag.find(|c: char| c.is_ascii_digit())?;
+    Some(&tag[start..])
+}
+
+/// 寫入版本檔案並只以這些檔案建立 release commit（其他 staged 的變更維持不變）
+pub fn commit(root: &Path, updates: &[Update], message: &str) -> Result<()> {
+    let paths: Vec<&str> = updates.iter().map(|update| update.path.as_str()).collect();
+    // 版本檔案有未 commit 的修改時中止，避免把不相關的修改一併 commit
+    let status = git_in(root, &[&["status", "--porcelain", "--"], paths.as_slice()].concat())?;
+    if !status.trim().is_empty() {
+        anyhow::bail!("版本檔案有未 commit 的修改（{}），請先 commit 或 stash 後再建立 tag", status.trim());
+    }
+
+    for update in updates {
+        let path = root.join(&update.path);
+        fs::write(&path, &update.content).with_context(|| format!("無法寫入 {}", path.display()))?;
+    }
+    // 子程序繼承終端機的輸出，讓 hooks 的訊息即時顯示
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["commit", "--quiet", "-m", message, "--"])
+        .args(&paths)
+        .status()
+        .context("無法執行 git commit")?;
+    if !status.success() {
+        anyhow::bail!("release commit 失敗，版本檔案已更新但未 commit，請檢查後手動 commit 並建立 tag");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    /// 預設設定中 path 檔案的第一個 regex
+    fn default_pattern(path: &str) -> Regex {
+        let config = VersionFilesConfig::default();
+        let file = config.files.iter().find(|file| file.path == path).expect("預設設定應包含此檔案");
+        Regex::new(&file.pattern).unwrap()
+    }
+
+    #[test]
+    fn rewrite_replaces_package_version() {
+        let content = "[package]\nname = \"demo\"\nauthors = [\"a\"]\n\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n";
+        let (from, updated) = rewrite(&default_pattern("Cargo.toml"), content, "0.2.0").unwrap();
+        assert_eq!(from, "0.1.0");
+        assert_eq!(updated, content.replace("0.1.0", "0.2.0"));
+    }
+
+    #[test]
+    fn rewrite_ignores_versions_outside_package_table() {
+        let content = "[dependencies.serde]\nversion = \"1.0.0\"\n\n[package]\nname = \"demo\"\nversion.workspace = true\n\n[dev-dependencies.tokio]\nversion = \"1.38.0\"\n";
+        assert_eq!(rewrite(&default_pattern("Cargo.toml"), content, "0.2.0"), None);
+    }
+
+    #[test]
+    fn rewrite_replaces_package_json_version() {
+        let content = "{\n  \"name\": \"demo\",\n  \"version\": \"1.2.3\"\n}\n";
+        let (from, updated) = rewrite(&default_pattern("package.json"), content, "1.3.0").unwrap();
+        assert_eq!(from, "1.2.3");
+        assert!(updated.contains("\"version\": \"1.3.0\""));
+    }
+
+    #[test]
+    fn rewrite_skips_current_version_and_missing_match() {
+        let re = default_pattern("Cargo.toml");
+        assert_eq!(rewrite(&re, "[package]\nversion = \"0.2.0\"\n", "0.2.0"), None);
+        assert_eq!(rewrite(&re, "[workspace]\nmembers = []\n", "0.2.0"), None);
+    }
+
+    #[test]
+    fn rewrite_without_group_replaces_whole_match() {
+        let re = Regex::new(r"\d+\.\d+\.\d+").unwrap();
+        assert_eq!(
+            rewrite(&re, "VERSION = 1.0.0\n", "1.1.0"),
+            Some(("1.0.0".to_string(), "VERSION = 1.1.0\n".to_string()))
+        );
+    }
+
+    #[test]
+    fn plan_updates_workspace_manifest() {
+        let root = env::temp_dir().join(format!("git-auto-commit-version-files-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let manifest = "\
+[workspace]
+members = [\"crates/*\"]
+
+[workspace.dependencies.serde]
+version = \"1.0.0\"
+
+[package]
+name = \"demo\"
+version = \"0.1.0\"
+
+[workspace.package]
+version = \"0.1.0\"
+edition = \"2021\"
+";
+        fs::write(root.join("Cargo.toml"), manifest).unwrap();
+        fs::write(root.join("pyproject.toml"), "[tool.poetry]\nname = \"demo\"\nversion = \"0.2.0\"\n").unwrap();
+
+        let updates = plan(&VersionFilesConfig::default(), &root, "0.2.0").unwrap();
+        fs::remove_dir_all(&root).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].path, "Cargo.toml");
+        assert_eq!(updates[0].from, "0.1.0");
+        assert_eq!(updates[0].content, manifest.replace("\"0.1.0\"", "\"0.2.0\""));
+    }
+
+    #[test]
+    fn version_of_strips_prefix() {
+        assert_eq!(version_of("v1.2.0-rc.1"), Some("1.2.0-rc.1"));
+        assert_eq!(version_of("git-auto-commit-v0.3.0"), Some("0.3.0"));
+        assert_eq!(version_of("latest"), None);
+    }
+}
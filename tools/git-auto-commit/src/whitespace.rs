@@ -0,0 +1,166 @@
+use crate::plain;
+use anyhow::{Context, Result};
+use colored::*;
+use dialoguer::Select;
+use git_tools_core::git::git_in;
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+/// 只有空白字元變更的檔案
+pub struct WhitespaceChange {
+    pub path: String,
+    /// 只有換行字元（CRLF / LF）不同；否則包含行尾空白的變更
+    line_endings_only: bool,
+}
+
+/// 忽略指定的空白差異後，staged 的內容與 HEAD 仍有差異的已修改檔案
+///
+/// --name-only 不套用忽略空白的選項，改以 --numstat 一次比對所有檔案：只有空白差異的檔案不會列出。
+fn still_changed(dir: &Path, flags: &[&str]) -> Result<HashSet<String>> {
+    let mut args = vec!["diff", "--cached", "--numstat", "-z", "--diff-filter=M", "--no-renames"];
+    args.extend_from_slice(flags);
+    // <新增行數>\t<刪除行數>\t<路徑>\0
+    Ok(git_in(dir, &args)?
+        .split('\0')
+        .filter_map(|entry| entry.splitn(3, '\t').nth(2))
+        .map(String::from)
+        .collect())
+}
+
+/// files 中內容有修改的 staged 檔案（不含新增、刪除與只有權限變更的檔案）
+fn modified(dir: &Path, files: &[String]) -> Result<Vec<String>> {
+    let output = git_in(dir, &["diff", "--cached", "--raw", "-z", "--no-abbrev", "--diff-filter=M", "--no-renames"])?;
+    let files: HashSet<&str> = files.iter().map(String::as_str).collect();
+    // :100644 100644 <舊 blob> <新 blob> M\0<路徑>\0
+    let fields: Vec<&str> = output.split('\0').collect();
+    Ok(fields
+        .chunks_exact(2)
+        .filter_map(|pair| {
+            let blobs: Vec<&str> = pair[0].split_whitespace().collect();
+            (blobs.len() == 5 && blobs[2] != blobs[3] && files.contains(pair[1])).then(|| pair[1].to_string())
+        })
+        .collect())
+}
+
+/// 找出 staged 變更只有換行字元或行尾空白不同的檔案（只檢查內容有修改的檔案，不含只有權限變更的檔案）
+pub fn detect(files: &[String]) -> Result<Vec<WhitespaceChange>> {
+    detect_in(Path::new("."), files)
+}
+
+fn detect_in(dir: &Path, files: &[String]) -> Result<Vec<WhitespaceChange>> {
+    let modified = modified(dir, files)?;
+    if modified.is_empty() {
+        return Ok(Vec::new());
+    }
+    let changed = still_changed(dir, &["--ignore-cr-at-eol", "--ignore-space-at-eol"])?;
+    let candidates: Vec<String> = modified.into_iter().filter(|path| !changed.contains(path)).collect();
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+    let line_endings = still_changed(dir, &["--ignore-cr-at-eol"])?;
+    Ok(candidates
+        .into_iter()
+        .map(|path| WhitespaceChange {
+            line_endings_only: !line_endings.contains(&path),
+            path,
+        })
+        .collect())
+}
+
+/// 找出 staged 變更只有格式調整（縮排、空白、空行）的檔案
+pub fn formatting_only(files: &[String]) -> Result<Vec<String>> {
+    let dir = Path::new(".");
+    let modified = modified(dir, files)?;
+    if modified.is_empty() {
+        return Ok(Vec::new());
+    }
+    let changed = still_changed(dir, &["--ignore-all-space", "--ignore-blank-lines"])?;
+    Ok(modified.into_iter().filter(|path| !changed.contains(path)).collect())
+}
+
+/// 顯示只有空白字元變更的檔案
+pub fn print(changes: &[WhitespaceChange]) {
+    println!("{}", "␣ 以下檔案只有換行字元或行尾空白的變更：".blue());
+    for change in changes {
+        let kind = if change.line_endings_only { "換行字元（CRLF / LF）" } else { "行尾空白" };
+        println!("  {}  {}", change.path, kind.dimmed());
+    }
+    println!("{}", "  這些檔案不會放入提示詞".dimmed());
+    println!();
+}
+
+/// 詢問是否將這些檔案移出 staged（保留工作目錄的內容），回傳是否已移出
+pub fn offer_unstage(changes: &[WhitespaceChange]) -> Result<bool> {
+    let selection = Select::with_theme(plain::theme())
+        .with_prompt("要如何處理這些檔案？")
+        .items(&[
+            format!("移出 staged，不放入這個 commit（{} 個檔案）", changes.len()),
+            "保留在這個 commit".to_string(),
+        ])
+        .default(0)
+        .interact()?;
+    if selection != 0 {
+        return Ok(false);
+    }
+
+    let status = Command::new("git")
+        .args(["reset", "-q", "--"])
+        .args(changes.iter().map(|c| c.path.as_str()))
+        .status()
+        .context("無法執行 git reset")?;
+    if !status.success() {
+        anyhow::bail!("git reset 執行失敗");
+    }
+    println!("{}", format!("✓ 已將 {} 個檔案移出 staged（工作目錄不受影響）", changes.len()).green());
+    println!("{}", "  換行字元反覆變動時，可在 .gitattributes 設定 text=auto 或調整 core.autocrlf".dimmed());
+    println!();
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::time::{Duration, Instant};
+
+    /// 建立含 count 個檔案的 repository，再修改全部檔案並加入 staged：
+    /// 依序輪流為換行字元、行尾空白與內容的變更
+    fn large_index(count: usize) -> (std::path::PathBuf, Vec<String>) {
+        let dir = env::temp_dir().join(format!("git-auto-commit-whitespace-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| git_in(&dir, args).unwrap();
+        git(&["init", "-q"]);
+        let files: Vec<String> = (0..count).map(|i| format!("檔案{}.txt", i)).collect();
+        for file in &files {
+            fs::write(dir.join(file), "a\nb\n").unwrap();
+        }
+        git(&["add", "."]);
+        git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"]);
+        for (i, file) in files.iter().enumerate() {
+            let content = ["a\r\nb\r\n", "a  \nb\n", "a\nc\n"][i % 3];
+            fs::write(dir.join(file), content).unwrap();
+        }
+        git(&["-c", "core.autocrlf=false", "add", "."]);
+        (dir, files)
+    }
+
+    #[test]
+    fn detect_large_index() {
+        let (dir, files) = large_index(3000);
+        let start = Instant::now();
+        let changes = detect_in(&dir, &files).unwrap();
+        let elapsed = start.elapsed();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(changes.len(), 2000);
+        assert_eq!(changes.iter().filter(|c| c.line_endings_only).count(), 1000);
+        assert!(changes.iter().any(|c| c.path == "檔案0.txt" && c.line_endings_only));
+        assert!(changes.iter().any(|c| c.path == "檔案1.txt" && !c.line_endings_only));
+        assert!(!changes.iter().any(|c| c.path == "檔案2.txt"));
+        // 每個檔案各執行一次 git diff 時需要數十秒，批次比對只需執行三次 git
+        assert!(elapsed < Duration::from_secs(5), "耗時 {:?}", elapsed);
+    }
+}
@@ -0,0 +1,61 @@
+use crate::config::Workflow;
+
+/// 未設定 workflow 時的分支前綴說明
+const DEFAULT_PREFIXES: &str = "\
+- feature/: For new features (e.g., feature/add-login-page, feat/add-login-page)
+- bugfix/: For bug fixes (e.g., bugfix/fix-header-bug, fix/header-bug)
+- hotfix/: For urgent fixes (e.g., hotfix/security-patch)
+- release/: For branches preparing a release (e.g., release/v1.2.0)
+- chore/: For non-code tasks like dependency, docs updates (e.g., chore/update-dependencies)";
+
+const GIT_FLOW_PREFIXES: &str = "\
+- feature/: For new features, branched from develop (e.g., feature/add-login-page)
+- bugfix/: For bug fixes on develop (e.g., bugfix/fix-header-bug)
+- release/: For branches preparing a release, branched from develop (e.g., release/1.2.0)
+- hotfix/: For urgent fixes to production, branched from main (e.g., hotfix/security-patch)
+- support/: For maintaining older releases (e.g., support/1.x)";
+
+const GITHUB_FLOW_PREFIXES: &str = "\
+- feature/: For new features (e.g., feature/add-login-page)
+- fix/: For bug fixes (e.g., fix/header-bug)
+- docs/: For documentation changes (e.g., docs/update-readme)
+- chore/: For dependency updates and maintenance (e.g., chore/update-dependencies)";
+
+const TRUNK_PREFIXES: &str = "\
+Branches are short-lived (merged within a day or two), so keep names short.
+- feat/: For small, incremental features (e.g., feat/login-button)
+- fix/: For bug fixes (e.g., fix/header-bug)
+- refactor/: For refactoring without behavior changes (e.g., refactor/extract-parser)
+- chore/: For maintenance tasks (e.g., chore/bump-deps)";
+
+/// 提示詞中 {branch_prefixes} 變數的內容
+pub fn branch_prefixes(workflow: Option<Workflow>) -> &'static str {
+    match workflow {
+        None => DEFAULT_PREFIXES,
+        Some(Workflow::GitFlow) => GIT_FLOW_PREFIXES,
+        Some(Workflow::GithubFlow) => GITHUB_FLOW_PREFIXES,
+        Some(Workflow::Trunk) => TRUNK_PREFIXES,
+    }
+}
+
+/// 新分支與 PR 預設的 base 分支（None 表示依 origin/HEAD 判斷）
+pub fn base_branch(workflow: Option<Workflow>) -> Option<&'static str> {
+    match workflow {
+        Some(Workflow::GitFlow) => Some("develop"),
+        _ => None,
+    }
+}
+
+/// 不允許直接 commit 的分支
+fn protected_branches(workflow: Option<Workflow>) -> &'static [&'static str] {
+    match workflow {
+        Some(Workflow::GitFlow) => &["main", "master", "develop"],
+        Some(Workflow::GithubFlow) => &["main", "master"],
+        Some(Workflow::Trunk) | None => &[],
+    }
+}
+
+/// 檢查 workflow 是否允許直接 commit 到指定分支
+pub fn allows_direct_commit(workflow: Option<Workflow>, branch: &str) -> bool {
+    !protected_branches(workflow).contains(&branch)
+}